@@ -5,8 +5,12 @@ use colored::*;
 use indicatif::{ProgressBar, ProgressStyle};
 use kanri_core::Cleanable;
 use serde::{Deserialize, Serialize};
-use std::io::{self, Write};
-use std::path::PathBuf;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+mod output;
+
+use output::{OutputFormat, Reporter};
 
 #[derive(Parser)]
 #[command(name = "kanri")]
@@ -14,6 +18,393 @@ use std::path::PathBuf;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// 一覧の並び順
+    #[arg(long, global = true, value_enum, default_value = "size")]
+    sort: SortOrder,
+
+    /// 大きい順に上位 N 件のみ表示
+    #[arg(long, global = true)]
+    top: Option<usize>,
+
+    /// --top で省略された項目も削除対象に含める
+    #[arg(long, global = true)]
+    all_items: bool,
+
+    /// 実行履歴（~/.kanri/history.jsonl）に記録しない
+    #[arg(long, global = true)]
+    no_history: bool,
+
+    /// 出力形式（human: 色付き / json: 結果を JSON で出力 / plain: 色なしタブ区切り）
+    #[arg(long, global = true, value_enum, default_value = "human")]
+    output: OutputFormat,
+
+    /// 結果とエラーを JSON で出力（--output json と同じ）
+    #[arg(long, global = true, conflicts_with = "output")]
+    json: bool,
+
+    /// clean コマンドで一覧・進捗を表示せず、最終結果の 1 行だけを出力する（インタラクティブモードでは無効）
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    /// 結果（human / json / plain の出力）をファイルに書き込む。色付けはしない
+    #[arg(long, global = true, value_name = "PATH")]
+    output_file: Option<PathBuf>,
+
+    /// --output-file のファイルを上書きせず、末尾に追記する
+    #[arg(long, global = true, requires = "output_file")]
+    append: bool,
+
+    /// 色付けを無効にする（環境変数 NO_COLOR でも無効化）
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// 探索・サイズ計算でシンボリックリンクをたどる
+    #[arg(long, global = true)]
+    follow_symlinks: bool,
+
+    /// 探索する深さの上限（プロジェクトは検索パス直下を 1 とする。省略時は無制限）
+    #[arg(long, global = true, value_name = "N")]
+    depth: Option<usize>,
+
+    /// 探索で隠しディレクトリ（. で始まる）に降りない（.venv など削除対象そのものは除く）
+    #[arg(long, global = true)]
+    no_hidden: bool,
+
+    /// 使用する設定プロファイル（省略時は default_profile、なければトップレベルの設定）
+    #[arg(long, global = true)]
+    profile: Option<String>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SortOrder {
+    /// サイズの大きい順
+    Size,
+    /// 名前順
+    Name,
+    /// パス順
+    Path,
+}
+
+impl From<SortOrder> for kanri_core::SortKey {
+    fn from(order: SortOrder) -> Self {
+        match order {
+            SortOrder::Size => kanri_core::SortKey::Size,
+            SortOrder::Name => kanri_core::SortKey::Name,
+            SortOrder::Path => kanri_core::SortKey::Path,
+        }
+    }
+}
+
+/// 一覧表示の並び替え・件数制限オプション
+#[derive(Clone, Copy)]
+struct ListOptions {
+    sort: kanri_core::SortKey,
+    top: Option<usize>,
+    all_items: bool,
+}
+
+impl ListOptions {
+    /// 上位 N 件に絞り込み、指定キーで並べ替える（省略した項目を返す）
+    fn select<T>(
+        &self,
+        items: &mut Vec<T>,
+        size: impl Fn(&T) -> u64,
+        name: impl Fn(&T) -> String,
+        path: impl Fn(&T) -> PathBuf,
+    ) -> Vec<T> {
+        let hidden = match self.top {
+            Some(n) => {
+                items.sort_by_key(|item| std::cmp::Reverse(size(item)));
+                items.split_off(n.min(items.len()))
+            }
+            None => Vec::new(),
+        };
+
+        match self.sort {
+            kanri_core::SortKey::Size => items.sort_by_key(|item| std::cmp::Reverse(size(item))),
+            kanri_core::SortKey::Name => items.sort_by_key(|item| name(item)),
+            kanri_core::SortKey::Path => items.sort_by_key(|item| path(item)),
+        }
+
+        hidden
+    }
+
+    /// CleanableItem 用の select
+    fn select_items(&self, items: &mut Vec<kanri_core::CleanableItem>) -> Vec<kanri_core::CleanableItem> {
+        let hidden = match self.top {
+            Some(n) => kanri_core::cleanable::split_top(items, n),
+            None => Vec::new(),
+        };
+        kanri_core::cleanable::sort_items(items, self.sort);
+        hidden
+    }
+}
+
+/// clean サブコマンド共通のオプション
+#[derive(Clone, Copy)]
+struct CleanOptions<'a> {
+    /// 安全な項目のみ対象にする
+    safe_only: bool,
+    /// 一括削除で要確認の項目も削除する
+    include_unsafe: bool,
+    /// 削除予定の項目を表示するだけで削除しない
+    dry_run: bool,
+    /// 実行履歴に記録する
+    history: bool,
+    /// 実行履歴に記録するコマンド名（例: "clean rust"）
+    command: &'static str,
+    /// 一覧の並び替え・件数制限
+    list: ListOptions,
+    /// --delete でも削除前に確認する（ユーザーのファイルを扱うクリーナー向け）
+    always_confirm: bool,
+    /// 削除後に空になったディレクトリを削除する範囲（--prune-empty-dirs。このディレクトリ自体は残す）
+    prune_root: Option<&'a Path>,
+}
+
+/// クラウド同期フォルダ内の項目を絞り込む
+///
+/// インタラクティブモードでは一括確認の後でも 1 件ずつ警告して確認し、
+/// それ以外では --include-unsafe なしなら削除対象から除外する
+fn filter_sync_folder_items<T>(
+    items: &mut Vec<T>,
+    path: impl Fn(&T) -> &Path,
+    size: impl Fn(&T) -> u64,
+    interactive: bool,
+    opts: CleanOptions,
+    reporter: &Reporter,
+) -> io::Result<()> {
+    let (synced, mut kept): (Vec<T>, Vec<T>) = items
+        .drain(..)
+        .partition(|item| kanri_core::utils::is_in_sync_folder(path(item)).is_some());
+
+    if interactive {
+        for item in synced {
+            let service = kanri_core::utils::is_in_sync_folder(path(&item)).unwrap_or_default();
+            let message = format!(
+                "{} は {} の同期フォルダ内です。削除すると再同期が発生しますが、削除しますか?",
+                path(&item).display(),
+                service
+            );
+            if reporter.ask(&message)? {
+                kept.push(item);
+            }
+        }
+    } else if !opts.include_unsafe {
+        reporter.skipped_unsafe(synced.len(), synced.iter().map(&size).sum());
+    } else {
+        kept.extend(synced);
+    }
+
+    *items = kept;
+    Ok(())
+}
+
+/// 保護されたパス（--protect・[safety] protected_paths）と重なる項目を除外し、除外した項目を表示する
+fn filter_protected_items<T>(items: &mut Vec<T>, path: impl Fn(&T) -> &Path, reporter: &Reporter) {
+    if kanri_core::utils::protected_paths().is_empty() {
+        return;
+    }
+    items.retain(|item| {
+        let protected = kanri_core::utils::is_protected_path(path(item));
+        if protected {
+            reporter.warn(&format!("🛡️ 保護されたパスのため削除しません: {}", path(item).display()));
+        }
+        !protected
+    });
+}
+
+/// インタラクティブモードで、一覧の番号を指定して削除する項目を選ぶ
+///
+/// 何も選ばなかった場合はキャンセル扱いにして false を返す
+fn select_by_number<T>(items: &mut Vec<T>, reporter: &mut Reporter) -> Result<bool> {
+    let selected = loop {
+        let input = reporter.prompt(&format!(
+            "削除する番号を入力してください (例: 1-3,5 / all / none, 1-{})",
+            items.len()
+        ))?;
+        match parse_selection(&input, items.len()) {
+            Ok(selected) => break selected,
+            Err(e) => reporter.warn(&format!("⚠ {}", e)),
+        }
+    };
+
+    if selected.is_empty() {
+        reporter.cancel();
+        return Ok(false);
+    }
+
+    let mut index = 0;
+    items.retain(|_| {
+        let keep = selected.binary_search(&index).is_ok();
+        index += 1;
+        keep
+    });
+    Ok(true)
+}
+
+/// インタラクティブモードで、項目ごとに削除するかを確認する
+///
+/// `describe`: 確認の文言に使う項目の説明。何も選ばなかった場合は false を返す
+fn select_each<T>(items: &mut Vec<T>, describe: impl Fn(&T) -> String, reporter: &mut Reporter) -> Result<bool> {
+    reporter.note("各項目について個別に確認します");
+    reporter.note("(y)削除 / (n)スキップ / (q)中断 / (a)全て削除");
+
+    let mut selected = Vec::new();
+    let mut delete_all = false;
+    for item in items.drain(..) {
+        if delete_all {
+            selected.push(item);
+            continue;
+        }
+
+        let choice = reporter
+            .prompt(&format!("{} を削除しますか? (y/n/q/a)", describe(&item)))?
+            .to_lowercase();
+        match choice.as_str() {
+            "y" | "yes" => {
+                selected.push(item);
+                reporter.note("  ✓ 削除対象に追加");
+            }
+            "n" | "no" | "" => reporter.note("  → スキップ"),
+            "q" | "quit" => {
+                reporter.warn("中断しました");
+                break;
+            }
+            "a" | "all" => {
+                selected.push(item);
+                delete_all = true;
+                reporter.note("  ✓ 以降すべて削除対象に追加");
+            }
+            _ => reporter.warn("  ⚠ 無効な入力、スキップします"),
+        }
+    }
+
+    if selected.is_empty() {
+        reporter.cancel();
+        return Ok(false);
+    }
+    *items = selected;
+    Ok(true)
+}
+
+/// 番号の指定（例: "1-3,5,8-10" / "all" / "none"）を解析
+///
+/// 番号は 1 始まり、戻り値は重複を除いて昇順に並べた 0 始まりのインデックス。
+/// 空の入力は "none" と同じ扱い
+fn parse_selection(input: &str, max: usize) -> Result<Vec<usize>> {
+    let input = input.trim();
+    if input.is_empty() || input.eq_ignore_ascii_case("none") {
+        return Ok(Vec::new());
+    }
+    if input.eq_ignore_ascii_case("all") {
+        return Ok((0..max).collect());
+    }
+
+    let parse_number = |s: &str| -> Result<usize> {
+        let n: usize = s
+            .trim()
+            .parse()
+            .map_err(|_| anyhow::anyhow!("番号として解釈できません: '{}'", s.trim()))?;
+        if n == 0 || n > max {
+            anyhow::bail!("番号 {} は範囲外です (1-{})", n, max);
+        }
+        Ok(n - 1)
+    };
+
+    let mut selected = std::collections::BTreeSet::new();
+    for part in input.split(',').map(str::trim) {
+        if part.is_empty() {
+            anyhow::bail!("空の指定があります: '{}'", input);
+        }
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (parse_number(start)?, parse_number(end)?);
+                if start > end {
+                    anyhow::bail!("範囲の開始が終了より大きいです: '{}'", part);
+                }
+                selected.extend(start..=end);
+            }
+            None => {
+                selected.insert(parse_number(part)?);
+            }
+        }
+    }
+
+    Ok(selected.into_iter().collect())
+}
+
+/// 実行履歴を記録（失敗しても処理は中断しない）
+fn record_history(enabled: bool, command: &str, paths: Vec<PathBuf>, bytes_freed: u64) {
+    if !enabled {
+        return;
+    }
+
+    let entry = kanri_core::history::HistoryEntry::new(command.to_string(), paths, bytes_freed);
+    if let Err(e) = kanri_core::history::append_entry(&entry) {
+        eprintln!(
+            "{} {}",
+            "⚠".yellow(),
+            format!("実行履歴の記録に失敗しました: {}", e).dimmed()
+        );
+    }
+}
+
+/// --prune-empty-dirs: 削除した項目の親から検索ディレクトリに向かって、空になったディレクトリを削除
+fn prune_after_clean(opts: CleanOptions, removed: &[PathBuf], reporter: &Reporter) {
+    let Some(root) = opts.prune_root else {
+        return;
+    };
+    match kanri_core::utils::prune_empty_parents(removed, root) {
+        Ok(0) => {}
+        Ok(count) => reporter.note(&format!("🧹 空になったディレクトリを {} 件削除しました", count)),
+        Err(e) => reporter.warn(&format!("⚠ 空のディレクトリの削除に失敗しました: {}", e)),
+    }
+}
+
+/// Ctrl-C で中断できる状態で削除を実行（中断が要求されると現在の項目を削除し終えた時点で止まる）
+fn run_cancellable<T, E>(delete: impl FnOnce() -> std::result::Result<T, E>) -> std::result::Result<T, E> {
+    kanri_core::utils::set_cancellable(true);
+    let result = delete();
+    kanri_core::utils::set_cancellable(false);
+    result
+}
+
+/// Ctrl-C で中断された場合は、削除済みの件数とともに警告を表示
+fn warn_if_cancelled(cleaned: usize, total: usize, reporter: &Reporter) {
+    if kanri_core::utils::is_cancel_requested() {
+        reporter.warn(&format!(
+            "⚠ Ctrl-C で中断しました（{} / {} 件を削除済み。残りは削除していません）",
+            cleaned, total
+        ));
+    }
+}
+
+/// --top で省略された項目のフッターを表示
+fn print_hidden_footer(hidden_count: usize, hidden_size: u64) {
+    if hidden_count > 0 {
+        println!(
+            "  {}",
+            format!(
+                "... 他 {} 件 ({})",
+                hidden_count,
+                kanri_core::utils::format_size(hidden_size)
+            )
+            .dimmed()
+        );
+    }
+}
+
+/// diagnose / history の出力形式
+#[derive(Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+enum ReportFormat {
+    /// 色付きの表示（デフォルト）
+    #[default]
+    Human,
+    /// JSON
+    Json,
+    /// CSV（サイズはバイト数のまま）
+    Csv,
 }
 
 #[derive(Clone, ValueEnum)]
@@ -24,6 +415,36 @@ enum RestoreMode {
     Version,
     /// タイムスタンプ付きでそのまま復元
     Raw,
+    /// 指定した期間（--since / --until）にアーカイブしたもののうち、最新版を復元
+    Range,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum DestStructure {
+    /// 相対パスのディレクトリ構造を保って復元（デフォルト）
+    Preserve,
+    /// ディレクトリを除き、すべてのファイルを復元先の直下に置く（同名は "name (1).ext" に改名）
+    Flat,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OverwriteMode {
+    /// 既存のファイルは上書きしない
+    Never,
+    /// 常に上書きする（デフォルト）
+    Always,
+    /// リモートの方が新しい場合のみ上書き（内容が同じファイルはスキップ）
+    IfNewer,
+}
+
+impl From<OverwriteMode> for kanri_core::archive::OverwritePolicy {
+    fn from(mode: OverwriteMode) -> Self {
+        match mode {
+            OverwriteMode::Never => Self::Never,
+            OverwriteMode::Always => Self::Always,
+            OverwriteMode::IfNewer => Self::IfNewer,
+        }
+    }
 }
 
 #[derive(Subcommand)]
@@ -32,6 +453,48 @@ enum Commands {
     Clean {
         #[command(subcommand)]
         target: CleanTarget,
+
+        /// 安全な項目のみ表示・削除
+        #[arg(long, global = true)]
+        safe_only: bool,
+
+        /// 一括削除で「⚠ 要確認」の項目も削除する
+        #[arg(long, global = true)]
+        include_unsafe: bool,
+
+        /// 削除予定の項目と解放サイズを表示するだけで、実際には削除しない
+        #[arg(long, global = true)]
+        dry_run: bool,
+
+        /// 削除後、空になった親ディレクトリも削除する（検索ディレクトリ自体は残す）
+        #[arg(long, global = true)]
+        prune_empty_dirs: bool,
+
+        /// 削除せず、見つかった件数と合計サイズだけを表示する
+        #[arg(long, global = true, conflicts_with = "dry_run")]
+        stats_only: bool,
+
+        /// --stats-only の結果を JSON のサイズレポートに追記する（既存のレポートにまとめる）
+        #[arg(long, global = true, value_name = "FILE", requires = "stats_only")]
+        report: Option<PathBuf>,
+
+        /// このパス（配下・親ディレクトリを含む）は何が見つかっても削除しない（複数指定可。設定の [safety] protected_paths に追加）
+        #[arg(long, global = true, value_name = "PATH")]
+        protect: Vec<PathBuf>,
+
+        /// ルート・/System・/Users・ホームディレクトリそのものなども削除の対象にする（通常は拒否する）
+        #[arg(long = "i-know-what-im-doing", global = true)]
+        allow_critical_targets: bool,
+
+        /// 削除前後にボリュームの空き容量を実測して表示し、削除したサイズより大幅に少なければ警告する
+        /// （Docker は VM のディスクイメージ内のため対象外）
+        #[arg(long, global = true)]
+        report_before_after: bool,
+
+        /// 実測で領域が解放されていなければ `tmutil thinlocalsnapshots` で Time Machine の
+        /// ローカルスナップショットを間引く（macOS のみ。--report-before-after を含む）
+        #[arg(long, global = true)]
+        thin_snapshots: bool,
     },
 
     /// ファイル・ディレクトリを B2 にアーカイブ
@@ -43,8 +506,12 @@ enum Commands {
     /// B2 からアーカイブを復元
     Restore {
         /// B2 上のアーカイブパス（プレフィックス）
-        #[arg(long)]
-        from: String,
+        #[arg(long, required_unless_present = "manifest")]
+        from: Option<String>,
+
+        /// マニフェスト（kanri archive manifest で書き出したもの）に記載されたファイルだけを復元
+        #[arg(long, conflicts_with_all = ["from", "list_versions", "version"])]
+        manifest: Option<PathBuf>,
 
         /// 復元先ディレクトリ
         #[arg(long, default_value = ".")]
@@ -54,17 +521,69 @@ enum Commands {
         #[arg(long, value_enum, default_value = "latest")]
         mode: RestoreMode,
 
+        /// 復元先のディレクトリ構造
+        #[arg(long, value_enum, default_value = "preserve")]
+        dest_structure: DestStructure,
+
+        /// 復元先に同じパスのファイルが既にある場合の扱い
+        #[arg(long, value_enum, default_value = "always")]
+        overwrite: OverwriteMode,
+
         /// 特定バージョンを指定（--mode version と併用）
         #[arg(long)]
         version: Option<String>,
 
+        /// この日時以降にアーカイブしたものを復元（--mode range と併用。YYYYMMDD_HHMMSS / YYYYMMDD / YYYY-MM-DD）
+        #[arg(long, value_parser = parse_range_start, conflicts_with = "manifest")]
+        since: Option<String>,
+
+        /// この日時以前にアーカイブしたものを復元（--mode range と併用。日付だけならその日の終わりまで）
+        #[arg(long, value_parser = parse_range_end, conflicts_with = "manifest")]
+        until: Option<String>,
+
         /// Dry-run モード
         #[arg(long)]
         dry_run: bool,
+
+        /// 利用可能なバージョン（タイムスタンプ）一覧を表示
+        #[arg(long)]
+        list_versions: bool,
+
+        /// 並列ダウンロード数
+        #[arg(short, long, default_value = "4")]
+        jobs: usize,
+
+        /// ダウンロード後に SHA256 を検証（アーカイブインデックスに記録がある場合はデフォルトで有効）
+        #[arg(long, conflicts_with = "no_verify")]
+        verify: bool,
+
+        /// SHA256 検証を無効化
+        #[arg(long)]
+        no_verify: bool,
+
+        /// SHA256 不一致時の再ダウンロード回数
+        #[arg(long, default_value = "3")]
+        retries: u32,
+
+        /// 転送帯域の上限（例: 10M。設定の storage.bwlimit より優先、rclone のみ対応）
+        #[arg(long)]
+        bwlimit: Option<String>,
     },
 
     /// アーカイブ一覧を表示
-    ListArchives,
+    ListArchives {
+        /// ローカルのインデックスではなく、リモートのファイルからアーカイブを復元して表示
+        #[arg(long)]
+        remote: bool,
+
+        /// リモートで検索するプレフィックス（省略時はバケット全体）
+        #[arg(long, requires = "remote")]
+        from: Option<String>,
+
+        /// リモートから復元したアーカイブをインデックスに書き戻す（既にあるアーカイブ先はそのまま）
+        #[arg(long, requires = "remote")]
+        rebuild_index: bool,
+    },
 
     /// 設定を初期化
     Config {
@@ -72,18 +591,36 @@ enum Commands {
         action: ConfigAction,
     },
 
+    /// クリーン / アーカイブの実行履歴を表示
+    History {
+        /// 表示する件数（新しい順）
+        #[arg(short = 'n', long, default_value = "20")]
+        limit: usize,
+
+        /// 出力形式（human / json / csv）
+        #[arg(long, value_enum, default_value = "human")]
+        format: ReportFormat,
+    },
+
     /// シェル補完スクリプトを生成
     Completions {
         /// シェルの種類
         #[arg(value_enum)]
         shell: Shell,
+
+        /// 標準出力ではなく、シェルの補完ディレクトリに書き込む（bash / zsh / fish）
+        #[arg(long)]
+        install: bool,
     },
 
+    /// 環境・ツールのバージョン・設定・キャッシュの場所をまとめて表示（不具合報告用）
+    Doctor,
+
     /// システム全体の診断を実行（削除可能な項目をサマリー表示）
     Diagnose {
-        /// JSON形式で出力
-        #[arg(long)]
-        json: bool,
+        /// 出力形式（human / json / csv。--json 指定時は json）
+        #[arg(long, value_enum, conflicts_with_all = ["watch", "json"])]
+        format: Option<ReportFormat>,
 
         /// 最小サイズ閾値（GB）
         #[arg(long)]
@@ -92,6 +629,30 @@ enum Commands {
         /// 検索開始ディレクトリ（デフォルト: カレントディレクトリ）
         #[arg(short, long, default_value = ".")]
         path: PathBuf,
+
+        /// 合計サイズがこの値（GB）を超えたら終了コード 2 で終了（CI 用）
+        #[arg(long, conflicts_with = "watch")]
+        fail_over: Option<f64>,
+
+        /// カテゴリごとの閾値（例: rust:5）。超えたら終了コード 2 で終了（複数指定可）
+        #[arg(long, value_parser = parse_category_threshold, conflicts_with = "watch")]
+        fail_category: Vec<(String, f64)>,
+
+        /// 前回の診断との差分と合計サイズの推移を表示
+        #[arg(long, conflicts_with_all = ["json", "format", "watch"])]
+        trend: bool,
+
+        /// --trend で推移を表示する実行回数
+        #[arg(long, default_value = "20")]
+        trend_runs: usize,
+
+        /// 定期的に再スキャンして表示を更新（Ctrl-C で終了）
+        #[arg(long, conflicts_with = "json")]
+        watch: bool,
+
+        /// --watch の更新間隔（秒）
+        #[arg(long, default_value = "5")]
+        interval: u64,
     },
 }
 
@@ -114,6 +675,18 @@ enum CleanTarget {
         /// インタラクティブモード（削除前に確認）
         #[arg(short, long)]
         interactive: bool,
+
+        /// 最小サイズ（GB単位、小数可）。これより小さいプロジェクトは表示しない
+        #[arg(long, default_value = "0")]
+        min_size: f64,
+
+        /// target 全体ではなく、指定日数以上更新されていない成果物のみ削除（cargo sweep --time 相当）
+        #[arg(long, value_name = "DAYS")]
+        stale: Option<u64>,
+
+        /// target 全体ではなく、指定したプロファイルの出力（target/debug など）のみ削除（複数指定可）
+        #[arg(long, value_name = "PROFILE", conflicts_with = "stale")]
+        profile_dir: Vec<String>,
     },
 
     /// Node.js プロジェクトの node_modules ディレクトリをクリーン
@@ -133,6 +706,63 @@ enum CleanTarget {
         /// インタラクティブモード（削除前に確認）
         #[arg(short, long)]
         interactive: bool,
+
+        /// 最小サイズ（GB単位、小数可）。これより小さいプロジェクトは表示しない
+        #[arg(long, default_value = "0")]
+        min_size: f64,
+    },
+
+    /// pnpm store / Bun / Deno のグローバルキャッシュをクリーン
+    JsCache {
+        /// 検索・表示のみ（デフォルト動作）
+        #[arg(short, long)]
+        search: bool,
+
+        /// 削除を実行
+        #[arg(short, long)]
+        delete: bool,
+
+        /// インタラクティブモード（削除前に確認）
+        #[arg(short, long)]
+        interactive: bool,
+    },
+
+    /// npm / yarn のグローバルキャッシュをクリーン
+    NpmCache {
+        /// 検索・表示のみ（デフォルト動作）
+        #[arg(short, long)]
+        search: bool,
+
+        /// 削除を実行
+        #[arg(short, long)]
+        delete: bool,
+
+        /// インタラクティブモード（削除前に確認）
+        #[arg(short, long)]
+        interactive: bool,
+
+        /// ディレクトリを直接削除せず `npm cache clean --force` / `yarn cache clean` を使う
+        #[arg(long)]
+        use_tool: bool,
+    },
+
+    /// pip のキャッシュ（wheel キャッシュを含む）をクリーン（python3 と pyenv の各バージョンを調べる）
+    PipCache {
+        /// 検索・表示のみ（デフォルト動作）
+        #[arg(short, long)]
+        search: bool,
+
+        /// 削除を実行
+        #[arg(short, long)]
+        delete: bool,
+
+        /// インタラクティブモード（削除前に確認）
+        #[arg(short, long)]
+        interactive: bool,
+
+        /// ディレクトリを直接削除せず `python -m pip cache purge` を使う
+        #[arg(long)]
+        use_tool: bool,
     },
 
     /// Docker の未使用データをクリーン
@@ -156,6 +786,16 @@ enum CleanTarget {
         /// ボリュームも削除
         #[arg(short, long)]
         volumes: bool,
+
+        /// dangling イメージ（<none>）のみ削除（docker image prune、コンテナやボリュームは残す）
+        #[arg(long, conflicts_with_all = ["all", "volumes"])]
+        dangling: bool,
+
+        /// 指定した期間より前に作成されたものだけを削除（例: 168h, 7d, 2w。docker の --filter until）
+        ///
+        /// 最近ビルドしたレイヤーは再利用しやすいため残す。ボリュームには使えない
+        #[arg(long, visible_alias = "older-than", value_parser = kanri_core::docker::parse_until, conflicts_with = "volumes")]
+        until: Option<String>,
     },
 
     /// Flutter プロジェクトの build/.dart_tool をクリーン
@@ -177,6 +817,25 @@ enum CleanTarget {
         interactive: bool,
     },
 
+    /// Android / Gradle プロジェクトの build/.gradle/.cxx をクリーン
+    Android {
+        /// 検索開始ディレクトリ（デフォルト: カレントディレクトリ）
+        #[arg(short, long, default_value = ".")]
+        path: PathBuf,
+
+        /// 検索・表示のみ（デフォルト動作）
+        #[arg(short, long)]
+        search: bool,
+
+        /// 削除を実行
+        #[arg(short, long)]
+        delete: bool,
+
+        /// インタラクティブモード（削除前に確認）
+        #[arg(short, long)]
+        interactive: bool,
+    },
+
     /// Mac アプリケーションキャッシュをクリーン (⚠️ Experimental)
     Cache {
         /// 検索・表示のみ（デフォルト動作）
@@ -195,9 +854,13 @@ enum CleanTarget {
         #[arg(long, default_value = "1")]
         min_size: u64,
 
-        /// 安全なキャッシュのみ表示
+        /// この日数以内に中身が更新されたキャッシュは使用中とみなして除外（0 で除外しない）
+        #[arg(long, value_name = "DAYS", default_value_t = kanri_core::cache::DEFAULT_KEEP_NEWER_THAN_DAYS)]
+        keep_newer_than: u64,
+
+        /// バンドル ID をアプリ名に解決し、アプリごとにまとめて表示（共有ディレクトリ内のキャッシュも振り分ける）
         #[arg(long)]
-        safe_only: bool,
+        by_app: bool,
     },
 
     /// Python 仮想環境をクリーン
@@ -232,6 +895,10 @@ enum CleanTarget {
         /// インタラクティブモード（削除前に確認）
         #[arg(short, long)]
         interactive: bool,
+
+        /// キャッシュ全体ではなく内訳（cache/download・展開済みモジュールなど）ごとに表示・削除する
+        #[arg(long)]
+        detailed: bool,
     },
 
     /// Gradle キャッシュをクリーン
@@ -247,6 +914,18 @@ enum CleanTarget {
         /// インタラクティブモード（削除前に確認）
         #[arg(short, long)]
         interactive: bool,
+
+        /// キャッシュ全体ではなく内訳（caches・wrapper・daemon など）ごとに表示・削除する
+        #[arg(long)]
+        detailed: bool,
+
+        /// ~/.gradle 全体ではなく ~/.gradle/caches のみを対象にする（ラッパーのディストリビューションを残す）
+        #[arg(long)]
+        caches_only: bool,
+
+        /// 削除前に実行中の Gradle デーモンを停止する
+        #[arg(long)]
+        daemon: bool,
     },
 
     /// Haskell ビルド成果物をクリーン
@@ -268,8 +947,16 @@ enum CleanTarget {
         interactive: bool,
     },
 
-    /// Xcode DerivedData をクリーン
-    Xcode {
+    /// .NET プロジェクトの bin / obj をクリーン
+    Dotnet {
+        /// 検索開始ディレクトリ（デフォルト: カレントディレクトリ）
+        #[arg(short, long, default_value = ".")]
+        path: PathBuf,
+
+        /// NuGet グローバルキャッシュ（~/.nuget/packages）も対象にする
+        #[arg(long)]
+        global: bool,
+
         /// 検索・表示のみ（デフォルト動作）
         #[arg(short, long)]
         search: bool,
@@ -283,13 +970,241 @@ enum CleanTarget {
         interactive: bool,
     },
 
-    /// 大きなファイル・ディレクトリをクリーン
-    LargeFiles {
+    /// CMake / C++ プロジェクトのビルドディレクトリをクリーン
+    Cmake {
         /// 検索開始ディレクトリ（デフォルト: カレントディレクトリ）
         #[arg(short, long, default_value = ".")]
         path: PathBuf,
 
-        /// 最小サイズ（GB）（デフォルト: 2GB）
+        /// 追加のビルドディレクトリパターン（`*` と `{name}` が使用可、例: {name}-build）
+        #[arg(long)]
+        build_dir_pattern: Vec<String>,
+
+        /// 検索・表示のみ（デフォルト動作）
+        #[arg(short, long)]
+        search: bool,
+
+        /// 削除を実行
+        #[arg(short, long)]
+        delete: bool,
+
+        /// インタラクティブモード（削除前に確認）
+        #[arg(short, long)]
+        interactive: bool,
+    },
+
+    /// Swift Package Manager の .build をクリーン
+    Swift {
+        /// 検索開始ディレクトリ（デフォルト: カレントディレクトリ）
+        #[arg(short, long, default_value = ".")]
+        path: PathBuf,
+
+        /// SwiftPM グローバルキャッシュ（~/Library/Caches/org.swift.swiftpm）も対象にする
+        #[arg(long)]
+        global: bool,
+
+        /// 検索・表示のみ（デフォルト動作）
+        #[arg(short, long)]
+        search: bool,
+
+        /// 削除を実行
+        #[arg(short, long)]
+        delete: bool,
+
+        /// インタラクティブモード（削除前に確認）
+        #[arg(short, long)]
+        interactive: bool,
+    },
+
+    /// R の renv ライブラリ・キャッシュ（renv / CRAN）をクリーン
+    R {
+        /// 検索開始ディレクトリ（デフォルト: カレントディレクトリ）
+        #[arg(short, long, default_value = ".")]
+        path: PathBuf,
+
+        /// 検索・表示のみ（デフォルト動作）
+        #[arg(short, long)]
+        search: bool,
+
+        /// 削除を実行
+        #[arg(short, long)]
+        delete: bool,
+
+        /// インタラクティブモード（削除前に確認）
+        #[arg(short, long)]
+        interactive: bool,
+    },
+
+    /// Jupyter / 機械学習の生成物（.ipynb_checkpoints, wandb, mlruns など）をクリーン
+    Ml {
+        /// 検索開始ディレクトリ（デフォルト: カレントディレクトリ）
+        #[arg(short, long, default_value = ".")]
+        path: PathBuf,
+
+        /// 対象のディレクトリ名（複数指定可、`*` が使用可。指定するとデフォルトの一覧を置き換える）
+        #[arg(long)]
+        name: Vec<String>,
+
+        /// 検索・表示のみ（デフォルト動作）
+        #[arg(short, long)]
+        search: bool,
+
+        /// 削除を実行
+        #[arg(short, long)]
+        delete: bool,
+
+        /// インタラクティブモード（削除前に確認）
+        #[arg(short, long)]
+        interactive: bool,
+    },
+
+    /// ブラウザキャッシュ（Chrome / Firefox / Safari / Edge / Brave）をクリーン
+    BrowserCache {
+        /// 検索・表示のみ（デフォルト動作）
+        #[arg(short, long)]
+        search: bool,
+
+        /// 削除を実行
+        #[arg(short, long)]
+        delete: bool,
+
+        /// インタラクティブモード（削除前に確認）
+        #[arg(short, long)]
+        interactive: bool,
+    },
+
+    /// ログ・クラッシュレポート（~/Library/Logs, /Library/Logs）をクリーン
+    Logs {
+        /// 検索・表示のみ（デフォルト動作）
+        #[arg(short, long)]
+        search: bool,
+
+        /// 削除を実行
+        #[arg(short, long)]
+        delete: bool,
+
+        /// インタラクティブモード（削除前に確認）
+        #[arg(short, long)]
+        interactive: bool,
+    },
+
+    /// Xcode の DerivedData・シミュレータ・古い DeviceSupport・Archives をクリーン
+    Xcode {
+        /// 検索・表示のみ（デフォルト動作）
+        #[arg(short, long)]
+        search: bool,
+
+        /// 削除を実行
+        #[arg(short, long)]
+        delete: bool,
+
+        /// インタラクティブモード（削除前に確認）
+        #[arg(short, long)]
+        interactive: bool,
+
+        /// OS ごとに残す DeviceSupport の数（新しいバージョンから。それ以外を削除対象にする）
+        #[arg(long, value_name = "N", default_value_t = kanri_core::xcode::DEFAULT_DEVICE_SUPPORT_KEEP)]
+        device_support_keep: usize,
+    },
+
+    /// 名前を指定してディレクトリをクリーン（.next, .turbo, .terraform など）
+    Custom {
+        /// 検索開始ディレクトリ（デフォルト: カレントディレクトリ）
+        #[arg(short, long, default_value = ".")]
+        path: PathBuf,
+
+        /// 削除対象のディレクトリ名（複数指定可、`*` ワイルドカード可）
+        #[arg(short, long = "name", visible_alias = "pattern", required = true)]
+        names: Vec<String>,
+
+        /// 検索・表示のみ（デフォルト動作）
+        #[arg(short, long)]
+        search: bool,
+
+        /// 削除を実行
+        #[arg(short, long)]
+        delete: bool,
+
+        /// インタラクティブモード（削除前に確認）
+        #[arg(short, long)]
+        interactive: bool,
+    },
+
+    /// 設定のプリセット（[clean.presets.<name>]）のディレクトリ名でクリーン
+    Preset {
+        /// プリセット名
+        name: String,
+
+        /// 検索開始ディレクトリ（デフォルト: カレントディレクトリ）
+        #[arg(short, long, default_value = ".")]
+        path: PathBuf,
+
+        /// 検索・表示のみ（デフォルト動作）
+        #[arg(short, long)]
+        search: bool,
+
+        /// 削除を実行
+        #[arg(short, long)]
+        delete: bool,
+
+        /// インタラクティブモード（削除前に確認）
+        #[arg(short, long)]
+        interactive: bool,
+    },
+
+    /// 利用できない iOS シミュレータを削除（xcrun simctl delete unavailable）
+    Simulators {
+        /// 検索・表示のみ（デフォルト動作）
+        #[arg(short, long)]
+        search: bool,
+
+        /// 削除を実行
+        #[arg(short, long)]
+        delete: bool,
+
+        /// インタラクティブモード（削除前に確認）
+        #[arg(short, long)]
+        interactive: bool,
+    },
+
+    /// ダウンロードフォルダの古い・大きいファイルをクリーン（削除前に必ず確認）
+    Downloads {
+        /// 検索するディレクトリ（デフォルト: ~/Downloads）
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+
+        /// この日数より前に更新されたファイルのみ対象（0 で日数を問わない）
+        #[arg(long, value_name = "DAYS", default_value_t = kanri_core::downloads::DEFAULT_OLDER_THAN_DAYS)]
+        older_than: u64,
+
+        /// 最小サイズ（MB単位、小数可）
+        #[arg(long, default_value = "0")]
+        min_size: f64,
+
+        /// 拡張子フィルタ（カンマ区切り、例: .dmg,.pkg,.zip）
+        #[arg(long)]
+        extensions: Option<String>,
+
+        /// 検索・表示のみ（デフォルト動作）
+        #[arg(short, long)]
+        search: bool,
+
+        /// 削除を実行（削除前に確認）
+        #[arg(short, long)]
+        delete: bool,
+
+        /// インタラクティブモード（削除前に確認）
+        #[arg(short, long)]
+        interactive: bool,
+    },
+
+    /// 大きなファイル・ディレクトリをクリーン
+    LargeFiles {
+        /// 検索開始ディレクトリ（デフォルト: カレントディレクトリ）
+        #[arg(short, long, default_value = ".")]
+        path: PathBuf,
+
+        /// 最小サイズ（GB）（デフォルト: 2GB）
         #[arg(long, default_value = "2")]
         min_size_gb: u64,
 
@@ -319,6 +1234,133 @@ enum CleanTarget {
     },
 }
 
+impl CleanTarget {
+    /// 実行履歴に記録するコマンド名
+    fn command_name(&self) -> &'static str {
+        match self {
+            CleanTarget::Rust { .. } => "clean rust",
+            CleanTarget::Node { .. } => "clean node",
+            CleanTarget::JsCache { .. } => "clean js-cache",
+            CleanTarget::NpmCache { .. } => "clean npm-cache",
+            CleanTarget::PipCache { .. } => "clean pip-cache",
+            CleanTarget::Docker { .. } => "clean docker",
+            CleanTarget::Flutter { .. } => "clean flutter",
+            CleanTarget::Android { .. } => "clean android",
+            CleanTarget::Cache { .. } => "clean cache",
+            CleanTarget::Python { .. } => "clean python",
+            CleanTarget::Go { .. } => "clean go",
+            CleanTarget::Gradle { .. } => "clean gradle",
+            CleanTarget::Haskell { .. } => "clean haskell",
+            CleanTarget::Dotnet { .. } => "clean dotnet",
+            CleanTarget::Cmake { .. } => "clean cmake",
+            CleanTarget::Swift { .. } => "clean swift",
+            CleanTarget::R { .. } => "clean r",
+            CleanTarget::Ml { .. } => "clean ml",
+            CleanTarget::BrowserCache { .. } => "clean browser-cache",
+            CleanTarget::Logs { .. } => "clean logs",
+            CleanTarget::Xcode { .. } => "clean xcode",
+            CleanTarget::Simulators { .. } => "clean simulators",
+            CleanTarget::Custom { .. } => "clean custom",
+            CleanTarget::Preset { .. } => "clean preset",
+            CleanTarget::Downloads { .. } => "clean downloads",
+            CleanTarget::LargeFiles { .. } => "clean large-files",
+        }
+    }
+
+    /// 検索開始ディレクトリ（検索ディレクトリを持たないクリーナーは None）
+    fn search_path(&self) -> Option<PathBuf> {
+        match self {
+            CleanTarget::Rust { path, .. }
+            | CleanTarget::Node { path, .. }
+            | CleanTarget::Flutter { path, .. }
+            | CleanTarget::Android { path, .. }
+            | CleanTarget::Python { path, .. }
+            | CleanTarget::Haskell { path, .. }
+            | CleanTarget::Dotnet { path, .. }
+            | CleanTarget::Cmake { path, .. }
+            | CleanTarget::Swift { path, .. }
+            | CleanTarget::R { path, .. }
+            | CleanTarget::Ml { path, .. }
+            | CleanTarget::Custom { path, .. }
+            | CleanTarget::Preset { path, .. }
+            | CleanTarget::LargeFiles { path, .. } => Some(path.clone()),
+            CleanTarget::Downloads { path, .. } => {
+                path.clone().or_else(kanri_core::downloads::default_downloads_dir)
+            }
+            _ => None,
+        }
+    }
+
+    /// 検索ディレクトリ内のファイルを直接削除するクリーナーか（large-files・downloads）
+    fn deletes_search_path_contents(&self) -> bool {
+        matches!(self, CleanTarget::LargeFiles { .. } | CleanTarget::Downloads { .. })
+    }
+
+    /// インタラクティブモードか（--quiet でも一覧を表示する必要がある）
+    fn is_interactive(&self) -> bool {
+        match self {
+            CleanTarget::Rust { interactive, .. }
+            | CleanTarget::Node { interactive, .. }
+            | CleanTarget::JsCache { interactive, .. }
+            | CleanTarget::NpmCache { interactive, .. }
+            | CleanTarget::PipCache { interactive, .. }
+            | CleanTarget::Docker { interactive, .. }
+            | CleanTarget::Flutter { interactive, .. }
+            | CleanTarget::Android { interactive, .. }
+            | CleanTarget::Cache { interactive, .. }
+            | CleanTarget::Python { interactive, .. }
+            | CleanTarget::Go { interactive, .. }
+            | CleanTarget::Gradle { interactive, .. }
+            | CleanTarget::Haskell { interactive, .. }
+            | CleanTarget::Dotnet { interactive, .. }
+            | CleanTarget::Cmake { interactive, .. }
+            | CleanTarget::Swift { interactive, .. }
+            | CleanTarget::R { interactive, .. }
+            | CleanTarget::Ml { interactive, .. }
+            | CleanTarget::BrowserCache { interactive, .. }
+            | CleanTarget::Logs { interactive, .. }
+            | CleanTarget::Xcode { interactive, .. }
+            | CleanTarget::Simulators { interactive, .. }
+            | CleanTarget::Custom { interactive, .. }
+            | CleanTarget::Preset { interactive, .. }
+            | CleanTarget::Downloads { interactive, .. }
+            | CleanTarget::LargeFiles { interactive, .. } => *interactive,
+        }
+    }
+
+    /// 削除を実行するモードか（--delete または --interactive）
+    fn is_delete(&self) -> bool {
+        match self {
+            CleanTarget::Rust { delete, interactive, .. }
+            | CleanTarget::Node { delete, interactive, .. }
+            | CleanTarget::JsCache { delete, interactive, .. }
+            | CleanTarget::NpmCache { delete, interactive, .. }
+            | CleanTarget::PipCache { delete, interactive, .. }
+            | CleanTarget::Docker { delete, interactive, .. }
+            | CleanTarget::Flutter { delete, interactive, .. }
+            | CleanTarget::Android { delete, interactive, .. }
+            | CleanTarget::Cache { delete, interactive, .. }
+            | CleanTarget::Python { delete, interactive, .. }
+            | CleanTarget::Go { delete, interactive, .. }
+            | CleanTarget::Gradle { delete, interactive, .. }
+            | CleanTarget::Haskell { delete, interactive, .. }
+            | CleanTarget::Dotnet { delete, interactive, .. }
+            | CleanTarget::Cmake { delete, interactive, .. }
+            | CleanTarget::Swift { delete, interactive, .. }
+            | CleanTarget::R { delete, interactive, .. }
+            | CleanTarget::Ml { delete, interactive, .. }
+            | CleanTarget::BrowserCache { delete, interactive, .. }
+            | CleanTarget::Logs { delete, interactive, .. }
+            | CleanTarget::Xcode { delete, interactive, .. }
+            | CleanTarget::Simulators { delete, interactive, .. }
+            | CleanTarget::Custom { delete, interactive, .. }
+            | CleanTarget::Preset { delete, interactive, .. }
+            | CleanTarget::Downloads { delete, interactive, .. }
+            | CleanTarget::LargeFiles { delete, interactive, .. } => *delete || *interactive,
+        }
+    }
+}
+
 #[derive(Subcommand)]
 enum ArchiveTarget {
     /// 大きなファイルをアーカイブ
@@ -354,6 +1396,71 @@ enum ArchiveTarget {
         /// Dry-run モード
         #[arg(long)]
         dry_run: bool,
+
+        /// アップロードの進捗（転送済みバイト数）をプログレスバーで表示
+        #[arg(long)]
+        progress: bool,
+
+        /// ディレクトリは b2 sync で並列アップロード（並列数は設定の b2.sync_threads）
+        #[arg(long)]
+        sync: bool,
+
+        /// ディレクトリ内のファイルを並列アップロードする数（設定の b2.upload_threads より優先）
+        #[arg(short, long)]
+        jobs: Option<u32>,
+
+        /// 1 ファイルをパートに分けてアップロードする並列数（設定の b2.file_threads より優先、1 で分割しない）
+        #[arg(long)]
+        threads: Option<u32>,
+
+        /// 転送帯域の上限（例: 10M。設定の storage.bwlimit より優先、rclone のみ対応）
+        #[arg(long)]
+        bwlimit: Option<String>,
+
+        /// アップロードから N 日後にリモートから自動削除する（B2 のライフサイクルルールを設定、B2 のみ対応）
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        expire_days: Option<u32>,
+
+        /// ディレクトリを 1 つの .tar にまとめてアップロード（小さなファイルが多い場合に高速・低コスト）
+        #[arg(long, conflicts_with = "sync")]
+        bundle: bool,
+
+        /// 同じアーカイブ元・アーカイブ先への前回のアーカイブから変更されたファイルのみアップロード
+        /// （変更のないファイルは前回のリモートパスを参照する）
+        #[arg(long)]
+        incremental: bool,
+
+        /// 他のアーカイブで同じ内容（SHA256）のファイルがアップロード済みなら、再アップロードせず参照する
+        /// （~/.kanri/blob_index.json で管理）
+        #[arg(long)]
+        dedup: bool,
+
+        /// 指定サイズ（例: 5G, 500M）を超えるファイルを <ファイル名>.part000, .part001, ... に分割してアップロード
+        /// （復元時に結合して SHA256 を検証する）
+        #[arg(long, value_parser = parse_split_size)]
+        split_size: Option<u64>,
+    },
+
+    /// アーカイブのチェックサムマニフェスト（B2 パス・SHA256・サイズ）を JSON で出力
+    Manifest {
+        /// アーカイブ ID
+        #[arg(long)]
+        id: String,
+    },
+
+    /// アーカイブを削除（インデックスとリモートのファイル）
+    Delete {
+        /// アーカイブ ID
+        #[arg(long)]
+        id: String,
+
+        /// リモートのファイルは残し、インデックスのエントリのみ削除
+        #[arg(long)]
+        keep_remote: bool,
+
+        /// Dry-run モード
+        #[arg(long)]
+        dry_run: bool,
     },
 }
 
@@ -379,125 +1486,486 @@ enum ConfigAction {
 
     /// B2 認証をテスト
     TestB2,
+
+    /// 設定・CLI ツール・認証情報をまとめて検証
+    Validate,
+
+    /// 設定ファイルをエディタ（$EDITOR）で開く
+    Edit,
+}
+
+/// 色付けを有効にするか
+///
+/// --no-color / NO_COLOR（空でない値）/ 人間向け以外の出力形式 / stdout が端末でない場合は無効
+fn color_enabled(cli: &Cli) -> bool {
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    !cli.no_color
+        && !no_color_env
+        && cli.output == OutputFormat::Human
+        && io::stdout().is_terminal()
 }
 
+/// ユーザーがキャンセルした（Ctrl-C・確認で拒否）場合の終了コード（SIGINT と同じ）
+const EXIT_CANCELLED: i32 = 130;
+
 fn main() -> Result<()> {
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+    if cli.json {
+        cli.output = OutputFormat::Json;
+    }
+    // 削除中の Ctrl-C は現在の項目を削除し終えた時点で止める。それ以外（2 回目を含む）はすぐに終了する
+    ctrlc::set_handler(|| {
+        if !kanri_core::utils::request_cancel() {
+            std::process::exit(EXIT_CANCELLED);
+        }
+    })?;
+    // 同じファイルへの同時実行が混ざらないよう、終了までロックを持ち続ける
+    let _output_file = match &cli.output_file {
+        Some(path) => Some(output::OutputFile::redirect_stdout(path, cli.append).map_err(|e| {
+            anyhow::anyhow!("出力ファイル {} を開けません: {}", path.display(), e)
+        })?),
+        None => None,
+    };
+    let format = cli.output;
+    let result = run(cli);
+    if let Err(e) = &result {
+        // キャンセルのメッセージは表示済みなので、終了コードだけで区別する
+        if matches!(e.downcast_ref(), Some(kanri_core::Error::Cancelled)) {
+            std::process::exit(EXIT_CANCELLED);
+        }
+        if format == OutputFormat::Json {
+            output::print_error_json(e)?;
+            std::process::exit(1);
+        }
+    }
+    result
+}
+
+fn run(cli: Cli) -> Result<()> {
+    if !color_enabled(&cli) {
+        colored::control::set_override(false);
+    }
+    kanri_core::utils::set_follow_symlinks(cli.follow_symlinks);
+    kanri_core::utils::set_max_depth(cli.depth);
+    kanri_core::utils::set_skip_hidden(cli.no_hidden);
+    // 設定ファイルが壊れていても各コマンド側でエラーを出すので、ここでは無視する
+    if let Ok(config) = kanri_core::config::Config::load() {
+        if let Some(timeout) = config.command_timeout() {
+            kanri_core::utils::set_command_timeout(timeout);
+        }
+        if let Some(timeout) = config.list_timeout() {
+            kanri_core::utils::set_list_timeout(timeout);
+        }
+        kanri_core::utils::set_protected_paths(config.protected_paths());
+    }
+    let list = ListOptions {
+        sort: cli.sort.into(),
+        top: cli.top,
+        all_items: cli.all_items,
+    };
 
     match cli.command {
-        Commands::Clean { target } => match target {
-            CleanTarget::Rust {
-                path,
-                search,
-                delete,
-                interactive,
-            } => clean_rust(&path, search, delete, interactive)?,
-            CleanTarget::Node {
-                path,
-                search,
-                delete,
-                interactive,
-            } => clean_node(&path, search, delete, interactive)?,
-            CleanTarget::Docker {
-                search,
-                delete,
-                interactive,
-                all,
-                volumes,
-            } => clean_docker(search, delete, interactive, all, volumes)?,
-            CleanTarget::Flutter {
-                path,
-                search,
-                delete,
-                interactive,
-            } => clean_flutter(&path, search, delete, interactive)?,
-            CleanTarget::Cache {
-                search,
-                delete,
-                interactive,
-                min_size,
-                safe_only,
-            } => clean_cache(search, delete, interactive, min_size, safe_only)?,
-            CleanTarget::Python {
-                path,
-                search,
-                delete,
-                interactive,
-            } => {
-                let cleaner = kanri_core::python::PythonCleaner::new(path);
-                clean_generic(&cleaner, "package.json", search, delete, interactive)?
-            }
-            CleanTarget::Go {
-                search,
-                delete,
-                interactive,
-            } => {
-                let cleaner = kanri_core::go::GoCleaner::new();
-                clean_generic(&cleaner, "Go module cache", search, delete, interactive)?
-            }
-            CleanTarget::Gradle {
-                search,
-                delete,
-                interactive,
-            } => {
-                let cleaner = kanri_core::gradle::GradleCleaner::new();
-                clean_generic(&cleaner, "Gradle cache", search, delete, interactive)?
-            }
-            CleanTarget::Haskell {
-                path,
-                search,
-                delete,
-                interactive,
-            } => {
-                let cleaner = kanri_core::haskell::HaskellCleaner::new(path);
-                clean_generic(&cleaner, "*.cabal or stack.yaml", search, delete, interactive)?
+        Commands::Clean {
+            target,
+            safe_only,
+            include_unsafe,
+            dry_run,
+            prune_empty_dirs,
+            stats_only,
+            report,
+            protect,
+            allow_critical_targets,
+            report_before_after,
+            thin_snapshots,
+        } => {
+            if stats_only && target.is_delete() {
+                anyhow::bail!("--stats-only は --delete / --interactive と同時に指定できません");
             }
-            CleanTarget::Xcode {
-                search,
-                delete,
-                interactive,
-            } => {
-                let cleaner = kanri_core::xcode::XcodeCleaner::new();
-                clean_generic(&cleaner, "DerivedData", search, delete, interactive)?
+            kanri_core::utils::set_allow_critical_targets(allow_critical_targets);
+            // node_modules などを探すクリーナーは見つけた項目だけを削除し、項目ごとに確認される。
+            // 検索ディレクトリ内のファイルを直接削除するクリーナーだけ、検索ディレクトリ自体も確認する
+            if target.is_delete() && target.deletes_search_path_contents() {
+                if let Some(path) = target.search_path() {
+                    kanri_core::utils::assert_safe_target(&path)?;
+                }
             }
-            CleanTarget::LargeFiles {
-                path,
-                min_size_gb,
-                extensions,
-                files_only,
-                dirs_only,
-                search,
-                delete,
-                interactive,
-            } => {
-                let min_size = min_size_gb * 1024 * 1024 * 1024; // GB to bytes
-                let ext_vec = extensions.map(|s| {
-                    s.split(',')
-                        .map(|e| e.trim().to_string())
-                        .collect::<Vec<_>>()
-                });
+            // 保護するパスを読めないまま削除しないよう、設定ファイルのエラーはここで止める
+            let mut protected = kanri_core::config::Config::load()?.protected_paths();
+            protected.extend(protect);
+            kanri_core::utils::set_protected_paths(protected);
+            let prune_root = if prune_empty_dirs {
+                let root = target.search_path();
+                if root.is_none() {
+                    eprintln!(
+                        "{}",
+                        "⚠ このクリーナーは検索ディレクトリを持たないため --prune-empty-dirs は無視されます"
+                            .yellow()
+                    );
+                }
+                root
+            } else {
+                None
+            };
+            let opts = CleanOptions {
+                safe_only,
+                include_unsafe,
+                dry_run,
+                history: !cli.no_history,
+                command: target.command_name(),
+                list,
+                always_confirm: false,
+                prune_root: prune_root.as_deref(),
+            };
+            let command = target.command_name();
+            let mut reporter = Reporter::new(cli.output)
+                .with_quiet(cli.quiet && !target.is_interactive())
+                .with_stats_only(stats_only)
+                .with_report_before_after(report_before_after || thin_snapshots)
+                .with_thin_snapshots(thin_snapshots);
+            match target {
+                CleanTarget::Rust {
+                    path,
+                    search,
+                    delete,
+                    interactive,
+                    min_size,
+                    stale,
+                    profile_dir,
+                } => clean_rust(
+                    &path,
+                    search,
+                    delete,
+                    interactive,
+                    min_size,
+                    stale,
+                    &profile_dir,
+                    opts,
+                    &mut reporter,
+                )?,
+                CleanTarget::Node {
+                    path,
+                    search,
+                    delete,
+                    interactive,
+                    min_size,
+                } => clean_node(&path, search, delete, interactive, min_size, opts, &mut reporter)?,
+                CleanTarget::JsCache {
+                    search,
+                    delete,
+                    interactive,
+                } => {
+                    let cleaner = kanri_core::js_cache::JsCacheCleaner::new();
+                    clean_generic(&cleaner, "pnpm/Bun/Deno cache", search, delete, interactive, opts, &mut reporter)?
+                }
+                CleanTarget::NpmCache {
+                    search,
+                    delete,
+                    interactive,
+                    use_tool,
+                } => {
+                    let cleaner = kanri_core::npm_cache::NpmCacheCleaner::new().with_use_tool(use_tool);
+                    clean_generic(&cleaner, "npm/yarn cache", search, delete, interactive, opts, &mut reporter)?
+                }
+                CleanTarget::PipCache {
+                    search,
+                    delete,
+                    interactive,
+                    use_tool,
+                } => {
+                    let cleaner = kanri_core::pip_cache::PipCacheCleaner::new().with_use_tool(use_tool);
+                    clean_generic(&cleaner, "pip cache", search, delete, interactive, opts, &mut reporter)?
+                }
+                CleanTarget::Docker {
+                    search,
+                    delete,
+                    interactive,
+                    all,
+                    volumes,
+                    dangling,
+                    until,
+                } => clean_docker(
+                    search,
+                    delete,
+                    interactive,
+                    all,
+                    volumes,
+                    dangling,
+                    until.as_deref(),
+                    opts,
+                    &mut reporter,
+                )?,
+                CleanTarget::Flutter {
+                    path,
+                    search,
+                    delete,
+                    interactive,
+                } => clean_flutter(&path, search, delete, interactive, opts, &mut reporter)?,
+                CleanTarget::Android {
+                    path,
+                    search,
+                    delete,
+                    interactive,
+                } => clean_android(&path, search, delete, interactive, opts, &mut reporter)?,
+                CleanTarget::Cache {
+                    search,
+                    delete,
+                    interactive,
+                    min_size,
+                    keep_newer_than,
+                    by_app,
+                } => clean_cache(
+                    search,
+                    delete,
+                    interactive,
+                    min_size,
+                    keep_newer_than,
+                    by_app,
+                    opts,
+                    &mut reporter,
+                )?,
+                CleanTarget::Python {
+                    path,
+                    search,
+                    delete,
+                    interactive,
+                } => {
+                    let cleaner = kanri_core::python::PythonCleaner::new(path);
+                    clean_generic(&cleaner, "package.json", search, delete, interactive, opts, &mut reporter)?
+                }
+                CleanTarget::Go {
+                    search,
+                    delete,
+                    interactive,
+                    detailed,
+                } => {
+                    let cleaner = kanri_core::go::GoCleaner::new().with_detailed(detailed);
+                    clean_generic(&cleaner, "Go module cache", search, delete, interactive, opts, &mut reporter)?
+                }
+                CleanTarget::Gradle {
+                    search,
+                    delete,
+                    interactive,
+                    detailed,
+                    caches_only,
+                    daemon,
+                } => {
+                    let cleaner = kanri_core::gradle::GradleCleaner::new()
+                        .with_caches_only(caches_only)
+                        .with_stop_daemons(daemon)
+                        .with_detailed(detailed);
+                    if daemon {
+                        let daemons = kanri_core::gradle::gradle_user_home()
+                            .map(|home| kanri_core::gradle::find_running_daemons(&home))
+                            .unwrap_or_default();
+                        if !daemons.is_empty() {
+                            reporter.note(&format!(
+                                "ℹ 実行中の Gradle デーモン {} 件は削除前に停止します",
+                                daemons.len()
+                            ));
+                        }
+                    }
+                    clean_generic(&cleaner, "Gradle cache", search, delete, interactive, opts, &mut reporter)?
+                }
+                CleanTarget::Haskell {
+                    path,
+                    search,
+                    delete,
+                    interactive,
+                } => {
+                    let cleaner = kanri_core::haskell::HaskellCleaner::new(path);
+                    clean_generic(&cleaner, "*.cabal or stack.yaml", search, delete, interactive, opts, &mut reporter)?
+                }
+                CleanTarget::Dotnet {
+                    path,
+                    global,
+                    search,
+                    delete,
+                    interactive,
+                } => {
+                    let cleaner = kanri_core::dotnet::DotnetCleaner::new(path).with_global(global);
+                    clean_generic(&cleaner, "*.csproj or *.sln", search, delete, interactive, opts, &mut reporter)?
+                }
+                CleanTarget::Cmake {
+                    path,
+                    build_dir_pattern,
+                    search,
+                    delete,
+                    interactive,
+                } => {
+                    let cleaner = kanri_core::cmake::CMakeCleaner::new(path)
+                        .with_build_dir_patterns(build_dir_pattern);
+                    clean_generic(&cleaner, "CMakeLists.txt", search, delete, interactive, opts, &mut reporter)?
+                }
+                CleanTarget::Swift {
+                    path,
+                    global,
+                    search,
+                    delete,
+                    interactive,
+                } => {
+                    let cleaner = kanri_core::swift::SwiftCleaner::new(path).with_global(global);
+                    clean_generic(&cleaner, "Package.swift", search, delete, interactive, opts, &mut reporter)?
+                }
+                CleanTarget::R {
+                    path,
+                    search,
+                    delete,
+                    interactive,
+                } => {
+                    let cleaner = kanri_core::r::RCleaner::new(path);
+                    clean_generic(&cleaner, "renv.lock", search, delete, interactive, opts, &mut reporter)?
+                }
+                CleanTarget::Ml {
+                    path,
+                    name,
+                    search,
+                    delete,
+                    interactive,
+                } => {
+                    let cleaner = kanri_core::ml::MlCleaner::new(path).with_names(name);
+                    clean_generic(&cleaner, "ML 生成物", search, delete, interactive, opts, &mut reporter)?
+                }
+                CleanTarget::BrowserCache {
+                    search,
+                    delete,
+                    interactive,
+                } => {
+                    let cleaner = kanri_core::browser_cache::BrowserCacheCleaner::new();
+                    clean_generic(&cleaner, "ブラウザキャッシュ", search, delete, interactive, opts, &mut reporter)?
+                }
+                CleanTarget::Logs {
+                    search,
+                    delete,
+                    interactive,
+                } => {
+                    let cleaner = kanri_core::logs::LogsCleaner::new();
+                    clean_generic(&cleaner, "ログ", search, delete, interactive, opts, &mut reporter)?
+                }
+                CleanTarget::Xcode {
+                    search,
+                    delete,
+                    interactive,
+                    device_support_keep,
+                } => {
+                    let cleaner =
+                        kanri_core::xcode::XcodeCleaner::new().with_device_support_keep(device_support_keep);
+                    clean_generic(&cleaner, "Xcode の削除可能なデータ", search, delete, interactive, opts, &mut reporter)?
+                }
+                CleanTarget::Custom {
+                    path,
+                    names,
+                    search,
+                    delete,
+                    interactive,
+                } => {
+                    let target = names.join(", ");
+                    let cleaner = kanri_core::custom::CustomCleaner::new(path, names);
+                    clean_generic(&cleaner, &target, search, delete, interactive, opts, &mut reporter)?
+                }
+                CleanTarget::Preset {
+                    name,
+                    path,
+                    search,
+                    delete,
+                    interactive,
+                } => {
+                    let names = resolve_clean_preset(&name)?;
+                    reporter.note(&format!("プリセット {}: {}", name, names.join(", ")));
+                    let cleaner = kanri_core::custom::CustomCleaner::new(path, names);
+                    clean_generic(&cleaner, &name, search, delete, interactive, opts, &mut reporter)?
+                }
+                CleanTarget::Simulators {
+                    search,
+                    delete,
+                    interactive,
+                } => clean_simulators(search, delete, interactive, opts, &mut reporter)?,
+                CleanTarget::LargeFiles {
+                    path,
+                    min_size_gb,
+                    extensions,
+                    files_only,
+                    dirs_only,
+                    search,
+                    delete,
+                    interactive,
+                } => {
+                    let min_size = min_size_gb * 1024 * 1024 * 1024; // GB to bytes
+                    let ext_vec = extensions.map(|s| {
+                        s.split(',')
+                            .map(|e| e.trim().to_string())
+                            .collect::<Vec<_>>()
+                    });
+
+                    // files_only と dirs_only が両方指定された場合はエラー
+                    let (include_files, include_dirs) = match (files_only, dirs_only) {
+                        (true, true) => {
+                            eprintln!("Error: --files-only and --dirs-only cannot be used together");
+                            std::process::exit(1);
+                        }
+                        (true, false) => (true, false),
+                        (false, true) => (false, true),
+                        (false, false) => (true, true),
+                    };
 
-                // files_only と dirs_only が両方指定された場合はエラー
-                let (include_files, include_dirs) = match (files_only, dirs_only) {
-                    (true, true) => {
-                        eprintln!("Error: --files-only and --dirs-only cannot be used together");
-                        std::process::exit(1);
+                    let mut cleaner = kanri_core::large_files::LargeFilesCleaner::new(path, min_size);
+                    if let Some(exts) = ext_vec {
+                        cleaner = cleaner.with_extensions(exts);
                     }
-                    (true, false) => (true, false),
-                    (false, true) => (false, true),
-                    (false, false) => (true, true),
-                };
+                    cleaner = cleaner.with_include_dirs(include_dirs);
+                    cleaner = cleaner.with_include_files(include_files);
 
-                let mut cleaner = kanri_core::large_files::LargeFilesCleaner::new(path, min_size);
-                if let Some(exts) = ext_vec {
-                    cleaner = cleaner.with_extensions(exts);
+                    clean_generic(&cleaner, "large items", search, delete, interactive, opts, &mut reporter)?
+                }
+                CleanTarget::Downloads {
+                    path,
+                    older_than,
+                    min_size,
+                    extensions,
+                    search,
+                    delete,
+                    interactive,
+                } => {
+                    let Some(path) = path.or_else(kanri_core::downloads::default_downloads_dir) else {
+                        anyhow::bail!("ダウンロードフォルダが見つかりません（--path で指定してください）");
+                    };
+                    let mut cleaner = kanri_core::downloads::DownloadsCleaner::new(path)
+                        .with_older_than(older_than)
+                        .with_min_size((min_size * 1024.0 * 1024.0) as u64);
+                    if let Some(extensions) = extensions {
+                        cleaner = cleaner
+                            .with_extensions(extensions.split(',').map(|e| e.trim().to_string()).collect());
+                    }
+                    // ユーザーのファイルなので --delete でも確認なしには削除しない
+                    let opts = CleanOptions {
+                        always_confirm: true,
+                        ..opts
+                    };
+                    clean_generic(&cleaner, "古いダウンロード", search, delete, interactive, opts, &mut reporter)?
                 }
-                cleaner = cleaner.with_include_dirs(include_dirs);
-                cleaner = cleaner.with_include_files(include_files);
-
-                clean_generic(&cleaner, "large items", search, delete, interactive)?
             }
-        },
+            if stats_only {
+                reporter.stats(command);
+                if let Some(report) = report {
+                    let (count, total_size) = reporter.found_summary();
+                    let mut stats = kanri_core::stats::StatsReport::load(&report)?;
+                    stats.merge(kanri_core::stats::StatsEntry::new(command.to_string(), count, total_size));
+                    stats.save(&report)?;
+                    if cli.output == OutputFormat::Human {
+                        println!(
+                            "{} {}",
+                            "📝".cyan(),
+                            format!("サイズレポートに追記しました: {}", report.display()).dimmed()
+                        );
+                    }
+                }
+            }
+            // 確認で拒否された・Ctrl-C で中断された場合も、結果（JSON など）は出力してから終了コードで区別する
+            let cancelled = reporter.is_cancelled() || kanri_core::utils::is_cancel_requested();
+            reporter.finish()?;
+            if cancelled {
+                return Err(kanri_core::Error::Cancelled.into());
+            }
+        }
         Commands::Archive { target } => match target {
             ArchiveTarget::LargeFiles {
                 path,
@@ -508,6 +1976,16 @@ fn main() -> Result<()> {
                 to,
                 delete_after,
                 dry_run,
+                progress,
+                sync,
+                jobs,
+                threads,
+                bwlimit,
+                expire_days,
+                bundle,
+                incremental,
+                dedup,
+                split_size,
             } => {
                 archive_large_files(
                     path,
@@ -518,845 +1996,822 @@ fn main() -> Result<()> {
                     to,
                     delete_after,
                     dry_run,
+                    progress,
+                    sync,
+                    jobs,
+                    threads,
+                    bwlimit,
+                    expire_days,
+                    bundle,
+                    incremental,
+                    dedup,
+                    split_size,
+                    !cli.no_history,
+                    cli.profile.as_deref(),
                 )?
             }
+            ArchiveTarget::Manifest { id } => export_archive_manifest(&id)?,
+            ArchiveTarget::Delete {
+                id,
+                keep_remote,
+                dry_run,
+            } => delete_archive(&id, keep_remote, dry_run, cli.profile.as_deref())?,
         },
         Commands::Restore {
             from,
+            manifest,
             to,
             mode,
+            dest_structure,
+            overwrite,
             version,
+            since,
+            until,
             dry_run,
-        } => restore_archive(&from, &to, mode, version.as_deref(), dry_run)?,
-        Commands::ListArchives => list_archives()?,
+            list_versions,
+            jobs,
+            verify,
+            no_verify,
+            retries,
+            bwlimit,
+        } => {
+            if let Some(manifest) = manifest {
+                restore_from_manifest(
+                    &manifest,
+                    &to,
+                    dest_structure,
+                    overwrite.into(),
+                    dry_run,
+                    jobs,
+                    !no_verify,
+                    retries,
+                    bwlimit,
+                    cli.profile.as_deref(),
+                )?
+            } else if list_versions {
+                // --manifest がなければ --from は必須（clap で検証済み）
+                let from = from.unwrap_or_default();
+                list_restore_versions(&from, cli.profile.as_deref())?
+            } else {
+                let from = from.unwrap_or_default();
+                let range = match mode {
+                    RestoreMode::Range if since.is_none() && until.is_none() => {
+                        anyhow::bail!("--mode range には --since / --until の少なくとも一方を指定してください")
+                    }
+                    RestoreMode::Range => kanri_core::archive::versioning::TimestampRange { since, until },
+                    _ if since.is_some() || until.is_some() => {
+                        anyhow::bail!("--since / --until は --mode range と併用してください")
+                    }
+                    _ => Default::default(),
+                };
+                // 明示指定がなければインデックスの記録有無で決める
+                let verify = if no_verify { Some(false) } else if verify { Some(true) } else { None };
+                restore_archive(
+                    &from,
+                    &to,
+                    mode,
+                    dest_structure,
+                    overwrite.into(),
+                    version.as_deref(),
+                    &range,
+                    dry_run,
+                    jobs,
+                    verify,
+                    retries,
+                    bwlimit,
+                    cli.profile.as_deref(),
+                )?
+            }
+        }
+        Commands::ListArchives {
+            remote,
+            from,
+            rebuild_index,
+        } => {
+            if remote {
+                list_remote_archives(
+                    from.as_deref().unwrap_or(""),
+                    rebuild_index,
+                    cli.profile.as_deref(),
+                )?
+            } else {
+                list_archives()?
+            }
+        }
         Commands::Config { action } => match action {
-            ConfigAction::Show => show_config()?,
+            ConfigAction::Show => show_config(cli.profile.as_deref())?,
             ConfigAction::InitB2 {
                 bucket,
                 key_id,
                 key,
-            } => init_b2_config(bucket, key_id, key)?,
-            ConfigAction::TestB2 => test_b2_auth()?,
+            } => init_b2_config(bucket, key_id, key, cli.profile.as_deref())?,
+            ConfigAction::TestB2 => test_b2_auth(cli.profile.as_deref())?,
+            ConfigAction::Validate => validate_config(cli.profile.as_deref())?,
+            ConfigAction::Edit => edit_config()?,
         },
-        Commands::Completions { shell } => {
-            generate_completions(shell)?;
+        Commands::History { limit, format } => {
+            let format = if cli.output == OutputFormat::Json {
+                ReportFormat::Json
+            } else {
+                format
+            };
+            show_history(limit, format)?
+        }
+        Commands::Completions { shell, install } => {
+            if install {
+                install_completions(shell)?;
+            } else {
+                generate_completions(shell)?;
+            }
         }
+        Commands::Doctor => run_doctor(cli.output == OutputFormat::Json, cli.profile.as_deref())?,
         Commands::Diagnose {
-            json,
+            format,
             threshold,
             path,
+            fail_over,
+            fail_category,
+            trend,
+            trend_runs,
+            watch,
+            interval,
         } => {
-            run_diagnostics(&path, json, threshold)?;
+            if watch {
+                watch_diagnostics(&path, threshold, list, interval)?;
+            } else {
+                let opts = DiagnoseOptions {
+                    format: match format {
+                        Some(format) => format,
+                        None if cli.output == OutputFormat::Json => ReportFormat::Json,
+                        None => ReportFormat::Human,
+                    },
+                    threshold,
+                    fail_over,
+                    fail_categories: fail_category,
+                    trend: trend.then_some(trend_runs),
+                    record: !cli.no_history,
+                };
+                run_diagnostics(&path, &opts, list)?;
+            }
         }
     }
 
     Ok(())
 }
 
-fn clean_rust(search_path: &PathBuf, search: bool, delete: bool, interactive: bool) -> Result<()> {
-    println!("{}", "🦀 Rust プロジェクトをスキャン中...".cyan().bold());
-
-    let spinner = ProgressBar::new_spinner();
-    spinner.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.cyan} {msg}")
-            .unwrap(),
-    );
-    spinner.set_message("Cargo.toml を検索中...");
-    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
-
-    let projects = kanri_core::rust::find_rust_projects(search_path)?;
-    spinner.finish_and_clear();
-
-    if projects.is_empty() {
-        println!("{}", "✨ target ディレクトリが見つかりませんでした".green());
-        return Ok(());
+#[allow(clippy::too_many_arguments)]
+fn clean_rust(
+    search_path: &Path,
+    search: bool,
+    delete: bool,
+    interactive: bool,
+    min_size_gb: f64,
+    stale_days: Option<u64>,
+    profiles: &[String],
+    opts: CleanOptions,
+    reporter: &mut Reporter,
+) -> Result<()> {
+    let cleaner = kanri_core::rust::RustCleaner::new(search_path.to_path_buf())
+        .with_min_size((min_size_gb * 1024.0 * 1024.0 * 1024.0) as u64)
+        .with_stale_days(stale_days)
+        .with_profiles(profiles.to_vec());
+
+    let mut notes = Vec::new();
+    if let Some(days) = stale_days {
+        notes.push(format!("{} 日以上更新されていない成果物のみ対象にします", days));
+    }
+    if !profiles.is_empty() {
+        notes.push(format!("target 内の {} のみ対象にします", profiles.join(", ")));
     }
 
-    let total_size: u64 = projects.iter().map(|p| p.size).sum();
+    // --profile-dir では項目がプロファイルの出力ディレクトリごとになる
+    let (found, cleaned) = if profiles.is_empty() {
+        ("Rust プロジェクト", "プロジェクト")
+    } else {
+        ("プロファイルの出力ディレクトリ", "ディレクトリ")
+    };
+    let labels = CleanLabels {
+        scanning: Some("🦀 Rust プロジェクトをスキャン中..."),
+        notes,
+        not_found: Some("target ディレクトリが見つかりませんでした"),
+        found,
+        cleaned,
+        ..CleanLabels::new("Cargo.toml")
+    };
+    clean_generic_with(&cleaner, &labels, search, delete, interactive, opts, reporter)
+}
 
-    println!(
-        "\n{} 件の Rust プロジェクトを発見 (合計: {})\n",
-        projects.len().to_string().yellow().bold(),
-        kanri_core::utils::format_size(total_size).yellow().bold()
-    );
+fn clean_node(
+    search_path: &Path,
+    search: bool,
+    delete: bool,
+    interactive: bool,
+    min_size_gb: f64,
+    opts: CleanOptions,
+    reporter: &mut Reporter,
+) -> Result<()> {
+    let cleaner = kanri_core::node::NodeCleaner::new(search_path.to_path_buf())
+        .with_min_size((min_size_gb * 1024.0 * 1024.0 * 1024.0) as u64);
+    let labels = CleanLabels {
+        scanning: Some("📦 Node.js プロジェクトをスキャン中..."),
+        notes: Vec::new(),
+        not_found: Some("node_modules ディレクトリが見つかりませんでした"),
+        found: "Node.js プロジェクト",
+        cleaned: "プロジェクト",
+        ..CleanLabels::new("package.json")
+    };
+    clean_generic_with(&cleaner, &labels, search, delete, interactive, opts, reporter)
+}
 
-    // プロジェクト一覧を表示
-    for (i, project) in projects.iter().enumerate() {
-        println!(
-            "  {}. {} - {}",
-            (i + 1).to_string().dimmed(),
-            project.root.display().to_string().bright_blue(),
-            project.formatted_size().yellow()
-        );
-    }
+#[allow(clippy::too_many_arguments)]
+fn clean_docker(
+    search: bool,
+    delete: bool,
+    interactive: bool,
+    all: bool,
+    volumes: bool,
+    dangling: bool,
+    until: Option<&str>,
+    opts: CleanOptions,
+    reporter: &mut Reporter,
+) -> Result<()> {
+    reporter.scanning("🐳 Docker システムをチェック中...");
 
-    // 検索モード（デフォルトまたは --search）
-    if search || (!delete && !interactive) {
-        println!(
-            "\n{} {}",
-            "ℹ".cyan(),
-            "検索モード: 削除対象を表示しています".dimmed()
-        );
-        println!(
-            "{} {}",
-            "💡".cyan(),
-            "削除するには --delete (-d) を指定してください".dimmed()
-        );
-        println!(
-            "{} {}",
-            "💡".cyan(),
-            "確認しながら削除するには --interactive (-i) を指定してください".dimmed()
-        );
+    // Docker がインストールされているかチェック
+    if !kanri_core::docker::is_docker_installed() {
+        reporter.warn("❌ Docker がインストールされていません");
         return Ok(());
     }
 
-    // インタラクティブモード
-    if interactive {
-        print!(
-            "\n{} 本当に削除しますか? (y/N): ",
-            "⚠".yellow().bold()
-        );
-        io::stdout().flush()?;
-
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-
-        if !input.trim().eq_ignore_ascii_case("y") {
-            println!("{}", "キャンセルされました".yellow());
-            return Ok(());
-        }
+    // Docker デーモンが起動しているかチェック
+    if !kanri_core::docker::is_docker_running() {
+        reporter.warn("❌ Docker デーモンが起動していません");
+        reporter.note("💡 Docker Desktop を起動してください");
+        return Ok(());
     }
 
-    // 実行モード
-    println!("\n{}", "🗑️  削除中...".red().bold());
+    let spinner = reporter.spinner("Docker システム情報を取得中...");
+    let info = kanri_core::docker::get_system_info()?;
+    spinner.finish_and_clear();
 
-    let pb = ProgressBar::new(projects.len() as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("{spinner:.green} [{bar:40.cyan/blue}] {pos}/{len} {msg}")
-            .unwrap()
-            .progress_chars("#>-"),
+    let reclaimable_bytes = info.reclaimable_bytes().unwrap_or(0);
+    reporter.found(1, "Docker の削除可能なデータ", reclaimable_bytes);
+    reporter.item(
+        1,
+        &format!("docker system ({})", info.reclaimable),
+        Path::new("docker"),
+        reclaimable_bytes,
+        None,
+        None,
     );
 
-    let cleaned = kanri_core::rust::clean_projects(&projects)?;
-
-    for project in &cleaned {
-        pb.inc(1);
-        pb.set_message(format!("{}", project.display()));
+    let mut prune_options = Vec::new();
+    if all {
+        prune_options.push("--all (未使用イメージもすべて削除)");
     }
-
-    pb.finish_and_clear();
-
-    println!(
-        "\n{} {} 件のプロジェクトをクリーンしました ({}削除)",
-        "✅".green(),
-        cleaned.len().to_string().green().bold(),
-        kanri_core::utils::format_size(total_size).green().bold()
-    );
-
-    Ok(())
-}
-
-fn clean_node(search_path: &PathBuf, search: bool, delete: bool, interactive: bool) -> Result<()> {
-    println!("{}", "📦 Node.js プロジェクトをスキャン中...".cyan().bold());
-
-    let spinner = ProgressBar::new_spinner();
-    spinner.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.cyan} {msg}")
-            .unwrap(),
-    );
-    spinner.set_message("package.json を検索中...");
-    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
-
-    let projects = kanri_core::node::find_node_projects(search_path)?;
-    spinner.finish_and_clear();
-
-    if projects.is_empty() {
-        println!("{}", "✨ node_modules ディレクトリが見つかりませんでした".green());
-        return Ok(());
+    if volumes {
+        prune_options.push("--volumes (ボリュームも削除)");
+    }
+    if dangling {
+        prune_options.push("--dangling (<none> イメージのみ削除)");
+    }
+    let until_option = until.map(|until| format!("--until {} (それより前に作成されたもののみ削除)", until));
+    if let Some(until_option) = &until_option {
+        prune_options.push(until_option);
     }
 
-    let total_size: u64 = projects.iter().map(|p| p.size).sum();
-
-    println!(
-        "\n{} 件の Node.js プロジェクトを発見 (合計: {})\n",
-        projects.len().to_string().yellow().bold(),
-        kanri_core::utils::format_size(total_size).yellow().bold()
-    );
-
-    // プロジェクト一覧を表示
-    for (i, project) in projects.iter().enumerate() {
-        println!(
-            "  {}. {} - {}",
-            (i + 1).to_string().dimmed(),
-            project.root.display().to_string().bright_blue(),
-            project.formatted_size().yellow()
-        );
+    if !prune_options.is_empty() {
+        reporter.note(&format!("\n⚙ オプション: {}", prune_options.join(", ")));
     }
 
     // 検索モード（デフォルトまたは --search）
-    if search || (!delete && !interactive) {
-        println!(
-            "\n{} {}",
-            "ℹ".cyan(),
-            "検索モード: 削除対象を表示しています".dimmed()
-        );
-        println!(
-            "{} {}",
-            "💡".cyan(),
-            "削除するには --delete (-d) を指定してください".dimmed()
-        );
-        println!(
-            "{} {}",
-            "💡".cyan(),
-            "確認しながら削除するには --interactive (-i) を指定してください".dimmed()
-        );
+    if !opts.dry_run && (search || (!delete && !interactive)) {
+        reporter.search_mode(&[]);
         return Ok(());
     }
 
     // インタラクティブモード
-    if interactive {
-        print!(
-            "\n{} 本当に削除しますか? (y/N): ",
-            "⚠".yellow().bold()
-        );
-        io::stdout().flush()?;
+    let label = if dangling {
+        "docker image prune (dangling)".to_string()
+    } else {
+        format!("docker system prune ({})", info.reclaimable)
+    };
+    if interactive && !reporter.confirm_deletion(&[(label, reclaimable_bytes)])? {
+        return Ok(());
+    }
 
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+    let until_filter = until
+        .map(|until| format!(" --filter until={}", until))
+        .unwrap_or_default();
 
-        if !input.trim().eq_ignore_ascii_case("y") {
-            println!("{}", "キャンセルされました".yellow());
-            return Ok(());
+    if opts.dry_run && dangling {
+        reporter.dry_run(&[(format!("docker image prune -f{}", until_filter), 0)]);
+        return Ok(());
+    }
+
+    if opts.dry_run {
+        let mut command = "docker system prune -f".to_string();
+        if all {
+            command.push_str(" --all");
+        }
+        if volumes {
+            command.push_str(" --volumes");
         }
+        command.push_str(&until_filter);
+        // --until では一部しか削除しないため、削除可能なデータ全体のサイズは表示しない
+        let size = if until.is_some() { 0 } else { reclaimable_bytes };
+        reporter.dry_run(&[(command, size)]);
+        return Ok(());
     }
 
-    // 実行モード
-    println!("\n{}", "🗑️  削除中...".red().bold());
+    // 実行モード（--dangling）
+    if dangling {
+        reporter.scanning("🗑️  dangling イメージを削除中...");
 
-    let pb = ProgressBar::new(projects.len() as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("{spinner:.green} [{bar:40.cyan/blue}] {pos}/{len} {msg}")
-            .unwrap()
-            .progress_chars("#>-"),
-    );
+        let spinner = reporter.spinner("docker image prune を実行中...");
+        let output = kanri_core::docker::prune_dangling_images(until)?;
+        spinner.finish_and_clear();
+
+        let reclaimed = kanri_core::docker::parse_reclaimed_space(&output).unwrap_or(0);
+        reporter.cleaned(1, "Docker イメージ", reclaimed);
+        reporter.note(&format!("\n{}", output));
 
-    let cleaned = kanri_core::node::clean_projects(&projects)?;
+        record_history(opts.history, opts.command, Vec::new(), reclaimed);
 
-    for project in &cleaned {
-        pb.inc(1);
-        pb.set_message(format!("{}", project.display()));
+        return Ok(());
     }
 
-    pb.finish_and_clear();
+    // 実行モード
+    reporter.scanning("🗑️  Docker システムをクリーンアップ中...");
 
-    println!(
-        "\n{} {} 件のプロジェクトをクリーンしました ({}削除)",
-        "✅".green(),
-        cleaned.len().to_string().green().bold(),
-        kanri_core::utils::format_size(total_size).green().bold()
-    );
+    let spinner = reporter.spinner("docker system prune を実行中...");
+    let output = kanri_core::docker::clean_system(all, volumes, until)?;
+    spinner.finish_and_clear();
+
+    // --until では一部しか削除しないため、docker が報告した解放サイズを使う
+    let reclaimed = match until {
+        Some(_) => kanri_core::docker::parse_reclaimed_space(&output).unwrap_or(0),
+        None => reclaimable_bytes,
+    };
+    reporter.cleaned(1, "Docker システム", reclaimed);
+    reporter.note(&format!("\n{}", output));
+
+    record_history(opts.history, opts.command, Vec::new(), reclaimed);
 
     Ok(())
 }
 
-fn clean_docker(search: bool, delete: bool, interactive: bool, all: bool, volumes: bool) -> Result<()> {
-    println!("{}", "🐳 Docker システムをチェック中...".cyan().bold());
+/// 設定からプリセットのディレクトリ名を取得（見つからなければ利用可能なプリセットを表示）
+fn resolve_clean_preset(name: &str) -> Result<Vec<String>> {
+    let config = kanri_core::config::Config::load()?;
 
-    // Docker がインストールされているかチェック
-    if !kanri_core::docker::is_docker_installed() {
-        println!("{}", "❌ Docker がインストールされていません".red());
-        return Ok(());
+    // 利用可能なプリセットの一覧はエラーに含まれるので、ここでは定義方法だけを案内する
+    if config.preset_names().is_empty() {
+        eprintln!("{}", "プリセットが定義されていません。設定ファイルに追加してください:".yellow());
+        eprintln!("  [clean.presets.{}]", name);
+        eprintln!("  names = [\".next\", \".turbo\"]");
     }
 
-    // Docker デーモンが起動しているかチェック
-    if !kanri_core::docker::is_docker_running() {
-        println!("{}", "❌ Docker デーモンが起動していません".red());
-        println!("{}", "💡 Docker Desktop を起動してください".dimmed());
+    Ok(config.resolve_preset(name)?)
+}
+
+fn clean_simulators(
+    search: bool,
+    delete: bool,
+    interactive: bool,
+    opts: CleanOptions,
+    reporter: &mut Reporter,
+) -> Result<()> {
+    // Xcode コマンドラインツールがインストールされているかチェック
+    if !kanri_core::xcode::is_simctl_available() {
+        reporter.warn("❌ Xcode コマンドラインツール（xcrun simctl）が見つかりません");
+        reporter.note("💡 xcode-select --install でインストールしてください");
         return Ok(());
     }
 
-    let spinner = ProgressBar::new_spinner();
-    spinner.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.cyan} {msg}")
-            .unwrap(),
-    );
-    spinner.set_message("Docker システム情報を取得中...");
-    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+    // 削除は SimulatorCleaner が xcrun simctl delete で行う
+    let cleaner = kanri_core::xcode::SimulatorCleaner::new();
+    clean_generic(&cleaner, "利用できないシミュレータ", search, delete, interactive, opts, reporter)
+}
 
-    let info = kanri_core::docker::get_system_info()?;
-    spinner.finish_and_clear();
-
-    println!(
-        "\n{} 削除可能: {}\n",
-        "📊".cyan(),
-        info.reclaimable.yellow().bold()
-    );
-
-    let mut prune_options = Vec::new();
-    if all {
-        prune_options.push("--all (未使用イメージもすべて削除)");
-    }
-    if volumes {
-        prune_options.push("--volumes (ボリュームも削除)");
-    }
-
-    if !prune_options.is_empty() {
-        println!("{} オプション:", "⚙".cyan());
-        for opt in &prune_options {
-            println!("  - {}", opt.dimmed());
-        }
-        println!();
-    }
+fn clean_flutter(
+    search_path: &Path,
+    search: bool,
+    delete: bool,
+    interactive: bool,
+    opts: CleanOptions,
+    reporter: &mut Reporter,
+) -> Result<()> {
+    let cleaner = kanri_core::flutter::FlutterCleaner::new(search_path.to_path_buf());
+    let labels = CleanLabels {
+        scanning: Some("🦋 Flutter プロジェクトをスキャン中..."),
+        notes: Vec::new(),
+        not_found: Some("Flutter プロジェクトが見つかりませんでした"),
+        found: "Flutter のビルド成果物ディレクトリ",
+        cleaned: "ディレクトリ",
+        ..CleanLabels::new("pubspec.yaml")
+    };
+    clean_generic_with(&cleaner, &labels, search, delete, interactive, opts, reporter)
+}
 
-    // 検索モード（デフォルトまたは --search）
-    if search || (!delete && !interactive) {
-        println!(
-            "{} {}",
-            "ℹ".cyan(),
-            "検索モード: 削除対象を表示しています".dimmed()
-        );
-        println!(
-            "{} {}",
-            "💡".cyan(),
-            "削除するには --delete (-d) を指定してください".dimmed()
-        );
-        println!(
-            "{} {}",
-            "💡".cyan(),
-            "確認しながら削除するには --interactive (-i) を指定してください".dimmed()
-        );
-        return Ok(());
-    }
+fn clean_android(
+    search_path: &Path,
+    search: bool,
+    delete: bool,
+    interactive: bool,
+    opts: CleanOptions,
+    reporter: &mut Reporter,
+) -> Result<()> {
+    let cleaner = kanri_core::android::AndroidCleaner::new(search_path.to_path_buf());
+    let labels = CleanLabels {
+        scanning: Some("🤖 Android / Gradle プロジェクトをスキャン中..."),
+        not_found: Some("Android / Gradle のビルド成果物が見つかりませんでした"),
+        found: "Android / Gradle プロジェクト",
+        cleaned: "プロジェクト",
+        ..CleanLabels::new("build.gradle / build.gradle.kts")
+    };
+    clean_generic_with(&cleaner, &labels, search, delete, interactive, opts, reporter)
+}
 
-    // インタラクティブモード
-    if interactive {
-        print!(
-            "\n{} 本当に削除しますか? (y/N): ",
-            "⚠".yellow().bold()
-        );
-        io::stdout().flush()?;
+#[allow(clippy::too_many_arguments)]
+fn clean_cache(
+    search: bool,
+    delete: bool,
+    interactive: bool,
+    min_size: u64,
+    keep_newer_than: u64,
+    by_app: bool,
+    opts: CleanOptions,
+    reporter: &mut Reporter,
+) -> Result<()> {
+    // Experimental 警告
+    reporter.warn("⚠️  EXPERIMENTAL FEATURE");
+    reporter.warn("このコマンドは実験的な機能です。削除前に必ず内容を確認してください。\n");
+
+    let cleaner = kanri_core::cache::CacheCleaner::new(min_size, false)
+        .with_config(kanri_core::config::Config::load()?)
+        .with_keep_newer_than(keep_newer_than)
+        .with_by_app(by_app);
+    let not_found = format!("{} GB 以上のキャッシュが見つかりませんでした", min_size);
+    // --by-app: アプリごとにまとめて表示し、インタラクティブモードではアプリ単位で選ぶ
+    let labels = CleanLabels {
+        scanning: Some("💾 Mac アプリケーションキャッシュをスキャン中..."),
+        notes: vec![format!("最小サイズ: {} GB 以上", min_size)],
+        not_found: Some(&not_found),
+        found: if by_app { "アプリのキャッシュ" } else { "キャッシュ" },
+        cleaned: "キャッシュ",
+        search_hints: vec!["安全なキャッシュのみ表示するには --safe-only を指定してください"],
+        // 要確認のキャッシュも 1 件ずつ確認して選べるようにする
+        select_each: true,
+        ..CleanLabels::new("~/Library/Caches")
+    };
+    clean_generic_with(&cleaner, &labels, search, delete, interactive, opts, reporter)
+}
 
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+/// clean_generic の表示に使う文言
+struct CleanLabels<'a> {
+    /// スキャン開始時のメッセージ（None なら「{icon} {name} をスキャン中...」）
+    scanning: Option<&'a str>,
+    /// 検索中に表示する検索対象（例: "Cargo.toml"）
+    search_target: &'a str,
+    /// スキャン後に表示する注記（例: --stale の対象）
+    notes: Vec<String>,
+    /// 見つからなかった場合のメッセージ（None なら「{search_target} が見つかりませんでした」）
+    not_found: Option<&'a str>,
+    /// 見つかった項目の呼び方（例: "Rust プロジェクト"）
+    found: &'a str,
+    /// 削除した項目の呼び方（例: "プロジェクト"）
+    cleaned: &'a str,
+    /// 検索モードで表示する追加のヒント
+    search_hints: Vec<&'a str>,
+    /// インタラクティブモードで番号ではなく項目ごとに確認する
+    select_each: bool,
+}
 
-        if !input.trim().eq_ignore_ascii_case("y") {
-            println!("{}", "キャンセルされました".yellow());
-            return Ok(());
+impl<'a> CleanLabels<'a> {
+    /// 検索対象だけを指定し、それ以外は既定の文言にする
+    fn new(search_target: &'a str) -> Self {
+        Self {
+            scanning: None,
+            search_target,
+            notes: Vec::new(),
+            not_found: None,
+            found: "",
+            cleaned: "",
+            search_hints: Vec::new(),
+            select_each: false,
         }
     }
+}
 
-    // 実行モード
-    println!("{}", "🗑️  Docker システムをクリーンアップ中...".red().bold());
+/// Cleanable trait ベースの汎用クリーン関数
+fn clean_generic(
+    cleaner: &impl kanri_core::Cleanable,
+    search_target: &str,
+    search: bool,
+    delete: bool,
+    interactive: bool,
+    opts: CleanOptions,
+    reporter: &mut Reporter,
+) -> Result<()> {
+    clean_generic_with(cleaner, &CleanLabels::new(search_target), search, delete, interactive, opts, reporter)
+}
 
-    let spinner = ProgressBar::new_spinner();
-    spinner.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.green} {msg}")
-            .unwrap(),
-    );
-    spinner.set_message("docker system prune を実行中...");
-    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+/// 表示の文言を指定して clean_generic を実行
+fn clean_generic_with(
+    cleaner: &impl kanri_core::Cleanable,
+    labels: &CleanLabels,
+    search: bool,
+    delete: bool,
+    interactive: bool,
+    opts: CleanOptions,
+    reporter: &mut Reporter,
+) -> Result<()> {
+    match labels.scanning {
+        Some(message) => reporter.scanning(message),
+        None => reporter.scanning(&format!("{} {} をスキャン中...", cleaner.icon(), cleaner.name())),
+    }
 
-    let output = kanri_core::docker::clean_system(all, volumes)?;
+    let spinner = reporter.spinner(&format!("{} を検索中...", labels.search_target));
+    let mut items = cleaner.scan()?;
     spinner.finish_and_clear();
 
-    println!("\n{}", "✅ クリーンアップ完了".green().bold());
-    println!("\n{}", output.dimmed());
-
-    Ok(())
-}
-
-fn clean_flutter(search_path: &PathBuf, search: bool, delete: bool, interactive: bool) -> Result<()> {
-    println!("{}", "🦋 Flutter プロジェクトをスキャン中...".cyan().bold());
-
-    let spinner = ProgressBar::new_spinner();
-    spinner.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.cyan} {msg}")
-            .unwrap(),
-    );
-    spinner.set_message("pubspec.yaml を検索中...");
-    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+    for note in labels.notes.iter().chain(&cleaner.scan_notes()) {
+        reporter.note(note);
+    }
 
-    let projects = kanri_core::flutter::find_flutter_projects(search_path)?;
-    spinner.finish_and_clear();
+    if opts.safe_only {
+        items.retain(|item| item.is_safe());
+    }
 
-    if projects.is_empty() {
-        println!("{}", "✨ Flutter プロジェクトが見つかりませんでした".green());
+    if items.is_empty() {
+        match labels.not_found {
+            Some(message) => reporter.not_found(message),
+            None => reporter.not_found(&format!("{} が見つかりませんでした", labels.search_target)),
+        }
         return Ok(());
     }
 
-    let total_size: u64 = projects.iter().map(|p| p.size).sum();
+    let total_size: u64 = items.iter().map(|item| item.size).sum();
+    reporter.found(items.len(), labels.found, total_size);
+    let breakdown = cleaner.breakdown(&items);
+    for (kind, count, size) in &breakdown {
+        reporter.note(&format!(
+            "  {}: {} 件 ({})",
+            kind,
+            count,
+            kanri_core::utils::format_size(*size)
+        ));
+    }
+    if !breakdown.is_empty() {
+        reporter.note("");
+    }
 
-    println!(
-        "\n{} 件の Flutter プロジェクトを発見 (合計: {})\n",
-        projects.len().to_string().yellow().bold(),
-        kanri_core::utils::format_size(total_size).yellow().bold()
-    );
+    let mut hidden = opts.list.select_items(&mut items);
 
-    // プロジェクト一覧を表示
-    for (i, project) in projects.iter().enumerate() {
-        println!(
-            "  {}. {} - {}",
-            (i + 1).to_string().dimmed(),
-            project.root.display().to_string().bright_blue(),
-            project.formatted_size().yellow()
+    // 一覧を表示
+    for (i, item) in items.iter().enumerate() {
+        reporter.group_item(
+            i + 1,
+            &item.name,
+            &item.paths(),
+            item.detail.as_deref(),
+            item.safety_label().map(|label| (item.is_safe(), label)),
         );
     }
+    reporter.hidden(hidden.len(), hidden.iter().map(|item| item.size).sum());
 
     // 検索モード（デフォルトまたは --search）
-    if search || (!delete && !interactive) {
-        println!(
-            "\n{} {}",
-            "ℹ".cyan(),
-            "検索モード: 削除対象を表示しています".dimmed()
-        );
-        println!(
-            "{} {}",
-            "💡".cyan(),
-            "削除するには --delete (-d) を指定してください".dimmed()
-        );
-        println!(
-            "{} {}",
-            "💡".cyan(),
-            "確認しながら削除するには --interactive (-i) を指定してください".dimmed()
-        );
+    if !opts.dry_run && (search || (!delete && !interactive)) {
+        reporter.search_mode(&labels.search_hints);
         return Ok(());
     }
 
-    // インタラクティブモード
+    // インタラクティブモード: 一覧の番号（または項目ごとの確認）で削除する項目を選ぶ
     if interactive {
-        print!(
-            "\n{} 本当に削除しますか? (y/N): ",
-            "⚠".yellow().bold()
-        );
-        io::stdout().flush()?;
+        let selected = if labels.select_each {
+            let describe = |item: &kanri_core::CleanableItem| {
+                format!(
+                    "{} {} {} - {}",
+                    if item.is_safe() { "✓" } else { "⚠" },
+                    item.name,
+                    item.formatted_size(),
+                    item.safety_label().unwrap_or_default()
+                )
+            };
+            select_each(&mut items, describe, reporter)?
+        } else {
+            select_by_number(&mut items, reporter)?
+        };
+        if !selected {
+            return Ok(());
+        }
+    }
 
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+    // --top で省略された項目は --all-items 指定時のみ削除（番号がないためインタラクティブでは対象外）
+    if opts.list.all_items && !interactive {
+        items.append(&mut hidden);
+    }
 
-        if !input.trim().eq_ignore_ascii_case("y") {
-            println!("{}", "キャンセルされました".yellow());
+    // 要確認の項目は --include-unsafe 指定時のみ削除（インタラクティブモードで番号を指定して選んだ項目は残す）
+    if !opts.include_unsafe && !interactive {
+        let skipped = kanri_core::cleanable::split_unsafe(&mut items);
+        reporter.skipped_unsafe(skipped.len(), skipped.iter().map(|item| item.size).sum());
+        if items.is_empty() {
             return Ok(());
         }
     }
 
-    // 実行モード
-    println!("\n{}", "🗑️  削除中...".red().bold());
+    // クラウド同期フォルダ内の項目はインタラクティブモードでは個別に確認
+    filter_protected_items(&mut items, |item| &item.path, reporter);
+    filter_sync_folder_items(&mut items, |item| &item.path, |item| item.size, interactive, opts, reporter)?;
+    if items.is_empty() {
+        return Ok(());
+    }
 
-    let pb = ProgressBar::new(projects.len() as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("{spinner:.green} [{bar:40.cyan/blue}] {pos}/{len} {msg}")
-            .unwrap()
-            .progress_chars("#>-"),
-    );
+    let total_size: u64 = items.iter().map(|item| item.size).sum();
 
-    let cleaned = kanri_core::flutter::clean_projects(&projects)?;
+    let entries: Vec<(String, u64)> = items
+        .iter()
+        .flat_map(|item| item.paths())
+        .map(|(path, size)| (path.display().to_string(), size))
+        .collect();
 
-    for project in &cleaned {
-        pb.inc(1);
-        pb.set_message(format!("{}", project.display()));
+    // 環境変数で決まるディレクトリは、設定ミスに気づけるよう削除前に解決結果を示す
+    if let Some((dir, source)) = cleaner.resolved_dir() {
+        reporter.resolved_dir(&dir, source);
     }
 
-    pb.finish_and_clear();
+    if opts.dry_run {
+        reporter.dry_run(&entries);
+        return Ok(());
+    }
 
-    println!(
-        "\n{} {} 件のプロジェクトをクリーンしました ({}削除)",
-        "✅".green(),
-        cleaned.len().to_string().green().bold(),
-        kanri_core::utils::format_size(total_size).green().bold()
-    );
+    // インタラクティブモード: 削除直前に内訳を表示して最終確認
+    if (interactive || opts.always_confirm) && !reporter.confirm_deletion(&entries)? {
+        return Ok(());
+    }
 
-    Ok(())
-}
+    // 実行モード
+    let pb = reporter.progress(total_size);
 
-fn clean_cache(search: bool, delete: bool, interactive: bool, min_size: u64, safe_only: bool) -> Result<()> {
-    // Experimental 警告
-    println!("{}", "⚠️  EXPERIMENTAL FEATURE".yellow().bold());
-    println!(
-        "{}",
-        "このコマンドは実験的な機能です。削除前に必ず内容を確認してください。"
-            .yellow()
-    );
-    println!();
+    let mut cleaned_paths = std::collections::HashSet::new();
+    run_cancellable(|| {
+        cleaner.clean(&items, &mut |item| {
+            pb.inc(item.size);
+            pb.set_message(item.name.clone());
+            cleaned_paths.insert(item.path.clone());
+        })
+    })?;
 
-    println!("{}", "💾 Mac アプリケーションキャッシュをスキャン中...".cyan().bold());
-    println!(
-        "{}",
-        format!("最小サイズ: {} GB 以上", min_size).dimmed()
-    );
+    pb.finish_and_clear();
 
-    let spinner = ProgressBar::new_spinner();
-    spinner.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.cyan} {msg}")
-            .unwrap(),
-    );
-    spinner.set_message("~/Library/Caches を検索中...");
-    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+    // 削除した項目だけを反映する（中断・スキャン後に消えた・保護されたパスなどで飛ばした項目は含めない）。
+    // --stale などはパスが残るため、削除時に報告された項目で判定する
+    let selected = items.len();
+    items.retain(|item| cleaned_paths.contains(&item.path));
+    warn_if_cancelled(items.len(), selected, reporter);
+    let total_size: u64 = items.iter().map(|item| item.size).sum();
 
-    let mut caches = kanri_core::cache::scan_user_caches(min_size)?;
-    spinner.finish_and_clear();
+    reporter.cleaned(items.len(), labels.cleaned, total_size);
 
-    if safe_only {
-        caches.retain(|c| c.is_safe);
-    }
+    let removed: Vec<PathBuf> = items
+        .iter()
+        .flat_map(|item| item.paths())
+        .map(|(path, _)| path.to_path_buf())
+        .collect();
+    prune_after_clean(opts, &removed, reporter);
+    record_history(opts.history, opts.command, removed, total_size);
 
-    if caches.is_empty() {
-        println!(
+    Ok(())
+}
+
+// ========== Archive / Restore Functions ==========
+
+/// 帯域制限に対応していないバックエンドで --bwlimit / storage.bwlimit が指定された場合の警告
+fn warn_unsupported_bwlimit(config: &kanri_core::config::Config) {
+    let backend = config.get_storage_backend();
+    if let (Some(bwlimit), "b2" | "gcs") = (config.bwlimit(), backend.as_str()) {
+        eprintln!(
             "{}",
-            format!("✨ {} GB 以上のキャッシュが見つかりませんでした", min_size).green()
+            format!(
+                "⚠ {} バックエンドは帯域制限に対応していないため bwlimit ({}) は無視されます（storage.backend = \"rclone\" で利用できます）",
+                backend.to_uppercase(),
+                bwlimit
+            )
+            .yellow()
         );
-        return Ok(());
     }
+}
 
-    let total_size: u64 = caches.iter().map(|c| c.size).sum();
-
-    println!(
-        "\n{} 件のキャッシュを発見 (合計: {})\n",
-        caches.len().to_string().yellow().bold(),
-        kanri_core::utils::format_size(total_size).yellow().bold()
-    );
+/// ファイルを `parts_dir` にパーツとして分割してアップロードし、アップロードしたパーツのリモートパスを返す
+///
+/// パーツは 1 つ書き出すごとにアップロードして削除する（ディスクに置くのは常に 1 パーツ分）
+fn upload_split_file(
+    storage_client: &dyn kanri_core::StorageClient,
+    bucket: &str,
+    local_path: &Path,
+    remote_path: &str,
+    part_size: u64,
+    parts_dir: &Path,
+    progress: kanri_core::UploadProgress,
+) -> Result<Vec<String>> {
+    use kanri_core::archive;
 
-    // キャッシュ一覧を表示
-    for (i, cache) in caches.iter().enumerate() {
-        let safety_icon = if cache.is_safe { "✓" } else { "⚠" };
-        let safety_color = if cache.is_safe {
-            cache.safety_label().green()
-        } else {
-            cache.safety_label().yellow()
+    let mut remote_parts = Vec::new();
+    let mut uploaded = 0;
+    archive::split_file(local_path, part_size, parts_dir, |i, part| {
+        let remote_part = archive::part_path(remote_path, i);
+        // 進捗はパーツごとではなくファイル全体のアップロード済みバイト数で報告する
+        let report_progress = |done: u64| {
+            if let Some(progress) = progress {
+                progress(uploaded + done);
+            }
         };
+        storage_client.upload_file(
+            bucket,
+            part,
+            &remote_part,
+            progress.map(|_| &report_progress as &dyn Fn(u64)),
+        )?;
+        uploaded += std::fs::metadata(part)?.len();
+        remote_parts.push(remote_part);
+        Ok(())
+    })?;
+
+    Ok(remote_parts)
+}
 
-        println!(
-            "  {}. {} {} - {} {}",
-            (i + 1).to_string().dimmed(),
-            safety_icon,
-            cache.name.bright_blue(),
-            cache.formatted_size().yellow(),
-            safety_color
-        );
-    }
+#[allow(clippy::too_many_arguments)]
+fn archive_large_files(
+    path: PathBuf,
+    min_size_gb: u64,
+    extensions: Option<String>,
+    files_only: bool,
+    dirs_only: bool,
+    to: String,
+    delete_after: bool,
+    dry_run: bool,
+    progress: bool,
+    sync: bool,
+    jobs: Option<u32>,
+    threads: Option<u32>,
+    bwlimit: Option<String>,
+    expire_days: Option<u32>,
+    bundle: bool,
+    incremental: bool,
+    dedup: bool,
+    split_size: Option<u64>,
+    history: bool,
+    profile: Option<&str>,
+) -> Result<()> {
+    use kanri_core::archive::bundle::{bundle_path, bundle_temp_path, create_bundle, estimated_bundle_size};
+    use kanri_core::{archive, config, large_files};
 
-    // 検索モード（デフォルトまたは --search）
-    if search || (!delete && !interactive) {
-        println!(
-            "\n{} {}",
-            "ℹ".cyan(),
-            "検索モード: 削除対象を表示しています".dimmed()
-        );
-        println!(
-            "{} {}",
-            "💡".cyan(),
-            "削除するには --delete (-d) を指定してください".dimmed()
-        );
-        println!(
-            "{} {}",
-            "💡".cyan(),
-            "確認しながら削除するには --interactive (-i) を指定してください".dimmed()
-        );
-        println!(
-            "{} {}",
-            "💡".cyan(),
-            "安全なキャッシュのみ表示するには --safe-only を指定してください".dimmed()
-        );
-        return Ok(());
+    // アップロード後にローカルを削除するため、システムのディレクトリなどは最初に拒否する
+    if delete_after {
+        kanri_core::utils::assert_safe_target(&path)?;
     }
 
-    // インタラクティブモード: 各キャッシュごとに確認
-    let caches_to_delete = if interactive {
-        println!("\n{}", "各キャッシュについて個別に確認します".cyan());
-        println!("{}", "(y)削除 / (n)スキップ / (q)中断 / (a)全て削除".dimmed());
-        println!();
-
-        let mut selected_caches = Vec::new();
-        let mut delete_all = false;
+    println!("{}", "📦 アーカイブ処理を開始...".cyan().bold());
 
-        for cache in &caches {
-            if delete_all {
-                selected_caches.push(cache.clone());
-                continue;
-            }
+    // 設定読み込み
+    let mut config = config::Config::load_profile(profile)?.with_bwlimit(bwlimit);
+    if let Some(b2) = config.b2_config_mut().as_mut() {
+        b2.upload_threads = jobs.or(b2.upload_threads);
+        b2.file_threads = threads.or(b2.file_threads);
+    }
+    let bucket = config.get_bucket()?;
+    let backend = config.get_storage_backend();
+    warn_unsupported_bwlimit(&config);
 
-            let safety_icon = if cache.is_safe { "✓" } else { "⚠" };
-            let safety_color = if cache.is_safe {
-                cache.safety_label().green()
-            } else {
-                cache.safety_label().yellow()
-            };
+    // Storage client を作成
+    let storage_client = config.create_storage_client()?;
 
-            print!(
-                "{} {} {} - {} {} を削除しますか? (y/n/q/a): ",
-                safety_icon,
-                cache.name.bright_blue(),
-                cache.formatted_size().yellow(),
-                safety_color,
-                "".dimmed()
-            );
-            io::stdout().flush()?;
+    // 自動削除はアップロード前に対応しているか確認する
+    if expire_days.is_some() && !storage_client.supports_expiry() {
+        anyhow::bail!(
+            "--expire-days は {} バックエンドでは使用できません（storage.backend = \"b2\" で利用できます）",
+            backend
+        );
+    }
 
-            let mut input = String::new();
-            io::stdin().read_line(&mut input)?;
-            let choice = input.trim().to_lowercase();
+    // 認証
+    println!("{}", format!("🔐 {} 認証中...", backend.to_uppercase()).cyan());
+    storage_client.authorize()?;
 
-            match choice.as_str() {
-                "y" | "yes" => {
-                    selected_caches.push(cache.clone());
-                    println!("  {} 削除対象に追加", "✓".green());
-                }
-                "n" | "no" | "" => {
-                    println!("  {} スキップ", "→".dimmed());
-                }
-                "q" | "quit" => {
-                    println!("\n{}", "中断しました".yellow());
-                    if selected_caches.is_empty() {
-                        return Ok(());
-                    }
-                    break;
-                }
-                "a" | "all" => {
-                    selected_caches.push(cache.clone());
-                    delete_all = true;
-                    println!("  {} 以降すべて削除対象に追加", "✓".green());
-                }
-                _ => {
-                    println!("  {} 無効な入力、スキップします", "⚠".yellow());
-                }
-            }
-        }
+    // 大きなファイルを検索
+    let min_size = min_size_gb * 1024 * 1024 * 1024;
+    let ext_vec: Option<Vec<String>> = extensions.map(|s| s.split(',').map(|e| e.trim().to_string()).collect());
 
-        if selected_caches.is_empty() {
-            println!("\n{}", "削除対象がありません".yellow());
+    let (include_files, include_dirs) = match (files_only, dirs_only) {
+        (true, true) => {
+            eprintln!("{}", "Error: --files-only and --dirs-only cannot be used together".red());
             return Ok(());
         }
-
-        println!(
-            "\n{} {} 件のキャッシュを削除します",
-            "📋".cyan(),
-            selected_caches.len()
-        );
-        selected_caches
-    } else {
-        // 非インタラクティブモード（--delete）は全て削除
-        caches.clone()
-    };
-
-    // 実行モード
-    println!("\n{}", "🗑️  削除中...".red().bold());
-
-    let pb = ProgressBar::new(caches_to_delete.len() as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("{spinner:.green} [{bar:40.cyan/blue}] {pos}/{len} {msg}")
-            .unwrap()
-            .progress_chars("#>-"),
-    );
-
-    let cleaned = kanri_core::cache::clean_caches(&caches_to_delete)?;
-
-    for cache_name in &cleaned {
-        pb.inc(1);
-        pb.set_message(cache_name.to_string());
-    }
-
-    pb.finish_and_clear();
-
-    // 削除したキャッシュの合計サイズを計算
-    let deleted_size: u64 = caches_to_delete.iter().map(|c| c.size).sum();
-
-    println!(
-        "\n{} {} 件のキャッシュをクリーンしました ({}削除)",
-        "✅".green(),
-        cleaned.len().to_string().green().bold(),
-        kanri_core::utils::format_size(deleted_size).green().bold()
-    );
-
-    Ok(())
-}
-
-/// Cleanable trait ベースの汎用クリーン関数
-fn clean_generic(
-    cleaner: &impl kanri_core::Cleanable,
-    search_target: &str,
-    search: bool,
-    delete: bool,
-    interactive: bool,
-) -> Result<()> {
-    println!(
-        "{}",
-        format!("{} {} をスキャン中...", cleaner.icon(), cleaner.name())
-            .cyan()
-            .bold()
-    );
-
-    let spinner = ProgressBar::new_spinner();
-    spinner.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.cyan} {msg}")
-            .unwrap(),
-    );
-    spinner.set_message(format!("{} を検索中...", search_target));
-    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
-
-    let items = cleaner.scan()?;
-    spinner.finish_and_clear();
-
-    if items.is_empty() {
-        println!(
-            "{}",
-            format!("✨ {} が見つかりませんでした", search_target).green()
-        );
-        return Ok(());
-    }
-
-    let total_size: u64 = items.iter().map(|item| item.size).sum();
-
-    println!(
-        "\n{} 件を発見 (合計: {})\n",
-        items.len().to_string().yellow().bold(),
-        kanri_core::utils::format_size(total_size).yellow().bold()
-    );
-
-    // 一覧を表示
-    for (i, item) in items.iter().enumerate() {
-        let display = if let Some(safety_label) = item.safety_label() {
-            let safety_icon = if item.is_safe() { "✓" } else { "⚠" };
-            let safety_color = if item.is_safe() {
-                safety_label.green()
-            } else {
-                safety_label.yellow()
-            };
-            format!(
-                "  {}. {} {} - {} {}",
-                (i + 1).to_string().dimmed(),
-                safety_icon,
-                item.name.bright_blue(),
-                item.formatted_size().yellow(),
-                safety_color
-            )
-        } else {
-            format!(
-                "  {}. {} - {}",
-                (i + 1).to_string().dimmed(),
-                item.name.bright_blue(),
-                item.formatted_size().yellow()
-            )
-        };
-        println!("{}", display);
-    }
-
-    // 検索モード（デフォルトまたは --search）
-    if search || (!delete && !interactive) {
-        println!(
-            "\n{} {}",
-            "ℹ".cyan(),
-            "検索モード: 削除対象を表示しています".dimmed()
-        );
-        println!(
-            "{} {}",
-            "💡".cyan(),
-            "削除するには --delete (-d) を指定してください".dimmed()
-        );
-        println!(
-            "{} {}",
-            "💡".cyan(),
-            "確認しながら削除するには --interactive (-i) を指定してください".dimmed()
-        );
-        return Ok(());
-    }
-
-    // インタラクティブモード
-    if interactive {
-        print!(
-            "\n{} 本当に削除しますか? (y/N): ",
-            "⚠".yellow().bold()
-        );
-        io::stdout().flush()?;
-
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-
-        if !input.trim().eq_ignore_ascii_case("y") {
-            println!("{}", "キャンセルされました".yellow());
-            return Ok(());
-        }
-    }
-
-    // 実行モード
-    println!("\n{}", "🗑️  削除中...".red().bold());
-
-    let pb = ProgressBar::new(items.len() as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("{spinner:.green} [{bar:40.cyan/blue}] {pos}/{len} {msg}")
-            .unwrap()
-            .progress_chars("#>-"),
-    );
-
-    let cleaned = kanri_core::cleanable::clean_items(&items)?;
-
-    for item_name in &cleaned {
-        pb.inc(1);
-        pb.set_message(item_name.to_string());
-    }
-
-    pb.finish_and_clear();
-
-    println!(
-        "\n{} {} 件をクリーンしました ({}削除)",
-        "✅".green(),
-        cleaned.len().to_string().green().bold(),
-        kanri_core::utils::format_size(total_size).green().bold()
-    );
-
-    Ok(())
-}
-
-// ========== Archive / Restore Functions ==========
-
-fn archive_large_files(
-    path: PathBuf,
-    min_size_gb: u64,
-    extensions: Option<String>,
-    files_only: bool,
-    dirs_only: bool,
-    to: String,
-    delete_after: bool,
-    dry_run: bool,
-) -> Result<()> {
-    use kanri_core::{archive, config, large_files};
-
-    println!("{}", "📦 アーカイブ処理を開始...".cyan().bold());
-
-    // 設定読み込み
-    let config = config::Config::load()?;
-    let bucket = config.get_b2_bucket()?;
-    let backend = config.get_storage_backend();
-
-    // Storage client を作成
-    let storage_client = config.create_storage_client()?;
-
-    // 認証
-    println!("{}", format!("🔐 {} 認証中...", backend.to_uppercase()).cyan());
-    storage_client.authorize()?;
-
-    // 大きなファイルを検索
-    let min_size = min_size_gb * 1024 * 1024 * 1024;
-    let ext_vec: Option<Vec<String>> = extensions.map(|s| s.split(',').map(|e| e.trim().to_string()).collect());
-
-    let (include_files, include_dirs) = match (files_only, dirs_only) {
-        (true, true) => {
-            eprintln!("{}", "Error: --files-only and --dirs-only cannot be used together".red());
-            return Ok(());
-        }
-        (true, false) => (true, false),
-        (false, true) => (false, true),
-        (false, false) => (true, true),
+        (true, false) => (true, false),
+        (false, true) => (false, true),
+        (false, false) => (true, true),
     };
 
     let items = large_files::find_large_items(
@@ -1402,58 +2857,361 @@ fn archive_large_files(
         "📍 アーカイブ先:".cyan().bold(),
         versioned_path.cyan()
     );
+    let expire_at = expire_days.map(|days| chrono::Utc::now() + chrono::Duration::days(days.into()));
+    if let Some(expire_at) = expire_at {
+        println!(
+            "{} {}",
+            "⏳ 自動削除:".cyan().bold(),
+            format!(
+                "{} 日後 ({})",
+                expire_days.unwrap_or_default(),
+                expire_at.with_timezone(&chrono::Local).format("%Y-%m-%d")
+            )
+            .cyan()
+        );
+    }
+
+    // --incremental: 同じアーカイブ元・アーカイブ先への前回のアーカイブを基準にする
+    let source_path = std::fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+    let parent = if incremental {
+        let parent = archive::ArchiveIndex::load()?.latest_for(&source_path, &to).cloned();
+        match &parent {
+            Some(parent) => println!(
+                "{} 前回のアーカイブ (ID: {}, {}) から変更されたファイルのみアップロードします",
+                "🔗".cyan(),
+                parent.id.cyan(),
+                parent.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S")
+            ),
+            None => println!(
+                "{} {}",
+                "ℹ".cyan(),
+                "前回のアーカイブが見つからないため、すべてアップロードします".dimmed()
+            ),
+        }
+        parent
+    } else {
+        None
+    };
 
     if dry_run {
         println!("\n{}", "ℹ Dry-run モード: 実際のアップロードは行いません".yellow());
         println!("\n{}", "アップロード予定:".cyan().bold());
         for item in &items {
             let relative_path = item.path.strip_prefix(&path).unwrap_or(item.path.as_path());
-            let remote_path = PathBuf::from(&versioned_path).join(relative_path);
-            println!("  {} -> {}", item.path.display(), remote_path.display().to_string().green());
+            let mut remote_path = PathBuf::from(&versioned_path).join(relative_path).display().to_string();
+            if item.is_dir && bundle {
+                remote_path = bundle_path(&remote_path);
+            }
+            // tar にまとめるディレクトリは作ってみるまで変更の有無がわからないため、ファイルのみ判定する
+            if let (Some(parent), false) = (&parent, item.is_dir) {
+                let mut archive_item = archive::ArchiveItem::from_file(&item.path, remote_path.clone())?;
+                if parent.link_unchanged(&mut archive_item) {
+                    println!(
+                        "  {} {}",
+                        item.path.display(),
+                        "(変更なし: 前回のアーカイブを参照)".dimmed()
+                    );
+                    continue;
+                }
+            }
+            match split_size.filter(|&part_size| !item.is_dir && item.size > part_size) {
+                Some(part_size) => println!(
+                    "  {} -> {} {}",
+                    item.path.display(),
+                    remote_path.green(),
+                    format!("({} パーツに分割)", item.size.div_ceil(part_size)).dimmed()
+                ),
+                None => println!("  {} -> {}", item.path.display(), remote_path.green()),
+            }
         }
         return Ok(());
     }
 
+    // 中断してもアップロード済みのファイルが残らないよう、アップロード前にルールを設定する
+    if let Some(days) = expire_days {
+        println!("{}", "⏳ 自動削除のライフサイクルルールを設定中...".cyan());
+        storage_client.set_expiry(&bucket, &format!("{}/", versioned_path), days)?;
+    }
+
     // アーカイブ作成
-    let mut archive_record = archive::Archive::new("large-files".to_string(), versioned_path.clone());
+    let mut archive_record = archive::Archive::new("large-files".to_string(), versioned_path.clone())
+        .with_source_path(source_path);
+    if let Some(parent) = &parent {
+        archive_record = archive_record.with_parent(parent.id.clone());
+    }
 
     // アップロード
     println!("\n{}", "⬆️ B2 にアップロード中...".cyan().bold());
 
-    for item in &items {
+    // --progress 指定時は全アイテムの合計バイト数に対するプログレスバーを表示
+    let pb = if progress {
+        let pb = ProgressBar::new(items.iter().map(|i| i.size).sum());
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template("{spinner:.green} [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}, 残り {eta}) {msg}")
+                .unwrap()
+                .progress_chars("#>-"),
+        );
+        pb
+    } else {
+        ProgressBar::hidden()
+    };
+    let mut completed_bytes = 0;
+    let mut skipped_count = 0;
+    let mut linked_count = 0;
+    let mut dedup_count = 0;
+    // リモートから消えていた blob（保存時に最新の blob インデックスからも外す）
+    let mut missing_blobs = Vec::new();
+
+    // アップロード済みの blob（中断後の再実行と --dedup の参照先）。初回は既存のアーカイブから作成して保存する
+    let archives = archive::ArchiveIndex::load()?.archives;
+    let mut blobs = archive::blob_index::BlobIndex::update(|blobs| {
+        if blobs.is_empty() {
+            for existing in &archives {
+                blobs.register_archive(existing);
+            }
+        }
+        blobs.clone()
+    })?;
+    let destination_prefix = format!("{}/", to.trim_end_matches('/'));
+
+    for (index, item) in items.iter().enumerate() {
         let relative_path = item.path.strip_prefix(&path).unwrap_or(item.path.as_path());
         let remote_path = PathBuf::from(&versioned_path).join(relative_path);
-        let remote_path_str = remote_path.to_string_lossy();
+        let mut archive_item = archive::ArchiveItem::from_file(&item.path, remote_path.to_string_lossy().to_string())?;
+        archive_item.expire_at = expire_at;
+
+        // --bundle: tar はディレクトリと同じボリュームに作る。空き容量が足りなければまとめずにアップロードする
+        let bundle_file = (item.is_dir && bundle).then(|| bundle_temp_path(&item.path)).filter(|bundle_file| {
+            let needed = estimated_bundle_size(archive_item.size, archive_item.file_count.unwrap_or(0));
+            let free = bundle_file
+                .parent()
+                .and_then(|dir| kanri_core::utils::disk_usage(dir).ok())
+                .map(|usage| usage.free);
+            let fits = free.is_none_or(|free| free >= needed);
+            if !fits {
+                pb.suspend(|| {
+                    eprintln!(
+                        "  {}",
+                        format!(
+                            "⚠ {} を tar にまとめる空き容量 ({}) がないため、まとめずにアップロードします",
+                            item.path.display(),
+                            kanri_core::utils::format_size(needed)
+                        )
+                        .yellow()
+                    );
+                });
+            }
+            fits
+        });
+        if bundle_file.is_some() {
+            archive_item.b2_path = bundle_path(&archive_item.b2_path);
+        }
+        let remote_path_str = archive_item.b2_path.clone();
+
+        // --split-size: 分割サイズを超えるファイルはパーツに分けてアップロードする
+        let split = split_size.filter(|&part_size| !item.is_dir && item.size > part_size);
+
+        // ディレクトリを tar にまとめ、tar 自体の SHA256 を記録する（復元時の検証用）
+        if let Some(bundle_file) = &bundle_file {
+            pb.suspend(|| println!("  📦 {} を tar にまとめています...", item.path.display()));
+            let members = create_bundle(&item.path, bundle_file)?;
+            archive_item.sha256 = kanri_core::b2::B2Client::calculate_sha256(bundle_file)?;
+            archive_item.bundle_members = Some(members);
+        }
+
+        // --incremental: 前回から変更のないものはアップロードせず、前回のリモートパスを参照する
+        if parent.as_ref().is_some_and(|parent| parent.link_unchanged(&mut archive_item)) {
+            if let Some(bundle_file) = &bundle_file {
+                let _ = std::fs::remove_file(bundle_file);
+            }
+            pb.suspend(|| {
+                println!(
+                    "  {} {} {}",
+                    "🔗".dimmed(),
+                    item.path.display(),
+                    "前回のアーカイブを参照（変更なし）".dimmed()
+                );
+            });
+            linked_count += 1;
+            completed_bytes += item.size;
+            pb.set_position(completed_bytes);
+            archive_record.add_item(archive_item);
+            continue;
+        }
 
-        println!("  📤 {} -> {}", item.path.display(), remote_path.display().to_string().green());
+        // 同じファイルを同じ内容でアップロード済み（中断した前回の実行など）なら参照する。
+        // --dedup では別のファイルの blob も参照する（いずれもリモートに残っているものに限る）
+        let blob = (split.is_none() && !archive_item.sha256.is_empty() && archive_item.expire_at.is_none())
+            .then(|| blobs.get(&archive_item.sha256).map(str::to_string))
+            .flatten()
+            .filter(|blob| *blob != remote_path_str);
+        let same_file = blob
+            .as_deref()
+            .is_some_and(|blob| is_same_archived_file(blob, &remote_path_str, &destination_prefix));
+        if let Some(blob) = blob.filter(|_| dedup || same_file) {
+            // 確認できなかった場合は参照せずにアップロードする
+            let exists = storage_client
+                .file_exists_with_sha(&bucket, &blob, &archive_item.sha256)
+                .unwrap_or_else(|e| {
+                    pb.suspend(|| {
+                        eprintln!("  {}", format!("⚠ 参照先 {} を確認できません: {}", blob, e).yellow());
+                    });
+                    false
+                });
+            if exists {
+                if let Some(bundle_file) = &bundle_file {
+                    let _ = std::fs::remove_file(bundle_file);
+                }
+                pb.suspend(|| {
+                    if same_file {
+                        println!(
+                            "  {} {} {}",
+                            "⏭".dimmed(),
+                            item.path.display(),
+                            format!("スキップ（変更なし: {} を参照）", blob).dimmed()
+                        );
+                    } else {
+                        println!(
+                            "  {} {} {}",
+                            "♻️".dimmed(),
+                            item.path.display(),
+                            format!("同じ内容のファイルを参照（{}）", blob).dimmed()
+                        );
+                    }
+                });
+                archive_item.blob_ref = Some(blob);
+                if same_file {
+                    skipped_count += 1;
+                } else {
+                    dedup_count += 1;
+                }
+                completed_bytes += item.size;
+                pb.set_position(completed_bytes);
+                archive_record.add_item(archive_item);
+                continue;
+            }
+            // リモートから消えている blob は参照先にしない
+            blobs.remove(&archive_item.sha256);
+            missing_blobs.push(archive_item.sha256.clone());
+        }
 
-        if item.is_dir {
-            let _files = storage_client.upload_directory(&bucket, &item.path, &remote_path_str)?;
+        pb.suspend(|| {
+            println!("  📤 {} -> {}", item.path.display(), remote_path.display().to_string().green());
+        });
+        pb.set_message(item.path.display().to_string());
+
+        let report_progress = |done: u64| pb.set_position(completed_bytes + done.min(item.size));
+        let upload_progress: kanri_core::UploadProgress =
+            progress.then_some(&report_progress as &dyn Fn(u64));
+
+        if let Some(bundle_file) = &bundle_file {
+            let result = storage_client.upload_file_with_sha256(
+                &bucket,
+                bundle_file,
+                &remote_path_str,
+                &archive_item.sha256,
+                upload_progress,
+            );
+            // アップロードに失敗しても一時ファイルは残さない
+            let _ = std::fs::remove_file(bundle_file);
+            result?;
+        } else if item.is_dir && sync {
+            storage_client.sync_directory(&bucket, &item.path, &remote_path_str)?;
+        } else if item.is_dir {
+            let _files = storage_client.upload_directory(&bucket, &item.path, &remote_path_str, upload_progress)?;
+        } else if let Some(part_size) = split {
+            pb.suspend(|| {
+                println!(
+                    "    ✂️ {} パーツに分割してアップロードします",
+                    item.size.div_ceil(part_size)
+                )
+            });
+            let parts_dir = std::env::temp_dir().join(format!("kanri-split-{}-{}", std::process::id(), index));
+            let result = upload_split_file(
+                storage_client.as_ref(),
+                &bucket,
+                &item.path,
+                &remote_path_str,
+                part_size,
+                &parts_dir,
+                upload_progress,
+            );
+            // アップロードに失敗しても分割したパーツは残さない
+            let _ = std::fs::remove_dir_all(&parts_dir);
+            archive_item.parts = Some(result?);
         } else {
-            let _file_id = storage_client.upload_file(&bucket, &item.path, &remote_path_str)?;
+            let _file_id = storage_client.upload_file_with_sha256(
+                &bucket,
+                &item.path,
+                &remote_path_str,
+                &archive_item.sha256,
+                upload_progress,
+            )?;
         }
 
-        let archive_item = archive::ArchiveItem::from_file(&item.path, remote_path_str.to_string())?;
+        // 中断しても再実行時にスキップできるよう、アップロードできた blob はすぐに記録する
+        if archive_item.parts.is_none() && !archive_item.sha256.is_empty() && archive_item.expire_at.is_none() {
+            let (sha256, remote_path) = (archive_item.sha256.clone(), remote_path_str.clone());
+            blobs.insert(sha256.clone(), remote_path.clone());
+            archive::blob_index::BlobIndex::update(|blobs| blobs.insert(sha256, remote_path))?;
+        }
+
+        completed_bytes += item.size;
+        pb.set_position(completed_bytes);
+
         archive_record.add_item(archive_item);
 
-        println!("    {}", "✅ 完了".green());
+        pb.suspend(|| println!("    {}", "✅ 完了".green()));
     }
+    pb.finish_and_clear();
 
-    // アーカイブインデックスに追加
-    let mut index = archive::ArchiveIndex::load()?;
-    index.add_archive(archive_record.clone());
-    index.save()?;
+    // アーカイブインデックスに追加（同時に実行された archive の変更を上書きしないよう、ロックを取って最新のものに追加する）
+    archive::ArchiveIndex::update(|index| index.add_archive(archive_record.clone()))?;
+    archive::blob_index::BlobIndex::update(|blobs| {
+        for sha256 in &missing_blobs {
+            blobs.remove(sha256);
+        }
+        blobs.register_archive(&archive_record);
+    })?;
 
     println!(
         "\n{} アーカイブ完了 (ID: {})",
         "✅".green(),
         archive_record.id.green().bold()
     );
+    if skipped_count > 0 {
+        println!(
+            "  {} {} 件は同じ内容でアップロード済みのためスキップしました（変更なし）",
+            "⏭".cyan(),
+            skipped_count.to_string().cyan().bold()
+        );
+    }
+    if linked_count > 0 {
+        println!(
+            "  {} {} 件は前回から変更がないため、前回のアーカイブを参照しています（アップロードなし）",
+            "🔗".cyan(),
+            linked_count.to_string().cyan().bold()
+        );
+    }
+    if dedup_count > 0 {
+        println!(
+            "  {} {} 件は同じ内容のファイルがアップロード済みのため、参照しています（アップロードなし）",
+            "♻️".cyan(),
+            dedup_count.to_string().cyan().bold()
+        );
+    }
 
-    // delete_after が指定されている場合は削除
+    // delete_after が指定されている場合は削除（実際に削除したものだけを解放サイズとして記録する）
+    let mut removed_paths = Vec::new();
+    let mut bytes_freed = 0;
     if delete_after {
         println!("\n{}", "🗑️ ローカルファイルを削除中...".yellow());
         for item in &items {
+            if kanri_core::utils::is_protected_path(&item.path) {
+                println!("  {} {} （保護されたパスのため削除しません）", "🛡️".yellow(), item.path.display());
+                continue;
+            }
             if item.path.exists() {
                 if item.is_dir {
                     std::fs::remove_dir_all(&item.path)?;
@@ -1461,30 +3219,54 @@ fn archive_large_files(
                     std::fs::remove_file(&item.path)?;
                 }
                 println!("  {} {}", "✅".green(), item.path.display());
+                removed_paths.push(item.path.clone());
+                bytes_freed += item.size;
             }
         }
         println!("{}", "✅ ローカルファイルを削除しました".green());
     }
+    record_history(history, "archive large-files", removed_paths, bytes_freed);
 
     Ok(())
 }
 
+/// アップロード済みの blob が、今回アップロードするファイルの以前のアーカイブ（`<宛先>/<タイムスタンプ>/<相対パス>`）か
+fn is_same_archived_file(blob: &str, remote_path: &str, destination_prefix: &str) -> bool {
+    let relative = |path: &str| {
+        path.strip_prefix(destination_prefix)
+            .and_then(|rest| rest.split_once('/'))
+            .map(|(_, relative)| relative.to_string())
+    };
+    relative(blob).is_some_and(|blob| Some(blob) == relative(remote_path))
+}
+
+#[allow(clippy::too_many_arguments)]
 fn restore_archive(
     from: &str,
     to: &str,
     mode: RestoreMode,
+    dest_structure: DestStructure,
+    overwrite: kanri_core::archive::OverwritePolicy,
     version: Option<&str>,
+    range: &kanri_core::archive::versioning::TimestampRange,
     dry_run: bool,
+    jobs: usize,
+    verify: Option<bool>,
+    retries: u32,
+    bwlimit: Option<String>,
+    profile: Option<&str>,
 ) -> Result<()> {
-    use kanri_core::config;
+    use kanri_core::archive::versioning::{extract_timestamp, latest_per_path, remove_timestamp};
+    use kanri_core::{archive, config};
     use std::collections::HashMap;
 
     println!("{}", "📥 アーカイブ復元処理を開始...".cyan().bold());
 
     // 設定読み込み
-    let config = config::Config::load()?;
-    let bucket = config.get_b2_bucket()?;
+    let config = config::Config::load_profile(profile)?.with_bwlimit(bwlimit);
+    let bucket = config.get_bucket()?;
     let backend = config.get_storage_backend();
+    warn_unsupported_bwlimit(&config);
 
     let storage_client = config.create_storage_client()?;
 
@@ -1494,69 +3276,88 @@ fn restore_archive(
 
     // ファイル一覧を取得
     println!("{}", format!("📋 {} からファイル一覧を取得中...", backend.to_uppercase()).cyan());
-    let all_files = storage_client.list_files(&bucket, from)?;
-
-    if all_files.is_empty() {
-        println!("{}", "⚠️ 該当するファイルが見つかりませんでした".yellow());
-        return Ok(());
-    }
+    let remote_files = storage_client.list_files_detailed(&bucket, from)?;
+    let mut all_files: Vec<String> = remote_files.iter().map(|f| f.path.clone()).collect();
+    let mut sizes: HashMap<String, u64> = remote_files
+        .iter()
+        .filter_map(|f| Some((f.path.clone(), f.size?)))
+        .collect();
 
-    println!("  {} {} 個のファイルを検出", "✅".green(), all_files.len());
+    let mut modified: HashMap<String, chrono::DateTime<chrono::Utc>> = remote_files
+        .iter()
+        .filter_map(|f| Some((f.path.clone(), f.modified?)))
+        .collect();
 
-    // タイムスタンプを抽出するヘルパー関数
-    fn extract_timestamp(path: &str) -> Option<String> {
-        // YYYYMMDD_HHMMSS パターンを探す
-        for part in path.split('/') {
-            if part.len() == 15 && part.chars().nth(8) == Some('_') {
-                let before_underscore = &part[..8];
-                let after_underscore = &part[9..];
-                if before_underscore.chars().all(|c| c.is_ascii_digit())
-                    && after_underscore.chars().all(|c| c.is_ascii_digit())
-                {
-                    return Some(part.to_string());
-                }
+    // --dedup で共有の blob を参照しているファイルと、--incremental で前回のアーカイブを参照している
+    // ファイルはリモートの一覧に現れないため、インデックスから補う
+    let index = archive::ArchiveIndex::load()?;
+    let blob_refs: HashMap<String, (String, String)> = index
+        .remote_refs_for_prefix(from)
+        .into_iter()
+        .map(|(path, item)| {
+            if !sizes.contains_key(&path) {
+                sizes.insert(path.clone(), sizes.get(item.remote_path()).copied().unwrap_or(item.size));
+            }
+            if let Some(time) = modified.get(item.remote_path()).copied() {
+                modified.insert(path.clone(), time);
             }
+            (path, (item.remote_path().to_string(), item.sha256.clone()))
+        })
+        .collect();
+    for b2_path in blob_refs.keys() {
+        if !all_files.contains(b2_path) {
+            all_files.push(b2_path.clone());
         }
-        None
     }
 
-    // タイムスタンプを除去するヘルパー関数
-    fn remove_timestamp(path: &str, timestamp: &str) -> String {
-        path.replace(&format!("/{}/", timestamp), "/")
+    // --split-size で分割したファイルは、パーツの代わりに元のファイルを復元対象にする
+    let parts = index.parts_for_prefix(from);
+    for (b2_path, file_parts) in &parts {
+        all_files.retain(|file| !file_parts.contains(file));
+        if !all_files.contains(b2_path) {
+            all_files.push(b2_path.clone());
+        }
+        if let Some(size) = file_parts.iter().map(|part| sizes.get(part).copied()).sum::<Option<u64>>() {
+            sizes.insert(b2_path.clone(), size);
+        }
+        if let Some(latest) = file_parts.iter().filter_map(|part| modified.get(part).copied()).max() {
+            modified.insert(b2_path.clone(), latest);
+        }
     }
 
-    // モードに応じてファイルをフィルタリング
-    let files_to_restore: Vec<(String, String)> = match mode {
-        RestoreMode::Latest => {
-            // タイムスタンプを除いた相対パスでグループ化
-            let mut file_groups: HashMap<String, Vec<String>> = HashMap::new();
-
-            for file in &all_files {
-                if let Some(timestamp) = extract_timestamp(file) {
-                    // タイムスタンプを除去した正規化パス
-                    let normalized = remove_timestamp(file, &timestamp);
-                    file_groups.entry(normalized).or_insert_with(Vec::new).push(file.clone());
-                }
-            }
+    if all_files.is_empty() {
+        println!("{}", "⚠️ 該当するファイルが見つかりませんでした".yellow());
+        return Ok(());
+    }
 
-            // 各グループで最新のタイムスタンプを持つファイルを選択
-            let mut selected_files = Vec::new();
-            for (_normalized_path, mut files) in file_groups {
-                files.sort_by(|a, b| b.cmp(a)); // 降順ソート（最新が先頭）
-                if let Some(latest_file) = files.first() {
-                    // タイムスタンプを除去した復元先パスを生成
-                    if let Some(timestamp) = extract_timestamp(latest_file) {
-                        let restore_path = remove_timestamp(latest_file, &timestamp);
-                        // from プレフィックスを除去
-                        let restore_path = restore_path.strip_prefix(from).unwrap_or(&restore_path).trim_start_matches('/');
-                        selected_files.push((latest_file.clone(), restore_path.to_string()));
-                    }
-                }
-            }
+    println!("  {} {} 個のファイルを検出", "✅".green(), all_files.len());
 
-            selected_files
+    // モードに応じてファイルをフィルタリング
+    let mut files_to_restore: Vec<(String, String)> = match mode {
+        RestoreMode::Latest => {
+            // タイムスタンプを除いた相対パスごとに最新のファイルを選び、from プレフィックスを除いて復元先にする
+            latest_per_path(&all_files)
+                .into_iter()
+                .map(|(file, normalized)| {
+                    let restore_path = normalized.strip_prefix(from).unwrap_or(&normalized).trim_start_matches('/');
+                    (file, restore_path.to_string())
+                })
+                .collect()
         }
-        RestoreMode::Version => {
+        RestoreMode::Range => {
+            // 期間内にアーカイブしたファイルから、Latest と同じく相対パスごとに最新のものを選ぶ
+            let in_range = all_files
+                .iter()
+                .filter(|file| extract_timestamp(file).is_some_and(|timestamp| range.contains(&timestamp)));
+            latest_per_path(in_range)
+                .into_iter()
+                .map(|(file, normalized)| {
+                    let restore_path = normalized.strip_prefix(from).unwrap_or(&normalized).trim_start_matches('/');
+                    (file, restore_path.to_string())
+                })
+                .collect()
+        }
+        RestoreMode::Version => {
             // 特定バージョンを指定
             let version_str = version.ok_or_else(|| anyhow::anyhow!("--version が指定されていません"))?;
 
@@ -1592,150 +3393,1611 @@ fn restore_archive(
         return Ok(());
     }
 
+    apply_dest_structure(&mut files_to_restore, dest_structure);
+
+    // アーカイブインデックスから SHA256 と tar にまとめたディレクトリを取得
+    let checksums = index.checksums_for_prefix(from);
+    let bundles = index.bundles_for_prefix(from);
+
+    let skipped = apply_overwrite_policy(
+        &mut files_to_restore,
+        Path::new(to),
+        overwrite,
+        &modified,
+        &checksums,
+    )?;
+    if files_to_restore.is_empty() {
+        println!("{}", "⚠️ 復元対象のファイルがありません".yellow());
+        return Ok(());
+    }
+
     // モード表示
     let mode_str = match mode {
         RestoreMode::Latest => "最新版のみ復元".to_string(),
         RestoreMode::Version => format!("バージョン {} を復元", version.unwrap()),
         RestoreMode::Raw => "タイムスタンプ付きでフル復元".to_string(),
+        RestoreMode::Range => format!(
+            "期間内の最新版を復元 ({} 〜 {})",
+            range.since.as_deref().unwrap_or("最初"),
+            range.until.as_deref().unwrap_or("最新")
+        ),
     };
     println!("\n{} {}", "📦 復元モード:".cyan(), mode_str);
-    println!("{} {} 個のファイルを復元", "📥".cyan(), files_to_restore.len());
+    let total_size = restore_total_size(&files_to_restore, &sizes);
+    match total_size {
+        Some(total) => println!(
+            "{} {} 個のファイルを復元 (合計: {})",
+            "📥".cyan(),
+            files_to_restore.len(),
+            kanri_core::utils::format_size(total).yellow().bold()
+        ),
+        None => println!("{} {} 個のファイルを復元", "📥".cyan(), files_to_restore.len()),
+    }
+    print_overwrite_skipped(skipped, overwrite);
+    if let Some(total) = total_size {
+        warn_restore_disk_space(Path::new(to), total);
+    }
 
     // Dry-run モード
     if dry_run {
         println!("\n{}", "ℹ  Dry-run モード: 実際のダウンロードは行いません".yellow());
         println!("\n{}", "ダウンロード予定:".cyan().bold());
-        for (remote_file, local_path) in &files_to_restore {
-            let full_local_path = std::path::Path::new(to).join(local_path);
-            println!("  {} -> {}", remote_file, full_local_path.display().to_string().green());
-        }
+        print_restore_plan(&files_to_restore, Path::new(to), &sizes);
         return Ok(());
     }
 
-    // 実際にダウンロード
-    println!("\n{}", "⬇️  B2 からダウンロード中...".cyan().bold());
+    let verify = verify.unwrap_or(!checksums.is_empty());
+    if verify {
+        if checksums.is_empty() {
+            println!(
+                "{} {}",
+                "⚠️".yellow(),
+                "アーカイブインデックスに SHA256 の記録がないため検証できません".yellow()
+            );
+        } else {
+            println!("{} {}", "🔒".cyan(), "ダウンロード後に SHA256 を検証します".dimmed());
+        }
+    }
+
+    // 検証しない場合は期待値を渡さない
+    let mut checksums = if verify { checksums } else { HashMap::new() };
+
+    // 共有の blob や前回のアーカイブを参照しているファイルは、実体のリモートパスからダウンロードする
+    let downloads: Vec<(String, String)> = files_to_restore
+        .iter()
+        .map(|(remote, local)| match blob_refs.get(remote) {
+            Some((blob, sha256)) => {
+                if verify {
+                    checksums.insert(blob.clone(), sha256.clone());
+                }
+                if let Some(size) = sizes.get(remote).copied() {
+                    sizes.insert(blob.clone(), size);
+                }
+                (blob.clone(), local.clone())
+            }
+            None => (remote.clone(), local.clone()),
+        })
+        .collect();
+    let has_blob_refs = files_to_restore.iter().any(|(remote, _)| blob_refs.contains_key(remote));
+    let has_parts = files_to_restore.iter().any(|(remote, _)| parts.contains_key(remote));
+
+    // ディレクトリをそのまま復元する場合は、ファイルごとではなくまとめてダウンロードする
+    // 一覧を取得した範囲（from）より上のディレクトリは、配下をすべて把握できていないため対象外
+    // 共有の blob を参照しているファイルはディレクトリの外にあるため、
+    // 分割したファイルはパーツの結合が必要なため、まとめてダウンロードしない
+    let bulk = archive::versioning::shared_directory(&files_to_restore, &all_files)
+        .filter(|(remote_dir, _)| format!("{}/", remote_dir).starts_with(from))
+        .filter(|_| !has_blob_refs && !has_parts);
+    let downloaded_in_bulk = match bulk {
+        Some((remote_dir, local_dir)) => download_directory_bulk(
+            storage_client.as_ref(),
+            &bucket,
+            &remote_dir,
+            Path::new(to),
+            &local_dir,
+            &files_to_restore,
+            &sizes,
+            jobs,
+            &checksums,
+            retries,
+        )?,
+        None => false,
+    };
+    if !downloaded_in_bulk {
+        download_files(
+            storage_client.as_ref(),
+            &bucket,
+            &downloads,
+            &sizes,
+            Path::new(to),
+            jobs,
+            &checksums,
+            &parts,
+            retries,
+        )?;
+    }
+
+    extract_restored_bundles(&files_to_restore, Path::new(to), &bundles)?;
+
+    println!("\n{}", "✅ 復元完了".green());
+
+    Ok(())
+}
+
+/// ダウンロードした tar（archive large-files --bundle）を元のディレクトリに展開し、tar を削除
+fn extract_restored_bundles(
+    files: &[(String, String)],
+    to: &Path,
+    bundles: &std::collections::HashSet<String>,
+) -> Result<()> {
+    use kanri_core::archive::bundle::{extract_bundle, unbundled_path};
+
+    for (_, local) in files.iter().filter(|(remote, _)| bundles.contains(remote)) {
+        let bundle_file = to.join(local);
+        let dest = unbundled_path(&bundle_file);
+        println!("{} {} を展開しています...", "📦".cyan(), dest.display());
+        extract_bundle(&bundle_file, &dest)?;
+        std::fs::remove_file(&bundle_file)?;
+    }
+    Ok(())
+}
+
+/// リモートのディレクトリをまとめてダウンロードし、SHA256 が一致しないファイルだけ個別に再ダウンロード
+///
+/// `local_dir`: `remote_dir` の復元先（`to` からの相対パス）。
+/// まとめてのダウンロード自体に失敗した場合は false を返す（呼び出し元でファイルごとにダウンロードする）
+#[allow(clippy::too_many_arguments)]
+fn download_directory_bulk(
+    storage_client: &dyn kanri_core::StorageClient,
+    bucket: &str,
+    remote_dir: &str,
+    to: &Path,
+    local_dir: &str,
+    files: &[(String, String)],
+    sizes: &std::collections::HashMap<String, u64>,
+    jobs: usize,
+    checksums: &std::collections::HashMap<String, String>,
+    retries: u32,
+) -> Result<bool> {
+    let local_dir = to.join(local_dir);
+    println!(
+        "\n{} {}",
+        "⬇️  ディレクトリをまとめてダウンロード中...".cyan().bold(),
+        format!("({} -> {})", remote_dir, local_dir.display()).dimmed()
+    );
+
+    let spinner = ProgressBar::new_spinner();
+    spinner.set_message(format!("{} 個のファイル", files.len()));
+    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+    let result = storage_client.download_directory(bucket, remote_dir, &local_dir);
+    spinner.finish_and_clear();
+
+    if let Err(e) = result {
+        println!(
+            "{} {}",
+            "⚠️".yellow(),
+            format!("まとめてのダウンロードに失敗したため、ファイルごとにダウンロードします: {}", e).yellow()
+        );
+        return Ok(false);
+    }
+
+    // 欠けている・SHA256 が一致しないファイルはファイルごとに再ダウンロード（再試行・検証付き）
+    let mut retry_files = Vec::new();
+    for (remote_file, local_path) in files {
+        let full_local_path = to.join(local_path);
+        let ok = match checksums.get(remote_file) {
+            Some(expected) => {
+                full_local_path.exists() && kanri_core::archive::verify_checksum(&full_local_path, expected)?
+            }
+            None => full_local_path.exists(),
+        };
+        if !ok {
+            retry_files.push((remote_file.clone(), local_path.clone()));
+        }
+    }
+
+    println!(
+        "{} {} 個のファイルをダウンロードしました",
+        "✅".green(),
+        (files.len() - retry_files.len()).to_string().green().bold()
+    );
+
+    if !retry_files.is_empty() {
+        println!(
+            "{} {}",
+            "⚠️".yellow(),
+            format!("{} 個のファイルが欠けているか SHA256 が一致しないため再ダウンロードします", retry_files.len())
+                .yellow()
+        );
+        download_files(
+            storage_client,
+            bucket,
+            &retry_files,
+            sizes,
+            to,
+            jobs,
+            checksums,
+            &std::collections::HashMap::new(),
+            retries,
+        )?;
+    }
+
+    Ok(true)
+}
+
+/// 復元するファイルの合計サイズ（サイズがわからないファイルがある場合は None）
+///
+/// `files`: (リモートのパス, 復元先ディレクトリからの相対パス)
+fn restore_total_size(
+    files: &[(String, String)],
+    sizes: &std::collections::HashMap<String, u64>,
+) -> Option<u64> {
+    files.iter().map(|(remote, _)| sizes.get(remote).copied()).sum()
+}
+
+/// 上書きの方針に従い、ダウンロードしないファイルを除外して件数を返す
+///
+/// `files`: (リモートのパス, 復元先ディレクトリからの相対パス)
+fn apply_overwrite_policy(
+    files: &mut Vec<(String, String)>,
+    to: &Path,
+    policy: kanri_core::archive::OverwritePolicy,
+    modified: &std::collections::HashMap<String, chrono::DateTime<chrono::Utc>>,
+    checksums: &std::collections::HashMap<String, String>,
+) -> Result<usize> {
+    let before = files.len();
+    let mut kept = Vec::with_capacity(before);
+    for (remote_file, local_path) in files.drain(..) {
+        if kanri_core::archive::should_download(
+            policy,
+            &to.join(&local_path),
+            modified.get(&remote_file).copied(),
+            checksums.get(&remote_file).map(String::as_str),
+        )? {
+            kept.push((remote_file, local_path));
+        }
+    }
+    *files = kept;
+    Ok(before - files.len())
+}
+
+/// 上書きの方針でスキップしたファイル数を表示
+fn print_overwrite_skipped(skipped: usize, policy: kanri_core::archive::OverwritePolicy) {
+    if skipped == 0 {
+        return;
+    }
+    let reason = match policy {
+        kanri_core::archive::OverwritePolicy::IfNewer => "ローカルの方が新しいか同じ内容",
+        _ => "復元先に既に存在",
+    };
+    println!(
+        "{} {}",
+        "⏭".cyan(),
+        format!(
+            "{} 個のファイルをスキップします（{}。上書きするには --overwrite always）",
+            skipped, reason
+        )
+        .dimmed()
+    );
+}
+
+/// 復元先の空き容量が足りない場合に警告
+fn warn_restore_disk_space(to: &Path, total_size: u64) {
+    let Ok(disk) = kanri_core::utils::disk_usage(to) else {
+        return;
+    };
+    if total_size > disk.free {
+        println!(
+            "{} {}",
+            "⚠️".yellow(),
+            format!(
+                "復元先の空き容量 ({}) が復元サイズ ({}) より少ないため、途中で失敗する可能性があります",
+                kanri_core::utils::format_size(disk.free),
+                kanri_core::utils::format_size(total_size)
+            )
+            .yellow()
+        );
+    }
+}
+
+/// Dry-run でダウンロード予定のファイルを表示（サイズがわかる場合はサイズも）
+fn print_restore_plan(
+    files: &[(String, String)],
+    to: &Path,
+    sizes: &std::collections::HashMap<String, u64>,
+) {
+    for (remote_file, local_path) in files {
+        let full_local_path = to.join(local_path);
+        let size_str = sizes
+            .get(remote_file)
+            .map(|&size| format!(" ({})", kanri_core::utils::format_size(size)))
+            .unwrap_or_default();
+        println!(
+            "  {} -> {}{}",
+            remote_file,
+            full_local_path.display().to_string().green(),
+            size_str.dimmed()
+        );
+    }
+}
+
+/// 復元先のディレクトリ構造を反映（flat ならファイル名だけにして同名を改名）
+///
+/// `files`: (リモートのパス, 復元先ディレクトリからの相対パス)
+fn apply_dest_structure(files: &mut [(String, String)], dest_structure: DestStructure) {
+    if let DestStructure::Flat = dest_structure {
+        // 改名される側が実行ごとに変わらないよう、リモートのパス順に並べる
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+        let local_paths: Vec<String> = files.iter().map(|(_, local)| local.clone()).collect();
+        let flattened = kanri_core::archive::versioning::flatten_paths(&local_paths);
+        for ((_, local), flat) in files.iter_mut().zip(flattened) {
+            *local = flat;
+        }
+    }
+}
+
+/// ファイルを並列でダウンロード（`checksums` に期待値があるファイルは SHA256 を検証）
+///
+/// `files`: (リモートのパス, 復元先ディレクトリからの相対パス)。
+/// すべてのファイルのサイズが `sizes` にある場合は、進捗をバイト数で表示する。
+/// `parts` にあるファイル（--split-size で分割したもの）はパーツをダウンロードして結合する
+#[allow(clippy::too_many_arguments)]
+fn download_files(
+    storage_client: &dyn kanri_core::StorageClient,
+    bucket: &str,
+    files: &[(String, String)],
+    sizes: &std::collections::HashMap<String, u64>,
+    to: &Path,
+    jobs: usize,
+    checksums: &std::collections::HashMap<String, String>,
+    parts: &std::collections::HashMap<String, Vec<String>>,
+    retries: u32,
+) -> Result<()> {
+    use std::sync::Mutex;
+
+    let jobs = jobs.max(1);
+    println!(
+        "\n{} {}",
+        "⬇️  B2 からダウンロード中...".cyan().bold(),
+        format!("(並列数: {})", jobs).dimmed()
+    );
+
+    let total_size = restore_total_size(files, sizes);
+    let (pb, template) = match total_size {
+        Some(total) => (
+            ProgressBar::new(total),
+            "{spinner:.green} [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta}) {msg}",
+        ),
+        None => (
+            ProgressBar::new(files.len() as u64),
+            "{spinner:.green} [{bar:40.cyan/blue}] {pos}/{len} {msg}",
+        ),
+    };
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template(template)
+            .unwrap()
+            .progress_chars("#>-"),
+    );
+
+    let queue = Mutex::new(files.iter());
+    let failures: Mutex<Vec<String>> = Mutex::new(Vec::new());
 
-    for (remote_file, local_path) in &files_to_restore {
-        let full_local_path = std::path::Path::new(to).join(local_path);
+    std::thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| loop {
+                let next = queue.lock().unwrap().next();
+                let Some((remote_file, local_path)) = next else {
+                    break;
+                };
+                let full_local_path = to.join(local_path);
+                pb.set_message(remote_file.clone());
+
+                let result = download_with_verify(
+                    storage_client,
+                    bucket,
+                    remote_file,
+                    &full_local_path,
+                    checksums.get(remote_file).map(String::as_str),
+                    parts.get(remote_file).map(Vec::as_slice),
+                    retries,
+                );
+
+                if let Err(e) = result {
+                    failures
+                        .lock()
+                        .unwrap()
+                        .push(format!("{}: {}", remote_file, e));
+                }
+                match total_size {
+                    Some(_) => pb.inc(sizes.get(remote_file).copied().unwrap_or(0)),
+                    None => pb.inc(1),
+                }
+            });
+        }
+    });
 
-        println!("  📥 {} -> {}", remote_file, full_local_path.display());
+    pb.finish_and_clear();
 
-        // 親ディレクトリを作成
-        if let Some(parent) = full_local_path.parent() {
-            std::fs::create_dir_all(parent)?;
+    let failures = failures.into_inner().unwrap();
+    if !failures.is_empty() {
+        eprintln!(
+            "\n{} {} 個のファイルのダウンロードに失敗しました",
+            "❌".red(),
+            failures.len()
+        );
+        for failure in &failures {
+            eprintln!("  {}", failure.red());
         }
+        anyhow::bail!("復元に失敗しました");
+    }
 
-        storage_client.download_file_by_name(&bucket, remote_file, &full_local_path)?;
-        println!("    {}", "✅ 完了".green());
+    println!(
+        "{} {} 個のファイルをダウンロードしました",
+        "✅".green(),
+        files.len().to_string().green().bold()
+    );
+
+    Ok(())
+}
+
+/// マニフェストに記載されたファイルを復元し、SHA256 を検証
+#[allow(clippy::too_many_arguments)]
+fn restore_from_manifest(
+    manifest_path: &Path,
+    to: &str,
+    dest_structure: DestStructure,
+    overwrite: kanri_core::archive::OverwritePolicy,
+    dry_run: bool,
+    jobs: usize,
+    verify: bool,
+    retries: u32,
+    bwlimit: Option<String>,
+    profile: Option<&str>,
+) -> Result<()> {
+    use kanri_core::{archive, config};
+
+    println!("{}", "📥 マニフェストから復元処理を開始...".cyan().bold());
+
+    let manifest = archive::import_manifest(manifest_path)?;
+    println!(
+        "  {} {} 個のファイル ({}) - アーカイブ {}",
+        "📋".cyan(),
+        manifest.files.len(),
+        kanri_core::utils::format_size(manifest.total_size()),
+        manifest.archive_id.dimmed()
+    );
+
+    if manifest.files.is_empty() {
+        println!("{}", "⚠️ 復元対象のファイルがありません".yellow());
+        return Ok(());
+    }
+
+    let mut files_to_restore: Vec<(String, String)> = manifest
+        .files
+        .iter()
+        .map(|entry| (entry.b2_path.clone(), manifest.restore_path(entry)))
+        .collect();
+    apply_dest_structure(&mut files_to_restore, dest_structure);
+    let sizes: std::collections::HashMap<String, u64> = manifest
+        .files
+        .iter()
+        .map(|entry| (entry.b2_path.clone(), entry.size))
+        .collect();
+    let manifest_checksums: std::collections::HashMap<String, String> = manifest
+        .files
+        .iter()
+        .map(|entry| (entry.b2_path.clone(), entry.sha256.clone()))
+        .collect();
+    let parts: std::collections::HashMap<String, Vec<String>> = manifest
+        .files
+        .iter()
+        .filter_map(|entry| Some((entry.b2_path.clone(), entry.parts.clone()?)))
+        .collect();
+
+    // マニフェストには更新日時がないため、if-newer は SHA256 だけで判定する
+    let skipped = apply_overwrite_policy(
+        &mut files_to_restore,
+        Path::new(to),
+        overwrite,
+        &std::collections::HashMap::new(),
+        &manifest_checksums,
+    )?;
+    print_overwrite_skipped(skipped, overwrite);
+    if files_to_restore.is_empty() {
+        println!("{}", "⚠️ 復元対象のファイルがありません".yellow());
+        return Ok(());
     }
+    warn_restore_disk_space(Path::new(to), restore_total_size(&files_to_restore, &sizes).unwrap_or(0));
+
+    if dry_run {
+        println!("\n{}", "ℹ  Dry-run モード: 実際のダウンロードは行いません".yellow());
+        println!("\n{}", "ダウンロード予定:".cyan().bold());
+        print_restore_plan(&files_to_restore, Path::new(to), &sizes);
+        return Ok(());
+    }
+
+    // 設定読み込み
+    let config = config::Config::load_profile(profile)?.with_bwlimit(bwlimit);
+    let bucket = config.get_bucket()?;
+    let backend = config.get_storage_backend();
+    warn_unsupported_bwlimit(&config);
+
+    let storage_client = config.create_storage_client()?;
+
+    // 認証
+    println!("{}", format!("🔐 {} 認証中...", backend.to_uppercase()).cyan());
+    storage_client.authorize()?;
+
+    let checksums = if verify {
+        println!("{} {}", "🔒".cyan(), "ダウンロード後に SHA256 を検証します".dimmed());
+        manifest_checksums
+    } else {
+        std::collections::HashMap::new()
+    };
+
+    download_files(
+        storage_client.as_ref(),
+        &bucket,
+        &files_to_restore,
+        &sizes,
+        Path::new(to),
+        jobs,
+        &checksums,
+        &parts,
+        retries,
+    )?;
 
     println!("\n{}", "✅ 復元完了".green());
 
     Ok(())
 }
 
-fn list_archives() -> Result<()> {
+/// アーカイブのマニフェストを stdout に出力
+fn export_archive_manifest(id: &str) -> Result<()> {
     use kanri_core::archive;
 
     let index = archive::ArchiveIndex::load()?;
+    let archive = index
+        .find_by_id(id)
+        .ok_or_else(|| anyhow::anyhow!("アーカイブが見つかりません: {}", id))?;
 
-    if index.archives.is_empty() {
-        println!("{}", "ℹ アーカイブが見つかりませんでした".yellow());
+    // リダイレクト先を汚さないよう、補足は stderr に出す
+    let skipped_dirs = archive.items.iter().filter(|item| item.is_dir).count();
+    if skipped_dirs > 0 {
+        eprintln!(
+            "{} {}",
+            "⚠".yellow(),
+            format!(
+                "ディレクトリ {} 件はファイルごとの SHA256 がないためマニフェストに含まれません",
+                skipped_dirs
+            )
+            .yellow()
+        );
+    }
+
+    println!("{}", archive::export_manifest(archive)?);
+
+    Ok(())
+}
+
+/// ファイルをダウンロードし、期待値があれば SHA256 を検証（不一致時は再ダウンロード）
+///
+/// `parts` が指定された場合は、各パーツをダウンロードして結合したものを検証する
+fn download_with_verify(
+    storage_client: &dyn kanri_core::StorageClient,
+    bucket: &str,
+    remote_file: &str,
+    local_path: &Path,
+    expected_sha256: Option<&str>,
+    parts: Option<&[String]>,
+    retries: u32,
+) -> Result<()> {
+    // 親ディレクトリを作成
+    if let Some(parent) = local_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    for attempt in 0..=retries {
+        match parts {
+            Some(parts) => download_parts(storage_client, bucket, parts, local_path)?,
+            None => storage_client.download_file_by_name(bucket, remote_file, local_path)?,
+        }
+
+        let Some(expected) = expected_sha256 else {
+            return Ok(());
+        };
+
+        if kanri_core::archive::verify_checksum(local_path, expected)? {
+            return Ok(());
+        }
+
+        if attempt < retries {
+            std::fs::remove_file(local_path)?;
+        }
+    }
+
+    anyhow::bail!(
+        "SHA256 が一致しません（{} 回再試行しました）",
+        retries
+    )
+}
+
+/// 分割してアップロードしたパーツを 1 つずつ復元先の隣にダウンロードし、`local_path` に追記する
+///
+/// ダウンロードしたパーツは追記のたびに削除する（ディスクに置くのは常に 1 パーツ分）。
+/// 失敗した場合は書きかけの `local_path` も削除する
+fn download_parts(
+    storage_client: &dyn kanri_core::StorageClient,
+    bucket: &str,
+    parts: &[String],
+    local_path: &Path,
+) -> Result<()> {
+    use kanri_core::archive::{append_part, part_path};
+
+    if let Some(parent) = local_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::File::create(local_path)?;
+    let result = parts.iter().enumerate().try_for_each(|(i, part)| {
+        let local_part = PathBuf::from(part_path(&local_path.to_string_lossy(), i));
+        let result = storage_client
+            .download_file_by_name(bucket, part, &local_part)
+            .and_then(|_| append_part(&local_part, local_path));
+        let _ = std::fs::remove_file(&local_part);
+        result
+    });
+    if result.is_err() {
+        let _ = std::fs::remove_file(local_path);
+    }
+
+    Ok(result?)
+}
+
+fn list_restore_versions(from: &str, profile: Option<&str>) -> Result<()> {
+    use kanri_core::{archive, config};
+
+    println!("{}", "📋 バージョン一覧を取得中...".cyan().bold());
+
+    // 設定読み込み
+    let config = config::Config::load_profile(profile)?;
+    let bucket = config.get_bucket()?;
+    let backend = config.get_storage_backend();
+
+    let storage_client = config.create_storage_client()?;
+
+    // 認証
+    println!("{}", format!("🔐 {} 認証中...", backend.to_uppercase()).cyan());
+    storage_client.authorize()?;
+
+    let all_files = storage_client.list_files(&bucket, from)?;
+    let versions = archive::versioning::summarize_versions(&all_files);
+
+    if versions.is_empty() {
+        println!("{}", "⚠️ バージョンが見つかりませんでした".yellow());
         return Ok(());
     }
 
+    // サイズはローカルのアーカイブインデックスから取得
+    let index = archive::ArchiveIndex::load()?;
+    let prefix = from.trim_end_matches('/');
+
     println!(
-        "{}",
-        format!("📦 アーカイブ一覧 ({} 件)", index.archives.len())
+        "\n{}",
+        format!("📦 利用可能なバージョン ({} 件)", versions.len())
             .cyan()
             .bold()
     );
 
-    for archive in &index.archives {
-        println!("\n{}", "─".repeat(80).dimmed());
-        println!("ID:         {}", archive.id.cyan().bold());
+    for version in &versions {
+        let destination = format!("{}/{}", prefix, version.timestamp);
+        let size_str = index
+            .find_by_destination(&destination)
+            .map(|a| kanri_core::utils::format_size(a.total_size))
+            .unwrap_or_else(|| "-".to_string());
+
         println!(
-            "作成日時:   {}",
-            archive.created_at.format("%Y-%m-%d %H:%M:%S")
+            "  {} - {} ファイル - {}",
+            version.timestamp.bright_blue(),
+            version.file_count.to_string().cyan(),
+            size_str.yellow()
         );
-        println!("クリーナー: {}", archive.cleaner);
-        println!("保存先:     {}", archive.destination);
-        println!("アイテム数: {}", archive.items.len());
+    }
+
+    println!(
+        "\n{} {}",
+        "💡".cyan(),
+        format!(
+            "復元するには: kanri restore --from {} --mode version --version <timestamp>",
+            from
+        )
+        .dimmed()
+    );
+
+    Ok(())
+}
+
+fn delete_archive(id: &str, keep_remote: bool, dry_run: bool, profile: Option<&str>) -> Result<()> {
+    use kanri_core::{archive, config};
+
+    println!("{}", "🗑️  アーカイブ削除処理を開始...".cyan().bold());
+
+    let index = archive::ArchiveIndex::load()?;
+    let archive_record = index
+        .find_by_id(id)
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("アーカイブが見つかりません: {}", id))?;
+
+    println!("ID:         {}", archive_record.id.cyan().bold());
+    println!("保存先:     {}", archive_record.destination);
+    println!("アイテム数: {}", archive_record.items.len());
+    println!(
+        "合計サイズ: {}",
+        kanri_core::utils::format_size(archive_record.total_size)
+    );
+
+    if keep_remote {
+        println!(
+            "\n{} {}",
+            "ℹ".cyan(),
+            "--keep-remote: リモートのファイルは削除しません".dimmed()
+        );
+    } else {
+        // 設定読み込み
+        let config = config::Config::load_profile(profile)?;
+        let bucket = config.get_bucket()?;
+        let backend = config.get_storage_backend();
+
+        let storage_client = config.create_storage_client()?;
+
+        // 認証
+        println!("{}", format!("🔐 {} 認証中...", backend.to_uppercase()).cyan());
+        storage_client.authorize()?;
+
+        // 削除対象のリモートファイルを収集（ディレクトリは配下のファイルを列挙）
+        let mut remote_files = Vec::new();
+        for item in &archive_record.items {
+            if item.is_dir {
+                remote_files.extend(storage_client.list_files(&bucket, &item.b2_path)?);
+            } else {
+                remote_files.extend(item.remote_paths());
+            }
+        }
+        remote_files.sort();
+        remote_files.dedup();
+
+        // 差分アーカイブ（--incremental）や --dedup で他のアーカイブが参照しているファイルは残す
+        let referenced = index.paths_referenced_by_others(&archive_record.id);
+        let total_files = remote_files.len();
+        remote_files.retain(|remote_file| !referenced.contains(remote_file));
+        if remote_files.len() < total_files {
+            println!(
+                "\n{} {}",
+                "ℹ".cyan(),
+                format!(
+                    "{} 件のファイルは他のアーカイブから参照されているため残します",
+                    total_files - remote_files.len()
+                )
+                .dimmed()
+            );
+        }
+
+        if dry_run {
+            println!("\n{}", "ℹ Dry-run モード: 実際の削除は行いません".yellow());
+            println!("\n{}", "削除予定:".cyan().bold());
+            for remote_file in &remote_files {
+                println!("  {}", remote_file.red());
+            }
+            println!("  {} (インデックス)", archive_record.id.red());
+            return Ok(());
+        }
+
+        println!(
+            "\n{}",
+            format!("🗑️  {} からファイルを削除中...", backend.to_uppercase())
+                .red()
+                .bold()
+        );
+
+        let pb = ProgressBar::new(remote_files.len() as u64);
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template("{spinner:.green} [{bar:40.cyan/blue}] {pos}/{len} {msg}")
+                .unwrap()
+                .progress_chars("#>-"),
+        );
+
+        for remote_file in &remote_files {
+            pb.set_message(remote_file.clone());
+            storage_client.delete_file(&bucket, remote_file)?;
+            pb.inc(1);
+        }
+
+        pb.finish_and_clear();
+
+        // 削除した blob は --dedup の参照先から外す
+        archive::blob_index::BlobIndex::update(|blobs| blobs.remove_paths(&remote_files.iter().cloned().collect()))?;
+
+        println!(
+            "{} {} 個のリモートファイルを削除しました",
+            "✅".green(),
+            remote_files.len().to_string().green().bold()
+        );
+    }
+
+    if dry_run {
+        println!("\n{}", "ℹ Dry-run モード: 実際の削除は行いません".yellow());
+        println!("\n{}", "削除予定:".cyan().bold());
+        println!("  {} (インデックス)", archive_record.id.red());
+        return Ok(());
+    }
+
+    archive::ArchiveIndex::update(|index| index.remove_archive(id))?;
+
+    println!(
+        "\n{} アーカイブを削除しました (ID: {})",
+        "✅".green(),
+        archive_record.id.green().bold()
+    );
+
+    Ok(())
+}
+
+fn list_archives() -> Result<()> {
+    use kanri_core::archive;
+
+    let index = archive::ArchiveIndex::load()?;
+
+    if index.archives.is_empty() {
+        println!("{}", "ℹ アーカイブが見つかりませんでした".yellow());
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        format!("📦 アーカイブ一覧 ({} 件)", index.archives.len())
+            .cyan()
+            .bold()
+    );
+
+    for archive in &index.archives {
+        println!("\n{}", "─".repeat(80).dimmed());
+        println!("ID:         {}", archive.id.cyan().bold());
+        println!(
+            "作成日時:   {}",
+            archive.created_at.format("%Y-%m-%d %H:%M:%S")
+        );
+        println!("クリーナー: {}", archive.cleaner);
+        println!("保存先:     {}", archive.destination);
+        if !archive.source_path.as_os_str().is_empty() {
+            println!("アーカイブ元: {}", archive.source_path.display());
+        }
+        if let Some(parent_id) = &archive.parent_id {
+            println!("差分元:     {}", parent_id);
+        }
+        println!("アイテム数: {}", archive.items.len());
+        println!("ファイル数: {}", archive.file_count());
+        if let Some(expire_at) = archive.expire_at() {
+            let label = expire_at.format("%Y-%m-%d %H:%M:%S").to_string();
+            if expire_at <= chrono::Utc::now() {
+                println!("自動削除:   {} {}", label, "(削除済み)".red());
+            } else {
+                println!("自動削除:   {}", label.yellow());
+            }
+        }
+        for item in &archive.items {
+            let count_label = match item.file_count {
+                Some(count) => format!("dir, {} ファイル", count),
+                None => "file".to_string(),
+            };
+            let linked_label = if item.blob_ref.is_some() {
+                format!(" {}", "(同じ内容のファイルを参照)".dimmed())
+            } else if archive.is_linked(item) {
+                format!(" {}", "(前回を参照)".dimmed())
+            } else {
+                String::new()
+            };
+            println!(
+                "  • {} ({}) - {}{}",
+                item.local_path.display(),
+                count_label.dimmed(),
+                kanri_core::utils::format_size(item.size),
+                linked_label
+            );
+        }
+        println!(
+            "合計サイズ: {}",
+            kanri_core::utils::format_size(archive.total_size)
+        );
+    }
+
+    Ok(())
+}
+
+fn list_remote_archives(from: &str, rebuild_index: bool, profile: Option<&str>) -> Result<()> {
+    use kanri_core::{archive, config};
+
+    println!("{}", "📋 リモートのファイル一覧を取得中...".cyan().bold());
+
+    // 設定読み込み
+    let config = config::Config::load_profile(profile)?;
+    let bucket = config.get_bucket()?;
+    let backend = config.get_storage_backend();
+
+    let storage_client = config.create_storage_client()?;
+
+    // 認証
+    println!("{}", format!("🔐 {} 認証中...", backend.to_uppercase()).cyan());
+    storage_client.authorize()?;
+
+    let files = storage_client.list_files_detailed(&bucket, from)?;
+    let remote_archives = archive::versioning::reconstruct_archives(&files);
+
+    if remote_archives.is_empty() {
+        println!("{}", "ℹ アーカイブが見つかりませんでした".yellow());
+        return Ok(());
+    }
+
+    println!(
+        "\n{}",
+        format!("📦 リモートのアーカイブ一覧 ({} 件)", remote_archives.len())
+            .cyan()
+            .bold()
+    );
+
+    for remote in &remote_archives {
+        let created_at = remote
+            .created_at()
+            .map(|t| {
+                t.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_else(|| remote.timestamp.clone());
+        let size_str = remote
+            .total_size()
+            .map(kanri_core::utils::format_size)
+            .unwrap_or_else(|| "-".to_string());
+
+        println!("\n{}", "─".repeat(80).dimmed());
+        println!("作成日時:   {}", created_at);
+        println!("保存先:     {}", remote.destination.cyan().bold());
+        println!("ファイル数: {}", remote.file_count());
+        println!("合計サイズ: {}", size_str.yellow());
+    }
+
+    if !rebuild_index {
+        println!(
+            "\n{} {}",
+            "💡".cyan(),
+            format!(
+                "インデックスに書き戻すには: kanri list-archives --remote --from {:?} --rebuild-index",
+                from
+            )
+            .dimmed()
+        );
+        return Ok(());
+    }
+
+    let added = archive::ArchiveIndex::update(|index| index.merge_remote(&remote_archives))?;
+
+    println!(
+        "\n{} {}",
+        "✅".green(),
+        format!(
+            "インデックスに {} 件を追加しました（既存 {} 件はそのまま）",
+            added,
+            remote_archives.len() - added
+        )
+        .green()
+        .bold()
+    );
+
+    Ok(())
+}
+
+fn show_history(limit: usize, format: ReportFormat) -> Result<()> {
+    use chrono::{Datelike, Local, TimeZone, Utc};
+    use kanri_core::{history, utils};
+
+    let entries = history::load_entries()?;
+
+    match format {
+        ReportFormat::Human => {}
+        ReportFormat::Json => {
+            let recent: Vec<_> = entries.iter().rev().take(limit).collect();
+            println!("{}", serde_json::to_string_pretty(&recent)?);
+            return Ok(());
+        }
+        ReportFormat::Csv => {
+            println!(
+                "{}",
+                utils::csv_row(&["timestamp", "command", "path_count", "bytes_freed"])
+            );
+            for entry in entries.iter().rev().take(limit) {
+                println!(
+                    "{}",
+                    utils::csv_row(&[
+                        entry.timestamp.to_rfc3339(),
+                        entry.command.clone(),
+                        entry.paths.len().to_string(),
+                        entry.bytes_freed.to_string(),
+                    ])
+                );
+            }
+            return Ok(());
+        }
+    }
+
+    if entries.is_empty() {
+        println!("{}", "ℹ 実行履歴が見つかりませんでした".yellow());
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        format!("📜 実行履歴 (直近 {} 件)", limit.min(entries.len()))
+            .cyan()
+            .bold()
+    );
+    println!();
+
+    for entry in entries.iter().rev().take(limit) {
+        println!(
+            "  {} {} - {} ({} 件)",
+            entry
+                .timestamp
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+                .dimmed(),
+            entry.command.bright_blue(),
+            utils::format_size(entry.bytes_freed).yellow(),
+            entry.paths.len()
+        );
+    }
+
+    // 今月と全期間の集計
+    let now = Local::now();
+    let month_start = Local
+        .with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0)
+        .single()
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(Utc::now);
+    let this_month = history::summarize_since(&entries, month_start);
+    let all_time = history::summarize_since(&entries, chrono::DateTime::<Utc>::MIN_UTC);
+
+    println!();
+    println!(
+        "{} 今月: {} 回の実行で {} を解放",
+        "📊".cyan(),
+        this_month.runs.to_string().green().bold(),
+        kanri_core::utils::format_size(this_month.bytes_freed).green().bold()
+    );
+    println!(
+        "{} 全期間: {} 回の実行で {} を解放",
+        "📊".cyan(),
+        all_time.runs.to_string().green().bold(),
+        kanri_core::utils::format_size(all_time.bytes_freed).green().bold()
+    );
+
+    Ok(())
+}
+
+fn show_config(profile: Option<&str>) -> Result<()> {
+    use kanri_core::config;
+
+    let config = config::Config::load_profile(profile)?;
+
+    println!("{}", "⚙️ 現在の設定".cyan().bold());
+    println!();
+
+    if !config.profiles.is_empty() {
+        println!("{}:", "Profiles".green().bold());
+        for name in config.profiles.keys() {
+            let marker = if config.active_profile() == Some(name.as_str()) {
+                "*".green().bold()
+            } else {
+                " ".normal()
+            };
+            println!("  {} {}", marker, name);
+        }
+        println!(
+            "  使用中: {}",
+            config.active_profile().unwrap_or("(トップレベル)").cyan()
+        );
+        println!();
+    }
+
+    if let Some(b2) = config.b2_config() {
+        println!("{}:", "B2 Configuration".green().bold());
+        println!("  Bucket: {}", b2.bucket);
+        println!(
+            "  Application Key ID: {}",
+            b2.application_key_id
+                .as_ref()
+                .map(|_| "****")
+                .unwrap_or("(環境変数)")
+        );
+        println!(
+            "  Application Key: {}",
+            b2.application_key
+                .as_ref()
+                .map(|_| "****")
+                .unwrap_or("(環境変数)")
+        );
+        println!(
+            "  Max Retries: {}",
+            b2.max_retries
+                .unwrap_or(kanri_core::b2::RetryPolicy::default().max_retries)
+        );
+    } else {
+        println!("{}", "B2 が設定されていません".yellow());
+        println!("設定するには: {}", "kanri config init-b2 --bucket <bucket-name>".cyan());
+    }
+
+    if let Some(storage) = config.storage_config() {
+        println!();
+        println!("{}:", "Storage Configuration".green().bold());
+        println!("  Backend: {}", storage.backend);
+        if let Some(remote) = &storage.rclone_remote {
+            println!("  Rclone Remote: {}", remote);
+        }
+        if let Some(bwlimit) = &storage.bwlimit {
+            println!("  Bandwidth Limit: {}", bwlimit);
+        }
+    }
+
+    if let Some(gcs) = config.gcs_config() {
+        println!();
+        println!("{}:", "GCS Configuration".green().bold());
+        println!("  Bucket: {}", gcs.bucket.cyan());
+        println!(
+            "  Credentials: {}",
+            gcs.credentials_file
+                .as_ref()
+                .map(|path| path.display().to_string())
+                .unwrap_or_else(|| "(環境変数)".to_string())
+        );
+    }
+
+    if let Some(cache) = &config.cache {
+        println!();
+        println!("{}:", "Cache Configuration".green().bold());
+        println!("  Safe patterns: {}", cache.safe_patterns.join(", "));
+        println!("  Unsafe patterns: {}", cache.unsafe_patterns.join(", "));
+    }
+
+    println!();
+    println!(
+        "設定ファイル: {}",
+        config::Config::config_path()?.display()
+    );
+
+    Ok(())
+}
+
+fn init_b2_config(
+    bucket: String,
+    key_id: Option<String>,
+    key: Option<String>,
+    profile: Option<&str>,
+) -> Result<()> {
+    use kanri_core::config;
+
+    // 不正な値（空のバケット名など）を修正できるよう、検証せずに読み込む
+    let mut config = config::Config::load_unchecked()?;
+
+    // --profile 指定時はそのプロファイルに保存（なければ作成）
+    if let Some(name) = profile {
+        config.profiles.entry(name.to_string()).or_default();
+    }
+    config.select_profile(profile)?;
+
+    let current = config.b2_config().cloned();
+    *config.b2_config_mut() = Some(config::B2Config {
+        bucket: bucket.clone(),
+        application_key_id: key_id,
+        application_key: key,
+        max_retries: current.as_ref().and_then(|b2| b2.max_retries),
+        sync_threads: current.as_ref().and_then(|b2| b2.sync_threads),
+        upload_threads: current.as_ref().and_then(|b2| b2.upload_threads),
+        file_threads: current.as_ref().and_then(|b2| b2.file_threads),
+    });
+
+    config.save_with_template()?;
+
+    println!(
+        "{}",
+        "✅ B2 設定を保存しました".green().bold()
+    );
+    if let Some(name) = config.active_profile() {
+        println!("  Profile: {}", name.cyan());
+    }
+    println!("  Bucket: {}", bucket.cyan());
+    println!();
+    println!("{}", "💡 認証情報は環境変数で設定することを推奨します:".yellow());
+    println!("  export B2_APPLICATION_KEY_ID=<your-key-id>");
+    println!("  export B2_APPLICATION_KEY=<your-key>");
+
+    Ok(())
+}
+
+fn edit_config() -> Result<()> {
+    use kanri_core::config;
+
+    let path = config::Config::config_path()?;
+
+    // 未作成ならテンプレートを書き出してから開く
+    if !path.exists() {
+        config::Config::default().save_with_template()?;
+        println!(
+            "{} {}",
+            "📝 設定ファイルを作成しました:".cyan(),
+            path.display()
+        );
+    }
+
+    loop {
+        open_in_editor(&path)?;
+
+        match config::Config::load() {
+            Ok(_) => {
+                println!("{}", "✅ 設定ファイルを保存しました".green().bold());
+                println!("  {}", path.display());
+                return Ok(());
+            }
+            Err(e) => {
+                eprintln!("{}", "❌ 設定ファイルの形式が正しくありません".red().bold());
+                eprintln!("{}", e);
+
+                output::write_prompt(&format!("\n{} 再編集しますか? (Y/n): ", "⚠".yellow().bold()))?;
+
+                let mut input = String::new();
+                io::stdin().read_line(&mut input)?;
+                if input.trim().eq_ignore_ascii_case("n") {
+                    anyhow::bail!("設定ファイルが不正なままです: {}", path.display());
+                }
+            }
+        }
+    }
+}
+
+/// ファイルをエディタで開いて終了を待つ
+///
+/// $VISUAL / $EDITOR（引数付きも可: `code --wait` など）を優先し、未設定なら vi、
+/// vi が見つからない macOS では `open -W -t` を使う
+fn open_in_editor(path: &Path) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty());
+
+    let status = match editor {
+        Some(editor) => {
+            let mut parts = editor.split_whitespace();
+            let program = parts.next().unwrap_or("vi");
+            std::process::Command::new(program)
+                .args(parts)
+                .arg(path)
+                .status()
+                .map_err(|e| anyhow::anyhow!("エディタ `{}` を起動できません: {}", editor, e))?
+        }
+        None => match std::process::Command::new("vi").arg(path).status() {
+            Ok(status) => status,
+            Err(e) if e.kind() == io::ErrorKind::NotFound && cfg!(target_os = "macos") => {
+                std::process::Command::new("open")
+                    .args(["-W", "-t"])
+                    .arg(path)
+                    .status()?
+            }
+            Err(e) => {
+                return Err(anyhow::anyhow!(
+                    "エディタを起動できません（$EDITOR を設定してください）: {}",
+                    e
+                ))
+            }
+        },
+    };
+
+    if !status.success() {
+        anyhow::bail!("エディタが異常終了しました: {}", status);
+    }
+
+    Ok(())
+}
+
+/// doctor の結果
+#[derive(Debug, Serialize)]
+struct DoctorReport {
+    kanri_version: &'static str,
+    os: &'static str,
+    arch: &'static str,
+    tools: Vec<ToolStatus>,
+    config: ConfigStatus,
+    cache_dirs: Vec<CacheDirStatus>,
+}
+
+/// 外部ツールのバージョン（見つからなければ None）
+#[derive(Debug, Serialize)]
+struct ToolStatus {
+    name: &'static str,
+    version: Option<String>,
+}
+
+/// 設定ファイルの場所と読み込み結果
+#[derive(Debug, Serialize)]
+struct ConfigStatus {
+    path: Option<PathBuf>,
+    exists: bool,
+    /// 読み込み・検証のエラー（正常なら None）
+    error: Option<String>,
+}
+
+/// 環境変数などから決まるキャッシュの場所
+#[derive(Debug, Serialize)]
+struct CacheDirStatus {
+    name: &'static str,
+    path: Option<PathBuf>,
+    /// 決まり方（"環境変数 GOPATH" など）
+    source: Option<String>,
+    exists: bool,
+}
+
+/// 環境・ツールのバージョン・設定・キャッシュの場所を集める
+fn collect_doctor_report(profile: Option<&str>) -> DoctorReport {
+    use kanri_core::{b2, docker, go, gradle, rclone, rust, xcode};
+
+    // gradle --version などは JVM の起動で時間がかかるため、並行して調べる
+    type VersionProbe = fn() -> Option<String>;
+    let probes: [(&'static str, VersionProbe); 6] = [
+        ("b2", b2::version),
+        ("rclone", rclone::version),
+        ("docker", docker::version),
+        ("cargo", rust::cargo_version),
+        ("go", go::version),
+        ("gradle", gradle::version),
+    ];
+    let tools = std::thread::scope(|scope| {
+        let handles: Vec<_> = probes
+            .iter()
+            .map(|(name, probe)| (*name, scope.spawn(probe)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|(name, handle)| ToolStatus {
+                name,
+                version: handle.join().ok().flatten(),
+            })
+            .collect()
+    });
+
+    let path = kanri_core::config::Config::config_path().ok();
+    let config = ConfigStatus {
+        exists: path.as_ref().is_some_and(|path| path.exists()),
+        error: kanri_core::config::Config::load_profile(profile).err().map(|e| e.to_string()),
+        path,
+    };
+
+    let cache_dir = |name, resolved: Option<(PathBuf, kanri_core::utils::DirSource)>| {
+        let (path, source) = resolved.unzip();
+        CacheDirStatus {
+            name,
+            exists: path.as_ref().is_some_and(|path| path.exists()),
+            path,
+            source: source.map(|source| source.to_string()),
+        }
+    };
+    let cache_dirs = vec![
+        cache_dir("GOMODCACHE", go::resolve_go_mod_cache_dir()),
+        cache_dir("GRADLE_USER_HOME", gradle::resolve_gradle_user_home()),
+        cache_dir(
+            "DerivedData",
+            xcode::derived_data_dir().map(|dir| (dir, kanri_core::utils::DirSource::Default)),
+        ),
+    ];
+
+    DoctorReport {
+        kanri_version: env!("CARGO_PKG_VERSION"),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        tools,
+        config,
+        cache_dirs,
+    }
+}
+
+fn run_doctor(json: bool, profile: Option<&str>) -> Result<()> {
+    if !json {
+        println!("{}", "🩺 環境を確認中...".cyan().bold());
+    }
+    let report = collect_doctor_report(profile);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!();
+    println!("kanri:  {}", report.kanri_version);
+    println!("OS:     {} ({})", report.os, report.arch);
+
+    println!("\n{}", "🔧 ツール".cyan().bold());
+    for tool in &report.tools {
+        match &tool.version {
+            Some(version) => println!("  {} {:<8} {}", "✅".green(), tool.name, version.dimmed()),
+            None => println!("  {} {:<8} {}", "➖".dimmed(), tool.name, "見つかりません".dimmed()),
+        }
+    }
+
+    println!("\n{}", "📄 設定ファイル".cyan().bold());
+    let config_path = report
+        .config
+        .path
+        .as_ref()
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|| "（HOME が未設定）".to_string());
+    match &report.config.error {
+        Some(error) => println!("  {} {} {}", "❌".red(), config_path, error.red()),
+        None if report.config.exists => println!("  {} {}", "✅".green(), config_path),
+        None => println!("  {} {} {}", "➖".dimmed(), config_path, "（未作成: 既定値を使用）".dimmed()),
+    }
+
+    println!("\n{}", "📂 キャッシュの場所".cyan().bold());
+    for dir in &report.cache_dirs {
+        let Some(path) = &dir.path else {
+            println!("  {:<16} {}", dir.name, "（解決できません）".dimmed());
+            continue;
+        };
+        let source = dir.source.as_deref().unwrap_or_default();
+        let exists = if dir.exists { "" } else { "・存在しない" };
         println!(
-            "合計サイズ: {}",
-            kanri_core::utils::format_size(archive.total_size)
+            "  {:<16} {} {}",
+            dir.name,
+            path.display(),
+            format!("（{}{}）", source, exists).dimmed()
         );
     }
 
     Ok(())
 }
 
-fn show_config() -> Result<()> {
-    use kanri_core::config;
+/// 検証結果を 1 行表示し、失敗なら failures を数える
+fn print_check(label: &str, result: std::result::Result<String, String>, failures: &mut usize) {
+    match result {
+        Ok(detail) if detail.is_empty() => println!("  {} {}", "✅".green(), label),
+        Ok(detail) => println!("  {} {} {}", "✅".green(), label, detail.dimmed()),
+        Err(e) => {
+            *failures += 1;
+            println!("  {} {} {}", "❌".red(), label, e.red());
+        }
+    }
+}
 
-    let config = config::Config::load()?;
+fn validate_config(profile: Option<&str>) -> Result<()> {
+    use kanri_core::{b2, config, rclone};
 
-    println!("{}", "⚙️ 現在の設定".cyan().bold());
+    println!("{}", "🩺 設定を検証中...".cyan().bold());
     println!();
 
-    if let Some(b2) = &config.b2 {
-        println!("{}:", "B2 Configuration".green().bold());
-        println!("  Bucket: {}", b2.bucket);
-        println!(
-            "  Application Key ID: {}",
-            b2.application_key_id
-                .as_ref()
-                .map(|_| "****")
-                .unwrap_or("(環境変数)")
-        );
-        println!(
-            "  Application Key: {}",
-            b2.application_key
-                .as_ref()
-                .map(|_| "****")
-                .unwrap_or("(環境変数)")
-        );
-    } else {
-        println!("{}", "B2 が設定されていません".yellow());
-        println!("設定するには: {}", "kanri config init-b2 --bucket <bucket-name>".cyan());
-    }
-
-    println!();
-    println!(
-        "設定ファイル: {}",
-        config::Config::config_path()?.display()
-    );
+    let mut failures = 0;
 
-    Ok(())
-}
+    // 設定ファイルの読み込み（失敗したら以降は検証できない）
+    let config = match config::Config::load_profile(profile) {
+        Ok(config) => {
+            let path = config::Config::config_path()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default();
+            print_check("設定ファイルの読み込み", Ok(path), &mut failures);
+            config
+        }
+        Err(e) => {
+            print_check("設定ファイルの読み込み", Err(e.to_string()), &mut failures);
+            anyhow::bail!("設定の検証に失敗しました");
+        }
+    };
 
-fn init_b2_config(bucket: String, key_id: Option<String>, key: Option<String>) -> Result<()> {
-    use kanri_core::config;
+    if let Some(name) = config.active_profile() {
+        print_check("プロファイル", Ok(name.to_string()), &mut failures);
+    }
 
-    let mut config = config::Config::load().unwrap_or_default();
+    let backend = config.get_storage_backend();
 
-    config.b2 = Some(config::B2Config {
-        bucket: bucket.clone(),
-        application_key_id: key_id,
-        application_key: key,
-    });
+    // バックエンドの CLI と認証情報
+    let settings = match backend.as_str() {
+        "b2" => {
+            let installed = b2::B2Client::is_installed();
+            print_check(
+                "B2 CLI のインストール",
+                installed
+                    .then(String::new)
+                    .ok_or_else(|| "pip install b2 または brew install b2-tools".to_string()),
+                &mut failures,
+            );
+            print_check(
+                "B2 バケット",
+                config.get_b2_bucket().map_err(|e| e.to_string()),
+                &mut failures,
+            );
+            let credentials = config.get_b2_credentials();
+            let ok = credentials.is_ok();
+            print_check(
+                "B2 認証情報",
+                credentials
+                    .map(|(id, _)| format!("Key ID: {}***", id.chars().take(8).collect::<String>()))
+                    .map_err(|e| e.to_string()),
+                &mut failures,
+            );
+            installed && ok
+        }
+        "rclone" => {
+            let installed = rclone::RcloneClient::is_installed();
+            print_check(
+                "Rclone CLI のインストール",
+                installed
+                    .then(String::new)
+                    .ok_or_else(|| "brew install rclone".to_string()),
+                &mut failures,
+            );
+            let remote = config
+                .storage_config()
+                .and_then(|s| s.rclone_remote.clone())
+                .ok_or_else(|| "storage.rclone_remote が設定されていません".to_string());
+            let ok = remote.is_ok();
+            print_check("Rclone リモート", remote, &mut failures);
+            installed && ok
+        }
+        "gcs" => {
+            let bucket = config.get_bucket();
+            let bucket_ok = bucket.is_ok();
+            print_check("GCS バケット", bucket.map_err(|e| e.to_string()), &mut failures);
+            let credentials = config
+                .get_gcs_credentials_file()
+                .map_err(|e| e.to_string())
+                .and_then(|path| {
+                    if path.is_file() {
+                        Ok(path.display().to_string())
+                    } else {
+                        Err(format!("{} が見つかりません", path.display()))
+                    }
+                });
+            let ok = credentials.is_ok();
+            print_check("GCS サービスアカウントキー", credentials, &mut failures);
+            bucket_ok && ok
+        }
+        other => {
+            print_check(
+                "ストレージバックエンド",
+                Err(format!("不明なバックエンドです: {}", other)),
+                &mut failures,
+            );
+            false
+        }
+    };
 
-    config.save_with_template()?;
+    // 接続確認（前提がそろっている場合のみ）
+    let connection_label = format!("{} への接続", backend.to_uppercase());
+    if settings {
+        let result = config
+            .create_storage_client()
+            .and_then(|client| client.health_check())
+            .map(|_| String::new())
+            .map_err(|e| e.to_string());
+        print_check(&connection_label, result, &mut failures);
+    } else {
+        println!("  {} {}", "–".dimmed(), format!("{}（スキップ）", connection_label).dimmed());
+    }
 
-    println!(
-        "{}",
-        "✅ B2 設定を保存しました".green().bold()
-    );
-    println!("  Bucket: {}", bucket.cyan());
     println!();
-    println!("{}", "💡 認証情報は環境変数で設定することを推奨します:".yellow());
-    println!("  export B2_APPLICATION_KEY_ID=<your-key-id>");
-    println!("  export B2_APPLICATION_KEY=<your-key>");
+    if failures > 0 {
+        anyhow::bail!("{} 件の検証に失敗しました", failures);
+    }
+    println!("{}", "✅ すべての検証に成功しました".green().bold());
 
     Ok(())
 }
 
-fn test_b2_auth() -> Result<()> {
+fn test_b2_auth(profile: Option<&str>) -> Result<()> {
     use kanri_core::{b2, config};
 
     println!("{}", "🔐 B2 認証テスト...".cyan().bold());
@@ -1753,7 +5015,7 @@ fn test_b2_auth() -> Result<()> {
     println!("{}", "✅ B2 CLI インストール確認済み".green());
 
     // 設定読み込み
-    let config = config::Config::load()?;
+    let config = config::Config::load_profile(profile)?;
 
     // バケット確認
     match config.get_b2_bucket() {
@@ -1834,241 +5096,904 @@ fn generate_completions(shell: Shell) -> Result<()> {
     Ok(())
 }
 
+/// 補完スクリプトをシェルの補完ディレクトリに書き込む
+fn install_completions(shell: Shell) -> Result<()> {
+    let home = std::env::var_os("HOME")
+        .map(PathBuf::from)
+        .ok_or_else(|| anyhow::anyhow!("HOME が設定されていません"))?;
+    let (path, hint) = completion_install_path(shell, &home)?;
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| anyhow::anyhow!("{} を作成できませんでした: {}", dir.display(), e))?;
+    }
+    let mut cmd = Cli::command();
+    let bin_name = cmd.get_name().to_string();
+    // シェルの起動時に読み込まれるため、書き込みの途中で中断しても壊れないよう置き換える
+    let mut script = Vec::new();
+    generate(shell, &mut cmd, bin_name, &mut script);
+    kanri_core::utils::write_atomic(&path, script)
+        .map_err(|e| anyhow::anyhow!("{} に書き込めませんでした: {}", path.display(), e))?;
+
+    println!("{} {} に補完スクリプトを書き込みました", "✅".green(), path.display());
+    println!("{} {}", "💡".yellow(), hint);
+    Ok(())
+}
+
+/// シェルごとの補完スクリプトの書き込み先と、反映方法の案内
+fn completion_install_path(shell: Shell, home: &Path) -> Result<(PathBuf, String)> {
+    Ok(match shell {
+        Shell::Zsh => {
+            // fpath（エクスポートされていれば FPATH）にホーム配下のディレクトリがあればそこに置く
+            let in_fpath = std::env::var("FPATH").ok().and_then(|fpath| {
+                fpath
+                    .split(':')
+                    .map(PathBuf::from)
+                    .find(|dir| dir.starts_with(home) && dir.is_dir())
+            });
+            match in_fpath {
+                Some(dir) => (dir.join("_kanri"), "シェルを再起動すると補完が有効になります".to_string()),
+                None => (
+                    home.join(".zsh").join("completions").join("_kanri"),
+                    "~/.zshrc の compinit より前に fpath=(~/.zsh/completions $fpath) を追加し、シェルを再起動してください"
+                        .to_string(),
+                ),
+            }
+        }
+        Shell::Bash => (
+            home.join(".bash_completion.d").join("kanri"),
+            "~/.bashrc に source ~/.bash_completion.d/kanri を追加し、シェルを再起動してください".to_string(),
+        ),
+        Shell::Fish => {
+            let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| home.join(".config"));
+            (
+                config_dir.join("fish").join("completions").join("kanri.fish"),
+                "新しい fish セッションで自動的に読み込まれます".to_string(),
+            )
+        }
+        _ => anyhow::bail!(
+            "--install は bash / zsh / fish のみ対応しています（kanri completions {} > <ファイル> で書き出してください）",
+            shell
+        ),
+    })
+}
+
 // ========== Diagnostic Functions ==========
 
 #[derive(Debug, Serialize, Deserialize)]
 struct DiagnosticCategory {
+    /// --fail-category で指定するキー（例: rust, node）
+    key: String,
     name: String,
     icon: String,
     count: usize,
     total_size: u64,
     command_hint: String,
     is_large: bool,
+    /// 項目のうち最も新しい更新日時（どれくらい使われていないかの目安）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_modified: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct DiagnosticReport {
     categories: Vec<DiagnosticCategory>,
+    /// --top で省略されたカテゴリ数
+    omitted_count: usize,
+    /// --top で省略されたカテゴリの合計サイズ
+    omitted_size: u64,
+    /// --top で省略されたカテゴリ（閾値判定用）
+    #[serde(skip)]
+    omitted_categories: Vec<DiagnosticCategory>,
     total_size: u64,
+    /// 診断したパスがあるボリュームの容量
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    disk: Option<kanri_core::utils::DiskUsage>,
     timestamp: String,
 }
 
-fn run_diagnostics(path: &PathBuf, json: bool, threshold: Option<f64>) -> Result<()> {
-    if !json {
-        println!("{}", "🔍 システム診断を実行中...".cyan().bold());
-        println!();
+/// diagnose の閾値超過時の終了コード（エラー時の 1 とは区別する）
+const EXIT_THRESHOLD_EXCEEDED: i32 = 2;
+
+/// diagnose のカテゴリキー（--fail-category で指定可能な値）
+const DIAGNOSTIC_KEYS: &[&str] = &[
+    "rust",
+    "node",
+    "flutter",
+    "android",
+    "python",
+    "haskell",
+    "dotnet",
+    "swift",
+    "r",
+    "ml",
+    "cmake",
+    "docker",
+    "go",
+    "js-cache",
+    "npm-cache",
+    "pip-cache",
+    "gradle",
+    "xcode",
+    "browser-cache",
+    "logs",
+    "cache",
+    "large-files",
+    "downloads",
+];
+
+/// `rust:5` 形式のカテゴリ閾値をパース
+fn parse_category_threshold(value: &str) -> std::result::Result<(String, f64), String> {
+    let (key, gb) = value
+        .split_once(':')
+        .ok_or_else(|| format!("'{}' は <カテゴリ>:<GB> 形式で指定してください", value))?;
+
+    if !DIAGNOSTIC_KEYS.contains(&key) {
+        return Err(format!(
+            "不明なカテゴリ '{}'（指定可能: {}）",
+            key,
+            DIAGNOSTIC_KEYS.join(", ")
+        ));
     }
 
-    let threshold_bytes = threshold.map(|gb| (gb * 1024.0 * 1024.0 * 1024.0) as u64);
+    let gb: f64 = gb
+        .parse()
+        .map_err(|_| format!("'{}' は数値（GB）で指定してください", gb))?;
 
-    let mut categories = Vec::new();
+    Ok((key.to_string(), gb))
+}
 
-    // Rust プロジェクト
-    if let Ok(projects) = kanri_core::rust::find_rust_projects(path) {
-        let total_size: u64 = projects.iter().map(|p| p.size).sum();
-        if threshold_bytes.is_none() || total_size >= threshold_bytes.unwrap() {
-            categories.push(DiagnosticCategory {
-                name: "Rust プロジェクト".to_string(),
-                icon: "🦀".to_string(),
-                count: projects.len(),
-                total_size,
-                command_hint: format!("kanri clean rust -p {} -i", path.display()),
-                is_large: total_size > 5 * 1024 * 1024 * 1024, // 5GB以上
-            });
-        }
-    }
+/// --since の値を YYYYMMDD_HHMMSS に正規化（日付だけならその日の始まり）
+fn parse_range_start(value: &str) -> std::result::Result<String, String> {
+    kanri_core::archive::versioning::parse_range_bound(value, false)
+        .ok_or_else(|| format!("'{}' は YYYYMMDD_HHMMSS / YYYYMMDD / YYYY-MM-DD 形式で指定してください", value))
+}
 
-    // Node.js プロジェクト
-    if let Ok(projects) = kanri_core::node::find_node_projects(path) {
-        let total_size: u64 = projects.iter().map(|p| p.size).sum();
-        if threshold_bytes.is_none() || total_size >= threshold_bytes.unwrap() {
-            categories.push(DiagnosticCategory {
-                name: "Node.js プロジェクト".to_string(),
-                icon: "📦".to_string(),
-                count: projects.len(),
-                total_size,
-                command_hint: format!("kanri clean node -p {} -i", path.display()),
-                is_large: total_size > 10 * 1024 * 1024 * 1024, // 10GB以上
-            });
+/// --split-size の値（例: 5G）をバイト数に変換
+fn parse_split_size(value: &str) -> std::result::Result<u64, String> {
+    kanri_core::utils::parse_size(value)
+        .filter(|&size| size > 0)
+        .ok_or_else(|| format!("'{}' は 5G / 500M のようなサイズで指定してください", value))
+}
+
+/// --until の値を YYYYMMDD_HHMMSS に正規化（日付だけならその日の終わり）
+fn parse_range_end(value: &str) -> std::result::Result<String, String> {
+    kanri_core::archive::versioning::parse_range_bound(value, true)
+        .ok_or_else(|| format!("'{}' は YYYYMMDD_HHMMSS / YYYYMMDD / YYYY-MM-DD 形式で指定してください", value))
+}
+
+/// 閾値を超えた項目のメッセージを返す
+fn check_diagnostic_thresholds(
+    report: &DiagnosticReport,
+    fail_over: Option<f64>,
+    fail_categories: &[(String, f64)],
+) -> Vec<String> {
+    let gb_to_bytes = |gb: f64| (gb * 1024.0 * 1024.0 * 1024.0) as u64;
+    let mut violations = Vec::new();
+
+    if let Some(gb) = fail_over {
+        if report.total_size > gb_to_bytes(gb) {
+            violations.push(format!(
+                "合計 {} が閾値 {} GB を超えています",
+                kanri_core::utils::format_size(report.total_size),
+                gb
+            ));
         }
     }
 
-    // Flutter プロジェクト
-    if let Ok(projects) = kanri_core::flutter::find_flutter_projects(path) {
-        let total_size: u64 = projects.iter().map(|p| p.size).sum();
-        if threshold_bytes.is_none() || total_size >= threshold_bytes.unwrap() {
-            categories.push(DiagnosticCategory {
-                name: "Flutter プロジェクト".to_string(),
-                icon: "🦋".to_string(),
-                count: projects.len(),
-                total_size,
-                command_hint: format!("kanri clean flutter -p {} -i", path.display()),
-                is_large: total_size > 5 * 1024 * 1024 * 1024,
-            });
+    for (key, gb) in fail_categories {
+        let category = report
+            .categories
+            .iter()
+            .chain(report.omitted_categories.iter())
+            .find(|c| &c.key == key);
+        if let Some(category) = category {
+            if category.total_size > gb_to_bytes(*gb) {
+                violations.push(format!(
+                    "{} の {} が閾値 {} GB を超えています",
+                    category.name,
+                    kanri_core::utils::format_size(category.total_size),
+                    gb
+                ));
+            }
         }
     }
 
-    // Python 仮想環境
-    let python_cleaner = kanri_core::python::PythonCleaner::new(path.clone());
-    if let Ok(items) = python_cleaner.scan() {
-        let total_size: u64 = items.iter().map(|p| p.size).sum();
-        if threshold_bytes.is_none() || total_size >= threshold_bytes.unwrap() {
-            categories.push(DiagnosticCategory {
-                name: "Python 仮想環境".to_string(),
-                icon: "🐍".to_string(),
-                count: items.len(),
-                total_size,
-                command_hint: format!("kanri clean python -p {} -i", path.display()),
-                is_large: total_size > 3 * 1024 * 1024 * 1024,
-            });
-        }
+    violations
+}
+
+/// diagnose のオプション
+struct DiagnoseOptions {
+    format: ReportFormat,
+    threshold: Option<f64>,
+    /// 合計サイズの閾値（GB）
+    fail_over: Option<f64>,
+    /// カテゴリごとの閾値（GB）
+    fail_categories: Vec<(String, f64)>,
+    /// 推移を表示する場合の実行回数
+    trend: Option<usize>,
+    /// 診断履歴に記録する
+    record: bool,
+}
+
+fn run_diagnostics(path: &Path, opts: &DiagnoseOptions, list: ListOptions) -> Result<()> {
+    if opts.format == ReportFormat::Human {
+        println!("{}", "🔍 システム診断を実行中...".cyan().bold());
+        println!();
     }
 
-    // Haskell プロジェクト
-    let haskell_cleaner = kanri_core::haskell::HaskellCleaner::new(path.clone());
-    if let Ok(items) = haskell_cleaner.scan() {
-        let total_size: u64 = items.iter().map(|p| p.size).sum();
-        if threshold_bytes.is_none() || total_size >= threshold_bytes.unwrap() {
-            categories.push(DiagnosticCategory {
-                name: "Haskell プロジェクト".to_string(),
-                icon: "λ".to_string(),
-                count: items.len(),
-                total_size,
-                command_hint: format!("kanri clean haskell -p {} -i", path.display()),
-                is_large: total_size > 2 * 1024 * 1024 * 1024,
-            });
-        }
+    let report = collect_diagnostics(path, opts.threshold, list);
+
+    match opts.format {
+        ReportFormat::Human => print_diagnostic_report(&report),
+        ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        ReportFormat::Csv => print_diagnostic_csv(&report),
     }
 
-    // Docker
-    if kanri_core::docker::is_docker_installed() && kanri_core::docker::is_docker_running() {
-        if let Ok(info) = kanri_core::docker::get_system_info() {
-            // reclaimable は "X.X GB" のような形式なので、パースする
-            if let Some(size_str) = info.reclaimable.split_whitespace().next() {
-                if let Ok(size_gb) = size_str.parse::<f64>() {
-                    let total_size = (size_gb * 1024.0 * 1024.0 * 1024.0) as u64;
-                    if threshold_bytes.is_none() || total_size >= threshold_bytes.unwrap() {
-                        categories.push(DiagnosticCategory {
-                            name: "Docker".to_string(),
-                            icon: "🐳".to_string(),
-                            count: 1,
-                            total_size,
-                            command_hint: "kanri clean docker -i".to_string(),
-                            is_large: total_size > 5 * 1024 * 1024 * 1024,
-                        });
-                    }
-                }
+    // 診断履歴の記録は失敗しても診断自体は中断しない
+    let history = if opts.record || opts.trend.is_some() {
+        match record_diagnose_history(&report, opts.record) {
+            Ok(history) => Some(history),
+            Err(e) => {
+                eprintln!(
+                    "{} {}",
+                    "⚠".yellow(),
+                    format!("診断履歴の記録に失敗しました: {}", e).dimmed()
+                );
+                None
             }
         }
+    } else {
+        None
+    };
+
+    if let (Some(runs), Some(history)) = (opts.trend, history) {
+        print_diagnose_trend(&history, runs);
     }
 
-    // Go モジュールキャッシュ
-    let go_cleaner = kanri_core::go::GoCleaner::new();
-    if let Ok(items) = go_cleaner.scan() {
-        let total_size: u64 = items.iter().map(|p| p.size).sum();
-        if threshold_bytes.is_none() || total_size >= threshold_bytes.unwrap() {
-            categories.push(DiagnosticCategory {
-                name: "Go モジュールキャッシュ".to_string(),
-                icon: "🐹".to_string(),
-                count: items.len(),
-                total_size,
-                command_hint: "kanri clean go -i".to_string(),
-                is_large: total_size > 2 * 1024 * 1024 * 1024,
-            });
+    // 閾値超過は stderr に出力し、JSON 出力を汚さない
+    let violations = check_diagnostic_thresholds(&report, opts.fail_over, &opts.fail_categories);
+    if !violations.is_empty() {
+        for violation in &violations {
+            eprintln!("{} {}", "❌".red(), violation.red());
         }
+        std::process::exit(EXIT_THRESHOLD_EXCEEDED);
     }
 
-    // Gradle キャッシュ
-    let gradle_cleaner = kanri_core::gradle::GradleCleaner::new();
-    if let Ok(items) = gradle_cleaner.scan() {
-        let total_size: u64 = items.iter().map(|p| p.size).sum();
-        if threshold_bytes.is_none() || total_size >= threshold_bytes.unwrap() {
-            categories.push(DiagnosticCategory {
-                name: "Gradle キャッシュ".to_string(),
-                icon: "🐘".to_string(),
-                count: items.len(),
-                total_size,
-                command_hint: "kanri clean gradle -i".to_string(),
-                is_large: total_size > 3 * 1024 * 1024 * 1024,
-            });
-        }
+    Ok(())
+}
+
+/// 診断結果を CSV で出力（サイズはスプレッドシートで並べ替えられるようバイト数のまま）
+fn print_diagnostic_csv(report: &DiagnosticReport) {
+    println!(
+        "{}",
+        kanri_core::utils::csv_row(&["category", "count", "total_size_bytes", "is_large"])
+    );
+    for category in &report.categories {
+        println!(
+            "{}",
+            kanri_core::utils::csv_row(&[
+                category.key.clone(),
+                category.count.to_string(),
+                category.total_size.to_string(),
+                category.is_large.to_string(),
+            ])
+        );
     }
+}
 
-    // Xcode DerivedData
-    let xcode_cleaner = kanri_core::xcode::XcodeCleaner::new();
-    if let Ok(items) = xcode_cleaner.scan() {
-        let total_size: u64 = items.iter().map(|p| p.size).sum();
-        if threshold_bytes.is_none() || total_size >= threshold_bytes.unwrap() {
-            categories.push(DiagnosticCategory {
-                name: "Xcode DerivedData".to_string(),
-                icon: "🍎".to_string(),
-                count: items.len(),
-                total_size,
-                command_hint: "kanri clean xcode -i".to_string(),
-                is_large: total_size > 5 * 1024 * 1024 * 1024,
-            });
+/// 一定間隔で診断を再実行し、画面を更新し続ける
+fn watch_diagnostics(path: &Path, threshold: Option<f64>, list: ListOptions, interval: u64) -> Result<()> {
+    use kanri_core::utils::is_cancel_requested;
+
+    // Ctrl-C で即終了せず、画面の更新を止めてから終了する
+    kanri_core::utils::set_cancellable(true);
+
+    // 1 回の診断の中では同じディレクトリのサイズを再計算しない
+    kanri_core::utils::enable_size_cache();
+
+    let interval = std::time::Duration::from_secs(interval.max(1));
+
+    while !is_cancel_requested() {
+        // 配下の変更を反映するため、更新ごとにサイズを計算し直す
+        kanri_core::utils::clear_size_cache();
+        let report = collect_diagnostics(path, threshold, list);
+
+        // 画面をクリアしてカーソルを先頭に戻す
+        print!("\x1B[2J\x1B[H");
+        println!(
+            "{}",
+            format!(
+                "🔍 システム診断 ({} 秒ごとに更新、Ctrl-C で終了)",
+                interval.as_secs()
+            )
+            .cyan()
+            .bold()
+        );
+        println!();
+        print_diagnostic_report(&report);
+        io::stdout().flush()?;
+
+        // Ctrl-C にすぐ反応できるよう短い間隔でスリープ
+        let started = std::time::Instant::now();
+        while !is_cancel_requested() && started.elapsed() < interval {
+            std::thread::sleep(std::time::Duration::from_millis(100));
         }
     }
 
-    // アプリケーションキャッシュ (1GB以上)
-    if let Ok(caches) = kanri_core::cache::scan_user_caches(1) {
-        let total_size: u64 = caches.iter().map(|c| c.size).sum();
-        if threshold_bytes.is_none() || total_size >= threshold_bytes.unwrap() {
-            categories.push(DiagnosticCategory {
-                name: "アプリケーションキャッシュ (1GB以上)".to_string(),
-                icon: "💾".to_string(),
-                count: caches.len(),
-                total_size,
-                command_hint: "kanri clean cache -i".to_string(),
-                is_large: total_size > 10 * 1024 * 1024 * 1024,
-            });
-        }
+    println!("\n{}", "👋 監視を終了しました".dimmed());
+
+    Ok(())
+}
+
+/// 診断カテゴリを作成（`large_gb` を超えると「大」と表示）
+#[allow(clippy::too_many_arguments)]
+fn diagnostic_category(
+    key: &str,
+    name: &str,
+    icon: &str,
+    count: usize,
+    total_size: u64,
+    last_modified: Option<std::time::SystemTime>,
+    command_hint: String,
+    large_gb: u64,
+) -> DiagnosticCategory {
+    DiagnosticCategory {
+        key: key.to_string(),
+        name: name.to_string(),
+        icon: icon.to_string(),
+        count,
+        total_size,
+        command_hint,
+        is_large: total_size > large_gb * 1024 * 1024 * 1024,
+        last_modified: last_modified.map(Into::into),
     }
+}
 
-    // 大きなファイル (2GB以上)
-    let min_size = 2 * 1024 * 1024 * 1024; // 2GB
-    if let Ok(large_items) = kanri_core::large_files::find_large_items(
-        path,
-        min_size,
-        None, // extensions
-        true, // include_dirs
-        true, // include_files
-    ) {
-        let total_size: u64 = large_items.iter().map(|i| i.size).sum();
-        if threshold_bytes.is_none() || total_size >= threshold_bytes.unwrap() {
-            categories.push(DiagnosticCategory {
-                name: "大きなファイル (2GB以上)".to_string(),
-                icon: "📁".to_string(),
-                count: large_items.len(),
+/// 診断カテゴリを 1 つ集計する処理（見つからない・失敗した場合は None）
+type DiagnosticTask<'a> = Box<dyn Fn() -> Option<DiagnosticCategory> + Sync + 'a>;
+
+/// 各カテゴリをスキャンして診断レポートを作成
+///
+/// カテゴリは互いに独立しているため並列に集計し、結果は下の定義順に並べる
+/// （--json の出力が実行ごとに変わらないように）
+fn collect_diagnostics(path: &Path, threshold: Option<f64>, list: ListOptions) -> DiagnosticReport {
+    let threshold_bytes = threshold.map(|gb| (gb * 1024.0 * 1024.0 * 1024.0) as u64);
+    let config = kanri_core::config::Config::load().unwrap_or_default();
+    let project_hint = |command: &str| format!("kanri clean {} -p {} -i", command, path.display());
+
+    let tasks: Vec<DiagnosticTask> = vec![
+        // Rust プロジェクト
+        Box::new(|| {
+            let projects = kanri_core::rust::find_rust_projects(path).ok()?;
+            let total_size = projects.iter().map(|p| p.size).sum();
+            let last_modified =
+                kanri_core::utils::newest_modified(projects.iter().map(|p| &p.target_dir));
+            Some(diagnostic_category(
+                "rust",
+                "Rust プロジェクト",
+                "🦀",
+                projects.len(),
                 total_size,
-                command_hint: format!(
-                    "kanri archive large-files -p {} --to archive/large-files --delete-after --dry-run",
-                    path.display()
-                ),
-                is_large: total_size > 10 * 1024 * 1024 * 1024,
-            });
-        }
-    }
+                last_modified,
+                project_hint("rust"),
+                5,
+            ))
+        }),
+        // Node.js プロジェクト
+        Box::new(|| {
+            let projects = kanri_core::node::find_node_projects(path).ok()?;
+            let total_size = projects.iter().map(|p| p.size).sum();
+            let last_modified =
+                kanri_core::utils::newest_modified(projects.iter().map(|p| &p.node_modules_dir));
+            Some(diagnostic_category(
+                "node",
+                "Node.js プロジェクト",
+                "📦",
+                projects.len(),
+                total_size,
+                last_modified,
+                project_hint("node"),
+                10,
+            ))
+        }),
+        // Flutter プロジェクト
+        Box::new(|| {
+            let projects = kanri_core::flutter::find_flutter_projects(path).ok()?;
+            let total_size = projects.iter().map(|p| p.size).sum();
+            let last_modified = kanri_core::utils::newest_modified(
+                projects
+                    .iter()
+                    .flat_map(|p| [&p.build_dir, &p.dart_tool_dir]),
+            );
+            Some(diagnostic_category(
+                "flutter",
+                "Flutter プロジェクト",
+                "🦋",
+                projects.len(),
+                total_size,
+                last_modified,
+                project_hint("flutter"),
+                5,
+            ))
+        }),
+        // Android / Gradle プロジェクト
+        Box::new(|| {
+            let projects = kanri_core::android::find_android_projects(path).ok()?;
+            let total_size = projects.iter().map(|p| p.size).sum();
+            let last_modified = kanri_core::utils::newest_modified(
+                projects
+                    .iter()
+                    .flat_map(|p| p.artifacts.iter().map(|(path, _)| path)),
+            );
+            Some(diagnostic_category(
+                "android",
+                "Android / Gradle プロジェクト",
+                "🤖",
+                projects.len(),
+                total_size,
+                last_modified,
+                project_hint("android"),
+                5,
+            ))
+        }),
+        // Python 仮想環境
+        Box::new(|| {
+            let items = kanri_core::python::PythonCleaner::new(path.to_path_buf())
+                .scan()
+                .ok()?;
+            let total_size = items.iter().map(|p| p.size).sum();
+            let last_modified = kanri_core::utils::newest_modified(items.iter().map(|p| &p.path));
+            Some(diagnostic_category(
+                "python",
+                "Python 仮想環境",
+                "🐍",
+                items.len(),
+                total_size,
+                last_modified,
+                project_hint("python"),
+                3,
+            ))
+        }),
+        // Haskell プロジェクト
+        Box::new(|| {
+            let items = kanri_core::haskell::HaskellCleaner::new(path.to_path_buf())
+                .scan()
+                .ok()?;
+            let total_size = items.iter().map(|p| p.size).sum();
+            let last_modified = kanri_core::utils::newest_modified(items.iter().map(|p| &p.path));
+            Some(diagnostic_category(
+                "haskell",
+                "Haskell プロジェクト",
+                "λ",
+                items.len(),
+                total_size,
+                last_modified,
+                project_hint("haskell"),
+                2,
+            ))
+        }),
+        // .NET プロジェクト
+        Box::new(|| {
+            let items = kanri_core::dotnet::DotnetCleaner::new(path.to_path_buf())
+                .scan()
+                .ok()?;
+            let total_size = items.iter().map(|p| p.size).sum();
+            let last_modified = kanri_core::utils::newest_modified(items.iter().map(|p| &p.path));
+            Some(diagnostic_category(
+                "dotnet",
+                ".NET プロジェクト",
+                "🟣",
+                items.len(),
+                total_size,
+                last_modified,
+                project_hint("dotnet"),
+                2,
+            ))
+        }),
+        // Swift Package Manager
+        Box::new(|| {
+            let items = kanri_core::swift::SwiftCleaner::new(path.to_path_buf())
+                .scan()
+                .ok()?;
+            let total_size = items.iter().map(|p| p.size).sum();
+            let last_modified = kanri_core::utils::newest_modified(items.iter().map(|p| &p.path));
+            Some(diagnostic_category(
+                "swift",
+                "Swift プロジェクト",
+                "🐦",
+                items.len(),
+                total_size,
+                last_modified,
+                project_hint("swift"),
+                2,
+            ))
+        }),
+        // R (renv / CRAN)
+        Box::new(|| {
+            let items = kanri_core::r::RCleaner::new(path.to_path_buf()).scan().ok()?;
+            let total_size = items.iter().map(|p| p.size).sum();
+            let last_modified = kanri_core::utils::newest_modified(items.iter().map(|p| &p.path));
+            Some(diagnostic_category(
+                "r",
+                "R (renv)",
+                "📊",
+                items.len(),
+                total_size,
+                last_modified,
+                project_hint("r"),
+                2,
+            ))
+        }),
+        // Jupyter / 機械学習の生成物
+        Box::new(|| {
+            let items = kanri_core::ml::MlCleaner::new(path.to_path_buf()).scan().ok()?;
+            let total_size = items.iter().map(|p| p.size).sum();
+            let last_modified = kanri_core::utils::newest_modified(items.iter().map(|p| &p.path));
+            Some(diagnostic_category(
+                "ml",
+                "ML 生成物",
+                "🧪",
+                items.len(),
+                total_size,
+                last_modified,
+                project_hint("ml"),
+                2,
+            ))
+        }),
+        // CMake / C++ ビルド
+        Box::new(|| {
+            let items = kanri_core::cmake::CMakeCleaner::new(path.to_path_buf())
+                .scan()
+                .ok()?;
+            let total_size = items.iter().map(|p| p.size).sum();
+            let last_modified = kanri_core::utils::newest_modified(items.iter().map(|p| &p.path));
+            Some(diagnostic_category(
+                "cmake",
+                "CMake / C++ ビルド",
+                "🛠",
+                items.len(),
+                total_size,
+                last_modified,
+                project_hint("cmake"),
+                5,
+            ))
+        }),
+        // Docker
+        Box::new(|| {
+            if !kanri_core::docker::is_docker_installed()
+                || !kanri_core::docker::is_docker_running()
+            {
+                return None;
+            }
+            let total_size = kanri_core::docker::get_system_info()
+                .ok()?
+                .reclaimable_bytes()?;
+            let last_modified = kanri_core::docker::last_used();
+            Some(diagnostic_category(
+                "docker",
+                "Docker",
+                "🐳",
+                1,
+                total_size,
+                last_modified,
+                "kanri clean docker -i".to_string(),
+                5,
+            ))
+        }),
+        // Go モジュールキャッシュ
+        Box::new(|| {
+            let items = kanri_core::go::GoCleaner::new().scan().ok()?;
+            let total_size = items.iter().map(|p| p.size).sum();
+            let last_modified = kanri_core::utils::newest_modified(items.iter().map(|p| &p.path));
+            Some(diagnostic_category(
+                "go",
+                "Go モジュールキャッシュ",
+                "🐹",
+                items.len(),
+                total_size,
+                last_modified,
+                "kanri clean go -i".to_string(),
+                2,
+            ))
+        }),
+        // JS グローバルキャッシュ
+        Box::new(|| {
+            let items = kanri_core::js_cache::JsCacheCleaner::new().scan().ok()?;
+            let total_size = items.iter().map(|p| p.size).sum();
+            let last_modified = kanri_core::utils::newest_modified(items.iter().map(|p| &p.path));
+            Some(diagnostic_category(
+                "js-cache",
+                "JS グローバルキャッシュ (pnpm/Bun/Deno)",
+                "📦",
+                items.len(),
+                total_size,
+                last_modified,
+                "kanri clean js-cache -i".to_string(),
+                5,
+            ))
+        }),
+        // npm / yarn グローバルキャッシュ
+        Box::new(|| {
+            let items = kanri_core::npm_cache::NpmCacheCleaner::new().scan().ok()?;
+            let total_size = items.iter().map(|p| p.size).sum();
+            let last_modified = kanri_core::utils::newest_modified(items.iter().map(|p| &p.path));
+            Some(diagnostic_category(
+                "npm-cache",
+                "npm / yarn キャッシュ",
+                "📦",
+                items.len(),
+                total_size,
+                last_modified,
+                "kanri clean npm-cache -i".to_string(),
+                5,
+            ))
+        }),
+        // pip キャッシュ
+        Box::new(|| {
+            let items = kanri_core::pip_cache::PipCacheCleaner::new().scan().ok()?;
+            let total_size = items.iter().map(|p| p.size).sum();
+            let last_modified = kanri_core::utils::newest_modified(items.iter().map(|p| &p.path));
+            Some(diagnostic_category(
+                "pip-cache",
+                "pip キャッシュ",
+                "🐍",
+                items.len(),
+                total_size,
+                last_modified,
+                "kanri clean pip-cache -i".to_string(),
+                5,
+            ))
+        }),
+        // Gradle キャッシュ
+        Box::new(|| {
+            let items = kanri_core::gradle::GradleCleaner::new().scan().ok()?;
+            let total_size = items.iter().map(|p| p.size).sum();
+            let last_modified = kanri_core::utils::newest_modified(items.iter().map(|p| &p.path));
+            Some(diagnostic_category(
+                "gradle",
+                "Gradle キャッシュ",
+                "🐘",
+                items.len(),
+                total_size,
+                last_modified,
+                "kanri clean gradle -i".to_string(),
+                3,
+            ))
+        }),
+        // Xcode（DerivedData・シミュレータ・DeviceSupport・Archives）
+        Box::new(|| {
+            let items = kanri_core::xcode::XcodeCleaner::new().scan().ok()?;
+            let total_size = items.iter().map(|p| p.size).sum();
+            let last_modified = kanri_core::utils::newest_modified(items.iter().map(|p| &p.path));
+            Some(diagnostic_category(
+                "xcode",
+                "Xcode",
+                "🍎",
+                items.len(),
+                total_size,
+                last_modified,
+                "kanri clean xcode -i".to_string(),
+                5,
+            ))
+        }),
+        // ブラウザキャッシュ
+        Box::new(|| {
+            let items = kanri_core::browser_cache::BrowserCacheCleaner::new()
+                .scan()
+                .ok()?;
+            let total_size = items.iter().map(|p| p.size).sum();
+            let last_modified = kanri_core::utils::newest_modified(items.iter().map(|p| &p.path));
+            Some(diagnostic_category(
+                "browser-cache",
+                "ブラウザキャッシュ",
+                "🌐",
+                items.len(),
+                total_size,
+                last_modified,
+                "kanri clean browser-cache -i".to_string(),
+                2,
+            ))
+        }),
+        // ログ・クラッシュレポート
+        Box::new(|| {
+            let items = kanri_core::logs::LogsCleaner::new().scan().ok()?;
+            let total_size = items.iter().map(|p| p.size).sum();
+            let last_modified = kanri_core::utils::newest_modified(items.iter().map(|p| &p.path));
+            Some(diagnostic_category(
+                "logs",
+                "ログ・クラッシュレポート",
+                "📜",
+                items.len(),
+                total_size,
+                last_modified,
+                "kanri clean logs -i".to_string(),
+                2,
+            ))
+        }),
+        // アプリケーションキャッシュ (1GB以上)
+        Box::new(|| {
+            // clean cache と同じく、最近使われたキャッシュは含めない
+            let (caches, _) = kanri_core::cache::split_recently_used(
+                kanri_core::cache::scan_user_caches(1, &config).ok()?,
+                kanri_core::cache::DEFAULT_KEEP_NEWER_THAN_DAYS,
+            );
+            let total_size = caches.iter().map(|c| c.size).sum();
+            let last_modified = caches.iter().filter_map(|c| c.last_modified).max();
+            Some(diagnostic_category(
+                "cache",
+                "アプリケーションキャッシュ (1GB以上)",
+                "💾",
+                caches.len(),
+                total_size,
+                last_modified,
+                "kanri clean cache -i".to_string(),
+                10,
+            ))
+        }),
+        // 大きなファイル (2GB以上)
+        Box::new(|| {
+            let large_items = kanri_core::large_files::find_large_items(
+                path,
+                2 * 1024 * 1024 * 1024, // 2GB
+                None,                   // extensions
+                true,                   // include_dirs
+                true,                   // include_files
+            )
+            .ok()?;
+            let total_size = large_items.iter().map(|i| i.size).sum();
+            let command_hint = format!(
+                "kanri archive large-files -p {} --to archive/large-files --delete-after --dry-run",
+                path.display()
+            );
+            let last_modified =
+                kanri_core::utils::newest_modified(large_items.iter().map(|i| &i.path));
+            Some(diagnostic_category(
+                "large-files",
+                "大きなファイル (2GB以上)",
+                "📁",
+                large_items.len(),
+                total_size,
+                last_modified,
+                command_hint,
+                10,
+            ))
+        }),
+        // ダウンロードフォルダ（30 日以上前のファイル）
+        Box::new(|| {
+            let dir = kanri_core::downloads::default_downloads_dir()?;
+            let items = kanri_core::downloads::DownloadsCleaner::new(dir).scan().ok()?;
+            let total_size = items.iter().map(|p| p.size).sum();
+            let last_modified = kanri_core::utils::newest_modified(items.iter().map(|p| &p.path));
+            Some(diagnostic_category(
+                "downloads",
+                "古いダウンロード",
+                "📥",
+                items.len(),
+                total_size,
+                last_modified,
+                "kanri clean downloads -i".to_string(),
+                2,
+            ))
+        }),
+    ];
+
+    // カテゴリごとにスレッドで集計（Docker の外部コマンドとファイルシステムのスキャンも並行する）
+    let mut categories: Vec<DiagnosticCategory> = std::thread::scope(|scope| {
+        let handles: Vec<_> = tasks.iter().map(|task| scope.spawn(task)).collect();
+        handles
+            .into_iter()
+            .filter_map(|handle| handle.join().ok().flatten())
+            .collect()
+    });
+
+    categories.retain(|c| threshold_bytes.is_none_or(|threshold| c.total_size >= threshold));
 
     // 空のカテゴリ（count=0 または total_size=0）を除外
     categories.retain(|c| c.count > 0 && c.total_size > 0);
 
-    // 総計
-    let total_size: u64 = categories.iter().map(|c| c.total_size).sum();
+    // 並び替え・上位 N 件への絞り込み（カテゴリにパスはないため path は名前順）
+    let omitted = list.select(
+        &mut categories,
+        |c| c.total_size,
+        |c| c.name.clone(),
+        |c| PathBuf::from(&c.name),
+    );
+
+    // 総計（--top で省略されたカテゴリも含む）
+    let omitted_size: u64 = omitted.iter().map(|c| c.total_size).sum();
+    let total_size: u64 = categories.iter().map(|c| c.total_size).sum::<u64>() + omitted_size;
 
-    let report = DiagnosticReport {
+    DiagnosticReport {
         categories,
+        omitted_count: omitted.len(),
+        omitted_size,
+        omitted_categories: omitted,
         total_size,
+        disk: kanri_core::utils::disk_usage(path).ok(),
         timestamp: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
-    };
+    }
+}
 
-    if json {
-        println!("{}", serde_json::to_string_pretty(&report)?);
-    } else {
-        print_diagnostic_report(&report);
+/// 診断結果を履歴に追加して保存（save = false の場合は保存せず追加後の履歴のみ返す）
+fn record_diagnose_history(
+    report: &DiagnosticReport,
+    save: bool,
+) -> Result<kanri_core::diagnose_history::DiagnoseHistory> {
+    use kanri_core::diagnose_history::{DiagnoseHistory, DiagnoseSnapshot, SnapshotCategory};
+
+    let mut history = DiagnoseHistory::load()?;
+
+    let categories = report
+        .categories
+        .iter()
+        .chain(report.omitted_categories.iter())
+        .map(|c| SnapshotCategory {
+            key: c.key.clone(),
+            name: c.name.clone(),
+            total_size: c.total_size,
+        })
+        .collect();
+    history.push(DiagnoseSnapshot::new(categories));
+
+    if save {
+        history.save()?;
     }
 
-    Ok(())
+    Ok(history)
+}
+
+/// 前回の診断との差分と合計サイズの推移を表示
+fn print_diagnose_trend(history: &kanri_core::diagnose_history::DiagnoseHistory, runs: usize) {
+    use kanri_core::diagnose_history;
+
+    println!("\n{}", "📈 推移".cyan().bold());
+    println!("{}", "─".repeat(80).dimmed());
+
+    let snapshots = &history.snapshots;
+    if snapshots.len() < 2 {
+        println!("{}", "ℹ 比較できる過去の診断がありません".dimmed());
+        return;
+    }
+
+    let previous = &snapshots[snapshots.len() - 2];
+    let current = &snapshots[snapshots.len() - 1];
+
+    println!(
+        "前回 ({}) との比較:",
+        previous
+            .timestamp
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M:%S")
+    );
+
+    let mut deltas = diagnose_history::compare(previous, current);
+    deltas.sort_by_key(|d| std::cmp::Reverse(d.delta().unsigned_abs()));
+
+    for delta in &deltas {
+        let change = delta.delta();
+        let change_label = match change {
+            0 => "±0".dimmed(),
+            c if c > 0 => format!("+{}", kanri_core::utils::format_size(c as u64)).red(),
+            c => format!("-{}", kanri_core::utils::format_size(c.unsigned_abs())).green(),
+        };
+        println!(
+            "  {:<40} {:>12} {}",
+            delta.name,
+            kanri_core::utils::format_size(delta.current),
+            change_label
+        );
+    }
+
+    let totals = history.recent_totals(runs);
+    println!(
+        "\n合計の推移 (直近 {} 回): {} {}",
+        totals.len(),
+        diagnose_history::sparkline(&totals).cyan(),
+        kanri_core::utils::format_size(current.total_size).yellow().bold()
+    );
 }
 
 fn print_diagnostic_report(report: &DiagnosticReport) {
@@ -2092,6 +6017,16 @@ fn print_diagnostic_report(report: &DiagnosticReport) {
         println!("{} {}", category.icon, category.name.bright_white().bold());
         println!("  • {} 件", category.count.to_string().cyan());
         println!("  • 合計: {}{}", size_str.yellow().bold(), warning);
+        if let Some(last_modified) = category.last_modified {
+            println!(
+                "  • 最終更新: {}",
+                kanri_core::utils::format_age(last_modified.into()).dimmed()
+            );
+        }
+        println!();
+    }
+    print_hidden_footer(report.omitted_count, report.omitted_size);
+    if report.omitted_count > 0 {
         println!();
     }
 
@@ -2104,6 +6039,21 @@ fn print_diagnostic_report(report: &DiagnosticReport) {
             .yellow()
             .bold()
     );
+    if let Some(disk) = &report.disk {
+        println!(
+            "  ディスク: {} / {} 空き ({:.1}% を解放可能)",
+            kanri_core::utils::format_size(disk.free),
+            kanri_core::utils::format_size(disk.total),
+            disk.percent_of_total(report.total_size)
+        );
+        println!(
+            "  反映後の空き容量: {} → {}",
+            kanri_core::utils::format_size(disk.free),
+            kanri_core::utils::format_size(disk.free_after(report.total_size))
+                .green()
+                .bold()
+        );
+    }
     println!();
 
     // 2GB以上のカテゴリのみを「次のアクション」に表示（サイズ順）
@@ -2114,7 +6064,7 @@ fn print_diagnostic_report(report: &DiagnosticReport) {
         .collect();
 
     // サイズの大きい順にソート
-    actionable_categories.sort_by(|a, b| b.total_size.cmp(&a.total_size));
+    actionable_categories.sort_by_key(|c| std::cmp::Reverse(c.total_size));
 
     if !actionable_categories.is_empty() {
         println!("{}", "💡 次のアクション (2GB以上):".cyan().bold());
@@ -0,0 +1,875 @@
+//! clean コマンドの出力フォーマット
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use clap::ValueEnum;
+use colored::*;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+
+/// 出力形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum OutputFormat {
+    /// 色・絵文字付きの表示（デフォルト）
+    #[default]
+    Human,
+    /// 実行結果をまとめた JSON
+    Json,
+    /// 色なしのタブ区切り（スクリプト向け）
+    Plain,
+}
+
+/// 一覧の 1 項目
+#[derive(Debug, Serialize)]
+struct ReportItem {
+    name: String,
+    /// 複数のパスをまとめた項目（アプリごとのキャッシュなど）では None で、`entries` に各パスを出力する
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    entries: Vec<ReportEntry>,
+    size: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    safe: Option<bool>,
+}
+
+/// 削除（予定）のパス
+#[derive(Debug, Serialize)]
+struct ReportEntry {
+    path: String,
+    size: u64,
+}
+
+/// 削除結果
+#[derive(Debug, Serialize)]
+struct CleanedSummary {
+    count: usize,
+    freed_size: u64,
+}
+
+/// 削除前後のボリュームの空き容量
+#[derive(Debug, Serialize)]
+struct DiskSummary {
+    total: u64,
+    free_before: u64,
+    free_after: u64,
+    /// 削除前後に実測した場合の空き容量の増加（--report-before-after）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    measured_freed: Option<u64>,
+    /// 領域を保持している可能性がある Time Machine のローカルスナップショット
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    local_snapshots: Vec<String>,
+}
+
+/// JSON 出力用の実行結果
+#[derive(Debug, Default, Serialize)]
+struct CleanReport {
+    /// search / dry-run / delete / cancelled
+    mode: &'static str,
+    items: Vec<ReportItem>,
+    total_size: u64,
+    hidden_count: usize,
+    hidden_size: u64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    would_delete: Vec<ReportEntry>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cleaned: Option<CleanedSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disk: Option<DiskSummary>,
+}
+
+/// JSON 出力用のエラー（`{"error": {...}}`）
+#[derive(Debug, Serialize)]
+struct ErrorReport {
+    error: ErrorDetail,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorDetail {
+    /// エラーの種類（kanri_core::Error::kind。それ以外のエラーは "other"）
+    kind: &'static str,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stderr: Option<String>,
+}
+
+/// エラーを JSON で stdout に出力（外部コマンドの失敗ならコマンド名・終了コード・stderr も含める）
+pub fn print_error_json(err: &anyhow::Error) -> serde_json::Result<()> {
+    let core_error = err.chain().find_map(|cause| cause.downcast_ref::<kanri_core::Error>());
+    let mut detail = ErrorDetail {
+        kind: core_error.map_or("other", kanri_core::Error::kind),
+        message: format!("{:#}", err),
+        tool: None,
+        code: None,
+        stderr: None,
+    };
+    if let Some(kanri_core::Error::ExternalTool { tool, code, stderr }) = core_error {
+        detail.tool = Some(tool.clone());
+        detail.code = *code;
+        detail.stderr = Some(stderr.clone());
+    }
+    println!("{}", serde_json::to_string_pretty(&ErrorReport { error: detail })?);
+    Ok(())
+}
+
+/// --output-file で stdout をファイルに向けたか
+static STDOUT_REDIRECTED: AtomicBool = AtomicBool::new(false);
+
+/// 確認のプロンプトを表示する
+///
+/// --output-file で stdout をファイルに向けている場合は、端末で入力できるよう stderr に出す
+pub fn write_prompt(text: &str) -> io::Result<()> {
+    if STDOUT_REDIRECTED.load(Ordering::SeqCst) {
+        eprint!("{}", text);
+        io::stderr().flush()
+    } else {
+        print!("{}", text);
+        io::stdout().flush()
+    }
+}
+
+/// --output-file の書き込み先
+///
+/// 保持している間はファイルの排他ロックが続き、同じファイルに書き込む他の kanri は終わるまで待つ
+pub struct OutputFile {
+    _file: File,
+}
+
+impl OutputFile {
+    /// ファイルを開いて排他ロックを取り、以降の stdout をファイルに向ける
+    ///
+    /// `append` が false なら既存の内容はロックを取ってから消す（待っている間に他の実行の結果を消さないため）。
+    /// stdout が端末でなくなるので、色付けは自動的に無効になる
+    pub fn redirect_stdout(path: &Path, append: bool) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(append).write(true).open(path)?;
+        file.lock()?;
+        if !append {
+            file.set_len(0)?;
+        }
+        io::stdout().flush()?;
+        redirect_stdout_fd(&file)?;
+        STDOUT_REDIRECTED.store(true, Ordering::SeqCst);
+        Ok(Self { _file: file })
+    }
+}
+
+#[cfg(unix)]
+fn redirect_stdout_fd(file: &File) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    // SAFETY: どちらも開いている有効なファイルディスクリプタ
+    if unsafe { libc::dup2(file.as_raw_fd(), libc::STDOUT_FILENO) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn redirect_stdout_fd(_file: &File) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "--output-file is only supported on Unix"))
+}
+
+/// 削除前の確認で内訳を表示する項目数
+const CONFIRM_TOP_ITEMS: usize = 5;
+
+/// clean コマンドの出力を形式ごとに切り替える
+pub struct Reporter {
+    format: OutputFormat,
+    /// 最終結果の 1 行以外を表示しない（--quiet）
+    quiet: bool,
+    /// 一覧を省略し、件数と合計サイズだけを表示する（--stats-only）
+    stats_only: bool,
+    /// 削除前後の空き容量を実測する（--report-before-after）
+    measure_disk: bool,
+    /// 削除開始前に実測した空き容量
+    disk_before: Option<kanri_core::utils::DiskUsage>,
+    /// 領域が解放されていない場合にローカルスナップショットを間引く（--thin-snapshots）
+    thin_snapshots: bool,
+    report: CleanReport,
+}
+
+impl Reporter {
+    pub fn new(format: OutputFormat) -> Self {
+        Self {
+            format,
+            quiet: false,
+            stats_only: false,
+            measure_disk: false,
+            disk_before: None,
+            thin_snapshots: false,
+            report: CleanReport {
+                mode: "search",
+                ..Default::default()
+            },
+        }
+    }
+
+    /// 一覧・進捗などを省略し、最終結果の 1 行だけを表示する（JSON はもともと結果のみ）
+    pub fn with_quiet(mut self, quiet: bool) -> Self {
+        self.quiet = quiet;
+        self
+    }
+
+    /// 一覧・検索モードの案内を省略し、`stats` で件数と合計サイズだけを表示する
+    pub fn with_stats_only(mut self, stats_only: bool) -> Self {
+        self.stats_only = stats_only;
+        self.quiet |= stats_only;
+        self
+    }
+
+    /// 削除前後の空き容量を実測し、削除したサイズと比べる
+    pub fn with_report_before_after(mut self, measure_disk: bool) -> Self {
+        self.measure_disk = measure_disk;
+        self
+    }
+
+    /// 実測で領域が解放されていなければ、Time Machine のローカルスナップショットを間引く
+    pub fn with_thin_snapshots(mut self, thin_snapshots: bool) -> Self {
+        self.thin_snapshots = thin_snapshots;
+        self
+    }
+
+    fn is_human(&self) -> bool {
+        self.format == OutputFormat::Human
+    }
+
+    /// 途中経過を表示するか（Human かつ --quiet でない）
+    fn is_verbose(&self) -> bool {
+        self.is_human() && !self.quiet
+    }
+
+    /// 最初の項目があるボリュームの容量（取得できなければ None）
+    fn disk_usage(&self) -> Option<kanri_core::utils::DiskUsage> {
+        let item = self.report.items.first()?;
+        let path = item
+            .path
+            .as_deref()
+            .or_else(|| item.entries.first().map(|entry| entry.path.as_str()))?;
+        kanri_core::utils::disk_usage(Path::new(path)).ok()
+    }
+
+    /// 空き容量の変化とディスク全体に占める割合
+    fn print_disk_change(&self, usage: &kanri_core::utils::DiskUsage, before: u64, after: u64, size: u64) {
+        if self.quiet {
+            return;
+        }
+        match self.format {
+            OutputFormat::Human => println!(
+                "{} 反映後の空き容量: {} → {} (ディスク全体 {} の {:.1}%)",
+                "💽".cyan(),
+                kanri_core::utils::format_size(before),
+                kanri_core::utils::format_size(after).green().bold(),
+                kanri_core::utils::format_size(usage.total),
+                usage.percent_of_total(size)
+            ),
+            OutputFormat::Plain => println!("disk\t{}\t{}\t{}", usage.total, before, after),
+            OutputFormat::Json => {}
+        }
+    }
+
+    /// 削除前後に実測した空き容量の変化（--report-before-after）
+    ///
+    /// 実測の増加が削除したサイズより大幅に少なければ、領域が解放されていない可能性を警告して true を返す
+    fn print_measured_disk_change(
+        &self,
+        before: &kanri_core::utils::DiskUsage,
+        after: &kanri_core::utils::DiskUsage,
+        freed_size: u64,
+    ) -> bool {
+        let measured = after.free.saturating_sub(before.free);
+        if !self.quiet {
+            match self.format {
+                OutputFormat::Human => println!(
+                    "{} 空き容量（実測）: {} → {} (+{} / 削除したサイズ {})",
+                    "💽".cyan(),
+                    kanri_core::utils::format_size(before.free),
+                    kanri_core::utils::format_size(after.free).green().bold(),
+                    kanri_core::utils::format_size(measured),
+                    kanri_core::utils::format_size(freed_size)
+                ),
+                OutputFormat::Plain => {
+                    println!("disk-measured\t{}\t{}\t{}\t{}", after.total, before.free, after.free, freed_size)
+                }
+                OutputFormat::Json => {}
+            }
+        }
+
+        let shortfall = kanri_core::utils::is_reclaim_shortfall(measured, freed_size);
+        if shortfall {
+            self.warn(&format!(
+                "⚠ 実測で増えた空き容量 ({}) が削除したサイズ ({}) より大幅に少なくなっています。\
+                 開いたままのファイルや APFS のローカルスナップショット（tmutil listlocalsnapshots / で確認）が\
+                 領域を保持している可能性があります",
+                kanri_core::utils::format_size(measured),
+                kanri_core::utils::format_size(freed_size)
+            ));
+        }
+        shortfall
+    }
+
+    /// 領域を保持している Time Machine のローカルスナップショットを調べ、間引き方を案内する
+    ///
+    /// --thin-snapshots では `purge_bytes` 分を目安に間引く。見つかったスナップショットを返す
+    fn check_local_snapshots(&self, purge_bytes: u64) -> Vec<String> {
+        let snapshots = match kanri_core::utils::local_snapshots() {
+            Ok(snapshots) => snapshots,
+            Err(e) => {
+                self.warn(&format!("⚠ ローカルスナップショットを確認できませんでした: {}", e));
+                return Vec::new();
+            }
+        };
+        if snapshots.is_empty() {
+            return snapshots;
+        }
+
+        let command = format!("tmutil thinlocalsnapshots / {} 4", purge_bytes);
+        self.warn(&format!(
+            "📸 Time Machine のローカルスナップショットが {} 件あります。削除したファイルの領域を保持している可能性があります",
+            snapshots.len()
+        ));
+        if !self.thin_snapshots {
+            self.note(&format!(
+                "💡 `{}` で間引けます（--thin-snapshots で自動実行）。時間が経つと macOS が自動的に削除します",
+                command
+            ));
+            return snapshots;
+        }
+
+        if self.is_verbose() {
+            println!("{} {}", "📸".cyan(), format!("{} を実行中...", command).cyan());
+        }
+        match kanri_core::utils::thin_local_snapshots(purge_bytes) {
+            Ok(output) => self.note(&output),
+            Err(e) => self.warn(&format!("⚠ ローカルスナップショットを間引けませんでした: {}", e)),
+        }
+        snapshots
+    }
+
+    /// スキャン開始のメッセージ
+    pub fn scanning(&self, message: &str) {
+        if self.is_verbose() {
+            println!("{}", message.cyan().bold());
+        }
+    }
+
+    /// 補足情報（Human のみ表示）
+    pub fn note(&self, message: &str) {
+        if self.is_verbose() {
+            println!("{}", message.dimmed());
+        }
+    }
+
+    /// 警告（Human 以外・--quiet では stderr に出力）
+    pub fn warn(&self, message: &str) {
+        if self.is_verbose() {
+            println!("{}", message.yellow());
+        } else {
+            eprintln!("{}", message);
+        }
+    }
+
+    /// 検索中のスピナー（Human 以外では非表示）
+    pub fn spinner(&self, message: &str) -> ProgressBar {
+        if !self.is_verbose() {
+            return ProgressBar::hidden();
+        }
+
+        let spinner = ProgressBar::new_spinner();
+        spinner.set_style(
+            ProgressStyle::default_spinner()
+                .template("{spinner:.cyan} {msg}")
+                .unwrap(),
+        );
+        spinner.set_message(message.to_string());
+        spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+        spinner
+    }
+
+    /// 削除中のプログレスバー（Human 以外では非表示）
+    ///
+    /// 件数ではなく合計バイト数で進めるため、削除した項目のサイズ分だけ `inc` する。
+    /// --report-before-after では、ここで削除前の空き容量を測る
+    pub fn progress(&mut self, total_bytes: u64) -> ProgressBar {
+        if self.measure_disk {
+            self.disk_before = self.disk_usage();
+        }
+
+        if !self.is_verbose() {
+            return ProgressBar::hidden();
+        }
+
+        println!("\n{}", "🗑️  削除中...".red().bold());
+
+        let pb = ProgressBar::new(total_bytes);
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template("{spinner:.green} [{bar:40.cyan/blue}] {bytes}/{total_bytes} {msg}")
+                .unwrap()
+                .progress_chars("#>-"),
+        );
+        pb
+    }
+
+    /// 対象が見つからなかった
+    pub fn not_found(&self, message: &str) {
+        if self.is_verbose() {
+            println!("{}", format!("✨ {}", message).green());
+        }
+    }
+
+    /// 見つかった件数と合計
+    ///
+    /// `noun`: 「N 件の {noun}を発見」の {noun} 部分（空なら「N 件を発見」）
+    pub fn found(&mut self, count: usize, noun: &str, total_size: u64) {
+        self.report.total_size = total_size;
+
+        if self.is_verbose() {
+            let noun = if noun.is_empty() {
+                String::new()
+            } else {
+                format!("の {}", noun)
+            };
+            println!(
+                "\n{} 件{}を発見 (合計: {})\n",
+                count.to_string().yellow().bold(),
+                noun,
+                kanri_core::utils::format_size(total_size).yellow().bold()
+            );
+        }
+    }
+
+    /// 一覧の 1 項目
+    ///
+    /// `detail`: 補足（例: 含まれるディレクトリ名）
+    /// `safety`: (安全かどうか, 安全性ラベル)
+    pub fn item(
+        &mut self,
+        index: usize,
+        name: &str,
+        path: &Path,
+        size: u64,
+        detail: Option<&str>,
+        safety: Option<(bool, &str)>,
+    ) {
+        self.group_item(index, name, &[(path, size)], detail, safety);
+    }
+
+    /// 複数のパスをまとめた一覧の 1 項目（例: アプリごとのキャッシュ）
+    ///
+    /// `paths`: (パス, サイズ)。Plain では 1 パス 1 行で出力し、JSON では 2 件以上なら `entries` に出力する
+    pub fn group_item(
+        &mut self,
+        index: usize,
+        name: &str,
+        paths: &[(&Path, u64)],
+        detail: Option<&str>,
+        safety: Option<(bool, &str)>,
+    ) {
+        let size = paths.iter().map(|(_, size)| size).sum();
+        match self.format {
+            _ if self.quiet => {}
+            OutputFormat::Human => {
+                let size_label = kanri_core::utils::format_size(size).yellow();
+                let detail = detail
+                    .map(|d| format!(" {}", format!("({})", d).dimmed()))
+                    .unwrap_or_default();
+                match safety {
+                    Some((is_safe, label)) => {
+                        let (icon, label) = if is_safe {
+                            ("✓", label.green())
+                        } else {
+                            ("⚠", label.yellow())
+                        };
+                        println!(
+                            "  {}. {} {} - {}{} {}",
+                            index.to_string().dimmed(),
+                            icon,
+                            name.bright_blue(),
+                            size_label,
+                            detail,
+                            label
+                        );
+                    }
+                    None => println!(
+                        "  {}. {} - {}{}",
+                        index.to_string().dimmed(),
+                        name.bright_blue(),
+                        size_label,
+                        detail
+                    ),
+                }
+            }
+            OutputFormat::Plain => {
+                let safe = safety
+                    .map(|(is_safe, _)| if is_safe { "safe" } else { "unsafe" })
+                    .unwrap_or("");
+                for (path, size) in paths {
+                    println!("{}\t{}\t{}\t{}", name, path.display(), size, safe);
+                }
+            }
+            OutputFormat::Json => {}
+        }
+
+        let (path, entries) = match paths {
+            [(path, _)] => (Some(path.display().to_string()), Vec::new()),
+            paths => (
+                None,
+                paths
+                    .iter()
+                    .map(|(path, size)| ReportEntry {
+                        path: path.display().to_string(),
+                        size: *size,
+                    })
+                    .collect(),
+            ),
+        };
+        self.report.items.push(ReportItem {
+            name: name.to_string(),
+            path,
+            entries,
+            size,
+            safe: safety.map(|(is_safe, _)| is_safe),
+        });
+    }
+
+    /// --top で省略された項目
+    pub fn hidden(&mut self, count: usize, size: u64) {
+        self.report.hidden_count = count;
+        self.report.hidden_size = size;
+
+        if count == 0 || self.quiet {
+            return;
+        }
+        match self.format {
+            OutputFormat::Human => println!(
+                "  {}",
+                format!(
+                    "... 他 {} 件 ({})",
+                    count,
+                    kanri_core::utils::format_size(size)
+                )
+                .dimmed()
+            ),
+            OutputFormat::Plain => println!("hidden\t{}\t{}", count, size),
+            OutputFormat::Json => {}
+        }
+    }
+
+    /// 検索モード（削除せずに終了）
+    pub fn search_mode(&self, extra_hints: &[&str]) {
+        if !self.is_human() || self.stats_only {
+            return;
+        }
+
+        // --quiet では見つかった件数と合計だけを 1 行で表示
+        if self.quiet {
+            let count = self.report.items.len() + self.report.hidden_count;
+            println!(
+                "{} {} 件を発見 (合計: {})",
+                "ℹ".cyan(),
+                count.to_string().yellow().bold(),
+                kanri_core::utils::format_size(self.report.total_size).yellow().bold()
+            );
+            return;
+        }
+
+        println!(
+            "\n{} {}",
+            "ℹ".cyan(),
+            "検索モード: 削除対象を表示しています".dimmed()
+        );
+        println!(
+            "{} {}",
+            "💡".cyan(),
+            "削除するには --delete (-d) を指定してください".dimmed()
+        );
+        println!(
+            "{} {}",
+            "💡".cyan(),
+            "確認しながら削除するには --interactive (-i) を指定してください".dimmed()
+        );
+        for hint in extra_hints {
+            println!("{} {}", "💡".cyan(), hint.dimmed());
+        }
+
+        if let Some(usage) = self.disk_usage() {
+            let size = self.report.total_size;
+            self.print_disk_change(&usage, usage.free, usage.free_after(size), size);
+        }
+    }
+
+    /// 削除対象から除外した要確認の項目
+    pub fn skipped_unsafe(&self, count: usize, size: u64) {
+        if count == 0 {
+            return;
+        }
+        self.warn(&format!(
+            "⚠ 要確認の {} 件 ({}) は削除対象から除外しました（含めるには --include-unsafe を指定）",
+            count,
+            kanri_core::utils::format_size(size)
+        ));
+    }
+
+    /// y/N で質問（Human 以外と --output-file 指定時はプロンプトを stderr に出す）
+    pub fn ask(&self, message: &str) -> io::Result<bool> {
+        if self.is_human() {
+            write_prompt(&format!("\n{} {} (y/N): ", "⚠".yellow().bold(), message))?;
+        } else {
+            eprint!("{} (y/N): ", message);
+            io::stderr().flush()?;
+        }
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        Ok(input.trim().eq_ignore_ascii_case("y"))
+    }
+
+    /// 1 行の入力を求める（Human 以外と --output-file 指定時はプロンプトを stderr に出す）
+    pub fn prompt(&self, message: &str) -> io::Result<String> {
+        if self.is_human() {
+            write_prompt(&format!("\n{} {}: ", "?".cyan().bold(), message))?;
+        } else {
+            eprint!("{}: ", message);
+            io::stderr().flush()?;
+        }
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        Ok(input.trim().to_string())
+    }
+
+    /// 削除前の確認（拒否された場合はキャンセル扱い）
+    pub fn confirm(&mut self, message: &str) -> io::Result<bool> {
+        let confirmed = self.ask(message)?;
+        if !confirmed {
+            self.cancel();
+        }
+        Ok(confirmed)
+    }
+
+    /// 削除直前の最終確認: サイズの大きい順に上位の項目と合計を表示してから確認する
+    ///
+    /// `entries`: (パス, サイズ)。拒否された場合はキャンセル扱い
+    pub fn confirm_deletion(&mut self, entries: &[(String, u64)]) -> io::Result<bool> {
+        let mut sorted: Vec<&(String, u64)> = entries.iter().collect();
+        sorted.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+        let total_size: u64 = entries.iter().map(|(_, size)| size).sum();
+        let rest = entries.len().saturating_sub(CONFIRM_TOP_ITEMS);
+
+        if self.is_human() {
+            // 確認のプロンプトと同じ出力先に出す
+            write_prompt(&format!("\n{}\n", "📋 削除する項目（サイズの大きい順）".cyan().bold()))?;
+            for (path, size) in sorted.iter().take(CONFIRM_TOP_ITEMS) {
+                let size = format!("{:>10}", kanri_core::utils::format_size(*size));
+                write_prompt(&format!("  {}  {}\n", size.yellow(), path))?;
+            }
+            if rest > 0 {
+                write_prompt(&format!("  {}\n", format!("... ほか {} 件", rest).dimmed()))?;
+            }
+        } else {
+            // 確認のプロンプトと同じく stderr に出す
+            for (path, size) in sorted.iter().take(CONFIRM_TOP_ITEMS) {
+                eprintln!("{}\t{}", kanri_core::utils::format_size(*size), path);
+            }
+            if rest > 0 {
+                eprintln!("... ほか {} 件", rest);
+            }
+        }
+
+        self.confirm(&format!(
+            "{} 件 ({}) を削除します。本当に削除しますか?",
+            entries.len(),
+            kanri_core::utils::format_size(total_size)
+        ))
+    }
+
+    /// 環境変数などから決まった対象ディレクトリ（絶対パス）とその決まり方を表示
+    ///
+    /// 削除前の確認用なので、Human 以外・--quiet でも stderr に出力する
+    pub fn resolved_dir(&self, path: &Path, source: kanri_core::utils::DirSource) {
+        let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+        if self.is_verbose() {
+            println!(
+                "\n{} {} {}",
+                "📍 対象ディレクトリ:".cyan(),
+                path.display().to_string().bold(),
+                format!("（{} から決定）", source).dimmed()
+            );
+        } else {
+            eprintln!("📍 対象ディレクトリ: {}（{} から決定）", path.display(), source);
+        }
+    }
+
+    /// キャンセル扱いにする
+    pub fn cancel(&mut self) {
+        self.report.mode = "cancelled";
+        self.warn("キャンセルされました");
+    }
+
+    /// 見つかった件数と合計サイズ（--top で省略された項目も含む）
+    pub fn found_summary(&self) -> (usize, u64) {
+        (
+            self.report.items.len() + self.report.hidden_count,
+            self.report.total_size,
+        )
+    }
+
+    /// --stats-only の結果（`command`: 例 "clean rust"）
+    pub fn stats(&self, command: &str) {
+        let (count, total_size) = self.found_summary();
+        match self.format {
+            OutputFormat::Human => println!(
+                "{} {}: {} 件 (合計: {})",
+                "📊".cyan(),
+                command,
+                count.to_string().yellow().bold(),
+                kanri_core::utils::format_size(total_size).yellow().bold()
+            ),
+            OutputFormat::Plain => println!("stats\t{}\t{}\t{}", command, count, total_size),
+            OutputFormat::Json => {}
+        }
+    }
+
+    /// 確認で拒否された・何も選ばれなかったか
+    pub fn is_cancelled(&self) -> bool {
+        self.report.mode == "cancelled"
+    }
+
+    /// ドライラン: 削除予定の項目と解放予定のサイズ
+    pub fn dry_run(&mut self, entries: &[(String, u64)]) {
+        self.report.mode = "dry-run";
+        let total_size: u64 = entries.iter().map(|(_, size)| size).sum();
+
+        match self.format {
+            OutputFormat::Human if self.quiet => println!(
+                "{} {} 件 ({}) を解放予定（実際には削除していません）",
+                "ℹ".cyan(),
+                entries.len().to_string().yellow().bold(),
+                kanri_core::utils::format_size(total_size).yellow().bold()
+            ),
+            OutputFormat::Human => {
+                println!("\n{}", "🔍 ドライラン: 以下を削除予定です".cyan().bold());
+                for (path, size) in entries {
+                    println!(
+                        "  {} {} - {}",
+                        "-".dimmed(),
+                        path.bright_blue(),
+                        kanri_core::utils::format_size(*size).yellow()
+                    );
+                }
+                println!(
+                    "\n{} {} 件 ({}) を解放予定（実際には削除していません）",
+                    "ℹ".cyan(),
+                    entries.len().to_string().yellow().bold(),
+                    kanri_core::utils::format_size(total_size).yellow().bold()
+                );
+            }
+            OutputFormat::Plain if self.quiet => {}
+            OutputFormat::Plain => {
+                for (path, size) in entries {
+                    println!("would-delete\t{}\t{}", path, size);
+                }
+            }
+            OutputFormat::Json => {}
+        }
+
+        if let Some(usage) = self.disk_usage() {
+            let free_after = usage.free_after(total_size);
+            self.print_disk_change(&usage, usage.free, free_after, total_size);
+            self.report.disk = Some(DiskSummary {
+                total: usage.total,
+                free_before: usage.free,
+                free_after,
+                measured_freed: None,
+                local_snapshots: Vec::new(),
+            });
+        }
+
+        self.report.would_delete = entries
+            .iter()
+            .map(|(path, size)| ReportEntry {
+                path: path.clone(),
+                size: *size,
+            })
+            .collect();
+    }
+
+    /// 削除完了
+    ///
+    /// `noun`: 「N 件の{noun}をクリーンしました」の {noun} 部分（空なら「N 件をクリーンしました」）
+    pub fn cleaned(&mut self, count: usize, noun: &str, freed_size: u64) {
+        self.report.mode = "delete";
+
+        match self.format {
+            OutputFormat::Human => {
+                let noun = if noun.is_empty() {
+                    String::new()
+                } else {
+                    format!("の{}", noun)
+                };
+                println!(
+                    "{}{} {} 件{}をクリーンしました ({}削除)",
+                    if self.quiet { "" } else { "\n" },
+                    "✅".green(),
+                    count.to_string().green().bold(),
+                    noun,
+                    kanri_core::utils::format_size(freed_size).green().bold()
+                );
+            }
+            OutputFormat::Plain => println!("cleaned\t{}\t{}", count, freed_size),
+            OutputFormat::Json => {}
+        }
+
+        if let (Some(before), Some(mut usage)) = (self.disk_before, self.disk_usage()) {
+            let mut local_snapshots = Vec::new();
+            if self.print_measured_disk_change(&before, &usage, freed_size) {
+                let measured = usage.free.saturating_sub(before.free);
+                local_snapshots = self.check_local_snapshots(freed_size - measured);
+                // 間引いた場合は、その後の空き容量を測り直す
+                if self.thin_snapshots && !local_snapshots.is_empty() {
+                    if let Some(thinned) = self.disk_usage() {
+                        usage = thinned;
+                        self.print_measured_disk_change(&before, &usage, freed_size);
+                    }
+                }
+            }
+            self.report.disk = Some(DiskSummary {
+                total: usage.total,
+                free_before: before.free,
+                free_after: usage.free,
+                measured_freed: Some(usage.free.saturating_sub(before.free)),
+                local_snapshots,
+            });
+        } else if let Some(usage) = self.disk_usage() {
+            // 削除後に測るため、削除前の空き容量は解放したサイズから逆算する
+            let free_before = usage.free.saturating_sub(freed_size);
+            self.print_disk_change(&usage, free_before, usage.free, freed_size);
+            self.report.disk = Some(DiskSummary {
+                total: usage.total,
+                free_before,
+                free_after: usage.free,
+                measured_freed: None,
+                local_snapshots: Vec::new(),
+            });
+        }
+
+        self.report.cleaned = Some(CleanedSummary { count, freed_size });
+    }
+
+    /// 出力を確定（JSON の場合はここでまとめて出力）
+    pub fn finish(self) -> serde_json::Result<()> {
+        if self.format == OutputFormat::Json {
+            println!("{}", serde_json::to_string_pretty(&self.report)?);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    cleanable::{Cleanable, CleanableItem},
+    utils, Result,
+};
+
+/// プロジェクトローカルのビルド成果物ディレクトリ名
+const ARTIFACT_DIRS: &[&str] = &["build", ".gradle", ".cxx"];
+
+/// Android / Gradle プロジェクト情報
+#[derive(Debug, Clone)]
+pub struct AndroidProject {
+    /// プロジェクトのルートディレクトリ（build.gradle / build.gradle.kts があるディレクトリ）
+    pub root: PathBuf,
+    /// 存在するビルド成果物ディレクトリ（build, .gradle, .cxx）とそのサイズ
+    pub artifacts: Vec<(PathBuf, u64)>,
+    /// 合計サイズ（バイト）
+    pub size: u64,
+}
+
+impl AndroidProject {
+    /// サイズを人間が読みやすい形式で取得
+    pub fn formatted_size(&self) -> String {
+        utils::format_size(self.size)
+    }
+}
+
+/// 指定されたディレクトリ以下の Android / Gradle プロジェクトを検索
+pub fn find_android_projects(search_path: &Path) -> Result<Vec<AndroidProject>> {
+    let mut projects = Vec::new();
+
+    let entries = utils::walk_entries(utils::walk_dir(search_path), |_| false, |e| {
+        // これから報告するビルド成果物ディレクトリには降りない
+        let file_name = e.file_name().to_string_lossy();
+        !matches!(
+            file_name.as_ref(),
+            "target" | ".git" | "node_modules" | ".cache" | "build" | ".gradle" | ".cxx"
+        )
+    });
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        if entry.file_type().is_file()
+            && matches!(
+                entry.file_name().to_string_lossy().as_ref(),
+                "build.gradle" | "build.gradle.kts"
+            )
+        {
+            if let Some(project_root) = entry.path().parent() {
+                // build.gradle と build.gradle.kts が両方ある場合の重複を避ける
+                if projects.iter().any(|p: &AndroidProject| p.root == project_root) {
+                    continue;
+                }
+
+                let mut artifacts = Vec::new();
+                for name in ARTIFACT_DIRS {
+                    let dir = project_root.join(name);
+                    if dir.is_dir() {
+                        let size = utils::calculate_dir_size(&dir)?;
+                        artifacts.push((dir, size));
+                    }
+                }
+
+                // ビルド成果物が存在する場合のみ追加
+                if !artifacts.is_empty() {
+                    let size = artifacts.iter().map(|(_, size)| size).sum();
+
+                    projects.push(AndroidProject {
+                        root: project_root.to_path_buf(),
+                        artifacts,
+                        size,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(projects)
+}
+
+/// Android プロジェクトのビルド成果物を削除
+pub fn clean_project(project: &AndroidProject) -> Result<()> {
+    for (dir, _) in &project.artifacts {
+        if dir.exists() {
+            fs::remove_dir_all(dir)?;
+        }
+    }
+    Ok(())
+}
+
+/// Android / Gradle プロジェクトクリーナー
+pub struct AndroidCleaner {
+    pub search_path: PathBuf,
+}
+
+impl AndroidCleaner {
+    pub fn new(search_path: PathBuf) -> Self {
+        Self { search_path }
+    }
+}
+
+impl Cleanable for AndroidCleaner {
+    fn scan(&self) -> Result<Vec<CleanableItem>> {
+        let projects = find_android_projects(&self.search_path)?;
+        let mut items = Vec::new();
+
+        // 1 プロジェクトを 1 項目にまとめ、削除するのはビルド成果物ディレクトリだけ
+        for project in projects {
+            let dir_names: Vec<String> = project
+                .artifacts
+                .iter()
+                .filter_map(|(dir, _)| dir.file_name())
+                .map(|name| name.to_string_lossy().to_string())
+                .collect();
+            items.push(
+                CleanableItem::new(project.root.display().to_string(), project.root.clone(), project.size)
+                    .with_entries(project.artifacts)
+                    .with_detail(Some(dir_names.join(", "))),
+            );
+        }
+
+        Ok(items)
+    }
+
+    fn name(&self) -> &str {
+        "Android"
+    }
+
+    fn icon(&self) -> &str {
+        "🤖"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_find_android_projects() -> Result<()> {
+        let temp = TempDir::new()?;
+        let project_dir = temp.path().join("android-app");
+        let app_dir = project_dir.join("app");
+        fs::create_dir_all(&app_dir)?;
+
+        // ルートとモジュールの build.gradle
+        fs::write(project_dir.join("build.gradle.kts"), "plugins {}")?;
+        fs::write(app_dir.join("build.gradle"), "android {}")?;
+
+        fs::create_dir(project_dir.join(".gradle"))?;
+        fs::write(project_dir.join(".gradle").join("cache.bin"), "cache")?;
+        fs::create_dir(app_dir.join("build"))?;
+        fs::write(app_dir.join("build").join("app.apk"), "apk data")?;
+        fs::create_dir(app_dir.join(".cxx"))?;
+        fs::write(app_dir.join(".cxx").join("obj.o"), "obj")?;
+
+        // build 配下の build.gradle は検出されない
+        let nested = app_dir.join("build").join("generated");
+        fs::create_dir_all(&nested)?;
+        fs::write(nested.join("build.gradle"), "")?;
+
+        let mut projects = find_android_projects(temp.path())?;
+        projects.sort_by(|a, b| a.root.cmp(&b.root));
+
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects[0].root, project_dir);
+        assert_eq!(projects[0].artifacts.len(), 1);
+        assert_eq!(projects[0].artifacts[0].0, project_dir.join(".gradle"));
+        assert_eq!(projects[1].root, app_dir);
+        assert_eq!(projects[1].artifacts.len(), 2);
+        assert!(projects[1].size > 0);
+
+        clean_project(&projects[1])?;
+        assert!(!app_dir.join("build").exists());
+        assert!(!app_dir.join(".cxx").exists());
+        assert!(app_dir.join("build.gradle").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_android_cleaner_clean() -> Result<()> {
+        let temp = TempDir::new()?;
+        let project_dir = temp.path().join("android-app");
+        fs::create_dir_all(project_dir.join("build"))?;
+        fs::create_dir_all(project_dir.join(".gradle"))?;
+        fs::write(project_dir.join("build.gradle"), "android {}")?;
+        fs::write(project_dir.join("build").join("app.apk"), vec![0u8; 200])?;
+        fs::write(project_dir.join(".gradle").join("cache.bin"), vec![0u8; 50])?;
+
+        // プロジェクトごとに 1 項目で、削除するパスはビルド成果物ディレクトリ
+        let cleaner = AndroidCleaner::new(temp.path().to_path_buf());
+        let items = cleaner.scan()?;
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].name, project_dir.display().to_string());
+        assert_eq!(items[0].size, 250);
+        assert_eq!(items[0].detail.as_deref(), Some("build, .gradle"));
+        assert_eq!(
+            items[0].paths(),
+            vec![(project_dir.join("build").as_path(), 200), (project_dir.join(".gradle").as_path(), 50)]
+        );
+
+        let cleaned = cleaner.clean(&items, &mut |_| {})?;
+        assert_eq!(cleaned.len(), 1);
+        assert!(!project_dir.join("build").exists());
+        assert!(!project_dir.join(".gradle").exists());
+        assert!(project_dir.join("build.gradle").exists());
+
+        Ok(())
+    }
+}
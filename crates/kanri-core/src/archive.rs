@@ -1,3 +1,8 @@
+pub mod blob_index;
+pub mod bundle;
+pub mod versioning;
+
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -24,10 +29,18 @@ pub struct Archive {
     pub cleaner: String,
     /// アーカイブ先（B2 パス）
     pub destination: String,
+    /// アーカイブ元の検索パス
+    #[serde(default)]
+    pub source_path: PathBuf,
     /// アーカイブアイテム
     pub items: Vec<ArchiveItem>,
     /// 合計サイズ
     pub total_size: u64,
+    /// 差分アーカイブ（--incremental）の基準にしたアーカイブの ID
+    ///
+    /// 変更のないアイテムは基準のアーカイブのリモートパスを参照する
+    #[serde(default)]
+    pub parent_id: Option<String>,
 }
 
 /// アーカイブアイテム
@@ -43,6 +56,163 @@ pub struct ArchiveItem {
     pub size: u64,
     /// ディレクトリかどうか
     pub is_dir: bool,
+    /// ディレクトリ内のファイル数（ディレクトリの場合のみ）
+    #[serde(default)]
+    pub file_count: Option<u64>,
+    /// リモートで自動削除される日時（--expire-days 指定時のみ）
+    #[serde(default)]
+    pub expire_at: Option<DateTime<Utc>>,
+    /// tar にまとめてアップロードした場合の、含まれるファイルの相対パス（--bundle 指定時のみ）
+    #[serde(default)]
+    pub bundle_members: Option<Vec<String>>,
+    /// 同じ内容でアップロード済みのファイル（blob）のリモートパス（--dedup で重複を検出した場合のみ）
+    ///
+    /// 設定されている場合は b2_path にはアップロードしておらず、復元時はこのパスからダウンロードする
+    #[serde(default)]
+    pub blob_ref: Option<String>,
+    /// 分割してアップロードしたパーツのリモートパス（--split-size 指定時のみ）
+    ///
+    /// 設定されている場合は b2_path にはアップロードしておらず、復元時はパーツを結合する
+    #[serde(default)]
+    pub parts: Option<Vec<String>>,
+}
+
+/// リモートから復元したアーカイブのクリーナー名
+pub const REMOTE_CLEANER: &str = "remote";
+
+/// マニフェストの形式のバージョン
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// アーカイブのチェックサムマニフェスト
+///
+/// アーカイブインデックスがない環境でも、記載されたファイルだけを復元・検証できる
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// マニフェストの形式のバージョン
+    pub version: u32,
+    /// 元のアーカイブ ID
+    pub archive_id: String,
+    /// 元のアーカイブの作成日時
+    pub created_at: DateTime<Utc>,
+    /// アーカイブ先（B2 パス）。復元先の相対パスの基準になる
+    pub destination: String,
+    /// ファイル一覧
+    pub files: Vec<ManifestEntry>,
+}
+
+/// マニフェストの 1 ファイル
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// B2 パス
+    pub b2_path: String,
+    /// SHA256 ハッシュ
+    pub sha256: String,
+    /// サイズ
+    pub size: u64,
+    /// 分割してアップロードしたパーツのリモートパス（--split-size 指定時のみ）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parts: Option<Vec<String>>,
+}
+
+impl Manifest {
+    /// アーカイブからマニフェストを作成
+    ///
+    /// ディレクトリアイテムは配下のファイルごとのハッシュを持たないため含まれない
+    pub fn from_archive(archive: &Archive) -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            archive_id: archive.id.clone(),
+            created_at: archive.created_at,
+            destination: archive.destination.clone(),
+            files: archive
+                .items
+                .iter()
+                .filter(|item| !item.is_dir && !item.sha256.is_empty())
+                .map(|item| ManifestEntry {
+                    b2_path: item.b2_path.clone(),
+                    sha256: item.sha256.clone(),
+                    size: item.size,
+                    parts: item.parts.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// 合計サイズ
+    pub fn total_size(&self) -> u64 {
+        self.files.iter().map(|entry| entry.size).sum()
+    }
+
+    /// 復元先ディレクトリからの相対パス（アーカイブ先のプレフィックスを除く）
+    pub fn restore_path(&self, entry: &ManifestEntry) -> String {
+        let path = match entry.b2_path.strip_prefix(&self.destination) {
+            Some(relative) => relative.to_string(),
+            None => match versioning::extract_timestamp(&entry.b2_path) {
+                Some(timestamp) => versioning::remove_timestamp(&entry.b2_path, &timestamp),
+                None => entry.b2_path.clone(),
+            },
+        };
+        path.trim_start_matches('/').to_string()
+    }
+
+    /// 記載内容を検証
+    fn validate(&self) -> Result<()> {
+        if self.version != MANIFEST_VERSION {
+            return Err(crate::Error::Archive(format!(
+                "Unsupported manifest version: {} (expected {})",
+                self.version, MANIFEST_VERSION
+            )));
+        }
+
+        for entry in &self.files {
+            if entry.b2_path.is_empty() {
+                return Err(crate::Error::Archive("Manifest entry has an empty b2_path".into()));
+            }
+            if entry.b2_path.split('/').any(|part| part == "..") {
+                return Err(crate::Error::Archive(format!(
+                    "Manifest entry escapes the restore directory: {}",
+                    entry.b2_path
+                )));
+            }
+            if entry.sha256.len() != 64 || !entry.sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(crate::Error::Archive(format!(
+                    "Invalid SHA256 for {}: {:?}",
+                    entry.b2_path, entry.sha256
+                )));
+            }
+            for part in entry.parts.iter().flatten() {
+                if part.split('/').any(|part| part == "..") {
+                    return Err(crate::Error::Archive(format!(
+                        "Manifest entry part escapes the restore directory: {}",
+                        part
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// アーカイブのマニフェストを JSON で書き出す
+pub fn export_manifest(archive: &Archive) -> Result<String> {
+    serde_json::to_string_pretty(&Manifest::from_archive(archive))
+        .map_err(|e| crate::Error::Archive(format!("Failed to serialize manifest: {}", e)))
+}
+
+/// マニフェストの JSON を読み込んで検証
+pub fn import_manifest(path: &Path) -> Result<Manifest> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| crate::Error::Archive(format!("Failed to read manifest: {}", e)))?;
+    parse_manifest(&content)
+}
+
+/// マニフェストの JSON を解析して検証
+fn parse_manifest(content: &str) -> Result<Manifest> {
+    let manifest: Manifest = serde_json::from_str(content)
+        .map_err(|e| crate::Error::Archive(format!("Failed to parse manifest: {}", e)))?;
+    manifest.validate()?;
+    Ok(manifest)
 }
 
 impl ArchiveIndex {
@@ -55,17 +225,25 @@ impl ArchiveIndex {
             .join("archive_index.json"))
     }
 
+    /// 読み込み・変更・保存の間に取るロックファイルのパスを取得
+    pub fn lock_path() -> Result<PathBuf> {
+        Ok(Self::index_path()?.with_file_name("archive_index.lock"))
+    }
+
     /// アーカイブインデックスを読み込み
     pub fn load() -> Result<Self> {
-        let path = Self::index_path()?;
+        Self::load_from(&Self::index_path()?)
+    }
 
+    /// 指定したファイルから読み込み（存在しない場合は空）
+    pub fn load_from(path: &Path) -> Result<Self> {
         if !path.exists() {
             return Ok(ArchiveIndex {
                 archives: Vec::new(),
             });
         }
 
-        let content = fs::read_to_string(&path).map_err(|e| {
+        let content = fs::read_to_string(path).map_err(|e| {
             crate::Error::Config(format!("Failed to read archive index: {}", e))
         })?;
 
@@ -78,8 +256,11 @@ impl ArchiveIndex {
 
     /// アーカイブインデックスを保存
     pub fn save(&self) -> Result<()> {
-        let path = Self::index_path()?;
+        self.save_to(&Self::index_path()?)
+    }
 
+    /// 指定したファイルに保存（一時ファイルに書き出してから置き換える）
+    pub fn save_to(&self, path: &Path) -> Result<()> {
         // ディレクトリを作成
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent).map_err(|e| {
@@ -91,13 +272,29 @@ impl ArchiveIndex {
             crate::Error::Config(format!("Failed to serialize archive index: {}", e))
         })?;
 
-        fs::write(&path, content).map_err(|e| {
+        crate::utils::write_atomic(path, content).map_err(|e| {
             crate::Error::Config(format!("Failed to write archive index: {}", e))
         })?;
 
         Ok(())
     }
 
+    /// ロックを取って最新のインデックスを読み込み、`f` で変更して保存する
+    ///
+    /// 同時に実行された archive などが互いの変更を上書きしないよう、変更はこの関数を通して行う
+    pub fn update<T>(f: impl FnOnce(&mut Self) -> T) -> Result<T> {
+        Self::update_at(&Self::index_path()?, &Self::lock_path()?, f)
+    }
+
+    /// 指定したファイルでロックを取り、読み込み・変更・保存を行う
+    fn update_at<T>(path: &Path, lock_path: &Path, f: impl FnOnce(&mut Self) -> T) -> Result<T> {
+        let _lock = crate::utils::FileLock::acquire(lock_path)?;
+        let mut index = Self::load_from(path)?;
+        let result = f(&mut index);
+        index.save_to(path)?;
+        Ok(result)
+    }
+
     /// 新しいアーカイブを追加
     pub fn add_archive(&mut self, archive: Archive) {
         self.archives.push(archive);
@@ -108,6 +305,90 @@ impl ArchiveIndex {
         self.archives.iter().find(|a| a.id == id)
     }
 
+    /// アーカイブ先パスでアーカイブを検索
+    pub fn find_by_destination(&self, destination: &str) -> Option<&Archive> {
+        self.archives.iter().find(|a| a.destination == destination)
+    }
+
+    /// 同じアーカイブ元から同じアーカイブ先（`to` 配下）への最新のアーカイブ（--incremental の基準）
+    pub fn latest_for(&self, source_path: &Path, to: &str) -> Option<&Archive> {
+        let prefix = format!("{}/", to.trim_end_matches('/'));
+        self.archives
+            .iter()
+            .filter(|a| a.source_path == source_path && a.destination.starts_with(&prefix))
+            .max_by_key(|a| a.created_at)
+    }
+
+    /// 指定したアーカイブ以外のアーカイブが参照しているリモートパス
+    ///
+    /// 差分アーカイブは前回のアーカイブのファイルを参照するため、削除時にはこれらを残す
+    pub fn paths_referenced_by_others(&self, id: &str) -> HashSet<String> {
+        self.archives
+            .iter()
+            .filter(|a| a.id != id)
+            .flat_map(|a| a.items.iter())
+            .flat_map(|item| item.remote_paths())
+            .collect()
+    }
+
+    /// 指定したプレフィックス配下のアイテムと、その復元パス
+    fn items_for_prefix<'a>(&'a self, prefix: &str) -> impl Iterator<Item = (String, &'a ArchiveItem)> + 'a {
+        let prefix = prefix.to_string();
+        self.archives
+            .iter()
+            .flat_map(|a| a.items.iter().map(move |item| (a.restore_path(item), item)))
+            .filter(move |(path, _)| path.starts_with(&prefix))
+    }
+
+    /// 指定したプレフィックス配下の、実体が別のリモートパスにあるアイテム（復元パスごと）
+    ///
+    /// 共有の blob を参照しているもの（--dedup）と、前回のアーカイブを参照しているもの（--incremental）。
+    /// これらは復元パスにファイルがないため、リモートの一覧には現れない
+    pub fn remote_refs_for_prefix(&self, prefix: &str) -> Vec<(String, &ArchiveItem)> {
+        self.items_for_prefix(prefix)
+            .filter(|(path, item)| item.parts.is_none() && path != item.remote_path())
+            .collect()
+    }
+
+    /// 指定したプレフィックス配下の、分割してアップロードしたファイルのパーツ（復元パスごと）
+    pub fn parts_for_prefix(&self, prefix: &str) -> HashMap<String, Vec<String>> {
+        self.items_for_prefix(prefix)
+            .filter_map(|(path, item)| Some((path, item.parts.clone()?)))
+            .collect()
+    }
+
+    /// プレフィックス配下のファイルの SHA256 を復元パスごとに取得
+    ///
+    /// ディレクトリアイテムはハッシュを持たないため含まれない
+    pub fn checksums_for_prefix(&self, prefix: &str) -> HashMap<String, String> {
+        self.items_for_prefix(prefix)
+            .filter(|(_, item)| (!item.is_dir || item.is_bundle()) && !item.sha256.is_empty())
+            .map(|(path, item)| (path, item.sha256.clone()))
+            .collect()
+    }
+
+    /// 指定したプレフィックス配下のバンドル（tar）の復元パス
+    pub fn bundles_for_prefix(&self, prefix: &str) -> HashSet<String> {
+        self.items_for_prefix(prefix)
+            .filter(|(_, item)| item.is_bundle())
+            .map(|(path, _)| path)
+            .collect()
+    }
+
+    /// リモートから復元したアーカイブのうち、インデックスにないもの（アーカイブ先で判定）を追加
+    ///
+    /// 追加した件数を返す
+    pub fn merge_remote(&mut self, remote_archives: &[versioning::RemoteArchive]) -> usize {
+        let mut added = 0;
+        for remote in remote_archives {
+            if self.find_by_destination(&remote.destination).is_none() {
+                self.add_archive(Archive::from_remote(remote));
+                added += 1;
+            }
+        }
+        added
+    }
+
     /// アーカイブを削除
     pub fn remove_archive(&mut self, id: &str) -> bool {
         if let Some(pos) = self.archives.iter().position(|a| a.id == id) {
@@ -127,8 +408,96 @@ impl Archive {
             created_at: Utc::now(),
             cleaner,
             destination,
+            source_path: PathBuf::new(),
             items: Vec::new(),
             total_size: 0,
+            parent_id: None,
+        }
+    }
+
+    /// リモートから復元したアーカイブからインデックスのアーカイブを作成
+    ///
+    /// 元のローカルパスと SHA256 はリモートからはわからないため、
+    /// ローカルパスはアーカイブ先からの相対パス、SHA256 は空になる
+    pub fn from_remote(remote: &versioning::RemoteArchive) -> Self {
+        let mut archive = Self::new(REMOTE_CLEANER.to_string(), remote.destination.clone());
+        if let Some(created_at) = remote.created_at() {
+            archive.created_at = created_at;
+        }
+
+        for file in &remote.files {
+            let relative = file
+                .path
+                .strip_prefix(&remote.destination)
+                .unwrap_or(&file.path)
+                .trim_start_matches('/');
+            archive.add_item(ArchiveItem::new(
+                PathBuf::from(relative),
+                file.path.clone(),
+                String::new(),
+                file.size.unwrap_or(0),
+                false,
+            ));
+        }
+
+        archive
+    }
+
+    /// アーカイブ元の検索パスを設定
+    pub fn with_source_path(mut self, source_path: PathBuf) -> Self {
+        self.source_path = source_path;
+        self
+    }
+
+    /// 差分アーカイブの基準にしたアーカイブを設定
+    pub fn with_parent(mut self, parent_id: String) -> Self {
+        self.parent_id = Some(parent_id);
+        self
+    }
+
+    /// 前回のアーカイブで内容が変わっていなければ、アイテムを前回のリモートパスに結び付ける
+    ///
+    /// 同じローカルパスで SHA256 とサイズが一致するものを変更なしとする。ハッシュを持たない
+    /// ディレクトリと、自動削除が設定されたアイテムは対象外。結び付けた場合（アップロード不要）は true
+    pub fn link_unchanged(&self, item: &mut ArchiveItem) -> bool {
+        if item.sha256.is_empty() {
+            return false;
+        }
+        let previous = self.items.iter().find(|previous| {
+            previous.local_path == item.local_path
+                && previous.sha256 == item.sha256
+                && previous.size == item.size
+                && previous.expire_at.is_none()
+        });
+        match previous {
+            Some(previous) => {
+                item.b2_path = previous.b2_path.clone();
+                item.blob_ref = previous.blob_ref.clone();
+                item.parts = previous.parts.clone();
+                item.expire_at = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// アイテムが前回のアーカイブのリモートパスを参照しているか（--incremental で変更なし）
+    pub fn is_linked(&self, item: &ArchiveItem) -> bool {
+        !item.b2_path.starts_with(&format!("{}/", self.destination))
+    }
+
+    /// アイテムの復元パス（このアーカイブ先配下のパス）
+    ///
+    /// 前回のアーカイブを参照しているアイテムは、前回のアーカイブ先からの相対パスをこのアーカイブ先に付け替える。
+    /// 前回のアーカイブ先も同じ `<to>/<タイムスタンプ>` の形のため、同じ階層数を除いた残りが相対パスになる
+    pub fn restore_path(&self, item: &ArchiveItem) -> String {
+        if !self.is_linked(item) {
+            return item.b2_path.clone();
+        }
+        let depth = self.destination.split('/').count();
+        match item.b2_path.splitn(depth + 1, '/').nth(depth) {
+            Some(relative) => format!("{}/{}", self.destination, relative),
+            None => item.b2_path.clone(),
         }
     }
 
@@ -137,6 +506,19 @@ impl Archive {
         self.total_size += item.size;
         self.items.push(item);
     }
+
+    /// リモートで自動削除される日時（最も早いもの）
+    pub fn expire_at(&self) -> Option<DateTime<Utc>> {
+        self.items.iter().filter_map(|item| item.expire_at).min()
+    }
+
+    /// アーカイブに含まれるファイルの総数
+    pub fn file_count(&self) -> u64 {
+        self.items
+            .iter()
+            .map(|item| item.file_count.unwrap_or(1))
+            .sum()
+    }
 }
 
 impl ArchiveItem {
@@ -154,6 +536,29 @@ impl ArchiveItem {
             sha256,
             size,
             is_dir,
+            file_count: None,
+            expire_at: None,
+            bundle_members: None,
+            blob_ref: None,
+            parts: None,
+        }
+    }
+
+    /// tar にまとめてアップロードしたアイテムか
+    pub fn is_bundle(&self) -> bool {
+        self.bundle_members.is_some()
+    }
+
+    /// 実際にアップロードされているリモートパス（共有の blob を参照している場合はそのパス）
+    pub fn remote_path(&self) -> &str {
+        self.blob_ref.as_deref().unwrap_or(&self.b2_path)
+    }
+
+    /// 実際にアップロードされているリモートパスの一覧（分割した場合は各パーツ）
+    pub fn remote_paths(&self) -> Vec<String> {
+        match &self.parts {
+            Some(parts) => parts.clone(),
+            None => vec![self.remote_path().to_string()],
         }
     }
 
@@ -163,26 +568,162 @@ impl ArchiveItem {
             crate::Error::Archive(format!("Failed to get file metadata: {}", e))
         })?;
 
-        let size = metadata.len();
         let is_dir = metadata.is_dir();
 
-        // ディレクトリの場合は SHA256 は空
-        let sha256 = if is_dir {
-            String::new()
-        } else {
-            crate::b2::B2Client::calculate_sha256(local_path)?
-        };
+        if is_dir {
+            // ディレクトリの場合は SHA256 は空で、配下のファイル数と合計サイズを記録
+            let (file_count, size) = count_dir_files(local_path);
+            let mut item = Self::new(local_path.to_path_buf(), b2_path, String::new(), size, true);
+            item.file_count = Some(file_count);
+            return Ok(item);
+        }
+
+        let sha256 = crate::b2::B2Client::calculate_sha256(local_path)?;
 
         Ok(Self::new(
             local_path.to_path_buf(),
             b2_path,
             sha256,
-            size,
-            is_dir,
+            metadata.len(),
+            false,
         ))
     }
 }
 
+/// ディレクトリ配下のファイル数と合計サイズを取得
+fn count_dir_files(path: &Path) -> (u64, u64) {
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .fold((0, 0), |(count, size), e| {
+            let len = e.metadata().map(|m| m.len()).unwrap_or(0);
+            (count + 1, size + len)
+        })
+}
+
+/// ファイルの SHA256 が期待値と一致するか検証
+pub fn verify_checksum(path: &Path, expected: &str) -> Result<bool> {
+    let actual = crate::b2::B2Client::calculate_sha256(path)?;
+    Ok(actual.eq_ignore_ascii_case(expected))
+}
+
+/// 分割したパーツのパス（`<path>.part000`, `<path>.part001`, ...）
+pub fn part_path(path: &str, index: usize) -> String {
+    format!("{}.part{:03}", path, index)
+}
+
+/// ファイルを `part_size` バイトごとのパーツに分割し、1 つずつ `dest_dir` に書き出して `f` に渡す
+///
+/// パーツは `<ファイル名>.part000` から順に作成する。ディスクに置くパーツを常に 1 つにするため、
+/// `f`（パーツの番号とパスを受け取る）の後、成否にかかわらずパーツを削除する。パーツの数を返す
+pub fn split_file(
+    path: &Path,
+    part_size: u64,
+    dest_dir: &Path,
+    mut f: impl FnMut(usize, &Path) -> Result<()>,
+) -> Result<usize> {
+    if part_size == 0 {
+        return Err(crate::Error::Archive("Split size must be greater than 0".into()));
+    }
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| {
+            crate::Error::Archive(format!("Invalid file path: {}", path.display()))
+        })?
+        .to_string_lossy()
+        .to_string();
+    let total = fs::metadata(path)?.len();
+    let mut reader = std::io::BufReader::new(fs::File::open(path)?);
+
+    fs::create_dir_all(dest_dir)?;
+    let mut count = 0;
+    let mut written = 0;
+    while written < total || count == 0 {
+        let part = dest_dir.join(part_path(&file_name, count));
+        let result = write_part(&mut reader, part_size, &part).and_then(|copied| {
+            f(count, &part)?;
+            Ok(copied)
+        });
+        let _ = fs::remove_file(&part);
+        let copied = result?;
+        count += 1;
+        if copied == 0 {
+            break;
+        }
+        written += copied;
+    }
+
+    Ok(count)
+}
+
+/// `reader` から最大 `part_size` バイトを `part` に書き出し、書き出したバイト数を返す
+fn write_part(reader: &mut impl std::io::Read, part_size: u64, part: &Path) -> Result<u64> {
+    let mut writer = std::io::BufWriter::new(fs::File::create(part)?);
+    let copied = std::io::copy(&mut std::io::Read::take(reader, part_size), &mut writer)?;
+    std::io::Write::flush(&mut writer)?;
+    Ok(copied)
+}
+
+/// パーツを `dest` の末尾に追記する（最初のパーツの前に `dest` を空にしておく）
+pub fn append_part(part: &Path, dest: &Path) -> Result<()> {
+    let mut reader = fs::File::open(part).map_err(|e| {
+        crate::Error::Archive(format!("Failed to open part {}: {}", part.display(), e))
+    })?;
+    let mut writer = fs::OpenOptions::new().append(true).open(dest)?;
+    std::io::copy(&mut reader, &mut writer)?;
+
+    Ok(())
+}
+
+/// 復元先に同じパスのファイルが既にある場合の扱い
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverwritePolicy {
+    /// 既存のファイルは上書きしない
+    Never,
+    /// 常に上書きする
+    #[default]
+    Always,
+    /// リモートの方が新しい場合のみ上書きする
+    IfNewer,
+}
+
+/// 復元先のファイルをダウンロードするか（既存のファイルの扱いは `policy` に従う）
+///
+/// `IfNewer` では、内容が同じ（SHA256 が一致）ファイルは上書きしない。
+/// リモートの更新日時がわかる場合はローカルより新しいときだけ上書きし、
+/// わからない場合は SHA256 の記録があって内容が異なるときに上書きする
+pub fn should_download(
+    policy: OverwritePolicy,
+    local_path: &Path,
+    remote_modified: Option<DateTime<Utc>>,
+    expected_sha256: Option<&str>,
+) -> Result<bool> {
+    if !local_path.exists() {
+        return Ok(true);
+    }
+
+    match policy {
+        OverwritePolicy::Never => Ok(false),
+        OverwritePolicy::Always => Ok(true),
+        OverwritePolicy::IfNewer => {
+            if let Some(expected) = expected_sha256 {
+                if verify_checksum(local_path, expected)? {
+                    return Ok(false);
+                }
+            }
+            match remote_modified {
+                Some(remote) => {
+                    let local: DateTime<Utc> = fs::metadata(local_path)?.modified()?.into();
+                    Ok(remote > local)
+                }
+                None => Ok(expected_sha256.is_some()),
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -226,4 +767,436 @@ mod tests {
         assert!(removed);
         assert_eq!(index.archives.len(), 0);
     }
+
+    #[test]
+    fn test_checksums_for_prefix() {
+        let mut archive = Archive::new("test".to_string(), "archive/20251114_130523".to_string());
+        archive.add_item(ArchiveItem::new(
+            PathBuf::from("/tmp/model.ckpt"),
+            "archive/20251114_130523/model.ckpt".to_string(),
+            "abc123".to_string(),
+            1024,
+            false,
+        ));
+        archive.add_item(ArchiveItem::new(
+            PathBuf::from("/tmp/data"),
+            "archive/20251114_130523/data".to_string(),
+            String::new(),
+            4096,
+            true,
+        ));
+
+        let index = ArchiveIndex {
+            archives: vec![archive],
+        };
+
+        let checksums = index.checksums_for_prefix("archive");
+        assert_eq!(checksums.len(), 1);
+        assert_eq!(
+            checksums.get("archive/20251114_130523/model.ckpt"),
+            Some(&"abc123".to_string())
+        );
+        assert!(index.checksums_for_prefix("other").is_empty());
+        assert!(index.bundles_for_prefix("archive").is_empty());
+    }
+
+    #[test]
+    fn test_bundles_for_prefix() {
+        let mut archive = Archive::new("test".to_string(), "archive/20251114_130523".to_string());
+        let mut bundle = ArchiveItem::new(
+            PathBuf::from("/tmp/data"),
+            "archive/20251114_130523/data.tar".to_string(),
+            "def456".to_string(),
+            4096,
+            true,
+        );
+        bundle.bundle_members = Some(vec!["a.txt".to_string(), "b.txt".to_string()]);
+        archive.add_item(bundle);
+
+        let index = ArchiveIndex {
+            archives: vec![archive],
+        };
+
+        // バンドルはディレクトリでも tar 自体の SHA256 で検証できる
+        assert_eq!(
+            index.checksums_for_prefix("archive").get("archive/20251114_130523/data.tar"),
+            Some(&"def456".to_string())
+        );
+        assert_eq!(
+            index.bundles_for_prefix("archive"),
+            HashSet::from(["archive/20251114_130523/data.tar".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_merge_remote() {
+        let remote = versioning::RemoteArchive {
+            destination: "archive/20251114_130523".to_string(),
+            timestamp: "20251114_130523".to_string(),
+            files: vec![crate::storage::RemoteFile {
+                path: "archive/20251114_130523/models/a.bin".to_string(),
+                size: Some(100),
+                modified: None,
+            }],
+        };
+        let mut index = ArchiveIndex {
+            archives: Vec::new(),
+        };
+
+        assert_eq!(index.merge_remote(std::slice::from_ref(&remote)), 1);
+        let archive = &index.archives[0];
+        assert_eq!(archive.cleaner, REMOTE_CLEANER);
+        assert_eq!(archive.total_size, 100);
+        assert_eq!(archive.items[0].local_path, PathBuf::from("models/a.bin"));
+        assert_eq!(Some(archive.created_at), remote.created_at());
+
+        // 既にインデックスにあるアーカイブ先は追加しない
+        assert_eq!(index.merge_remote(&[remote]), 0);
+        assert_eq!(index.archives.len(), 1);
+    }
+
+    #[test]
+    fn test_verify_checksum() -> Result<()> {
+        use tempfile::TempDir;
+
+        let temp = TempDir::new()?;
+        let file_path = temp.path().join("test.txt");
+        fs::write(&file_path, b"hello world")?;
+
+        assert!(verify_checksum(
+            &file_path,
+            "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+        )?);
+        assert!(!verify_checksum(&file_path, "deadbeef")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_should_download() -> Result<()> {
+        use std::time::{Duration, SystemTime};
+        use tempfile::TempDir;
+
+        let temp = TempDir::new()?;
+        let missing = temp.path().join("missing.txt");
+        let local = temp.path().join("local.txt");
+        fs::write(&local, b"hello world")?;
+        let hello_sha = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+
+        let local_time = SystemTime::now() - Duration::from_secs(60 * 60);
+        fs::File::open(&local)?.set_modified(local_time)?;
+        let older: DateTime<Utc> = (local_time - Duration::from_secs(60)).into();
+        let newer: DateTime<Utc> = (local_time + Duration::from_secs(60)).into();
+
+        // 存在しないファイルは常にダウンロード
+        for policy in [OverwritePolicy::Never, OverwritePolicy::Always, OverwritePolicy::IfNewer] {
+            assert!(should_download(policy, &missing, None, None)?);
+        }
+
+        assert!(!should_download(OverwritePolicy::Never, &local, Some(newer), None)?);
+        assert!(should_download(OverwritePolicy::Always, &local, Some(older), None)?);
+
+        // 更新日時で判定
+        assert!(should_download(OverwritePolicy::IfNewer, &local, Some(newer), None)?);
+        assert!(!should_download(OverwritePolicy::IfNewer, &local, Some(older), None)?);
+        // 内容が同じなら新しくても上書きしない
+        assert!(!should_download(OverwritePolicy::IfNewer, &local, Some(newer), Some(hello_sha))?);
+        // 更新日時がわからない場合は SHA256 で判定
+        assert!(should_download(OverwritePolicy::IfNewer, &local, None, Some("deadbeef"))?);
+        assert!(!should_download(OverwritePolicy::IfNewer, &local, None, None)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_index() -> Result<()> {
+        use tempfile::TempDir;
+
+        let temp = TempDir::new()?;
+        let path = temp.path().join("archive_index.json");
+        let lock_path = temp.path().join("archive_index.lock");
+
+        let first = Archive::new("test".to_string(), "archive/20251114_130523".to_string());
+        let first_id = first.id.clone();
+        ArchiveIndex::update_at(&path, &lock_path, |index| index.add_archive(first))?;
+
+        // 保存の途中で中断した（一時ファイルが途中まで書かれた）状態でも、インデックスは読み込める
+        fs::write(temp.path().join("archive_index.json.tmp.1.0"), r#"{"archives": [{"id": "#)?;
+        let index = ArchiveIndex::load_from(&path)?;
+        assert_eq!(index.archives.len(), 1);
+
+        // 別々に読み込んだ変更が互いを上書きしない
+        let second = Archive::new("test".to_string(), "archive/20251115_090000".to_string());
+        let added = ArchiveIndex::update_at(&path, &lock_path, |index| {
+            index.add_archive(second);
+            index.archives.len()
+        })?;
+        assert_eq!(added, 2);
+        assert!(ArchiveIndex::load_from(&path)?.find_by_id(&first_id).is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_and_append_parts() -> Result<()> {
+        use tempfile::TempDir;
+
+        let temp = TempDir::new()?;
+        let original = temp.path().join("model.bin");
+        let data: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+        fs::write(&original, &data)?;
+        let expected = crate::b2::B2Client::calculate_sha256(&original)?;
+
+        // パーツは 1 つずつ渡され、次のパーツを作る前に削除される
+        let parts_dir = temp.path().join("parts");
+        let joined = temp.path().join("restored.bin");
+        fs::write(&joined, "")?;
+        let mut names = Vec::new();
+        let count = split_file(&original, 4096, &parts_dir, |i, part| {
+            assert_eq!(fs::read_dir(&parts_dir)?.count(), 1);
+            names.push((i, part.file_name().unwrap().to_string_lossy().to_string(), fs::metadata(part)?.len()));
+            append_part(part, &joined)
+        })?;
+        assert_eq!(count, 3);
+        assert_eq!(names[2], (2, "model.bin.part002".to_string(), 10_000 - 2 * 4096));
+        assert_eq!(fs::read_dir(&parts_dir)?.count(), 0);
+        assert!(verify_checksum(&joined, &expected)?);
+
+        // 分割サイズより小さいファイルは 1 パーツ
+        assert_eq!(split_file(&original, 20_000, &temp.path().join("single"), |_, _| Ok(()))?, 1);
+
+        // 途中で失敗してもパーツは残さない
+        let failed = split_file(&original, 4096, &parts_dir, |i, _| match i {
+            1 => Err(crate::Error::Archive("upload failed".into())),
+            _ => Ok(()),
+        });
+        assert!(failed.is_err());
+        assert_eq!(fs::read_dir(&parts_dir)?.count(), 0);
+        assert!(split_file(&original, 0, temp.path(), |_, _| Ok(())).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parts_for_prefix() {
+        let mut archive = Archive::new("test".to_string(), "archive/20251114_130523".to_string());
+        let mut item = ArchiveItem::new(
+            PathBuf::from("/tmp/model.bin"),
+            "archive/20251114_130523/model.bin".to_string(),
+            "abc123".to_string(),
+            3000,
+            false,
+        );
+        let parts = vec![
+            part_path("archive/20251114_130523/model.bin", 0),
+            part_path("archive/20251114_130523/model.bin", 1),
+        ];
+        item.parts = Some(parts.clone());
+        assert_eq!(item.remote_paths(), parts);
+        archive.add_item(item);
+        let archive_id = archive.id.clone();
+
+        let index = ArchiveIndex {
+            archives: vec![archive],
+        };
+        assert_eq!(
+            index.parts_for_prefix("archive").get("archive/20251114_130523/model.bin"),
+            Some(&parts)
+        );
+        assert!(index.paths_referenced_by_others("other").contains(&parts[1]));
+        assert!(index.paths_referenced_by_others(&archive_id).is_empty());
+    }
+
+    #[test]
+    fn test_archive_item_from_dir() -> Result<()> {
+        use tempfile::TempDir;
+
+        let temp = TempDir::new()?;
+        let dir = temp.path().join("data");
+        fs::create_dir_all(dir.join("nested"))?;
+        fs::write(dir.join("a.bin"), b"12345")?;
+        fs::write(dir.join("nested").join("b.bin"), b"678")?;
+
+        let item = ArchiveItem::from_file(&dir, "archive/data".to_string())?;
+        assert!(item.is_dir);
+        assert!(item.sha256.is_empty());
+        assert_eq!(item.file_count, Some(2));
+        assert_eq!(item.size, 8);
+
+        let mut archive = Archive::new("test".to_string(), "archive".to_string())
+            .with_source_path(temp.path().to_path_buf());
+        archive.add_item(item);
+        archive.add_item(ArchiveItem::new(
+            PathBuf::from("/tmp/model.ckpt"),
+            "archive/model.ckpt".to_string(),
+            "abc123".to_string(),
+            1024,
+            false,
+        ));
+        assert_eq!(archive.source_path, temp.path());
+        assert_eq!(archive.file_count(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_manifest_roundtrip() -> Result<()> {
+        let sha = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+        let mut archive = Archive::new("large-files".to_string(), "archive/20251114_130523".to_string());
+        archive.add_item(ArchiveItem::new(
+            PathBuf::from("/tmp/models/model.ckpt"),
+            "archive/20251114_130523/models/model.ckpt".to_string(),
+            sha.to_string(),
+            1024,
+            false,
+        ));
+        archive.add_item(ArchiveItem::new(
+            PathBuf::from("/tmp/data"),
+            "archive/20251114_130523/data".to_string(),
+            String::new(),
+            4096,
+            true,
+        ));
+
+        let json = export_manifest(&archive)?;
+        let manifest = parse_manifest(&json)?;
+        assert_eq!(manifest, Manifest::from_archive(&archive));
+        assert_eq!(manifest.archive_id, archive.id);
+
+        // ディレクトリはハッシュがないため含まれない
+        assert_eq!(manifest.files.len(), 1);
+        assert_eq!(manifest.total_size(), 1024);
+        assert_eq!(manifest.restore_path(&manifest.files[0]), "models/model.ckpt");
+
+        // アーカイブ先の外のパスはタイムスタンプを除いて復元する
+        let other = ManifestEntry {
+            b2_path: "shared/20251201_000000/data.bin".to_string(),
+            sha256: sha.to_string(),
+            size: 1,
+            parts: None,
+        };
+        assert_eq!(manifest.restore_path(&other), "shared/data.bin");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_manifest_invalid() {
+        let manifest = |version: u32, b2_path: &str, sha256: &str| {
+            format!(
+                r#"{{"version":{},"archive_id":"x","created_at":"2025-11-14T13:05:23Z","destination":"archive","files":[{{"b2_path":"{}","sha256":"{}","size":1}}]}}"#,
+                version, b2_path, sha256
+            )
+        };
+        let sha = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+
+        assert!(parse_manifest(&manifest(1, "archive/a", sha)).is_ok());
+        assert!(parse_manifest(&manifest(2, "archive/a", sha)).is_err());
+        assert!(parse_manifest(&manifest(1, "archive/../../etc/passwd", sha)).is_err());
+        assert!(parse_manifest(&manifest(1, "archive/a", "deadbeef")).is_err());
+        assert!(parse_manifest("not json").is_err());
+    }
+
+    #[test]
+    fn test_archive_deserialize_legacy() {
+        // source_path / file_count がない古いインデックスも読み込める
+        let json = r#"{"archives":[{"id":"x","created_at":"2025-11-14T13:05:23Z","cleaner":"large-files","destination":"archive","items":[{"local_path":"/tmp/a","b2_path":"archive/a","sha256":"","size":1,"is_dir":true}],"total_size":1}]}"#;
+        let index: ArchiveIndex = serde_json::from_str(json).unwrap();
+        assert_eq!(index.archives[0].source_path, PathBuf::new());
+        assert_eq!(index.archives[0].items[0].file_count, None);
+        assert_eq!(index.archives[0].parent_id, None);
+    }
+
+    #[test]
+    fn test_incremental_restore_resolves_linked_items() -> Result<()> {
+        use tempfile::TempDir;
+
+        let temp = TempDir::new()?;
+        let model = temp.path().join("model.ckpt");
+        let data = temp.path().join("data.bin");
+        fs::write(&model, vec![1u8; 100])?;
+        fs::write(&data, vec![2u8; 50])?;
+
+        let mut first = Archive::new("large-files".to_string(), "archive/20250101_000000".to_string());
+        first.add_item(ArchiveItem::from_file(&model, "archive/20250101_000000/model.ckpt".to_string())?);
+        first.add_item(ArchiveItem::from_file(&data, "archive/20250101_000000/data.bin".to_string())?);
+
+        // model.ckpt は変更なしのため前回のリモートパスを参照し、data.bin だけアップロードする
+        fs::write(&data, vec![3u8; 60])?;
+        let mut second = Archive::new("large-files".to_string(), "archive/20250108_000000".to_string())
+            .with_parent(first.id.clone());
+        let mut unchanged = ArchiveItem::from_file(&model, "archive/20250108_000000/model.ckpt".to_string())?;
+        assert!(first.link_unchanged(&mut unchanged));
+        let mut changed = ArchiveItem::from_file(&data, "archive/20250108_000000/data.bin".to_string())?;
+        assert!(!first.link_unchanged(&mut changed));
+        second.add_item(unchanged);
+        second.add_item(changed);
+        assert!(second.is_linked(&second.items[0]));
+        assert!(!second.is_linked(&second.items[1]));
+        assert_eq!(second.total_size, 160);
+
+        // 2 回目のアーカイブから復元すると、参照しているファイルも前回のリモートパスからダウンロードする
+        let model_sha256 = second.items[0].sha256.clone();
+        let data_sha256 = second.items[1].sha256.clone();
+        let index = ArchiveIndex {
+            archives: vec![first.clone(), second.clone()],
+        };
+        let refs: Vec<(String, &str)> = index
+            .remote_refs_for_prefix("archive/20250108_000000")
+            .into_iter()
+            .map(|(path, item)| (path, item.remote_path()))
+            .collect();
+        assert_eq!(
+            refs,
+            vec![(
+                "archive/20250108_000000/model.ckpt".to_string(),
+                "archive/20250101_000000/model.ckpt"
+            )]
+        );
+        let checksums = index.checksums_for_prefix("archive/20250108_000000");
+        assert_eq!(checksums.len(), 2);
+        assert_eq!(checksums.get("archive/20250108_000000/model.ckpt"), Some(&model_sha256));
+        assert_eq!(checksums.get("archive/20250108_000000/data.bin"), Some(&data_sha256));
+
+        // 自動削除されるファイルは参照しない
+        let mut expiring = second.clone();
+        expiring.items[1].expire_at = Some(Utc::now());
+        let mut item = ArchiveItem::from_file(&data, "archive/20250115_000000/data.bin".to_string())?;
+        assert!(!expiring.link_unchanged(&mut item));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_latest_for_and_referenced_paths() {
+        let source = PathBuf::from("/data/training");
+        let mut old = Archive::new("large-files".to_string(), "archive/20250101_000000".to_string())
+            .with_source_path(source.clone());
+        old.created_at = Utc::now() - chrono::Duration::days(7);
+        old.add_item(ArchiveItem::new(
+            source.join("model.ckpt"),
+            "archive/20250101_000000/model.ckpt".to_string(),
+            "abc".to_string(),
+            10,
+            false,
+        ));
+        let mut new = Archive::new("large-files".to_string(), "archive/20250108_000000".to_string())
+            .with_source_path(source.clone())
+            .with_parent(old.id.clone());
+        new.add_item(old.items[0].clone());
+        let other = Archive::new("large-files".to_string(), "other/20250109_000000".to_string())
+            .with_source_path(source.clone());
+
+        let index = ArchiveIndex {
+            archives: vec![old.clone(), new.clone(), other],
+        };
+        assert_eq!(index.latest_for(&source, "archive").map(|a| &a.id), Some(&new.id));
+        assert_eq!(index.latest_for(&source, "archive/").map(|a| &a.id), Some(&new.id));
+        assert!(index.latest_for(Path::new("/elsewhere"), "archive").is_none());
+
+        // 新しいアーカイブが参照しているファイルは、古いアーカイブの削除時にも残す
+        assert!(index
+            .paths_referenced_by_others(&old.id)
+            .contains("archive/20250101_000000/model.ckpt"));
+    }
 }
@@ -0,0 +1,193 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::Archive;
+use crate::Result;
+
+/// アップロード済みのファイル（blob）の SHA256 → リモートパスの対応表
+///
+/// アーカイブをまたいで同じ内容のファイルを再アップロードせず、参照で済ませるために使う（--dedup）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BlobIndex {
+    /// SHA256 → リモートパス
+    pub blobs: BTreeMap<String, String>,
+}
+
+impl BlobIndex {
+    /// blob インデックスのパスを取得
+    pub fn index_path() -> Result<PathBuf> {
+        let home = std::env::var("HOME")
+            .map_err(|_| crate::Error::Config("HOME environment variable not set".into()))?;
+        Ok(PathBuf::from(home).join(".kanri").join("blob_index.json"))
+    }
+
+    /// blob インデックスを読み込み（存在しない場合は空）
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::index_path()?)
+    }
+
+    /// 指定したファイルから読み込み（存在しない場合は空）
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path)
+            .map_err(|e| crate::Error::Config(format!("Failed to read blob index: {}", e)))?;
+        serde_json::from_str(&content)
+            .map_err(|e| crate::Error::Config(format!("Failed to parse blob index: {}", e)))
+    }
+
+    /// blob インデックスを保存
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::index_path()?)
+    }
+
+    /// 指定したファイルに保存
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                crate::Error::Config(format!("Failed to create archive directory: {}", e))
+            })?;
+        }
+
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| crate::Error::Config(format!("Failed to serialize blob index: {}", e)))?;
+        crate::utils::write_atomic(path, content)
+            .map_err(|e| crate::Error::Config(format!("Failed to write blob index: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// ロックを取って最新の blob インデックスを読み込み、`f` で変更して保存する
+    pub fn update<T>(f: impl FnOnce(&mut Self) -> T) -> Result<T> {
+        let path = Self::index_path()?;
+        let _lock = crate::utils::FileLock::acquire(&path.with_file_name("blob_index.lock"))?;
+        let mut index = Self::load_from(&path)?;
+        let result = f(&mut index);
+        index.save_to(&path)?;
+        Ok(result)
+    }
+
+    /// 登録されている blob がないか
+    pub fn is_empty(&self) -> bool {
+        self.blobs.is_empty()
+    }
+
+    /// SHA256 が一致する blob のリモートパス
+    pub fn get(&self, sha256: &str) -> Option<&str> {
+        self.blobs.get(sha256).map(String::as_str)
+    }
+
+    /// アップロードした blob を登録（同じ SHA256 の登録は置き換える）
+    pub fn insert(&mut self, sha256: String, remote_path: String) {
+        self.blobs.insert(sha256, remote_path);
+    }
+
+    /// blob の登録を解除（リモートに見つからなかった場合など）
+    pub fn remove(&mut self, sha256: &str) -> bool {
+        self.blobs.remove(sha256).is_some()
+    }
+
+    /// 削除したリモートパスを指す登録を解除し、解除した件数を返す
+    pub fn remove_paths(&mut self, remote_paths: &HashSet<String>) -> usize {
+        let before = self.blobs.len();
+        self.blobs.retain(|_, remote_path| !remote_paths.contains(remote_path));
+        before - self.blobs.len()
+    }
+
+    /// アーカイブのうち、参照先として使えるアイテムを登録（既存の登録は変更しない）
+    ///
+    /// ハッシュを持たないディレクトリ、自動削除されるアイテム、他の blob を参照しているアイテム、
+    /// 分割してアップロードしたアイテムは対象外
+    pub fn register_archive(&mut self, archive: &Archive) {
+        for item in &archive.items {
+            if item.sha256.is_empty()
+                || item.expire_at.is_some()
+                || item.blob_ref.is_some()
+                || item.parts.is_some()
+            {
+                continue;
+            }
+            self.blobs
+                .entry(item.sha256.clone())
+                .or_insert_with(|| item.b2_path.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::{ArchiveIndex, ArchiveItem};
+    use chrono::Utc;
+    use tempfile::TempDir;
+
+    fn item(name: &str, destination: &str, sha256: &str) -> ArchiveItem {
+        ArchiveItem::new(
+            PathBuf::from(name),
+            format!("{}/{}", destination, name),
+            sha256.to_string(),
+            100,
+            false,
+        )
+    }
+
+    #[test]
+    fn test_register_and_dedup_across_archives() -> Result<()> {
+        let temp = TempDir::new()?;
+        let path = temp.path().join("blob_index.json");
+
+        let mut first = Archive::new("large-files".to_string(), "models/20250101_000000".to_string());
+        first.add_item(item("model.ckpt", &first.destination.clone(), "aaa"));
+        let mut expiring = item("tmp.bin", &first.destination.clone(), "bbb");
+        expiring.expire_at = Some(Utc::now());
+        first.add_item(expiring);
+
+        let mut blobs = BlobIndex::load_from(&path)?;
+        assert!(blobs.is_empty());
+        blobs.register_archive(&first);
+        blobs.save_to(&path)?;
+
+        // 自動削除されるアイテムは参照先にしない
+        let blobs = BlobIndex::load_from(&path)?;
+        assert_eq!(blobs.get("aaa"), Some("models/20250101_000000/model.ckpt"));
+        assert_eq!(blobs.get("bbb"), None);
+
+        // 別のパスにある同じ内容のファイルは、既存の blob を参照する
+        let mut second = Archive::new("large-files".to_string(), "datasets/20250201_000000".to_string());
+        let mut copy = item("copy.ckpt", &second.destination.clone(), "aaa");
+        copy.blob_ref = blobs.get(&copy.sha256).map(str::to_string);
+        second.add_item(copy);
+        assert_eq!(second.items[0].remote_path(), "models/20250101_000000/model.ckpt");
+
+        // 参照しているアーカイブがある間は、元のアーカイブを削除しても blob を残す
+        let index = ArchiveIndex {
+            archives: vec![first.clone(), second.clone()],
+        };
+        assert!(index
+            .paths_referenced_by_others(&first.id)
+            .contains("models/20250101_000000/model.ckpt"));
+        let refs = index.remote_refs_for_prefix("datasets/");
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].0, "datasets/20250201_000000/copy.ckpt");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_remove_paths() {
+        let mut blobs = BlobIndex::default();
+        blobs.insert("aaa".to_string(), "models/a.ckpt".to_string());
+        blobs.insert("bbb".to_string(), "models/b.ckpt".to_string());
+
+        let deleted: HashSet<String> = ["models/a.ckpt".to_string()].into_iter().collect();
+        assert_eq!(blobs.remove_paths(&deleted), 1);
+        assert_eq!(blobs.get("aaa"), None);
+        assert!(blobs.remove("bbb"));
+        assert!(blobs.is_empty());
+    }
+}
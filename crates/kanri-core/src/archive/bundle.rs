@@ -0,0 +1,145 @@
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use crate::Result;
+
+/// バンドル（ディレクトリをまとめた tar）の拡張子
+pub const BUNDLE_EXTENSION: &str = ".tar";
+
+/// バンドルのリモートパス（ディレクトリのパスに .tar を付ける）
+pub fn bundle_path(remote_dir: &str) -> String {
+    format!("{}{}", remote_dir.trim_end_matches('/'), BUNDLE_EXTENSION)
+}
+
+/// バンドルを展開するディレクトリ（.tar を除いたパス）
+pub fn unbundled_path(bundle: &Path) -> PathBuf {
+    let name = bundle.to_string_lossy();
+    PathBuf::from(name.strip_suffix(BUNDLE_EXTENSION).unwrap_or(&name))
+}
+
+/// tar のヘッダとブロック境界までの詰め物として、エントリ 1 つあたりに見込むサイズ
+const BUNDLE_OVERHEAD_PER_ENTRY: u64 = 1024;
+
+/// ディレクトリをまとめた tar を一時的に置くパス
+///
+/// 一時ディレクトリは別のボリュームのことが多いため、まとめるディレクトリと同じ場所（隠しファイル）に作る
+pub fn bundle_temp_path(dir: &Path) -> PathBuf {
+    let name = dir.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    dir.with_file_name(format!(".{}.kanri-bundle-{}{}", name, std::process::id(), BUNDLE_EXTENSION))
+}
+
+/// `size` バイト・`file_count` 個のファイルを含むディレクトリをまとめた tar のおおよそのサイズ
+///
+/// 作成前の空き容量の確認に使う（ディレクトリのエントリ分も含めて多めに見積もる）
+pub fn estimated_bundle_size(size: u64, file_count: u64) -> u64 {
+    size + (file_count + 1) * 2 * BUNDLE_OVERHEAD_PER_ENTRY
+}
+
+/// ディレクトリを 1 つの tar ファイルにまとめ、含めたファイルの相対パスを返す
+///
+/// 空のディレクトリも復元できるよう、サブディレクトリのエントリも格納する。
+/// 相対パスはディレクトリからのパス（"/" 区切り、パス順）で、ファイルのみ
+pub fn create_bundle(dir: &Path, output: &Path) -> Result<Vec<String>> {
+    let mut builder = tar::Builder::new(BufWriter::new(File::create(output)?));
+    // シンボリックリンクはリンクのまま格納する
+    builder.follow_symlinks(false);
+
+    let mut members = Vec::new();
+    let entries: Vec<walkdir::DirEntry> = walkdir::WalkDir::new(dir)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter()
+        .collect::<std::result::Result<_, _>>()?;
+
+    for entry in entries {
+        let relative = entry
+            .path()
+            .strip_prefix(dir)
+            .map_err(|_| crate::Error::InvalidPath(entry.path().display().to_string()))?;
+        // ディレクトリは中身を含まないエントリとして格納される
+        builder.append_path_with_name(entry.path(), relative)?;
+        if !entry.file_type().is_dir() {
+            members.push(relative.to_string_lossy().replace('\\', "/"));
+        }
+    }
+
+    builder.into_inner()?;
+    Ok(members)
+}
+
+/// tar ファイルを指定ディレクトリに展開
+///
+/// ディレクトリの外を指すパス（"../" など）を含むエントリは展開しない
+pub fn extract_bundle(bundle: &Path, dest: &Path) -> Result<()> {
+    fs::create_dir_all(dest)?;
+    let mut archive = tar::Archive::new(BufReader::new(File::open(bundle)?));
+    archive.set_preserve_mtime(true);
+    archive.unpack(dest).map_err(|e| {
+        crate::Error::Archive(format!("Failed to extract {}: {}", bundle.display(), e))
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_bundle_round_trip() -> Result<()> {
+        let temp = TempDir::new()?;
+        let source = temp.path().join("dataset");
+        fs::create_dir_all(source.join("images"))?;
+        fs::create_dir_all(source.join("empty"))?;
+        fs::write(source.join("labels.csv"), "id,label")?;
+        fs::write(source.join("images").join("0001.png"), vec![1u8; 64])?;
+
+        let bundle = temp.path().join(bundle_path("dataset"));
+        let members = create_bundle(&source, &bundle)?;
+        assert_eq!(members, vec!["images/0001.png", "labels.csv"]);
+
+        let restored = unbundled_path(&temp.path().join("restore").join("dataset.tar"));
+        assert_eq!(restored, temp.path().join("restore").join("dataset"));
+        extract_bundle(&bundle, &restored)?;
+        assert_eq!(fs::read(restored.join("images").join("0001.png"))?, vec![1u8; 64]);
+        assert_eq!(fs::read_to_string(restored.join("labels.csv"))?, "id,label");
+        // 空のディレクトリも復元される
+        assert!(restored.join("empty").is_dir());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bundle_path() {
+        assert_eq!(bundle_path("archive/20250101_000000/data"), "archive/20250101_000000/data.tar");
+        assert_eq!(bundle_path("archive/data/"), "archive/data.tar");
+    }
+
+    #[test]
+    fn test_bundle_temp_path() {
+        let temp = bundle_temp_path(Path::new("/data/training/dataset"));
+        assert_eq!(temp.parent(), Some(Path::new("/data/training")));
+        assert_eq!(
+            temp.file_name().unwrap().to_string_lossy(),
+            format!(".dataset.kanri-bundle-{}.tar", std::process::id())
+        );
+    }
+
+    #[test]
+    fn test_estimated_bundle_size_covers_bundle() -> Result<()> {
+        let temp = TempDir::new()?;
+        let source = temp.path().join("dataset");
+        fs::create_dir_all(source.join("a").join("b"))?;
+        for i in 0..20 {
+            fs::write(source.join("a").join(format!("{}.txt", i)), vec![0u8; i * 100])?;
+        }
+        let size: u64 = (0..20).map(|i| i * 100).sum();
+
+        let bundle = temp.path().join("dataset.tar");
+        let members = create_bundle(&source, &bundle)?;
+        assert!(fs::metadata(&bundle)?.len() <= estimated_bundle_size(size, members.len() as u64));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,563 @@
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+use crate::storage::RemoteFile;
+
+/// バージョン（タイムスタンプ）ごとの集計情報
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionSummary {
+    /// タイムスタンプ（YYYYMMDD_HHMMSS）
+    pub timestamp: String,
+    /// ファイル数
+    pub file_count: usize,
+}
+
+/// リモートのファイル一覧から復元したアーカイブ（タイムスタンプのフォルダごと）
+///
+/// ローカルのアーカイブインデックスがなくても、リモートの状態だけから一覧できる
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteArchive {
+    /// アーカイブ先（タイムスタンプのフォルダまでのパス）
+    pub destination: String,
+    /// タイムスタンプ（YYYYMMDD_HHMMSS）
+    pub timestamp: String,
+    /// ファイル一覧
+    pub files: Vec<RemoteFile>,
+}
+
+impl RemoteArchive {
+    /// ファイル数
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    /// 合計サイズ（サイズを取得できないファイルがある場合は None）
+    pub fn total_size(&self) -> Option<u64> {
+        self.files.iter().map(|file| file.size).sum()
+    }
+
+    /// 作成日時（タイムスタンプはアーカイブ時のローカル時刻）
+    pub fn created_at(&self) -> Option<DateTime<Utc>> {
+        let naive = NaiveDateTime::parse_from_str(&self.timestamp, "%Y%m%d_%H%M%S").ok()?;
+        Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|local| local.with_timezone(&Utc))
+    }
+}
+
+/// YYYYMMDD_HHMMSS 形式のパスの要素か
+///
+/// 形式だけでなく日時として正しいか（"12345678_1234567" や 13 月などを除く）も確認する
+fn is_timestamp(part: &str) -> bool {
+    part.len() == 15
+        && part.char_indices().all(|(i, c)| match i {
+            8 => c == '_',
+            _ => c.is_ascii_digit(),
+        })
+        && NaiveDateTime::parse_from_str(part, "%Y%m%d_%H%M%S").is_ok()
+}
+
+/// パスから YYYYMMDD_HHMMSS 形式のタイムスタンプを抽出
+///
+/// 候補が複数ある場合は最初（アーカイブ先に近い方）のものを使う
+pub fn extract_timestamp(path: &str) -> Option<String> {
+    path.split('/')
+        .find(|part| is_timestamp(part))
+        .map(|part| part.to_string())
+}
+
+/// パスをタイムスタンプのフォルダまでのアーカイブ先とタイムスタンプに分ける
+fn split_destination(path: &str) -> Option<(String, String)> {
+    let mut destination = Vec::new();
+    for part in path.split('/') {
+        destination.push(part);
+        if is_timestamp(part) {
+            return Some((destination.join("/"), part.to_string()));
+        }
+    }
+    None
+}
+
+/// リモートのファイル一覧をタイムスタンプのフォルダごとにまとめ、アーカイブを復元（新しい順）
+///
+/// タイムスタンプを含まないパスのファイルは無視する
+pub fn reconstruct_archives(files: &[RemoteFile]) -> Vec<RemoteArchive> {
+    let mut archives: BTreeMap<String, RemoteArchive> = BTreeMap::new();
+
+    for file in files {
+        let Some((destination, timestamp)) = split_destination(&file.path) else {
+            continue;
+        };
+        archives
+            .entry(destination.clone())
+            .or_insert_with(|| RemoteArchive {
+                destination,
+                timestamp,
+                files: Vec::new(),
+            })
+            .files
+            .push(file.clone());
+    }
+
+    let mut archives: Vec<RemoteArchive> = archives.into_values().collect();
+    archives.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    archives
+}
+
+/// パスからタイムスタンプのセグメントを除去
+///
+/// 取り除くのは最初に一致するパスの要素だけ（同じ文字列を含むファイル名などは変えない）
+pub fn remove_timestamp(path: &str, timestamp: &str) -> String {
+    let mut removed = false;
+    path.split('/')
+        .filter(|part| {
+            if !removed && *part == timestamp {
+                removed = true;
+                return false;
+            }
+            true
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// 復元先の相対パスからディレクトリを除き、ファイル名だけにする
+///
+/// 同じファイル名が複数ある場合は 2 つ目以降を "name (1).ext" のように改名する。
+/// 順番は入力の順に従う
+pub fn flatten_paths(paths: &[String]) -> Vec<String> {
+    let mut used = HashSet::new();
+
+    paths
+        .iter()
+        .map(|path| {
+            let file_name = path.rsplit('/').next().unwrap_or(path);
+            let (stem, extension) = split_extension(file_name);
+
+            let mut candidate = file_name.to_string();
+            let mut n = 1;
+            while !used.insert(candidate.clone()) {
+                candidate = format!("{} ({}){}", stem, n, extension);
+                n += 1;
+            }
+            candidate
+        })
+        .collect()
+}
+
+/// 復元するファイルがリモートの 1 つのディレクトリをそのまま復元するものか判定
+///
+/// `files`: (リモートのパス, 復元先ディレクトリからの相対パス)、`all_files`: リモートのファイル一覧。
+/// すべてのファイルが共通のディレクトリ配下にあり、相対的な配置を保ったまま復元され、
+/// かつそのディレクトリ配下のファイルをすべて含む場合に (リモートのディレクトリ, 復元先の相対ディレクトリ) を返す。
+/// 一部のファイルだけを復元する場合は None（ファイルごとにダウンロードする）
+pub fn shared_directory(files: &[(String, String)], all_files: &[String]) -> Option<(String, String)> {
+    if files.len() < 2 {
+        return None;
+    }
+
+    // リモートのパスに共通するディレクトリ
+    let mut common: Vec<&str> = files[0].0.split('/').collect();
+    common.pop();
+    for (remote, _) in &files[1..] {
+        let dirs: Vec<&str> = remote.split('/').collect();
+        let shared = common
+            .iter()
+            .zip(&dirs[..dirs.len() - 1])
+            .take_while(|(a, b)| a == b)
+            .count();
+        common.truncate(shared);
+    }
+    if common.is_empty() {
+        return None;
+    }
+    let remote_dir = common.join("/");
+
+    // 復元先でもディレクトリ内の配置がそのまま保たれているか
+    let mut local_dir: Option<&str> = None;
+    for (remote, local) in files {
+        let relative = &remote[remote_dir.len() + 1..];
+        let prefix = local.strip_suffix(relative)?;
+        if !(prefix.is_empty() || prefix.ends_with('/')) || local_dir.is_some_and(|dir| dir != prefix) {
+            return None;
+        }
+        local_dir = Some(prefix);
+    }
+
+    // ディレクトリ配下のファイルがすべて復元対象か
+    let selected: HashSet<&str> = files.iter().map(|(remote, _)| remote.as_str()).collect();
+    let dir_prefix = format!("{}/", remote_dir);
+    if all_files
+        .iter()
+        .any(|file| file.starts_with(&dir_prefix) && !selected.contains(file.as_str()))
+    {
+        return None;
+    }
+
+    let local_dir = local_dir.unwrap_or_default().trim_end_matches('/').to_string();
+    Some((remote_dir, local_dir))
+}
+
+/// ファイル名を (拡張子を除いた部分, ".ext") に分ける（".env" のような名前は拡張子なし扱い）
+fn split_extension(file_name: &str) -> (&str, &str) {
+    match file_name.rfind('.') {
+        Some(pos) if pos > 0 => file_name.split_at(pos),
+        _ => (file_name, ""),
+    }
+}
+
+/// タイムスタンプ（YYYYMMDD_HHMMSS）の範囲。両端を含み、None の側は制限しない
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TimestampRange {
+    /// 開始（これ以降）
+    pub since: Option<String>,
+    /// 終了（これ以前）
+    pub until: Option<String>,
+}
+
+impl TimestampRange {
+    /// タイムスタンプが範囲内か（同じ形式なので文字列の比較で日時順になる）
+    pub fn contains(&self, timestamp: &str) -> bool {
+        self.since.as_deref().is_none_or(|since| timestamp >= since)
+            && self.until.as_deref().is_none_or(|until| timestamp <= until)
+    }
+}
+
+/// 範囲の指定（YYYYMMDD_HHMMSS / YYYYMMDD / YYYY-MM-DD）を YYYYMMDD_HHMMSS に正規化
+///
+/// 日付だけの場合、`end` なら その日の終わり（235959）、そうでなければ始まり（000000）にする
+pub fn parse_range_bound(value: &str, end: bool) -> Option<String> {
+    if is_timestamp(value) {
+        return Some(value.to_string());
+    }
+
+    let date = NaiveDate::parse_from_str(value, "%Y%m%d")
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y-%m-%d"))
+        .ok()?;
+    let time = if end { "235959" } else { "000000" };
+    Some(format!("{}_{}", date.format("%Y%m%d"), time))
+}
+
+/// タイムスタンプを除いたパスごとに、最新のファイルを選ぶ（タイムスタンプを除いたパス順）
+///
+/// 戻り値は (リモートのパス, タイムスタンプを除いたパス)。タイムスタンプを含まないパスは無視する
+pub fn latest_per_path<'a>(files: impl IntoIterator<Item = &'a String>) -> Vec<(String, String)> {
+    let mut latest: BTreeMap<String, (String, &String)> = BTreeMap::new();
+
+    for file in files {
+        let Some(timestamp) = extract_timestamp(file) else {
+            continue;
+        };
+        let normalized = remove_timestamp(file, &timestamp);
+        match latest.get(&normalized) {
+            Some((newest, _)) if *newest >= timestamp => {}
+            _ => {
+                latest.insert(normalized, (timestamp, file));
+            }
+        }
+    }
+
+    latest
+        .into_iter()
+        .map(|(normalized, (_, file))| (file.clone(), normalized))
+        .collect()
+}
+
+/// ファイル一覧からバージョンごとのファイル数を集計（新しい順）
+pub fn summarize_versions(files: &[String]) -> Vec<VersionSummary> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+
+    for file in files {
+        if let Some(timestamp) = extract_timestamp(file) {
+            *counts.entry(timestamp).or_default() += 1;
+        }
+    }
+
+    counts
+        .into_iter()
+        .rev()
+        .map(|(timestamp, file_count)| VersionSummary {
+            timestamp,
+            file_count,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_timestamp() {
+        assert_eq!(
+            extract_timestamp("archive/20251114_130523/model.ckpt"),
+            Some("20251114_130523".to_string())
+        );
+        assert_eq!(extract_timestamp("archive/latest/model.ckpt"), None);
+        assert_eq!(extract_timestamp("20251114_130523/model.ckpt"), Some("20251114_130523".to_string()));
+    }
+
+    #[test]
+    fn test_extract_timestamp_near_misses() {
+        // 形式は合っていても日時として正しくないもの
+        for segment in [
+            "12345678_1234567",
+            "20251314_130523",
+            "20250230_130523",
+            "20251114_250000",
+            "20251114_126000",
+        ] {
+            assert_eq!(extract_timestamp(&format!("archive/{}/a.bin", segment)), None, "{}", segment);
+        }
+
+        // 長さ・区切り・数字以外
+        for segment in ["20251114_13052", "20251114_1305230", "20251114-130523", "2025111a_130523", "+2025111_130523"] {
+            assert_eq!(extract_timestamp(&format!("archive/{}/a.bin", segment)), None, "{}", segment);
+        }
+
+        // ファイル名の一部に含まれるだけのものは対象外
+        assert_eq!(extract_timestamp("archive/model_20251114_130523.ckpt"), None);
+    }
+
+    #[test]
+    fn test_extract_timestamp_multiple_candidates() {
+        // 日時として正しくない候補は飛ばし、最初の正しいものを使う
+        let path = "archive/12345678_1234567/20251114_130523/runs/20240101_000000/a.bin";
+        let timestamp = extract_timestamp(path).unwrap();
+        assert_eq!(timestamp, "20251114_130523");
+        assert_eq!(
+            remove_timestamp(path, &timestamp),
+            "archive/12345678_1234567/runs/20240101_000000/a.bin"
+        );
+        assert_eq!(
+            split_destination(path),
+            Some(("archive/12345678_1234567/20251114_130523".to_string(), timestamp))
+        );
+    }
+
+    #[test]
+    fn test_latest_per_path_in_range() {
+        let files: Vec<String> = [
+            "exp/20250101_000000/a.bin",
+            "exp/20250105_120000/a.bin",
+            "exp/20250110_000000/a.bin",
+            "exp/20250103_000000/b.bin",
+            "exp/20250112_000000/c.bin",
+            "exp/untimed/d.bin",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let pair = |remote: &str, normalized: &str| (remote.to_string(), normalized.to_string());
+        assert_eq!(
+            latest_per_path(&files),
+            vec![
+                pair("exp/20250110_000000/a.bin", "exp/a.bin"),
+                pair("exp/20250103_000000/b.bin", "exp/b.bin"),
+                pair("exp/20250112_000000/c.bin", "exp/c.bin"),
+            ]
+        );
+
+        // 範囲内の最新版を選ぶ
+        let select = |range: &TimestampRange| {
+            latest_per_path(files.iter().filter(|file| {
+                extract_timestamp(file).is_some_and(|timestamp| range.contains(&timestamp))
+            }))
+        };
+        let range = TimestampRange {
+            since: parse_range_bound("2025-01-02", false),
+            until: parse_range_bound("20250109", true),
+        };
+        assert_eq!(
+            select(&range),
+            vec![
+                pair("exp/20250105_120000/a.bin", "exp/a.bin"),
+                pair("exp/20250103_000000/b.bin", "exp/b.bin"),
+            ]
+        );
+
+        // 片側だけの範囲
+        let since_only = TimestampRange {
+            since: parse_range_bound("20250110_000000", false),
+            until: None,
+        };
+        assert_eq!(
+            select(&since_only),
+            vec![
+                pair("exp/20250110_000000/a.bin", "exp/a.bin"),
+                pair("exp/20250112_000000/c.bin", "exp/c.bin"),
+            ]
+        );
+        let until_only = TimestampRange {
+            since: None,
+            until: parse_range_bound("20250101", true),
+        };
+        assert_eq!(select(&until_only), vec![pair("exp/20250101_000000/a.bin", "exp/a.bin")]);
+    }
+
+    #[test]
+    fn test_parse_range_bound() {
+        assert_eq!(parse_range_bound("20250105_120000", true), Some("20250105_120000".to_string()));
+        assert_eq!(parse_range_bound("20250105", false), Some("20250105_000000".to_string()));
+        assert_eq!(parse_range_bound("2025-01-05", true), Some("20250105_235959".to_string()));
+        assert_eq!(parse_range_bound("2025-13-01", false), None);
+        assert_eq!(parse_range_bound("last week", false), None);
+    }
+
+    #[test]
+    fn test_reconstruct_archives() {
+        let file = |path: &str, size: Option<u64>| RemoteFile {
+            path: path.to_string(),
+            size,
+            modified: None,
+        };
+        let files = vec![
+            file("archive/20251114_130523/a.bin", Some(100)),
+            file("archive/20251114_130523/sub/b.bin", Some(20)),
+            file("archive/20251201_090000/a.bin", Some(5)),
+            file("models/20251114_130523/c.bin", None),
+            file("archive/no-version/c.bin", Some(1)),
+        ];
+
+        let archives = reconstruct_archives(&files);
+        let summary: Vec<(&str, usize, Option<u64>)> = archives
+            .iter()
+            .map(|a| (a.destination.as_str(), a.file_count(), a.total_size()))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("archive/20251201_090000", 1, Some(5)),
+                ("archive/20251114_130523", 2, Some(120)),
+                // サイズがわからないファイルを含む
+                ("models/20251114_130523", 1, None),
+            ]
+        );
+        assert_eq!(archives[0].timestamp, "20251201_090000");
+        assert!(archives[0].created_at().is_some());
+    }
+
+    #[test]
+    fn test_remove_timestamp() {
+        assert_eq!(
+            remove_timestamp("archive/20251114_130523/model.ckpt", "20251114_130523"),
+            "archive/model.ckpt"
+        );
+        // 先頭の要素や、同じ要素が複数ある場合は最初のものだけを取り除く
+        assert_eq!(
+            remove_timestamp("20251114_130523/model.ckpt", "20251114_130523"),
+            "model.ckpt"
+        );
+        assert_eq!(
+            remove_timestamp("a/20251114_130523/20251114_130523/b", "20251114_130523"),
+            "a/20251114_130523/b"
+        );
+        assert_eq!(remove_timestamp("a/x20251114_130523/b", "20251114_130523"), "a/x20251114_130523/b");
+    }
+
+    #[test]
+    fn test_shared_directory() {
+        let pair = |remote: &str, local: &str| (remote.to_string(), local.to_string());
+        let all_files: Vec<String> = [
+            "archive/20250101_000000/data/a.bin",
+            "archive/20250101_000000/data/sub/b.bin",
+            "archive/20250101_000000/model.ckpt",
+        ]
+        .iter()
+        .map(|p| p.to_string())
+        .collect();
+
+        // バージョン全体を、タイムスタンプを除いた配置で復元
+        let files = vec![
+            pair("archive/20250101_000000/data/a.bin", "data/a.bin"),
+            pair("archive/20250101_000000/data/sub/b.bin", "data/sub/b.bin"),
+            pair("archive/20250101_000000/model.ckpt", "model.ckpt"),
+        ];
+        assert_eq!(
+            shared_directory(&files, &all_files),
+            Some(("archive/20250101_000000".to_string(), String::new()))
+        );
+
+        // 一部のディレクトリだけ（そのディレクトリ配下はすべて含む）
+        let files = vec![
+            pair("archive/20250101_000000/data/a.bin", "restore/data/a.bin"),
+            pair("archive/20250101_000000/data/sub/b.bin", "restore/data/sub/b.bin"),
+        ];
+        assert_eq!(
+            shared_directory(&files, &all_files),
+            Some(("archive/20250101_000000/data".to_string(), "restore/data".to_string()))
+        );
+
+        // ディレクトリ配下の一部だけを復元する場合
+        let files = vec![
+            pair("archive/20250101_000000/data/a.bin", "data/a.bin"),
+            pair("archive/20250101_000000/model.ckpt", "model.ckpt"),
+        ];
+        assert_eq!(shared_directory(&files, &all_files), None);
+
+        // flat などで配置が変わる場合
+        let files = vec![
+            pair("archive/20250101_000000/data/a.bin", "a.bin"),
+            pair("archive/20250101_000000/data/sub/b.bin", "b.bin"),
+        ];
+        assert_eq!(shared_directory(&files, &all_files), None);
+
+        // 1 ファイルだけならファイルごとにダウンロード
+        let files = vec![pair("archive/20250101_000000/model.ckpt", "model.ckpt")];
+        assert_eq!(shared_directory(&files, &all_files), None);
+    }
+
+    #[test]
+    fn test_flatten_paths() {
+        let paths: Vec<String> = [
+            "run1/checkpoints/model.ckpt",
+            "run2/checkpoints/model.ckpt",
+            "run3/model.ckpt",
+            "run4/model (1).ckpt",
+            "data/.env",
+            "other/.env",
+            "README",
+            "docs/README",
+        ]
+        .iter()
+        .map(|p| p.to_string())
+        .collect();
+
+        assert_eq!(
+            flatten_paths(&paths),
+            vec![
+                "model.ckpt",
+                "model (1).ckpt",
+                "model (2).ckpt",
+                // 改名後の名前と衝突する場合はさらに番号を進める
+                "model (1) (1).ckpt",
+                ".env",
+                ".env (1)",
+                "README",
+                "README (1)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_summarize_versions() {
+        let files = vec![
+            "archive/20251114_130523/a.bin".to_string(),
+            "archive/20251114_130523/b.bin".to_string(),
+            "archive/20251201_090000/a.bin".to_string(),
+            "archive/no-version/c.bin".to_string(),
+        ];
+
+        let versions = summarize_versions(&files);
+
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].timestamp, "20251201_090000");
+        assert_eq!(versions[0].file_count, 1);
+        assert_eq!(versions[1].timestamp, "20251114_130523");
+        assert_eq!(versions[1].file_count, 2);
+    }
+}
@@ -1,12 +1,395 @@
+use std::io::{self, BufReader, Read};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use crate::{Result, StorageClient};
+use chrono::DateTime;
+
+use crate::{
+    storage::{RemoteFile, UploadProgress},
+    utils, Result, StorageClient,
+};
+
+/// B2 CLI 呼び出しの再試行ポリシー
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// 最大再試行回数（0 なら再試行しない）
+    pub max_retries: u32,
+    /// 初回の待機時間
+    pub base_delay: Duration,
+    /// 待機時間の上限（ジッター加算前）
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// 最大再試行回数を指定して作成
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            ..Default::default()
+        }
+    }
+
+    /// attempt 回目（0 始まり）の再試行前に待つ時間
+    ///
+    /// 指数バックオフ（base_delay * 2^attempt、max_delay で頭打ち）に 0〜50% のジッターを加える
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+
+        let max_jitter_ms = backoff.as_millis() as u64 / 2;
+        if max_jitter_ms == 0 {
+            return backoff;
+        }
+
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.subsec_nanos() as u64)
+            .unwrap_or(0);
+        backoff + Duration::from_millis(seed % (max_jitter_ms + 1))
+    }
+}
+
+/// 再試行しても解決しないエラー（認証・権限など）
+const FATAL_ERROR_PATTERNS: &[&str] = &[
+    "unauthorized",
+    "bad_auth_token",
+    "expired_auth_token",
+    "invalid application key",
+    "access_denied",
+    "access denied",
+];
+
+/// 一時的なエラー（ネットワーク・タイムアウトなど）
+///
+/// ファイル名やバイト数に含まれる語と取り違えないよう、エラーメッセージに現れる言い回しだけを並べる
+const RETRYABLE_ERROR_PATTERNS: &[&str] = &[
+    "timed out",
+    "read timeout",
+    "connect timeout",
+    "timeouterror",
+    "connectionerror",
+    "connection error",
+    "connection reset",
+    "connection refused",
+    "connection aborted",
+    "network is unreachable",
+    "temporarily unavailable",
+    "service_unavailable",
+    "service unavailable",
+    "too many requests",
+    "too_many_requests",
+    "internal server error",
+    "bad gateway",
+    "gateway timeout",
+    "reset by peer",
+    "broken pipe",
+    "name resolution",
+];
+
+/// 再試行すべき HTTP ステータスコード
+const RETRYABLE_STATUS_CODES: &[u16] = &[429, 500, 502, 503, 504];
+
+/// ステータスコードとして書かれた数値か（"status: 503"・"HTTP 503"・"(503)" など）
+fn has_retryable_status(stderr: &str) -> bool {
+    RETRYABLE_STATUS_CODES.iter().any(|code| {
+        [
+            format!("status: {}", code),
+            format!("status {}", code),
+            format!("status={}", code),
+            format!("status code {}", code),
+            format!("http {}", code),
+            format!("http/1.1 {}", code),
+            format!("error: {} ", code),
+            format!("({})", code),
+            format!("({} ", code),
+        ]
+        .iter()
+        .any(|pattern| {
+            stderr.match_indices(pattern.as_str()).any(|(i, _)| {
+                // "status: 5031" のように続きがある数値は別の値
+                !stderr[i + pattern.len()..].starts_with(|c: char| c.is_ascii_digit())
+            })
+        })
+    })
+}
+
+/// b2 CLI の stderr から再試行すべきエラーか判定
+pub fn is_retryable_error(stderr: &str) -> bool {
+    let stderr = stderr.to_lowercase();
+    if FATAL_ERROR_PATTERNS.iter().any(|p| stderr.contains(p)) {
+        return false;
+    }
+    RETRYABLE_ERROR_PATTERNS.iter().any(|p| stderr.contains(p)) || has_retryable_status(&stderr)
+}
+
+/// アップロード時に SHA256 を記録するファイル情報のキー
+const SHA256_INFO_KEY: &str = "sha256";
+
+/// ファイルが存在しないことを示すエラーか判定
+fn is_not_found_error(stderr: &str) -> bool {
+    let stderr = stderr.to_lowercase();
+    stderr.contains("not present") || stderr.contains("not found") || stderr.contains("no such file")
+}
+
+/// `b2 file info` の JSON 出力から記録済みの SHA256 を取得
+fn parse_file_info_sha256(json: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    value
+        .get("fileInfo")?
+        .get(SHA256_INFO_KEY)?
+        .as_str()
+        .map(|s| s.to_string())
+}
+
+/// `b2 file ls --json` の出力からファイル名・サイズ・更新日時を取得（フォルダや非表示マーカーは除く）
+///
+/// 更新日時はアップロード時に記録された元ファイルの更新日時、なければアップロード日時
+fn parse_ls_json(json: &str) -> Option<Vec<RemoteFile>> {
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    let files = value
+        .as_array()?
+        .iter()
+        .filter(|file| {
+            matches!(
+                file.get("action").and_then(|a| a.as_str()),
+                None | Some("upload")
+            )
+        })
+        .filter_map(|file| {
+            let path = file.get("fileName")?.as_str()?.to_string();
+            // CLI のバージョンによってキー名が異なる
+            let size = file
+                .get("size")
+                .or_else(|| file.get("contentLength"))
+                .and_then(|size| size.as_u64());
+            let modified_millis = file
+                .get("fileInfo")
+                .and_then(|info| info.get("src_last_modified_millis"))
+                .and_then(|millis| millis.as_str()?.parse().ok())
+                .or_else(|| file.get("uploadTimestamp")?.as_i64());
+            let modified = modified_millis.and_then(DateTime::from_timestamp_millis);
+            Some(RemoteFile {
+                path,
+                size,
+                modified,
+            })
+        })
+        .collect();
+    Some(files)
+}
+
+/// `b2 file ls --versions --json` の出力から、名前が `file_name` と一致するバージョンの ID を取得
+///
+/// 一覧はプレフィックスで絞り込まれるため、`file_name` で始まる別のファイルは除く。非表示マーカーも含める
+fn parse_file_version_ids(json: &str, file_name: &str) -> Option<Vec<String>> {
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    Some(
+        value
+            .as_array()?
+            .iter()
+            .filter(|file| file.get("fileName").and_then(|name| name.as_str()) == Some(file_name))
+            .filter_map(|file| Some(file.get("fileId")?.as_str()?.to_string()))
+            .collect(),
+    )
+}
+
+/// 既存のライフサイクルルールに、`prefix` 以下をアップロードから `days` 日後に削除するルールを追加
+///
+/// B2 にはファイル単位の有効期限がないため、プレフィックス単位のルールで表現する。
+/// 同じプレフィックスのルールと `stale` のルール（ファイルが残っていない過去のアーカイブ）は取り除き、
+/// それ以外のルールはそのまま残す
+fn expiry_lifecycle_rules(
+    existing: Vec<serde_json::Value>,
+    prefix: &str,
+    days: u32,
+    stale: &[String],
+) -> Vec<serde_json::Value> {
+    let mut rules: Vec<serde_json::Value> = existing
+        .into_iter()
+        .filter(|rule| {
+            rule.get("fileNamePrefix")
+                .and_then(|p| p.as_str())
+                .is_none_or(|p| p != prefix && !stale.iter().any(|s| s == p))
+        })
+        .collect();
+    // 非表示にした翌日に完全に削除する
+    rules.push(serde_json::json!({
+        "fileNamePrefix": prefix,
+        "daysFromUploadingToHiding": days,
+        "daysFromHidingToDeleting": 1,
+    }));
+    rules
+}
+
+/// 過去に `set_expiry` で追加した、`prefix` と同じ保存先（親のプレフィックス）にあるアーカイブのルールのプレフィックス
+///
+/// ファイルが残っていなければ取り除けるので、呼び出し側で一覧を確認する。
+/// 手動で追加されたルールを消さないよう、kanri が追加する形（非表示の翌日に削除）のルールだけを対象にする
+fn expiry_rule_candidates(existing: &[serde_json::Value], prefix: &str) -> Vec<String> {
+    let root = match prefix.trim_end_matches('/').rsplit_once('/') {
+        Some((root, _)) => format!("{}/", root),
+        None => String::new(),
+    };
+    existing
+        .iter()
+        .filter(|rule| rule.get("daysFromHidingToDeleting").and_then(|d| d.as_u64()) == Some(1))
+        .filter_map(|rule| rule.get("fileNamePrefix")?.as_str())
+        .filter(|p| *p != prefix && p.starts_with(&root) && p.ends_with('/'))
+        .filter(|p| !p[root.len()..].trim_end_matches('/').contains('/'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// 進捗表示のサイズ表記（例: "4.50G"）をバイト数に変換
+fn parse_scaled_size(value: &str) -> Option<u64> {
+    let value = value.trim_end_matches('B');
+    let (number, multiplier) = match value.chars().last()? {
+        'k' | 'K' => (&value[..value.len() - 1], 1e3),
+        'M' => (&value[..value.len() - 1], 1e6),
+        'G' => (&value[..value.len() - 1], 1e9),
+        'T' => (&value[..value.len() - 1], 1e12),
+        _ => (value, 1.0),
+    };
+    let number: f64 = number.parse().ok()?;
+    (number >= 0.0).then_some((number * multiplier) as u64)
+}
+
+/// b2 CLI の進捗行（例: "file:  45%|████▌     | 4.50G/10.0G [01:23<01:40, 54.0MB/s]"）から
+/// 転送済みバイト数を取得
+pub fn parse_progress_bytes(line: &str) -> Option<u64> {
+    line.split_whitespace().find_map(|token| {
+        let (done, total) = token.split_once('/')?;
+        parse_scaled_size(total)?;
+        parse_scaled_size(done)
+    })
+}
+
+/// 子プロセスの stderr を読みながら実行し、進捗行をコールバックに渡す
+///
+/// 進捗以外の stderr はエラーメッセージ用に `Output::stderr` に残す
+fn output_with_progress(command: &mut Command, on_progress: &dyn Fn(u64)) -> io::Result<Output> {
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // stdout が詰まらないよう別スレッドで読み切る
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let stdout_reader = thread::spawn(move || {
+        let mut buf = Vec::new();
+        stdout.read_to_end(&mut buf).map(|_| buf)
+    });
+
+    let mut stderr_log = Vec::new();
+    let mut line = Vec::new();
+    let stderr = BufReader::new(child.stderr.take().expect("stderr is piped"));
+    let mut flush_line = |line: &mut Vec<u8>| {
+        let text = String::from_utf8_lossy(line);
+        if let Some(done) = parse_progress_bytes(&text) {
+            on_progress(done);
+        } else if !text.trim().is_empty() {
+            stderr_log.extend_from_slice(line);
+            stderr_log.push(b'\n');
+        }
+        line.clear();
+    };
+
+    // 進捗は \r で上書きされるため、\r と \n の両方を行区切りとして扱う
+    for byte in stderr.bytes() {
+        match byte? {
+            b'\r' | b'\n' => flush_line(&mut line),
+            b => line.push(b),
+        }
+    }
+    flush_line(&mut line);
+
+    let status = child.wait()?;
+    let stdout = stdout_reader
+        .join()
+        .map_err(|_| io::Error::other("failed to read stdout"))??;
+
+    Ok(Output {
+        status,
+        stdout,
+        stderr: stderr_log,
+    })
+}
+
+/// b2 sync のデフォルト並列数
+pub const DEFAULT_SYNC_THREADS: u32 = 10;
+
+/// upload_directory のデフォルト並列数
+pub const DEFAULT_UPLOAD_THREADS: u32 = 4;
+
+/// 1 ファイルをパートに分けてアップロードする際のデフォルト並列数
+pub const DEFAULT_FILE_THREADS: u32 = 4;
+
+/// upload_directory で 1 ファイル分のアップロード
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct UploadTask {
+    pub(crate) local_path: PathBuf,
+    pub(crate) remote_path: String,
+    pub(crate) size: u64,
+}
+
+/// ディレクトリ内のファイルをパス順に列挙し、アップロード先を決める
+pub(crate) fn collect_upload_tasks(local_dir: &Path, remote_prefix: &str) -> Result<Vec<UploadTask>> {
+    use walkdir::WalkDir;
+
+    let mut tasks = Vec::new();
+
+    for entry in WalkDir::new(local_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        let local_path = entry.path();
+        let relative_path = local_path
+            .strip_prefix(local_dir)
+            .map_err(|e| crate::Error::B2(format!("Failed to get relative path: {}", e)))?;
+
+        let remote_path = PathBuf::from(remote_prefix).join(relative_path);
+
+        tasks.push(UploadTask {
+            local_path: local_path.to_path_buf(),
+            remote_path: remote_path.to_string_lossy().to_string(),
+            size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+        });
+    }
+
+    Ok(tasks)
+}
+
+/// B2 CLI のバージョン（インストールされていなければ None）
+pub fn version() -> Option<String> {
+    utils::tool_version(Command::new("b2").arg("version"))
+}
 
 /// B2 CLI のラッパー
 pub struct B2Client {
     key_id: String,
     key: String,
+    retry_policy: RetryPolicy,
+    sync_threads: u32,
+    upload_threads: u32,
+    file_threads: u32,
 }
 
 impl B2Client {
@@ -21,37 +404,125 @@ impl B2Client {
                 "B2 Application Key is empty".into(),
             ));
         }
-        Ok(Self { key_id, key })
+        Ok(Self {
+            key_id,
+            key,
+            retry_policy: RetryPolicy::default(),
+            sync_threads: DEFAULT_SYNC_THREADS,
+            upload_threads: DEFAULT_UPLOAD_THREADS,
+            file_threads: DEFAULT_FILE_THREADS,
+        })
+    }
+
+    /// 再試行ポリシーを設定
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// b2 sync の並列数を設定
+    pub fn with_sync_threads(mut self, sync_threads: u32) -> Self {
+        self.sync_threads = sync_threads.max(1);
+        self
+    }
+
+    /// upload_directory の並列数を設定（1 なら順番にアップロード）
+    pub fn with_upload_threads(mut self, upload_threads: u32) -> Self {
+        self.upload_threads = upload_threads.max(1);
+        self
+    }
+
+    /// 1 ファイルのアップロードで使う並列数を設定（b2 file upload の --threads、1 で分割しない）
+    pub fn with_file_threads(mut self, file_threads: u32) -> Self {
+        self.file_threads = file_threads.max(1);
+        self
+    }
+
+    /// 認証情報を設定した b2 コマンド
+    fn command(&self) -> Command {
+        let mut command = Command::new("b2");
+        command
+            .env("B2_APPLICATION_KEY_ID", &self.key_id)
+            .env("B2_APPLICATION_KEY", &self.key);
+        command
+    }
+
+    /// 一時的なエラーの間は再試行ポリシーに従って b2 コマンドを再実行
+    ///
+    /// 最後の実行結果（失敗を含む）を返す。コマンド自体を起動できない場合はエラー。
+    /// `timeout` を指定した場合、時間内に終わらなければ一時的なエラーとして再試行する
+    fn run_with_retry(
+        &self,
+        operation: &str,
+        progress: UploadProgress,
+        timeout: Option<Duration>,
+        build: impl Fn(&mut Command) -> &mut Command,
+    ) -> Result<Output> {
+        let mut attempt = 0;
+        loop {
+            let mut command = self.command();
+            let command = build(&mut command);
+            let result = match (progress, timeout) {
+                (Some(on_progress), _) => output_with_progress(command, on_progress).map_err(Into::into),
+                (None, Some(timeout)) => utils::run_command_with_timeout(command, timeout),
+                (None, None) => command.output().map_err(Into::into),
+            };
+
+            let stderr = match result {
+                Ok(output) => {
+                    if output.status.success() || attempt >= self.retry_policy.max_retries {
+                        return Ok(output);
+                    }
+                    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+                    if !is_retryable_error(&stderr) {
+                        return Ok(output);
+                    }
+                    stderr
+                }
+                Err(e @ crate::Error::Timeout { .. }) if attempt < self.retry_policy.max_retries => {
+                    e.to_string()
+                }
+                Err(crate::Error::Io(e)) => {
+                    return Err(crate::Error::B2(format!("Failed to {}: {}", operation, e)));
+                }
+                Err(e) => return Err(e),
+            };
+
+            let delay = self.retry_policy.delay(attempt);
+            attempt += 1;
+            eprintln!(
+                "⚠ B2 の {} に失敗しました。{:.1} 秒後に再試行します ({}/{}): {}",
+                operation,
+                delay.as_secs_f64(),
+                attempt,
+                self.retry_policy.max_retries,
+                stderr.trim()
+            );
+            thread::sleep(delay);
+        }
     }
 
     /// B2 CLI がインストールされているか確認
     pub fn is_installed() -> bool {
-        Command::new("b2")
-            .arg("version")
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .status()
-            .map(|s| s.success())
+        utils::run_command_with_timeout(Command::new("b2").arg("version"), utils::command_timeout())
+            .map(|output| output.status.success())
             .unwrap_or(false)
     }
 
     /// B2 にログイン（認証）
     /// B2 CLI v4+ では環境変数経由で認証情報を渡すことを推奨
     pub fn authorize(&self) -> Result<()> {
-        let output = Command::new("b2")
-            .env("B2_APPLICATION_KEY_ID", &self.key_id)
-            .env("B2_APPLICATION_KEY", &self.key)
-            .arg("account")
-            .arg("authorize")
-            .output()
-            .map_err(|e| crate::Error::B2(format!("Failed to run b2 account authorize: {}", e)))?;
+        let output = utils::run_command_with_timeout(
+            self.command().arg("account").arg("authorize"),
+            utils::command_timeout(),
+        )
+        .map_err(|e| match e {
+            crate::Error::Io(e) => crate::Error::B2(format!("Failed to run b2 account authorize: {}", e)),
+            e => e,
+        })?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(crate::Error::B2(format!(
-                "Failed to authorize B2 account: {}",
-                stderr
-            )));
+            return Err(crate::Error::external_tool("b2 account authorize", &output));
         }
 
         Ok(())
@@ -59,29 +530,47 @@ impl B2Client {
 
     /// ファイルを B2 にアップロード
     /// 注意: 事前に authorize() を呼び出しておく必要があります
+    ///
+    /// `progress` を指定すると b2 CLI の進捗出力を読み取り、転送済みバイト数を通知する。
+    /// 再アップロードを省略できるよう、ファイル情報に SHA256 を `sha256` として記録する
     pub fn upload_file(
         &self,
         bucket: &str,
         local_path: &Path,
         remote_path: &str,
+        progress: UploadProgress,
     ) -> Result<String> {
-        let output = Command::new("b2")
-            .env("B2_APPLICATION_KEY_ID", &self.key_id)
-            .env("B2_APPLICATION_KEY", &self.key)
-            .arg("file")
-            .arg("upload")
-            .arg("--no-progress")
-            .arg("--threads")
-            .arg("1")
-            .arg(bucket)
-            .arg(local_path)
-            .arg(remote_path)
-            .output()
-            .map_err(|e| crate::Error::B2(format!("Failed to upload file: {}", e)))?;
+        let sha256 = Self::calculate_sha256(local_path)?;
+        self.upload_file_with_sha256(bucket, local_path, remote_path, &sha256, progress)
+    }
+
+    /// SHA256 を計算済みのファイルを B2 にアップロード（`upload_file` と同じく `sha256` として記録する）
+    /// 注意: 事前に authorize() を呼び出しておく必要があります
+    pub fn upload_file_with_sha256(
+        &self,
+        bucket: &str,
+        local_path: &Path,
+        remote_path: &str,
+        sha256: &str,
+        progress: UploadProgress,
+    ) -> Result<String> {
+        let output = self.run_with_retry("upload file", progress, None, |command| {
+            command.arg("file").arg("upload");
+            if progress.is_none() {
+                command.arg("--no-progress");
+            }
+            command
+                .arg("--info")
+                .arg(format!("{}={}", SHA256_INFO_KEY, sha256))
+                .arg("--threads")
+                .arg(self.file_threads.to_string())
+                .arg(bucket)
+                .arg(local_path)
+                .arg(remote_path)
+        })?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(crate::Error::B2(format!("Upload failed: {}", stderr)));
+            return Err(crate::Error::external_tool("b2 file upload", &output));
         }
 
         // 出力から file ID を取得（JSON パース）
@@ -100,20 +589,43 @@ impl B2Client {
         // B2 URI 形式に変換
         let b2_uri = format!("b2://{}/{}", bucket, remote_path);
 
-        let output = Command::new("b2")
-            .env("B2_APPLICATION_KEY_ID", &self.key_id)
-            .env("B2_APPLICATION_KEY", &self.key)
-            .arg("file")
-            .arg("download")
-            .arg("--no-progress")
-            .arg(&b2_uri)
-            .arg(local_path)
-            .output()
-            .map_err(|e| crate::Error::B2(format!("Failed to download file: {}", e)))?;
+        let output = self.run_with_retry("download file", None, None, |command| {
+            command
+                .arg("file")
+                .arg("download")
+                .arg("--no-progress")
+                .arg(&b2_uri)
+                .arg(local_path)
+        })?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(crate::Error::B2(format!("Download failed: {}", stderr)));
+            return Err(crate::Error::external_tool("b2 file download", &output));
+        }
+
+        Ok(())
+    }
+
+    /// `b2 sync` でリモートのディレクトリをまとめてダウンロード
+    ///
+    /// ローカルの方が新しいファイルも上書きし、ローカルにだけあるファイルは残す
+    /// 注意: 事前に authorize() を呼び出しておく必要があります
+    pub fn download_directory(&self, bucket: &str, remote_prefix: &str, local_dir: &Path) -> Result<()> {
+        let b2_uri = format!("b2://{}/{}", bucket, remote_prefix.trim_end_matches('/'));
+
+        std::fs::create_dir_all(local_dir)?;
+        let output = self.run_with_retry("download directory", None, None, |command| {
+            command
+                .arg("sync")
+                .arg("--no-progress")
+                .arg("--replace-newer")
+                .arg("--threads")
+                .arg(self.sync_threads.to_string())
+                .arg(&b2_uri)
+                .arg(local_dir)
+        })?;
+
+        if !output.status.success() {
+            return Err(crate::Error::external_tool("b2 sync", &output));
         }
 
         Ok(())
@@ -145,68 +657,309 @@ impl B2Client {
     }
 
     /// ディレクトリを再帰的にアップロード
+    ///
+    /// `upload_threads` が 2 以上ならファイルごとのアップロードを並列に行う。
+    /// 戻り値の file ID はパス順。`progress` にはディレクトリ全体での転送済みバイト数を通知する
     pub fn upload_directory(
         &self,
         bucket: &str,
         local_dir: &Path,
         remote_prefix: &str,
+        progress: UploadProgress,
     ) -> Result<Vec<String>> {
-        use walkdir::WalkDir;
+        let tasks = collect_upload_tasks(local_dir, remote_prefix)?;
+
+        if self.upload_threads <= 1 || tasks.len() <= 1 {
+            self.upload_sequential(bucket, &tasks, progress)
+        } else {
+            self.upload_parallel(bucket, &tasks, progress)
+        }
+    }
 
+    /// 1 ファイルずつ順番にアップロード
+    fn upload_sequential(
+        &self,
+        bucket: &str,
+        tasks: &[UploadTask],
+        progress: UploadProgress,
+    ) -> Result<Vec<String>> {
         let mut uploaded = Vec::new();
+        let mut uploaded_bytes = 0;
 
-        for entry in WalkDir::new(local_dir)
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().is_file())
-        {
-            let local_path = entry.path();
-            let relative_path = local_path
-                .strip_prefix(local_dir)
-                .map_err(|e| crate::Error::B2(format!("Failed to get relative path: {}", e)))?;
-
-            let remote_path = PathBuf::from(remote_prefix).join(relative_path);
-            let remote_path_str = remote_path.to_string_lossy();
-
-            let file_id = self.upload_file(bucket, local_path, &remote_path_str)?;
+        for task in tasks {
+            let report_file_progress = |done: u64| {
+                if let Some(on_progress) = progress {
+                    on_progress(uploaded_bytes + done);
+                }
+            };
+            let file_progress: UploadProgress = progress.map(|_| &report_file_progress as &dyn Fn(u64));
+
+            let file_id = self.upload_file(bucket, &task.local_path, &task.remote_path, file_progress)?;
             uploaded.push(file_id);
+
+            uploaded_bytes += task.size;
+            if let Some(on_progress) = progress {
+                on_progress(uploaded_bytes);
+            }
         }
 
         Ok(uploaded)
     }
 
-    /// B2 上のファイル一覧を取得
+    /// upload_threads 個のワーカーで並列にアップロード
+    ///
+    /// 進捗はワーカーからチャネル経由で受け取り、呼び出し元のスレッドで通知する
+    fn upload_parallel(
+        &self,
+        bucket: &str,
+        tasks: &[UploadTask],
+        progress: UploadProgress,
+    ) -> Result<Vec<String>> {
+        let next = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+        let file_ids: Mutex<Vec<Option<String>>> = Mutex::new(vec![None; tasks.len()]);
+        let first_error: Mutex<Option<crate::Error>> = Mutex::new(None);
+        let workers = (self.upload_threads as usize).min(tasks.len());
+        let (tx, rx) = mpsc::channel::<(usize, u64)>();
+        // コールバック自体はスレッド間で共有できないため、ワーカーには有無だけを渡す
+        let report_progress = progress.is_some();
+
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                let tx = tx.clone();
+                let (next, failed, file_ids, first_error) = (&next, &failed, &file_ids, &first_error);
+                scope.spawn(move || {
+                    while !failed.load(Ordering::Relaxed) {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(task) = tasks.get(index) else {
+                            break;
+                        };
+
+                        let report_file_progress = |done: u64| {
+                            let _ = tx.send((index, done.min(task.size)));
+                        };
+                        let file_progress: UploadProgress =
+                            report_progress.then_some(&report_file_progress as &dyn Fn(u64));
+
+                        match self.upload_file(bucket, &task.local_path, &task.remote_path, file_progress) {
+                            Ok(file_id) => {
+                                file_ids.lock().unwrap()[index] = Some(file_id);
+                                let _ = tx.send((index, task.size));
+                            }
+                            Err(e) => {
+                                failed.store(true, Ordering::Relaxed);
+                                first_error.lock().unwrap().get_or_insert(e);
+                            }
+                        }
+                    }
+                });
+            }
+            drop(tx);
+
+            let mut done = vec![0; tasks.len()];
+            for (index, bytes) in rx {
+                done[index] = bytes;
+                if let Some(on_progress) = progress {
+                    on_progress(done.iter().sum());
+                }
+            }
+        });
+
+        if let Some(e) = first_error.into_inner().unwrap() {
+            return Err(e);
+        }
+
+        Ok(file_ids.into_inner().unwrap().into_iter().flatten().collect())
+    }
+
+    /// `b2 sync` でディレクトリをアップロード
+    ///
+    /// ファイルごとに `b2 file upload` を呼ぶ upload_directory より高速で、
+    /// 中断後に再実行するとアップロード済みのファイルはスキップされる
+    /// 注意: 事前に authorize() を呼び出しておく必要があります
+    pub fn sync_directory(&self, bucket: &str, local_dir: &Path, remote_prefix: &str) -> Result<()> {
+        let b2_uri = format!("b2://{}/{}", bucket, remote_prefix);
+
+        let output = self.run_with_retry("sync directory", None, None, |command| {
+            command
+                .arg("sync")
+                .arg("--no-progress")
+                .arg("--threads")
+                .arg(self.sync_threads.to_string())
+                .arg(local_dir)
+                .arg(&b2_uri)
+        })?;
+
+        if !output.status.success() {
+            return Err(crate::Error::external_tool("b2 sync", &output));
+        }
+
+        Ok(())
+    }
+
+    /// B2 上のファイル一覧（パスのみ）を取得
     /// 注意: 事前に authorize() を呼び出しておく必要があります
     pub fn list_files(&self, bucket: &str, prefix: &str) -> Result<Vec<String>> {
-        let output = Command::new("b2")
-            .env("B2_APPLICATION_KEY_ID", &self.key_id)
-            .env("B2_APPLICATION_KEY", &self.key)
-            .arg("file")
-            .arg("ls")
-            .arg("--recursive")
-            .arg(bucket)
-            .arg(prefix)
-            .output()
-            .map_err(|e| crate::Error::B2(format!("Failed to list files: {}", e)))?;
+        Ok(self
+            .list_files_detailed(bucket, prefix)?
+            .into_iter()
+            .map(|file| file.path)
+            .collect())
+    }
+
+    /// B2 上のファイル一覧をサイズ・更新日時付きで取得
+    /// 注意: 事前に authorize() を呼び出しておく必要があります
+    pub fn list_files_detailed(&self, bucket: &str, prefix: &str) -> Result<Vec<RemoteFile>> {
+        let output = self.run_with_retry("list files", None, Some(utils::list_timeout()), |command| {
+            command
+                .arg("file")
+                .arg("ls")
+                .arg("--recursive")
+                .arg("--json")
+                .arg(bucket)
+                .arg(prefix)
+        })?;
+
+        if !output.status.success() {
+            return Err(crate::Error::external_tool("b2 file ls", &output));
+        }
+
+        parse_ls_json(&String::from_utf8_lossy(&output.stdout))
+            .ok_or_else(|| crate::Error::B2("Failed to parse file list".into()))
+    }
+
+    /// 指定パスのファイルが同じ SHA256 で既にアップロード済みか確認
+    /// 注意: 事前に authorize() を呼び出しておく必要があります
+    pub fn file_exists_with_sha(&self, bucket: &str, remote_path: &str, sha256: &str) -> Result<bool> {
+        let b2_uri = format!("b2://{}/{}", bucket, remote_path);
+
+        let output = self.run_with_retry("get file info", None, Some(utils::command_timeout()), |command| {
+            command.arg("file").arg("info").arg(&b2_uri)
+        })?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(crate::Error::B2(format!("List files failed: {}", stderr)));
+            if is_not_found_error(&stderr) {
+                return Ok(false);
+            }
+            return Err(crate::Error::external_tool("b2 file info", &output));
         }
 
         let stdout = String::from_utf8_lossy(&output.stdout);
-        let files: Vec<String> = stdout
-            .lines()
-            .filter(|line| !line.is_empty())
-            .map(|line| {
-                // B2 の ls 出力形式: "filename  size  upload_time"
-                // ファイル名部分だけを抽出
-                line.split_whitespace().next().unwrap_or("").to_string()
-            })
-            .filter(|s| !s.is_empty())
-            .collect();
+        Ok(parse_file_info_sha256(&stdout).is_some_and(|remote| remote.eq_ignore_ascii_case(sha256)))
+    }
+
+    /// `prefix` 以下の全バージョン（非表示にしたファイルを含む）の一覧（`b2 file ls --versions --json` の出力）
+    /// 注意: 事前に authorize() を呼び出しておく必要があります
+    fn list_file_versions(&self, bucket: &str, prefix: &str) -> Result<String> {
+        let output = self.run_with_retry("list file versions", None, Some(utils::list_timeout()), |command| {
+            command
+                .arg("file")
+                .arg("ls")
+                .arg("--recursive")
+                .arg("--versions")
+                .arg("--json")
+                .arg(bucket)
+                .arg(prefix)
+        })?;
+
+        if !output.status.success() {
+            return Err(crate::Error::external_tool("b2 file ls", &output));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// B2 上のファイルを全バージョン削除
+    ///
+    /// `b2 rm` はパスをプレフィックスとして扱い、`a.bin` で `a.bin.part000` なども消えるため、
+    /// 同じ名前のバージョンだけを一覧してから 1 つずつ削除する
+    /// 注意: 事前に authorize() を呼び出しておく必要があります
+    pub fn delete_file(&self, bucket: &str, remote_path: &str) -> Result<()> {
+        let file_ids = parse_file_version_ids(&self.list_file_versions(bucket, remote_path)?, remote_path)
+            .ok_or_else(|| crate::Error::B2("Failed to parse file versions".into()))?;
+
+        for file_id in file_ids {
+            let output = utils::run_command_with_timeout(
+                self.command().arg("file").arg("delete").arg(remote_path).arg(&file_id),
+                utils::command_timeout(),
+            )
+            .map_err(|e| match e {
+                crate::Error::Io(e) => crate::Error::B2(format!("Failed to delete file: {}", e)),
+                e => e,
+            })?;
+
+            if !output.status.success() {
+                return Err(crate::Error::external_tool("b2 file delete", &output));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl B2Client {
+    /// バケットのライフサイクルルールを取得
+    /// 注意: 事前に authorize() を呼び出しておく必要があります
+    fn lifecycle_rules(&self, bucket: &str) -> Result<Vec<serde_json::Value>> {
+        let output = utils::run_command_with_timeout(
+            self.command().arg("bucket").arg("get").arg(bucket),
+            utils::command_timeout(),
+        )
+        .map_err(|e| match e {
+            crate::Error::Io(e) => crate::Error::B2(format!("Failed to get bucket: {}", e)),
+            e => e,
+        })?;
 
-        Ok(files)
+        if !output.status.success() {
+            return Err(crate::Error::external_tool("b2 bucket get", &output));
+        }
+
+        let value: serde_json::Value = serde_json::from_slice(&output.stdout)
+            .map_err(|e| crate::Error::B2(format!("Failed to parse b2 bucket get output: {}", e)))?;
+        Ok(value
+            .get("lifecycleRules")
+            .and_then(|rules| rules.as_array())
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    /// `prefix` 以下のファイルをアップロードから `days` 日後に自動削除するライフサイクルルールを設定
+    ///
+    /// バケットの既存のルールは残したまま追加する
+    /// 注意: 事前に authorize() を呼び出しておく必要があります
+    pub fn set_expiry(&self, bucket: &str, prefix: &str, days: u32) -> Result<()> {
+        let existing = self.lifecycle_rules(bucket)?;
+        // アーカイブごとにルールが増え続けて上限（100 件）に達しないよう、期限切れで空になったものを取り除く
+        let mut stale = Vec::new();
+        // 非表示にしただけのファイルはルールが削除するので、全バージョンがなくなってから取り除く
+        for candidate in expiry_rule_candidates(&existing, prefix) {
+            let versions = self.list_file_versions(bucket, &candidate)?;
+            if serde_json::from_str::<Vec<serde_json::Value>>(&versions).is_ok_and(|v| v.is_empty()) {
+                stale.push(candidate);
+            }
+        }
+        let rules = expiry_lifecycle_rules(existing, prefix, days, &stale);
+
+        let output = utils::run_command_with_timeout(
+            self.command()
+                .arg("bucket")
+                .arg("update")
+                .arg("--lifecycle-rules")
+                .arg(serde_json::Value::Array(rules).to_string())
+                .arg(bucket),
+            utils::command_timeout(),
+        )
+        .map_err(|e| match e {
+            crate::Error::Io(e) => crate::Error::B2(format!("Failed to update bucket: {}", e)),
+            e => e,
+        })?;
+
+        if !output.status.success() {
+            return Err(crate::Error::external_tool("b2 bucket update", &output));
+        }
+
+        Ok(())
     }
 }
 
@@ -215,8 +968,32 @@ impl StorageClient for B2Client {
         B2Client::authorize(self)
     }
 
-    fn upload_file(&self, bucket: &str, local_path: &Path, remote_path: &str) -> Result<String> {
-        B2Client::upload_file(self, bucket, local_path, remote_path)
+    fn health_check(&self) -> Result<()> {
+        if !B2Client::is_installed() {
+            return Err(crate::Error::B2("B2 CLI is not installed".into()));
+        }
+        B2Client::authorize(self)
+    }
+
+    fn upload_file(
+        &self,
+        bucket: &str,
+        local_path: &Path,
+        remote_path: &str,
+        progress: UploadProgress,
+    ) -> Result<String> {
+        B2Client::upload_file(self, bucket, local_path, remote_path, progress)
+    }
+
+    fn upload_file_with_sha256(
+        &self,
+        bucket: &str,
+        local_path: &Path,
+        remote_path: &str,
+        sha256: &str,
+        progress: UploadProgress,
+    ) -> Result<String> {
+        B2Client::upload_file_with_sha256(self, bucket, local_path, remote_path, sha256, progress)
     }
 
     fn upload_directory(
@@ -224,8 +1001,13 @@ impl StorageClient for B2Client {
         bucket: &str,
         local_dir: &Path,
         remote_prefix: &str,
+        progress: UploadProgress,
     ) -> Result<Vec<String>> {
-        B2Client::upload_directory(self, bucket, local_dir, remote_prefix)
+        B2Client::upload_directory(self, bucket, local_dir, remote_prefix, progress)
+    }
+
+    fn sync_directory(&self, bucket: &str, local_dir: &Path, remote_prefix: &str) -> Result<()> {
+        B2Client::sync_directory(self, bucket, local_dir, remote_prefix)
     }
 
     fn download_file_by_name(
@@ -237,9 +1019,33 @@ impl StorageClient for B2Client {
         B2Client::download_file_by_name(self, bucket, remote_path, local_path)
     }
 
+    fn download_directory(&self, bucket: &str, remote_prefix: &str, local_dir: &Path) -> Result<()> {
+        B2Client::download_directory(self, bucket, remote_prefix, local_dir)
+    }
+
+    fn file_exists_with_sha(&self, bucket: &str, remote_path: &str, sha256: &str) -> Result<bool> {
+        B2Client::file_exists_with_sha(self, bucket, remote_path, sha256)
+    }
+
     fn list_files(&self, bucket: &str, prefix: &str) -> Result<Vec<String>> {
         B2Client::list_files(self, bucket, prefix)
     }
+
+    fn list_files_detailed(&self, bucket: &str, prefix: &str) -> Result<Vec<RemoteFile>> {
+        B2Client::list_files_detailed(self, bucket, prefix)
+    }
+
+    fn delete_file(&self, bucket: &str, remote_path: &str) -> Result<()> {
+        B2Client::delete_file(self, bucket, remote_path)
+    }
+
+    fn supports_expiry(&self) -> bool {
+        true
+    }
+
+    fn set_expiry(&self, bucket: &str, prefix: &str, days: u32) -> Result<()> {
+        B2Client::set_expiry(self, bucket, prefix, days)
+    }
 }
 
 #[cfg(test)]
@@ -274,6 +1080,214 @@ mod tests {
         assert_eq!(result, "base/sub/dir/file.txt");
     }
 
+    #[test]
+    fn test_retry_policy_delay() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(1000),
+        };
+
+        // 指数バックオフ + 最大 50% のジッター
+        for (attempt, base) in [(0, 100), (1, 200), (2, 400), (3, 800)] {
+            let delay = policy.delay(attempt);
+            assert!(delay >= Duration::from_millis(base));
+            assert!(delay <= Duration::from_millis(base + base / 2));
+        }
+
+        // 上限で頭打ち
+        let delay = policy.delay(10);
+        assert!(delay >= Duration::from_millis(1000));
+        assert!(delay <= Duration::from_millis(1500));
+
+        assert_eq!(RetryPolicy::default().max_retries, 3);
+    }
+
+    #[test]
+    fn test_is_retryable_error() {
+        assert!(is_retryable_error("ConnectionError: Connection reset by peer"));
+        assert!(is_retryable_error("Read timed out. (read timeout=60)"));
+        assert!(is_retryable_error("ERROR: 503 service_unavailable"));
+        assert!(!is_retryable_error("ERROR: unauthorized: invalid application key"));
+        assert!(!is_retryable_error("ERROR: bad_auth_token (401)"));
+        assert!(!is_retryable_error("ERROR: bucket does not exist"));
+
+        // ステータスコードとして書かれた場合だけ再試行する
+        assert!(is_retryable_error("ERROR: Unknown error: 500 internal_error"));
+        assert!(is_retryable_error("HTTP 502 returned by upstream"));
+        assert!(is_retryable_error("request failed with status: 504"));
+        assert!(is_retryable_error("ERROR: rate limited (429)"));
+        assert!(!is_retryable_error("ERROR: file archive/500.bin not present"));
+        assert!(!is_retryable_error("ERROR: upload size 5030 exceeds limit"));
+        assert!(!is_retryable_error("ERROR: status: 5031"));
+        assert!(!is_retryable_error("ERROR: no connection profile named 'default'"));
+    }
+
+    #[test]
+    fn test_parse_file_version_ids() {
+        let json = r#"[
+            {"action": "upload", "fileId": "4_z1", "fileName": "archive/a.bin"},
+            {"action": "hide", "fileId": "4_z2", "fileName": "archive/a.bin"},
+            {"action": "upload", "fileId": "4_z3", "fileName": "archive/a.bin.part000"}
+        ]"#;
+        assert_eq!(
+            parse_file_version_ids(json, "archive/a.bin"),
+            Some(vec!["4_z1".to_string(), "4_z2".to_string()])
+        );
+        assert_eq!(parse_file_version_ids("[]", "archive/a.bin"), Some(Vec::new()));
+        assert_eq!(parse_file_version_ids("not json", "archive/a.bin"), None);
+    }
+
+    #[test]
+    fn test_expiry_lifecycle_rules() {
+        let existing = vec![
+            serde_json::json!({"fileNamePrefix": "logs/", "daysFromUploadingToHiding": 7}),
+            serde_json::json!({"fileNamePrefix": "tmp/20250101_000000/", "daysFromUploadingToHiding": 3}),
+        ];
+
+        let rules = expiry_lifecycle_rules(existing, "tmp/20250101_000000/", 30, &[]);
+
+        // 他のプレフィックスのルールは残し、同じプレフィックスは置き換える
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0]["fileNamePrefix"], "logs/");
+        assert_eq!(rules[1]["fileNamePrefix"], "tmp/20250101_000000/");
+        assert_eq!(rules[1]["daysFromUploadingToHiding"], 30);
+        assert_eq!(rules[1]["daysFromHidingToDeleting"], 1);
+
+        // ファイルが残っていない過去のアーカイブのルールは取り除く
+        let existing = rules;
+        let rules = expiry_lifecycle_rules(existing, "tmp/20250201_000000/", 7, &["tmp/20250101_000000/".to_string()]);
+        let prefixes: Vec<&str> = rules.iter().filter_map(|r| r["fileNamePrefix"].as_str()).collect();
+        assert_eq!(prefixes, vec!["logs/", "tmp/20250201_000000/"]);
+    }
+
+    #[test]
+    fn test_expiry_rule_candidates() {
+        let existing = vec![
+            serde_json::json!({"fileNamePrefix": "logs/", "daysFromUploadingToHiding": 7}),
+            serde_json::json!({"fileNamePrefix": "tmp/old/", "daysFromUploadingToHiding": 7}),
+            serde_json::json!({"fileNamePrefix": "tmp/20250101_000000/", "daysFromUploadingToHiding": 3, "daysFromHidingToDeleting": 1}),
+            serde_json::json!({"fileNamePrefix": "tmp/20250101_000000/sub/", "daysFromUploadingToHiding": 3, "daysFromHidingToDeleting": 1}),
+            serde_json::json!({"fileNamePrefix": "other/20250101_000000/", "daysFromUploadingToHiding": 3, "daysFromHidingToDeleting": 1}),
+            serde_json::json!({"fileNamePrefix": "tmp/20250201_000000/", "daysFromUploadingToHiding": 3, "daysFromHidingToDeleting": 1}),
+        ];
+
+        // 同じ保存先にある kanri のルールだけが候補になる（手動のルール・他の保存先・自分自身は除く）
+        assert_eq!(
+            expiry_rule_candidates(&existing, "tmp/20250201_000000/"),
+            vec!["tmp/20250101_000000/".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_parse_ls_json() {
+        let json = r#"[
+  {"action": "upload", "fileName": "archive/20250101_000000/a.bin", "size": 100,
+   "fileInfo": {"src_last_modified_millis": "1735689600000"}, "uploadTimestamp": 1735700000000},
+  {"action": "upload", "fileName": "archive/20250101_000000/b.bin", "contentLength": 20,
+   "uploadTimestamp": 1735700000000},
+  {"action": "folder", "fileName": "archive/20250101_000000/sub/"},
+  {"action": "hide", "fileName": "archive/20250101_000000/old.bin", "size": 0}
+]"#;
+        assert_eq!(
+            parse_ls_json(json),
+            Some(vec![
+                RemoteFile {
+                    path: "archive/20250101_000000/a.bin".to_string(),
+                    size: Some(100),
+                    modified: DateTime::from_timestamp_millis(1735689600000),
+                },
+                RemoteFile {
+                    path: "archive/20250101_000000/b.bin".to_string(),
+                    size: Some(20),
+                    modified: DateTime::from_timestamp_millis(1735700000000),
+                },
+            ])
+        );
+        assert_eq!(parse_ls_json("not json"), None);
+    }
+
+    #[test]
+    fn test_parse_file_info_sha256() {
+        let json = r#"{
+  "contentSha1": "2aae6c35c94fcfb415dbe95f408b9ce91ee846ed",
+  "fileId": "4_z27c88f1d182b150646ff0b16_f1004ba650fe24e6b_d20250101_m000000_c002_v0001015_t0000",
+  "fileInfo": {
+    "sha256": "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9",
+    "src_last_modified_millis": "1735689600000"
+  },
+  "fileName": "archive/20250101_000000/data.bin"
+}"#;
+        assert_eq!(
+            parse_file_info_sha256(json).as_deref(),
+            Some("b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9")
+        );
+
+        // SHA256 を記録せずにアップロードされたファイル
+        assert_eq!(parse_file_info_sha256(r#"{"fileInfo": {}}"#), None);
+        assert_eq!(parse_file_info_sha256("not json"), None);
+
+        assert!(is_not_found_error("ERROR: File not present: archive/data.bin"));
+        assert!(!is_not_found_error("ERROR: bad_auth_token (401)"));
+    }
+
+    #[test]
+    fn test_parse_progress_bytes() {
+        assert_eq!(
+            parse_progress_bytes("data.bin:  45%|████▌     | 4.50G/10.0G [01:23<01:40, 54.0MB/s]"),
+            Some(4_500_000_000)
+        );
+        assert_eq!(
+            parse_progress_bytes("small.txt: 100%|██████████| 512/512 [00:00<00:00, 1.02kB/s]"),
+            Some(512)
+        );
+        assert_eq!(parse_progress_bytes("ERROR: Connection reset by peer"), None);
+        assert_eq!(parse_progress_bytes("b2://bucket/path"), None);
+    }
+
+    #[test]
+    fn test_output_with_progress() {
+        use std::cell::RefCell;
+
+        let reported = RefCell::new(Vec::new());
+        let output = output_with_progress(
+            Command::new("sh").arg("-c").arg(
+                "printf 'f: 10%%| | 1.0k/2.0k [00:01]\\rf: 100%%| | 2.0k/2.0k [00:02]\\n' >&2; \
+                 echo uploaded; echo 'WARNING: slow' >&2",
+            ),
+            &|done| reported.borrow_mut().push(done),
+        )
+        .unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "uploaded");
+        assert_eq!(String::from_utf8_lossy(&output.stderr).trim(), "WARNING: slow");
+        assert_eq!(*reported.borrow(), vec![1000, 2000]);
+    }
+
+    #[test]
+    fn test_collect_upload_tasks() -> Result<()> {
+        use tempfile::TempDir;
+
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join("sub"))?;
+        std::fs::write(temp.path().join("b.txt"), "bb")?;
+        std::fs::write(temp.path().join("a.txt"), "a")?;
+        std::fs::write(temp.path().join("sub").join("c.txt"), "ccc")?;
+
+        let tasks = collect_upload_tasks(temp.path(), "archive/20250101")?;
+        let remote_paths: Vec<&str> = tasks.iter().map(|t| t.remote_path.as_str()).collect();
+
+        // パス順で並ぶ
+        assert_eq!(
+            remote_paths,
+            vec!["archive/20250101/a.txt", "archive/20250101/b.txt", "archive/20250101/sub/c.txt"]
+        );
+        assert_eq!(tasks.iter().map(|t| t.size).sum::<u64>(), 6);
+
+        Ok(())
+    }
+
     #[test]
     fn test_b2_cli_check() {
         // B2 CLI がインストールされているかチェック（環境依存）
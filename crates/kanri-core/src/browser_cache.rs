@@ -0,0 +1,307 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    cleanable::{Cleanable, CleanableItem, CleanableMetadata},
+    utils, Result,
+};
+
+/// 安全性ラベル（キャッシュのみでクッキー・ログイン状態には影響しない）
+const SAFETY_LABEL: &str = "✓ 安全（ログイン状態は維持）";
+
+/// Chromium 系ブラウザのキャッシュディレクトリ（~/Library/Caches からの相対パス）
+const CHROMIUM_BROWSERS: &[(&str, &str)] = &[
+    ("Chrome", "Google/Chrome"),
+    ("Edge", "Microsoft Edge"),
+    ("Brave", "BraveSoftware/Brave-Browser"),
+];
+
+/// Chromium 系プロファイル内のキャッシュディレクトリ名
+const CHROMIUM_CACHE_DIRS: &[&str] = &["Cache", "Code Cache"];
+
+/// ブラウザキャッシュ情報
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrowserCache {
+    /// ブラウザ名
+    pub browser: String,
+    /// プロファイル名
+    pub profile: String,
+    /// キャッシュディレクトリのパス
+    pub cache_dir: PathBuf,
+    /// サイズ（バイト）
+    pub size: u64,
+}
+
+impl BrowserCache {
+    /// 表示名（例: Chrome (Default) Cache）
+    pub fn label(&self) -> String {
+        let dir_name = self
+            .cache_dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        format!("{} ({}) {}", self.browser, self.profile, dir_name)
+    }
+
+    /// サイズを人間が読みやすい形式で取得
+    pub fn formatted_size(&self) -> String {
+        utils::format_size(self.size)
+    }
+}
+
+/// Firefox のプロファイル情報（profiles.ini の 1 セクション）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FirefoxProfile {
+    /// プロファイル名
+    pub name: String,
+    /// プロファイルのパス
+    pub path: String,
+    /// パスが Firefox ディレクトリからの相対パスかどうか
+    pub is_relative: bool,
+}
+
+/// profiles.ini の内容をパースして Firefox のプロファイル一覧を取得
+pub fn parse_firefox_profiles(content: &str) -> Vec<FirefoxProfile> {
+    let mut profiles = Vec::new();
+    let mut current: Option<FirefoxProfile> = None;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            profiles.extend(current.take().filter(|p| !p.path.is_empty()));
+            // [Install...] や [General] は対象外
+            if line.starts_with("[Profile") {
+                current = Some(FirefoxProfile {
+                    name: String::new(),
+                    path: String::new(),
+                    is_relative: true,
+                });
+            }
+            continue;
+        }
+
+        let (Some(profile), Some((key, value))) = (current.as_mut(), line.split_once('=')) else {
+            continue;
+        };
+        match key.trim() {
+            "Name" => profile.name = value.trim().to_string(),
+            "Path" => profile.path = value.trim().to_string(),
+            "IsRelative" => profile.is_relative = value.trim() == "1",
+            _ => {}
+        }
+    }
+    profiles.extend(current.filter(|p| !p.path.is_empty()));
+
+    profiles
+}
+
+/// Chromium 系ブラウザのプロファイルディレクトリか確認
+fn is_chromium_profile(name: &str) -> bool {
+    name == "Default" || name.starts_with("Profile ") || name == "Guest Profile"
+}
+
+/// ホームディレクトリからブラウザキャッシュ候補を解決（存在するもののみ）
+fn resolve_browser_cache_dirs(home: &Path) -> Vec<(String, String, PathBuf)> {
+    let caches_dir = home.join("Library").join("Caches");
+    let mut dirs = Vec::new();
+
+    // Chrome / Edge / Brave: プロファイルごとの Cache
+    for (browser, relative) in CHROMIUM_BROWSERS {
+        let base = caches_dir.join(relative);
+        let Ok(read_dir) = fs::read_dir(&base) else {
+            continue;
+        };
+
+        let mut profiles: Vec<String> = read_dir
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_dir())
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .filter(|name| is_chromium_profile(name))
+            .collect();
+        profiles.sort();
+
+        for profile in profiles {
+            for cache_name in CHROMIUM_CACHE_DIRS {
+                let cache_dir = base.join(&profile).join(cache_name);
+                if cache_dir.is_dir() {
+                    dirs.push((browser.to_string(), profile.clone(), cache_dir));
+                }
+            }
+        }
+    }
+
+    // Firefox: profiles.ini からプロファイルを検出し cache2 を対象にする
+    let firefox_dir = home
+        .join("Library")
+        .join("Application Support")
+        .join("Firefox");
+    if let Ok(content) = fs::read_to_string(firefox_dir.join("profiles.ini")) {
+        for profile in parse_firefox_profiles(&content) {
+            let candidates = if profile.is_relative {
+                // キャッシュはプロファイル本体とは別に ~/Library/Caches 側に置かれる
+                vec![
+                    caches_dir.join("Firefox").join(&profile.path).join("cache2"),
+                    firefox_dir.join(&profile.path).join("cache2"),
+                ]
+            } else {
+                vec![PathBuf::from(&profile.path).join("cache2")]
+            };
+
+            if let Some(cache_dir) = candidates.into_iter().find(|p| p.is_dir()) {
+                let name = if profile.name.is_empty() {
+                    profile.path.clone()
+                } else {
+                    profile.name.clone()
+                };
+                dirs.push(("Firefox".to_string(), name, cache_dir));
+            }
+        }
+    }
+
+    // Safari: プロファイルの区別なし
+    let safari_dir = caches_dir.join("com.apple.Safari");
+    if safari_dir.is_dir() {
+        dirs.push(("Safari".to_string(), "Default".to_string(), safari_dir));
+    }
+
+    dirs
+}
+
+/// ブラウザキャッシュを検索
+pub fn find_browser_caches() -> Result<Vec<BrowserCache>> {
+    let home = match env::var("HOME") {
+        Ok(home) => PathBuf::from(home),
+        Err(_) => return Ok(Vec::new()),
+    };
+
+    let mut caches = Vec::new();
+    for (browser, profile, cache_dir) in resolve_browser_cache_dirs(&home) {
+        let size = utils::calculate_dir_size(&cache_dir)?;
+        caches.push(BrowserCache {
+            browser,
+            profile,
+            cache_dir,
+            size,
+        });
+    }
+
+    Ok(caches)
+}
+
+/// ブラウザキャッシュクリーナー
+pub struct BrowserCacheCleaner;
+
+impl BrowserCacheCleaner {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for BrowserCacheCleaner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cleanable for BrowserCacheCleaner {
+    fn scan(&self) -> Result<Vec<CleanableItem>> {
+        Ok(find_browser_caches()?
+            .into_iter()
+            .map(|c| {
+                let metadata = CleanableMetadata {
+                    is_safe: Some(true),
+                    safety_label: Some(SAFETY_LABEL.to_string()),
+                };
+                CleanableItem::with_metadata(c.label(), c.cache_dir, c.size, metadata)
+            })
+            .collect())
+    }
+
+    fn name(&self) -> &str {
+        "Browser cache"
+    }
+
+    fn icon(&self) -> &str {
+        "🌐"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_firefox_profiles() {
+        let content = "\
+[Install4F96D1932A9F858E]
+Default=Profiles/abc.default-release
+
+[Profile1]
+Name=work
+IsRelative=0
+Path=/Volumes/Data/firefox-work
+
+[Profile0]
+Name=default-release
+IsRelative=1
+Path=Profiles/abc.default-release
+Default=1
+
+[General]
+StartWithLastProfile=1
+";
+        let profiles = parse_firefox_profiles(content);
+        assert_eq!(
+            profiles,
+            vec![
+                FirefoxProfile {
+                    name: "work".to_string(),
+                    path: "/Volumes/Data/firefox-work".to_string(),
+                    is_relative: false,
+                },
+                FirefoxProfile {
+                    name: "default-release".to_string(),
+                    path: "Profiles/abc.default-release".to_string(),
+                    is_relative: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_browser_cache_dirs() -> Result<()> {
+        let temp = TempDir::new()?;
+        let home = temp.path();
+        let caches = home.join("Library").join("Caches");
+
+        let chrome = caches.join("Google").join("Chrome");
+        fs::create_dir_all(chrome.join("Default").join("Cache"))?;
+        fs::create_dir_all(chrome.join("Profile 1").join("Cache"))?;
+        // プロファイル以外のディレクトリは無視
+        fs::create_dir_all(chrome.join("Crashpad").join("Cache"))?;
+
+        let firefox = home.join("Library").join("Application Support").join("Firefox");
+        fs::create_dir_all(&firefox)?;
+        fs::write(
+            firefox.join("profiles.ini"),
+            "[Profile0]\nName=default\nIsRelative=1\nPath=Profiles/xyz.default\n",
+        )?;
+        fs::create_dir_all(caches.join("Firefox").join("Profiles").join("xyz.default").join("cache2"))?;
+
+        let dirs = resolve_browser_cache_dirs(home);
+        let labels: Vec<(&str, &str)> = dirs
+            .iter()
+            .map(|(browser, profile, _)| (browser.as_str(), profile.as_str()))
+            .collect();
+
+        assert_eq!(
+            labels,
+            vec![("Chrome", "Default"), ("Chrome", "Profile 1"), ("Firefox", "default")]
+        );
+        assert!(dirs[2].2.ends_with("cache2"));
+
+        Ok(())
+    }
+}
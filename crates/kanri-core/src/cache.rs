@@ -1,8 +1,13 @@
+use std::cell::Cell;
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
 
 use crate::{
     cleanable::{Cleanable, CleanableItem, CleanableMetadata},
+    config::{CacheConfig, Config},
     utils, Result,
 };
 
@@ -17,6 +22,10 @@ pub struct CacheEntry {
     pub size: u64,
     /// 削除が安全かどうか
     pub is_safe: bool,
+    /// 配下で最も新しい更新日時
+    pub last_modified: Option<SystemTime>,
+    /// バンドル ID から解決したアプリ名（--by-app のスキャンでのみ設定）
+    pub app_name: Option<String>,
 }
 
 impl CacheEntry {
@@ -25,6 +34,19 @@ impl CacheEntry {
         utils::format_size(self.size)
     }
 
+    /// `now` から `days` 日以内に中身が更新されたか（使用中とみなす）
+    ///
+    /// `days` が 0 の場合は常に false
+    pub fn modified_within(&self, days: u64, now: SystemTime) -> bool {
+        if days == 0 {
+            return false;
+        }
+        let threshold = now
+            .checked_sub(Duration::from_secs(days * 24 * 60 * 60))
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        self.last_modified.is_some_and(|modified| modified >= threshold)
+    }
+
     /// 安全性ラベルを取得
     pub fn safety_label(&self) -> &str {
         if self.is_safe {
@@ -35,7 +57,7 @@ impl CacheEntry {
     }
 }
 
-/// 削除しても安全なことが知られているキャッシュのリスト
+/// 削除しても安全なことが知られているキャッシュのリスト（組み込みのデフォルト）
 const SAFE_CACHE_PATTERNS: &[&str] = &[
     "Homebrew",
     "pip",
@@ -56,78 +78,313 @@ const SAFE_CACHE_PATTERNS: &[&str] = &[
     "Xcode/DerivedData",
 ];
 
+/// 最近使われたキャッシュとして除外する日数のデフォルト
+///
+/// 使用中のアプリのキャッシュを消して再ログインなどが必要になるのを避ける
+pub const DEFAULT_KEEP_NEWER_THAN_DAYS: u64 = 1;
+
+/// キャッシュを (対象, `days` 日以内に更新されたため除外) に分ける
+pub fn split_recently_used(entries: Vec<CacheEntry>, days: u64) -> (Vec<CacheEntry>, Vec<CacheEntry>) {
+    let now = SystemTime::now();
+    entries
+        .into_iter()
+        .partition(|entry| !entry.modified_within(days, now))
+}
+
 /// キャッシュエントリが安全かどうかチェック
-fn is_safe_cache(name: &str) -> bool {
+///
+/// 優先順位: 設定の unsafe_patterns > 設定の safe_patterns > SAFE_CACHE_PATTERNS
+pub fn is_safe_cache(name: &str, config: Option<&CacheConfig>) -> bool {
+    if let Some(config) = config {
+        if config.unsafe_patterns.iter().any(|pattern| name.contains(pattern.as_str())) {
+            return false;
+        }
+        if config.safe_patterns.iter().any(|pattern| name.contains(pattern.as_str())) {
+            return true;
+        }
+    }
     SAFE_CACHE_PATTERNS.iter().any(|pattern| name.contains(pattern))
 }
 
+/// ユーザーの Library/Caches ディレクトリ
+fn user_cache_dir() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| "/Users".to_string());
+    PathBuf::from(home).join("Library/Caches")
+}
+
 /// ユーザーの Library/Caches ディレクトリをスキャン
 ///
 /// `min_size_gb`: 最小サイズ（GB単位）。これより小さいキャッシュは無視
-pub fn scan_user_caches(min_size_gb: u64) -> Result<Vec<CacheEntry>> {
-    let home = std::env::var("HOME").unwrap_or_else(|_| "/Users".to_string());
-    let cache_dir = PathBuf::from(home).join("Library/Caches");
+/// `config`: 安全性の判定に使う設定（`[cache]` セクション）
+pub fn scan_user_caches(min_size_gb: u64, config: &Config) -> Result<Vec<CacheEntry>> {
+    scan_cache_dir(&user_cache_dir(), min_size_gb * 1024 * 1024 * 1024, config)
+}
 
+fn scan_cache_dir(cache_dir: &Path, min_size_bytes: u64, config: &Config) -> Result<Vec<CacheEntry>> {
     if !cache_dir.exists() {
         return Ok(Vec::new());
     }
 
-    let min_size_bytes = min_size_gb * 1024 * 1024 * 1024;
     let mut entries = Vec::new();
 
-    for entry in fs::read_dir(&cache_dir)? {
+    for entry in fs::read_dir(cache_dir)? {
         let entry = entry?;
         let path = entry.path();
 
         if path.is_dir() {
-            // サイズを計算
-            let size = utils::calculate_dir_size(&path)?;
+            // サイズと、使用中か判定するための最終更新日時を計算
+            let (size, last_modified) = utils::calculate_dir_size_and_modified_dedup(&path)?;
 
             // 最小サイズ以上の場合のみ追加
             if size >= min_size_bytes {
                 let name = entry.file_name().to_string_lossy().to_string();
-                let is_safe = is_safe_cache(&name);
+                let is_safe = config.cache_safety(&name);
 
                 entries.push(CacheEntry {
                     name,
                     path,
                     size,
                     is_safe,
+                    last_modified,
+                    app_name: None,
                 });
             }
         }
     }
 
     // サイズの大きい順にソート
-    entries.sort_by(|a, b| b.size.cmp(&a.size));
+    entries.sort_by_key(|e| std::cmp::Reverse(e.size));
 
     Ok(entries)
 }
 
-/// キャッシュエントリを削除
-pub fn clean_cache(entry: &CacheEntry) -> Result<()> {
-    if entry.path.exists() {
-        fs::remove_dir_all(&entry.path)?;
+/// アプリを探すディレクトリ（ホームからの相対パスは ~/ 付き）
+const APP_DIRS: &[&str] = &["/Applications", "/Applications/Utilities", "/System/Applications", "~/Applications"];
+
+/// .app の Info.plist から (CFBundleIdentifier, アプリ名) を読む
+///
+/// アプリ名は CFBundleName、なければ .app を除いたファイル名
+fn read_bundle_info(app: &Path) -> Option<(String, String)> {
+    let info = plist::Value::from_file(app.join("Contents/Info.plist")).ok()?;
+    let info = info.as_dictionary()?;
+    let bundle_id = info.get("CFBundleIdentifier")?.as_string()?.to_string();
+    let name = info
+        .get("CFBundleName")
+        .and_then(|name| name.as_string())
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .or_else(|| app.file_stem().map(|stem| stem.to_string_lossy().to_string()))?;
+    Some((bundle_id, name))
+}
+
+/// ディレクトリ直下の .app からバンドル ID → アプリ名の対応を作る
+fn app_names_in(dirs: &[PathBuf]) -> HashMap<String, String> {
+    let mut names = HashMap::new();
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "app") {
+                if let Some((bundle_id, name)) = read_bundle_info(&path) {
+                    // 先に見つかったディレクトリ（/Applications）を優先する
+                    names.entry(bundle_id).or_insert(name);
+                }
+            }
+        }
     }
-    Ok(())
+    names
 }
 
-/// 複数のキャッシュエントリを削除
-pub fn clean_caches(entries: &[CacheEntry]) -> Result<Vec<String>> {
-    let mut cleaned = Vec::new();
+/// バンドル ID からアプリ名を引く
+///
+/// 完全一致しなければ末尾の要素を 1 つずつ外して探す（com.company.App.ShipIt → com.company.App）
+fn lookup_app_name(names: &HashMap<String, String>, bundle_id: &str) -> Option<String> {
+    let mut id = bundle_id;
+    loop {
+        if let Some(name) = names.get(id) {
+            return Some(name.clone());
+        }
+        // com.company のような 2 要素より短くはしない（別アプリへの誤った帰属を避ける）
+        let (parent, _) = id.rsplit_once('.')?;
+        if !parent.contains('.') {
+            return None;
+        }
+        id = parent;
+    }
+}
 
+/// バンドル ID（例: com.company.App）をインストール済みアプリの名前に解決
+///
+/// /Applications などの .app の Info.plist から CFBundleName を読む。見つからなければ None
+pub fn resolve_app_name(bundle_id: &str) -> Option<String> {
+    static APP_NAMES: OnceLock<HashMap<String, String>> = OnceLock::new();
+    let names = APP_NAMES.get_or_init(|| {
+        let home = std::env::var("HOME").unwrap_or_else(|_| "/Users".to_string());
+        let dirs: Vec<PathBuf> = APP_DIRS
+            .iter()
+            .map(|dir| match dir.strip_prefix("~/") {
+                Some(rest) => Path::new(&home).join(rest),
+                None => PathBuf::from(dir),
+            })
+            .collect();
+        app_names_in(&dirs)
+    });
+    lookup_app_name(names, bundle_id)
+}
+
+/// アプリごとにまとめたキャッシュ
+#[derive(Debug, Clone)]
+pub struct AppCacheGroup {
+    /// アプリ名（解決できなければキャッシュディレクトリ名）
+    pub app_name: String,
+    /// このアプリのキャッシュ（サイズの大きい順）
+    pub entries: Vec<CacheEntry>,
+}
+
+impl AppCacheGroup {
+    /// 合計サイズ（バイト）
+    pub fn size(&self) -> u64 {
+        self.entries.iter().map(|e| e.size).sum()
+    }
+
+    /// すべてのキャッシュが削除して安全か
+    pub fn is_safe(&self) -> bool {
+        self.entries.iter().all(|e| e.is_safe)
+    }
+
+    /// このアプリのキャッシュのパスとサイズ（サイズの大きい順）
+    ///
+    /// 複数のキャッシュの親ディレクトリは Library/Caches 自体になることがあるため、代表のパスは作らない
+    pub fn paths(&self) -> Vec<(&Path, u64)> {
+        self.entries.iter().map(|e| (e.path.as_path(), e.size)).collect()
+    }
+}
+
+/// キャッシュをアプリ名でまとめる（合計サイズの大きい順）
+///
+/// アプリ名が解決できなかったキャッシュは自身の名前でまとめる
+pub fn group_by_app(entries: Vec<CacheEntry>) -> Vec<AppCacheGroup> {
+    let mut groups: Vec<AppCacheGroup> = Vec::new();
     for entry in entries {
-        clean_cache(entry)?;
-        cleaned.push(entry.name.clone());
+        let app_name = entry.app_name.clone().unwrap_or_else(|| entry.name.clone());
+        match groups.iter_mut().find(|g| g.app_name == app_name) {
+            Some(group) => group.entries.push(entry),
+            None => groups.push(AppCacheGroup { app_name, entries: vec![entry] }),
+        }
     }
+    for group in &mut groups {
+        group.entries.sort_by_key(|e| std::cmp::Reverse(e.size));
+    }
+    groups.sort_by_key(|g| std::cmp::Reverse(g.size()));
+    groups
+}
 
-    Ok(cleaned)
+/// ユーザーの Library/Caches ディレクトリをアプリ単位でスキャン
+///
+/// 直下のディレクトリ名がアプリに解決できず、その中にアプリに解決できるサブディレクトリがある場合
+/// （共有ディレクトリの中に各アプリが書き込んでいる場合）は、サブディレクトリを個別のキャッシュとして扱う。
+/// `min_size_gb` はアプリごとの合計サイズに対して適用する
+pub fn scan_user_caches_by_app(min_size_gb: u64, config: &Config) -> Result<Vec<CacheEntry>> {
+    scan_cache_dir_by_app(&user_cache_dir(), min_size_gb * 1024 * 1024 * 1024, config, resolve_app_name)
+}
+
+fn scan_cache_dir_by_app(
+    cache_dir: &Path,
+    min_size_bytes: u64,
+    config: &Config,
+    resolve: impl Fn(&str) -> Option<String>,
+) -> Result<Vec<CacheEntry>> {
+    if !cache_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    // (表示名, パス, アプリ名)
+    let mut targets: Vec<(String, PathBuf, Option<String>)> = Vec::new();
+    for entry in fs::read_dir(cache_dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        if let Some(app_name) = resolve(&name) {
+            targets.push((name, path, Some(app_name)));
+            continue;
+        }
+
+        // 読めないディレクトリ（macOS のプライバシー保護の対象など）は中を調べずに 1 件として扱う
+        let Ok(read_dir) = fs::read_dir(&path) else {
+            targets.push((name, path, None));
+            continue;
+        };
+        let mut children: Vec<(String, PathBuf, Option<String>)> = read_dir
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_dir())
+            .map(|e| {
+                let child = e.file_name().to_string_lossy().to_string();
+                let app_name = resolve(&child);
+                (format!("{}/{}", name, child), e.path(), app_name)
+            })
+            .collect();
+        if children.iter().any(|(_, _, app_name)| app_name.is_some()) {
+            // 解決できなかったサブディレクトリは親ディレクトリの名前でまとめる
+            for (_, _, app_name) in &mut children {
+                app_name.get_or_insert_with(|| name.clone());
+            }
+            targets.append(&mut children);
+        } else {
+            targets.push((name, path, None));
+        }
+    }
+
+    let mut entries = Vec::new();
+    for (name, path, app_name) in targets {
+        let (size, last_modified) = utils::calculate_dir_size_and_modified_dedup(&path)?;
+        let is_safe = config.cache_safety(&name);
+        entries.push(CacheEntry {
+            name,
+            path,
+            size,
+            is_safe,
+            last_modified,
+            app_name,
+        });
+    }
+
+    // 最小サイズはアプリごとの合計で判定する
+    let mut entries: Vec<CacheEntry> = group_by_app(entries)
+        .into_iter()
+        .filter(|group| group.size() >= min_size_bytes)
+        .flat_map(|group| group.entries)
+        .collect();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.size));
+
+    Ok(entries)
+}
+
+/// キャッシュエントリを削除
+pub fn clean_cache(entry: &CacheEntry) -> Result<()> {
+    if entry.path.exists() {
+        fs::remove_dir_all(&entry.path)?;
+    }
+    Ok(())
 }
 
 /// Mac キャッシュクリーナー
 pub struct CacheCleaner {
     pub min_size_gb: u64,
     pub safe_only: bool,
+    pub config: Config,
+    /// この日数以内に更新されたキャッシュは使用中とみなして除外する（0 で除外しない）
+    pub keep_newer_than_days: u64,
+    /// アプリごとにまとめた項目にする（--by-app）
+    pub by_app: bool,
+    /// スキャンするキャッシュディレクトリ（デフォルトは ~/Library/Caches）
+    pub cache_dir: PathBuf,
+    /// 直前の scan で使用中とみなして除外した件数と合計サイズ
+    recently_used: Cell<(usize, u64)>,
 }
 
 impl CacheCleaner {
@@ -135,30 +392,108 @@ impl CacheCleaner {
         Self {
             min_size_gb,
             safe_only,
+            config: Config::default(),
+            keep_newer_than_days: DEFAULT_KEEP_NEWER_THAN_DAYS,
+            by_app: false,
+            cache_dir: user_cache_dir(),
+            recently_used: Cell::new((0, 0)),
         }
     }
+
+    /// 指定した日数以内に更新されたキャッシュを除外する（0 で除外しない）
+    pub fn with_keep_newer_than(mut self, days: u64) -> Self {
+        self.keep_newer_than_days = days;
+        self
+    }
+
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// キャッシュをアプリごとにまとめる
+    pub fn with_by_app(mut self, by_app: bool) -> Self {
+        self.by_app = by_app;
+        self
+    }
+
+    /// スキャンするキャッシュディレクトリを設定
+    pub fn with_cache_dir(mut self, cache_dir: PathBuf) -> Self {
+        self.cache_dir = cache_dir;
+        self
+    }
+}
+
+/// キャッシュの安全性から項目のメタデータを作成
+fn cache_metadata(is_safe: bool) -> CleanableMetadata {
+    if is_safe {
+        CleanableMetadata::safe()
+    } else {
+        CleanableMetadata::needs_review()
+    }
 }
 
 impl Cleanable for CacheCleaner {
     fn scan(&self) -> Result<Vec<CleanableItem>> {
-        let mut caches = scan_user_caches(self.min_size_gb)?;
+        let min_size_bytes = self.min_size_gb * 1024 * 1024 * 1024;
+        let scanned = if self.by_app {
+            scan_cache_dir_by_app(&self.cache_dir, min_size_bytes, &self.config, resolve_app_name)?
+        } else {
+            scan_cache_dir(&self.cache_dir, min_size_bytes, &self.config)?
+        };
+        let (mut caches, recent) = split_recently_used(scanned, self.keep_newer_than_days);
+        self.recently_used
+            .set((recent.len(), recent.iter().map(|c| c.size).sum()));
 
         if self.safe_only {
             caches.retain(|c| c.is_safe);
         }
 
+        // --by-app: アプリ単位で選んで削除できるよう、アプリのキャッシュを 1 項目にまとめる
+        if self.by_app {
+            return Ok(group_by_app(caches)
+                .into_iter()
+                .map(|group| {
+                    let names: Vec<&str> = group.entries.iter().map(|c| c.name.as_str()).collect();
+                    let detail = format!("{} 件: {}", names.len(), names.join(", "));
+                    let entries = group.entries.iter().map(|c| (c.path.clone(), c.size)).collect();
+                    CleanableItem::with_metadata(
+                        group.app_name.clone(),
+                        group.entries[0].path.clone(),
+                        group.size(),
+                        cache_metadata(group.is_safe()),
+                    )
+                    .with_entries(entries)
+                    .with_detail(Some(detail))
+                })
+                .collect());
+        }
+
         Ok(caches
             .into_iter()
             .map(|c| {
-                let metadata = CleanableMetadata {
-                    is_safe: Some(c.is_safe),
-                    safety_label: Some(c.safety_label().to_string()),
-                };
-                CleanableItem::with_metadata(c.name, c.path, c.size, metadata)
+                let detail = c
+                    .last_modified
+                    .map(|t| format!("最終更新: {}", utils::format_age(t)));
+                CleanableItem::with_metadata(c.name, c.path, c.size, cache_metadata(c.is_safe))
+                    .with_detail(detail)
             })
             .collect())
     }
 
+    fn scan_notes(&self) -> Vec<String> {
+        let (count, size) = self.recently_used.get();
+        if count == 0 {
+            return Vec::new();
+        }
+        vec![format!(
+            "ℹ {} 日以内に更新された {} 件 ({}) は使用中とみなして除外しました（含めるには --keep-newer-than 0）",
+            self.keep_newer_than_days,
+            count,
+            utils::format_size(size)
+        )]
+    }
+
     fn name(&self) -> &str {
         "Mac Cache"
     }
@@ -174,17 +509,183 @@ mod tests {
 
     #[test]
     fn test_is_safe_cache() {
-        assert!(is_safe_cache("Homebrew"));
-        assert!(is_safe_cache("com.spotify.client"));
-        assert!(is_safe_cache("Google/Chrome"));
-        assert!(!is_safe_cache("com.apple.Safari"));
-        assert!(!is_safe_cache("some.random.app"));
+        assert!(is_safe_cache("Homebrew", None));
+        assert!(is_safe_cache("com.spotify.client", None));
+        assert!(is_safe_cache("Google/Chrome", None));
+        assert!(!is_safe_cache("com.apple.Safari", None));
+        assert!(!is_safe_cache("some.random.app", None));
+    }
+
+    #[test]
+    fn test_split_recently_used() {
+        let now = SystemTime::now();
+        let entry = |name: &str, age_hours: Option<u64>| CacheEntry {
+            name: name.to_string(),
+            path: PathBuf::from(name),
+            size: 1,
+            is_safe: true,
+            last_modified: age_hours.map(|h| now - Duration::from_secs(h * 60 * 60)),
+            app_name: None,
+        };
+
+        assert!(entry("fresh", Some(2)).modified_within(1, now));
+        assert!(!entry("stale", Some(48)).modified_within(1, now));
+        assert!(!entry("unknown", None).modified_within(1, now));
+        // 0 日なら除外しない
+        assert!(!entry("fresh", Some(0)).modified_within(0, now));
+
+        let entries = vec![entry("fresh", Some(2)), entry("stale", Some(48)), entry("unknown", None)];
+        let (targets, kept) = split_recently_used(entries.clone(), 1);
+        let names = |entries: &[CacheEntry]| entries.iter().map(|e| e.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&targets), vec!["stale", "unknown"]);
+        assert_eq!(names(&kept), vec!["fresh"]);
+        assert_eq!(split_recently_used(entries, 0).0.len(), 3);
     }
 
     #[test]
     fn test_scan_user_caches() {
         // このテストは環境依存なので、エラーが出ないことだけ確認
-        let result = scan_user_caches(1);
+        let result = scan_user_caches(1, &Config::default());
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_read_bundle_info() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let write_app = |file: &str, plist: &str| {
+            let contents = temp.path().join(file).join("Contents");
+            fs::create_dir_all(&contents).unwrap();
+            fs::write(contents.join("Info.plist"), plist).unwrap();
+        };
+        write_app(
+            "Example.app",
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+    <key>CFBundleIdentifier</key>
+    <string>com.example.App</string>
+    <key>CFBundleName</key>
+    <string>Example</string>
+</dict>
+</plist>"#,
+        );
+        // CFBundleName がなければファイル名を使う
+        write_app(
+            "No Name.app",
+            r#"<plist version="1.0"><dict><key>CFBundleIdentifier</key><string>com.example.NoName</string></dict></plist>"#,
+        );
+
+        let names = app_names_in(&[temp.path().to_path_buf(), temp.path().join("missing")]);
+        assert_eq!(names.len(), 2);
+        assert_eq!(lookup_app_name(&names, "com.example.App"), Some("Example".to_string()));
+        assert_eq!(lookup_app_name(&names, "com.example.NoName"), Some("No Name".to_string()));
+        // 補助プロセスのバンドル ID は親アプリに帰属させる
+        assert_eq!(lookup_app_name(&names, "com.example.App.ShipIt"), Some("Example".to_string()));
+        assert_eq!(lookup_app_name(&names, "com.example"), None);
+        assert_eq!(lookup_app_name(&names, "Homebrew"), None);
+    }
+
+    #[test]
+    fn test_scan_cache_dir_by_app() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let write = |dir: &str, size: usize| {
+            let dir = temp.path().join(dir);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("data"), vec![0u8; size]).unwrap();
+        };
+        write("com.example.App", 100);
+        write("com.example.App.ShipIt", 50);
+        write("Shared/com.example.Other", 30);
+        write("Shared/misc", 10);
+        write("Homebrew", 70);
+        write("tiny", 1);
+
+        let resolve = |id: &str| match id {
+            "com.example.App" | "com.example.App.ShipIt" => Some("Example".to_string()),
+            "com.example.Other" => Some("Other".to_string()),
+            _ => None,
+        };
+        let entries = scan_cache_dir_by_app(temp.path(), 5, &Config::default(), resolve)?;
+        let groups = group_by_app(entries);
+        let summary: Vec<(&str, u64, usize)> = groups
+            .iter()
+            .map(|g| (g.app_name.as_str(), g.size(), g.entries.len()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("Example", 150, 2), ("Homebrew", 70, 1), ("Other", 30, 1), ("Shared", 10, 1)]
+        );
+        // 共有ディレクトリの中のキャッシュは個別に削除できる
+        assert_eq!(groups[2].entries[0].name, "Shared/com.example.Other");
+        assert_eq!(
+            groups[2].paths(),
+            vec![(temp.path().join("Shared/com.example.Other").as_path(), 30)]
+        );
+        // 複数のキャッシュをまとめたアプリは、Library/Caches 自体ではなく各キャッシュのパスを示す
+        assert_eq!(
+            groups[0].paths(),
+            vec![
+                (temp.path().join("com.example.App").as_path(), 100),
+                (temp.path().join("com.example.App.ShipIt").as_path(), 50),
+            ]
+        );
+        assert!(groups[1].is_safe());
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_cache_dir_by_app_unreadable() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempfile::TempDir::new()?;
+        let locked = temp.path().join("com.apple.locked");
+        fs::create_dir_all(&locked)?;
+        fs::write(temp.path().join("placeholder"), "")?;
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000))?;
+
+        // 読めないディレクトリがあってもスキャン全体は失敗せず、1 件として扱う
+        let result = scan_cache_dir_by_app(temp.path(), 0, &Config::default(), |_| None);
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755))?;
+        let names: Vec<String> = result?.into_iter().map(|e| e.name).collect();
+        assert_eq!(names, vec!["com.apple.locked".to_string()]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cache_cleaner_clean() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        for (name, size) in [("Homebrew", 300), ("com.example.App", 100)] {
+            let dir = temp.path().join(name);
+            fs::create_dir_all(&dir)?;
+            fs::write(dir.join("data"), vec![0u8; size])?;
+        }
+        // 作ったばかりのキャッシュも対象にする
+        let cleaner = |safe_only: bool| {
+            CacheCleaner::new(0, safe_only)
+                .with_keep_newer_than(0)
+                .with_cache_dir(temp.path().to_path_buf())
+        };
+
+        let items = cleaner(false).scan()?;
+        assert_eq!(items.iter().map(|i| i.size).sum::<u64>(), 400);
+        let items = cleaner(false).with_by_app(true).scan()?;
+        assert_eq!(items.iter().map(|i| i.size).sum::<u64>(), 400);
+
+        // 安全なキャッシュだけを削除する
+        let cleaner = cleaner(true);
+        let items = cleaner.scan()?;
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].path, temp.path().join("Homebrew"));
+        let mut reported = 0;
+        let cleaned = cleaner.clean(&items, &mut |item| reported += item.size)?;
+        assert_eq!(cleaned.len(), 1);
+        assert_eq!(reported, 300);
+        assert!(!temp.path().join("Homebrew").exists());
+        assert!(temp.path().join("com.example.App").exists());
+
+        Ok(())
+    }
 }
@@ -1,8 +1,8 @@
-use crate::Result;
-use std::path::PathBuf;
+use crate::{utils::DirSource, Result};
+use std::path::{Path, PathBuf};
 
 /// クリーンアップ可能な項目のメタデータ
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct CleanableMetadata {
     /// 安全性フラグ（キャッシュクリーナーなどで使用）
     pub is_safe: Option<bool>,
@@ -10,13 +10,30 @@ pub struct CleanableMetadata {
     pub safety_label: Option<String>,
 }
 
-impl Default for CleanableMetadata {
-    fn default() -> Self {
+impl CleanableMetadata {
+    /// 削除しても安全な項目のメタデータ
+    pub fn safe() -> Self {
         Self {
-            is_safe: None,
-            safety_label: None,
+            is_safe: Some(true),
+            safety_label: Some("✓ 安全".to_string()),
         }
     }
+
+    /// 削除前に確認が必要な項目のメタデータ
+    pub fn needs_review() -> Self {
+        Self {
+            is_safe: Some(false),
+            safety_label: Some("⚠ 要確認".to_string()),
+        }
+    }
+
+    /// パスがクラウド同期フォルダ内にある場合の警告メタデータ
+    pub fn sync_folder(path: &Path) -> Option<Self> {
+        crate::utils::is_in_sync_folder(path).map(|service| Self {
+            is_safe: Some(false),
+            safety_label: Some(format!("⚠ {} の同期フォルダ内（削除すると再同期が発生）", service)),
+        })
+    }
 }
 
 /// クリーンアップ可能な項目を表すtrait
@@ -29,6 +46,35 @@ pub trait Cleanable: Sized {
 
     /// アイコン（例: "🦀", "📦", "💾"）
     fn icon(&self) -> &str;
+
+    /// scan で見つけた項目を削除し、削除した項目の名前を返す
+    ///
+    /// `on_cleaned` は項目を 1 件削除するたびに呼ばれる（プログレスバーの更新用）。
+    /// ディレクトリの削除以外の方法（外部コマンドなど）が必要なクリーナーは上書きする
+    fn clean(
+        &self,
+        items: &[CleanableItem],
+        on_cleaned: &mut dyn FnMut(&CleanableItem),
+    ) -> Result<Vec<String>> {
+        clean_items(items, on_cleaned)
+    }
+
+    /// 種類ごとの内訳（種類, 件数, 合計サイズ）。複数の種類を扱うクリーナーは上書きする
+    fn breakdown(&self, _items: &[CleanableItem]) -> Vec<(String, usize, u64)> {
+        Vec::new()
+    }
+
+    /// scan の後に表示する注記（例: 対象から除外した項目）
+    fn scan_notes(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// 環境変数などから決まる対象ディレクトリと、その決まり方
+    ///
+    /// 環境変数の設定ミスで想定外のディレクトリを削除しないよう、削除前に表示する
+    fn resolved_dir(&self) -> Option<(PathBuf, DirSource)> {
+        None
+    }
 }
 
 /// クリーンアップ可能な個別項目
@@ -42,32 +88,61 @@ pub struct CleanableItem {
     pub size: u64,
     /// メタデータ
     pub metadata: CleanableMetadata,
+    /// 一覧に添える補足（例: ワークスペースのメンバー数）
+    pub detail: Option<String>,
+    /// 複数のパスをまとめた項目（例: アプリごとのキャッシュ）の各パスとサイズ。空なら `path` のみ
+    pub entries: Vec<(PathBuf, u64)>,
 }
 
 impl CleanableItem {
     /// 新しい CleanableItem を作成
+    ///
+    /// クラウド同期フォルダ内のパスは要確認として扱う
     pub fn new(name: String, path: PathBuf, size: u64) -> Self {
-        Self {
-            name,
-            path,
-            size,
-            metadata: CleanableMetadata::default(),
-        }
+        Self::with_metadata(name, path, size, CleanableMetadata::default())
     }
 
     /// メタデータ付きで新しい CleanableItem を作成
+    ///
+    /// クラウド同期フォルダ内のパスは、同期フォルダの警告がメタデータより優先される
     pub fn with_metadata(
         name: String,
         path: PathBuf,
         size: u64,
         metadata: CleanableMetadata,
     ) -> Self {
+        let metadata = CleanableMetadata::sync_folder(&path).unwrap_or(metadata);
         Self {
             name,
             path,
             size,
             metadata,
+            detail: None,
+            entries: Vec::new(),
+        }
+    }
+
+    /// 一覧に添える補足を設定
+    pub fn with_detail(mut self, detail: Option<String>) -> Self {
+        self.detail = detail;
+        self
+    }
+
+    /// 複数のパスをまとめた項目にする（`path` は最初のパスにする）
+    pub fn with_entries(mut self, entries: Vec<(PathBuf, u64)>) -> Self {
+        if let Some((path, _)) = entries.first() {
+            self.path = path.clone();
         }
+        self.entries = entries;
+        self
+    }
+
+    /// 削除するパスとそのサイズ
+    pub fn paths(&self) -> Vec<(&Path, u64)> {
+        if self.entries.is_empty() {
+            return vec![(self.path.as_path(), self.size)];
+        }
+        self.entries.iter().map(|(path, size)| (path.as_path(), *size)).collect()
     }
 
     /// サイズを人間が読みやすい形式で取得
@@ -86,15 +161,87 @@ impl CleanableItem {
     }
 }
 
-/// 複数のアイテムをまとめて削除
-pub fn clean_items(items: &[CleanableItem]) -> Result<Vec<String>> {
+/// 一覧の並び順
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortKey {
+    /// サイズの大きい順
+    #[default]
+    Size,
+    /// 名前順
+    Name,
+    /// パス順
+    Path,
+}
+
+/// アイテムを指定したキーで並べ替え
+pub fn sort_items(items: &mut [CleanableItem], key: SortKey) {
+    match key {
+        SortKey::Size => items.sort_by_key(|item| std::cmp::Reverse(item.size)),
+        SortKey::Name => items.sort_by(|a, b| a.name.cmp(&b.name)),
+        SortKey::Path => items.sort_by(|a, b| a.path.cmp(&b.path)),
+    }
+}
+
+/// サイズの大きい上位 N 件だけを残し、それ以外を返す
+pub fn split_top(items: &mut Vec<CleanableItem>, n: usize) -> Vec<CleanableItem> {
+    sort_items(items, SortKey::Size);
+    items.split_off(n.min(items.len()))
+}
+
+/// 安全なアイテムだけを残し、要確認のアイテムを返す
+pub fn split_unsafe(items: &mut Vec<CleanableItem>) -> Vec<CleanableItem> {
+    let (safe, unsafe_items): (Vec<_>, Vec<_>) = items.drain(..).partition(|item| item.is_safe());
+    *items = safe;
+    unsafe_items
+}
+
+/// 複数のアイテムをまとめて削除し、1 件削除するたびに `on_cleaned` を呼ぶ
+///
+/// Ctrl-C で中断が要求された場合は、削除中の項目を削除し終えた時点で止まる
+pub fn clean_items(
+    items: &[CleanableItem],
+    on_cleaned: impl FnMut(&CleanableItem),
+) -> Result<Vec<String>> {
+    // システムのディレクトリなどが含まれていれば、1 件も削除せずに止める
+    for (path, _) in items.iter().flat_map(|item| item.paths()) {
+        crate::utils::assert_safe_target(path)?;
+    }
+    clean_items_until(items, crate::utils::is_cancel_requested, on_cleaned)
+}
+
+/// `cancelled` が true を返すまで項目を順に削除
+fn clean_items_until(
+    items: &[CleanableItem],
+    cancelled: impl Fn() -> bool,
+    mut on_cleaned: impl FnMut(&CleanableItem),
+) -> Result<Vec<String>> {
     let mut cleaned = Vec::new();
 
     for item in items {
-        if item.path.exists() {
-            std::fs::remove_dir_all(&item.path)?;
-            cleaned.push(item.name.clone());
+        if cancelled() {
+            break;
+        }
+        let mut removed = false;
+        for (path, _) in item.paths() {
+            // 保護されたパス（--protect・[safety] protected_paths）は何が見つけても削除しない
+            if crate::utils::is_protected_path(path) {
+                continue;
+            }
+            if path.is_dir() {
+                std::fs::remove_dir_all(path)?;
+            } else if path.exists() {
+                // large-files などはファイル単体も削除対象になる
+                std::fs::remove_file(path)?;
+            } else {
+                continue;
+            }
+            removed = true;
+        }
+        if !removed {
+            continue;
         }
+        cleaned.push(item.name.clone());
+        on_cleaned(item);
     }
 
     Ok(cleaned)
@@ -134,4 +281,129 @@ mod tests {
         assert!(!item.is_safe());
         assert_eq!(item.safety_label(), Some("⚠ 要確認"));
     }
+
+    #[test]
+    fn test_sort_items() {
+        let mut items = vec![
+            CleanableItem::new("b".to_string(), PathBuf::from("/tmp/a"), 10),
+            CleanableItem::new("a".to_string(), PathBuf::from("/tmp/c"), 30),
+            CleanableItem::new("c".to_string(), PathBuf::from("/tmp/b"), 20),
+        ];
+
+        sort_items(&mut items, SortKey::Size);
+        assert_eq!(items.iter().map(|i| i.size).collect::<Vec<_>>(), vec![30, 20, 10]);
+
+        sort_items(&mut items, SortKey::Name);
+        assert_eq!(items[0].name, "a");
+
+        sort_items(&mut items, SortKey::Path);
+        assert_eq!(items[0].path, PathBuf::from("/tmp/a"));
+    }
+
+    #[test]
+    fn test_split_top() {
+        let mut items = vec![
+            CleanableItem::new("small".to_string(), PathBuf::from("/tmp/s"), 10),
+            CleanableItem::new("large".to_string(), PathBuf::from("/tmp/l"), 30),
+            CleanableItem::new("medium".to_string(), PathBuf::from("/tmp/m"), 20),
+        ];
+
+        let rest = split_top(&mut items, 2);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].name, "large");
+        assert_eq!(items[1].name, "medium");
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].name, "small");
+
+        let rest = split_top(&mut items, 5);
+        assert!(rest.is_empty());
+        assert_eq!(items.len(), 2);
+    }
+
+    #[test]
+    fn test_split_unsafe() {
+        let mut items = vec![
+            CleanableItem::new("default".to_string(), PathBuf::from("/tmp/d"), 10),
+            CleanableItem::with_metadata(
+                "shared".to_string(),
+                PathBuf::from("/tmp/s"),
+                20,
+                CleanableMetadata::needs_review(),
+            ),
+            CleanableItem::with_metadata(
+                "cache".to_string(),
+                PathBuf::from("/tmp/c"),
+                30,
+                CleanableMetadata::safe(),
+            ),
+        ];
+
+        let unsafe_items = split_unsafe(&mut items);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].name, "default");
+        assert_eq!(items[1].name, "cache");
+        assert_eq!(unsafe_items.len(), 1);
+        assert_eq!(unsafe_items[0].name, "shared");
+        assert_eq!(unsafe_items[0].safety_label(), Some("⚠ 要確認"));
+    }
+
+    #[test]
+    fn test_clean_items_until_cancelled() -> Result<()> {
+        use std::cell::Cell;
+
+        let temp = tempfile::TempDir::new()?;
+        let items: Vec<CleanableItem> = ["first", "second", "third"]
+            .iter()
+            .map(|name| {
+                let path = temp.path().join(name);
+                std::fs::create_dir(&path).unwrap();
+                CleanableItem::new(name.to_string(), path, 0)
+            })
+            .collect();
+
+        // 2 件目を削除した後に中断が要求された場合、3 件目は残す
+        let checks = Cell::new(0);
+        let mut reported = Vec::new();
+        let cleaned = clean_items_until(
+            &items,
+            || {
+                checks.set(checks.get() + 1);
+                checks.get() > 2
+            },
+            |item| reported.push(item.name.clone()),
+        )?;
+        assert_eq!(cleaned, vec!["first", "second"]);
+        assert_eq!(reported, cleaned);
+        assert!(!items[1].path.exists());
+        assert!(items[2].path.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_clean_items_with_entries() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let entries: Vec<(PathBuf, u64)> = ["com.example.App", "com.example.App.ShipIt"]
+            .iter()
+            .map(|name| {
+                let path = temp.path().join(name);
+                std::fs::create_dir(&path).unwrap();
+                (path, 10)
+            })
+            .collect();
+        let other = temp.path().join("other");
+        std::fs::create_dir(&other)?;
+
+        // まとめた項目はすべてのパスを削除し、1 件として数える
+        let item = CleanableItem::new("Example".to_string(), temp.path().to_path_buf(), 20)
+            .with_entries(entries.clone());
+        assert_eq!(item.path, entries[0].0);
+        assert_eq!(item.paths().len(), 2);
+        let cleaned = clean_items(&[item], |_| {})?;
+        assert_eq!(cleaned, vec!["Example"]);
+        assert!(entries.iter().all(|(path, _)| !path.exists()));
+        assert!(other.exists());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,268 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    cleanable::{Cleanable, CleanableItem},
+    utils, Result,
+};
+
+/// デフォルトのビルドディレクトリパターン
+const DEFAULT_BUILD_DIR_PATTERNS: &[&str] = &["build*", "cmake-build-*"];
+
+/// CMake ビルド成果物情報
+#[derive(Debug, Clone)]
+pub struct CMakeBuild {
+    /// プロジェクトのルートディレクトリ（CMakeLists.txt があるディレクトリ）
+    pub root: PathBuf,
+    /// ビルドディレクトリのパス
+    pub build_dir: PathBuf,
+    /// サイズ（バイト）
+    pub size: u64,
+    /// マッチしたパターン（build*, cmake-build-*, CMakeCache.txt など）
+    pub build_type: String,
+}
+
+/// `*` ワイルドカードのみをサポートする簡易パターンマッチ
+pub(crate) fn matches_pattern(name: &str, pattern: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return name == pattern;
+    }
+
+    let first = parts[0];
+    let last = parts[parts.len() - 1];
+    if !name.starts_with(first) || name.len() < first.len() + last.len() {
+        return false;
+    }
+
+    let mut rest = &name[first.len()..name.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+
+    name.ends_with(last)
+}
+
+/// CMake のビルドディレクトリか確認（CMakeCache.txt または CMakeFiles の存在）
+fn is_cmake_build_dir(path: &Path) -> bool {
+    path.join("CMakeCache.txt").is_file() || path.join("CMakeFiles").is_dir()
+}
+
+/// ディレクトリ名がどのパターンにマッチするかを返す
+fn match_build_type(path: &Path, name: &str, patterns: &[String]) -> Option<String> {
+    if !is_cmake_build_dir(path) {
+        return None;
+    }
+
+    Some(
+        patterns
+            .iter()
+            .find(|p| matches_pattern(name, p))
+            .cloned()
+            .unwrap_or_else(|| "CMakeCache.txt".to_string()),
+    )
+}
+
+/// 指定されたディレクトリ以下の CMake ビルド成果物を検索
+///
+/// `extra_patterns`: 追加のビルドディレクトリパターン。`{name}` はソースディレクトリ名に置換され、
+/// プロジェクト直下に加えて兄弟ディレクトリ（out-of-source ビルド）も検索する
+pub fn find_cmake_builds(search_path: &Path, extra_patterns: &[String]) -> Result<Vec<CMakeBuild>> {
+    let mut builds: Vec<CMakeBuild> = Vec::new();
+
+    let entries = utils::walk_entries(utils::walk_dir(search_path), |_| false, |e| {
+        let file_name = e.file_name().to_string_lossy();
+        if matches!(
+            file_name.as_ref(),
+            "target" | ".git" | "node_modules" | ".cache" | "CMakeFiles"
+        ) {
+            return false;
+        }
+        // ビルドディレクトリ自体には降りない
+        !e.file_type().is_dir() || !is_cmake_build_dir(e.path())
+    });
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        if !(entry.file_type().is_file() && entry.file_name() == "CMakeLists.txt") {
+            continue;
+        }
+
+        let Some(project_root) = entry.path().parent() else {
+            continue;
+        };
+        let source_name = project_root
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let extra: Vec<String> = extra_patterns
+            .iter()
+            .map(|p| p.replace("{name}", &source_name))
+            .collect();
+        let patterns: Vec<String> = DEFAULT_BUILD_DIR_PATTERNS
+            .iter()
+            .map(|p| p.to_string())
+            .chain(extra.iter().cloned())
+            .collect();
+
+        // プロジェクト直下のビルドディレクトリ
+        let mut candidates: Vec<(PathBuf, Option<String>)> = Vec::new();
+        if let Ok(read_dir) = fs::read_dir(project_root) {
+            for child in read_dir.filter_map(|e| e.ok()) {
+                let path = child.path();
+                if path.is_dir() {
+                    let name = child.file_name().to_string_lossy().to_string();
+                    candidates.push((path.clone(), match_build_type(&path, &name, &patterns)));
+                }
+            }
+        }
+
+        // 兄弟ディレクトリ（out-of-source ビルド）は追加パターンのみ対象
+        if !extra.is_empty() {
+            if let Some(parent) = project_root.parent() {
+                if let Ok(read_dir) = fs::read_dir(parent) {
+                    for sibling in read_dir.filter_map(|e| e.ok()) {
+                        let path = sibling.path();
+                        let name = sibling.file_name().to_string_lossy().to_string();
+                        if path.is_dir()
+                            && path != project_root
+                            && extra.iter().any(|p| matches_pattern(&name, p))
+                        {
+                            candidates.push((path.clone(), match_build_type(&path, &name, &extra)));
+                        }
+                    }
+                }
+            }
+        }
+
+        for (build_dir, build_type) in candidates {
+            let Some(build_type) = build_type else {
+                continue;
+            };
+            if builds.iter().any(|b| b.build_dir == build_dir) {
+                continue;
+            }
+
+            let size = utils::calculate_dir_size(&build_dir)?;
+            builds.push(CMakeBuild {
+                root: project_root.to_path_buf(),
+                build_dir,
+                size,
+                build_type,
+            });
+        }
+    }
+
+    Ok(builds)
+}
+
+/// CMake ビルド成果物を削除
+pub fn clean_build(build: &CMakeBuild) -> Result<()> {
+    if build.build_dir.exists() {
+        fs::remove_dir_all(&build.build_dir)?;
+    }
+    Ok(())
+}
+
+/// CMake / C++ クリーナー
+pub struct CMakeCleaner {
+    pub search_path: PathBuf,
+    pub build_dir_patterns: Vec<String>,
+}
+
+impl CMakeCleaner {
+    pub fn new(search_path: PathBuf) -> Self {
+        Self {
+            search_path,
+            build_dir_patterns: Vec::new(),
+        }
+    }
+
+    pub fn with_build_dir_patterns(mut self, patterns: Vec<String>) -> Self {
+        self.build_dir_patterns = patterns;
+        self
+    }
+}
+
+impl Cleanable for CMakeCleaner {
+    fn scan(&self) -> Result<Vec<CleanableItem>> {
+        let builds = find_cmake_builds(&self.search_path, &self.build_dir_patterns)?;
+
+        Ok(builds
+            .into_iter()
+            .map(|b| {
+                CleanableItem::new(
+                    format!("{} ({})", b.build_dir.display(), b.build_type),
+                    b.build_dir,
+                    b.size,
+                )
+            })
+            .collect())
+    }
+
+    fn name(&self) -> &str {
+        "CMake"
+    }
+
+    fn icon(&self) -> &str {
+        "🛠"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern("build", "build*"));
+        assert!(matches_pattern("build-release", "build*"));
+        assert!(matches_pattern("cmake-build-debug", "cmake-build-*"));
+        assert!(matches_pattern("myproj-build", "myproj-*"));
+        assert!(matches_pattern("out", "out"));
+        assert!(!matches_pattern("src", "build*"));
+        assert!(!matches_pattern("cmake-build", "cmake-build-*"));
+    }
+
+    #[test]
+    fn test_find_cmake_builds() -> Result<()> {
+        let temp = TempDir::new()?;
+        let project_dir = temp.path().join("myproj");
+        fs::create_dir(&project_dir)?;
+        fs::write(project_dir.join("CMakeLists.txt"), "project(test)")?;
+
+        // in-source ビルド
+        let build_dir = project_dir.join("cmake-build-debug");
+        fs::create_dir(&build_dir)?;
+        fs::write(build_dir.join("CMakeCache.txt"), "cache")?;
+        // ビルドディレクトリ内の CMakeLists.txt は無視される
+        fs::write(build_dir.join("CMakeLists.txt"), "")?;
+
+        // CMakeCache.txt がない build* はソースとみなして除外
+        let scripts_dir = project_dir.join("buildscripts");
+        fs::create_dir(&scripts_dir)?;
+        fs::write(scripts_dir.join("run.sh"), "echo")?;
+
+        // out-of-source ビルド（兄弟ディレクトリ）
+        let sibling = temp.path().join("myproj-build");
+        fs::create_dir_all(sibling.join("CMakeFiles"))?;
+        fs::write(sibling.join("Makefile"), "all:")?;
+
+        let builds = find_cmake_builds(temp.path(), &[])?;
+        assert_eq!(builds.len(), 1);
+        assert_eq!(builds[0].build_dir, build_dir);
+        assert_eq!(builds[0].build_type, "cmake-build-*");
+
+        let builds = find_cmake_builds(temp.path(), &["{name}-build".to_string()])?;
+        assert_eq!(builds.len(), 2);
+        let out_of_source = builds.iter().find(|b| b.build_dir == sibling).unwrap();
+        assert_eq!(out_of_source.root, project_dir);
+        assert_eq!(out_of_source.build_type, "myproj-build");
+
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
+use std::collections::BTreeMap;
 use std::env;
 use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
@@ -8,13 +10,49 @@ use crate::Result;
 
 /// Kanri 設定
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
+    /// --profile 未指定時に使うプロファイル名
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<String>,
+    /// プロファイル未選択時の B2 設定（暗黙のデフォルトプロファイル）
     pub b2: Option<B2Config>,
+    /// プロファイル未選択時の Storage 設定
     pub storage: Option<StorageConfig>,
+    /// プロファイル未選択時の GCS 設定（backend = "gcs" の場合に使用）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gcs: Option<GcsConfig>,
+    pub cache: Option<CacheConfig>,
+    /// 外部コマンド（docker / b2 / rclone など）の設定
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commands: Option<CommandsConfig>,
+    /// clean コマンドの設定
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clean: Option<CleanConfig>,
+    /// 削除の安全設定
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub safety: Option<SafetyConfig>,
+    /// 名前付きプロファイル（`[profiles.<name>]`）
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>,
+    /// 選択中のプロファイル名（None ならトップレベルの設定を使う）
+    #[serde(skip)]
+    active_profile: Option<String>,
+}
+
+/// 名前付きプロファイル（バケットやバックエンドの切り替え用）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    pub b2: Option<B2Config>,
+    pub storage: Option<StorageConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gcs: Option<GcsConfig>,
 }
 
 /// B2 設定
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct B2Config {
     /// デフォルトのバケット名
     pub bucket: String,
@@ -22,22 +60,339 @@ pub struct B2Config {
     pub application_key_id: Option<String>,
     /// Application Key（オプション、環境変数優先）
     pub application_key: Option<String>,
+    /// 一時的なエラー時の最大再試行回数（省略時は 3）
+    #[serde(default)]
+    pub max_retries: Option<u32>,
+    /// b2 sync の並列数（省略時は 10）
+    #[serde(default)]
+    pub sync_threads: Option<u32>,
+    /// ディレクトリを 1 ファイルずつアップロードする際の並列数（省略時は 4、1 で順番に実行）
+    #[serde(default)]
+    pub upload_threads: Option<u32>,
+    /// 1 ファイルをパートに分けてアップロードする並列数（省略時は 4、1 で分割しない）
+    #[serde(default)]
+    pub file_threads: Option<u32>,
+}
+
+/// Google Cloud Storage 設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GcsConfig {
+    /// バケット名
+    pub bucket: String,
+    /// サービスアカウントの JSON キーのパス（オプション、環境変数 GOOGLE_APPLICATION_CREDENTIALS 優先）
+    #[serde(default)]
+    pub credentials_file: Option<PathBuf>,
 }
 
 /// Storage 設定
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct StorageConfig {
-    /// ストレージバックエンド ("b2" or "rclone")
+    /// ストレージバックエンド ("b2", "rclone" or "gcs")
     #[serde(default = "default_backend")]
     pub backend: String,
     /// Rclone リモート名（backend="rclone"の場合に使用）
     pub rclone_remote: Option<String>,
+    /// 転送帯域の上限（例: "10M"。rclone の --bwlimit と同じ書式）
+    #[serde(default)]
+    pub bwlimit: Option<String>,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            backend: default_backend(),
+            rclone_remote: None,
+            bwlimit: None,
+        }
+    }
+}
+
+/// Cache 設定
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct CacheConfig {
+    /// 削除しても安全とみなすキャッシュ名パターン（組み込みのデフォルトに追加）
+    #[serde(default)]
+    pub safe_patterns: Vec<String>,
+    /// 削除に確認が必要なキャッシュ名パターン（safe_patterns とデフォルトより優先）
+    #[serde(default)]
+    pub unsafe_patterns: Vec<String>,
+}
+
+/// 外部コマンドの設定
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct CommandsConfig {
+    /// メタデータ取得など短時間で終わるはずのコマンドのタイムアウト（秒、省略時は 30）
+    ///
+    /// アップロード・ダウンロードなどの転送には適用しない（ファイル一覧には list_timeout_secs を使う）
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// バケットのファイル一覧など、件数に比例して時間がかかるコマンドのタイムアウト（秒、省略時は 1800）
+    #[serde(default)]
+    pub list_timeout_secs: Option<u64>,
+}
+
+/// プロファイル・プリセット名をテーブル名に使える形に（英数字・-・_ 以外を含む場合は引用符で囲む）
+fn profile_key(name: &str) -> String {
+    if !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        name.to_string()
+    } else {
+        format!("{:?}", name)
+    }
+}
+
+/// テンプレートに B2 セクションを追加（未定義項目はコメントアウト）
+fn push_b2_section(content: &mut String, table: &str, b2: &B2Config) {
+    content.push_str(&format!("[{}]\n", table));
+    content.push_str(&format!("bucket = \"{}\"\n", b2.bucket));
+    if let Some(key_id) = &b2.application_key_id {
+        content.push_str(&format!("application_key_id = \"{}\"\n", key_id));
+    } else {
+        content.push_str("# application_key_id = \"your-key-id\"\n");
+    }
+    if let Some(key) = &b2.application_key {
+        content.push_str(&format!("application_key = \"{}\"\n", key));
+    } else {
+        content.push_str("# application_key = \"your-key\"\n");
+    }
+    if let Some(max_retries) = b2.max_retries {
+        content.push_str(&format!("max_retries = {}\n", max_retries));
+    } else {
+        content.push_str("# max_retries = 3\n");
+    }
+    if let Some(sync_threads) = b2.sync_threads {
+        content.push_str(&format!("sync_threads = {}\n", sync_threads));
+    } else {
+        content.push_str("# sync_threads = 10\n");
+    }
+    if let Some(upload_threads) = b2.upload_threads {
+        content.push_str(&format!("upload_threads = {}\n", upload_threads));
+    } else {
+        content.push_str("# upload_threads = 4\n");
+    }
+    if let Some(file_threads) = b2.file_threads {
+        content.push_str(&format!("file_threads = {}\n", file_threads));
+    } else {
+        content.push_str("# file_threads = 4\n");
+    }
+    content.push('\n');
+}
+
+/// テンプレートに Storage セクションを追加
+fn push_storage_section(content: &mut String, table: &str, storage: &StorageConfig) {
+    content.push_str(&format!("[{}]\n", table));
+    content.push_str(&format!("backend = \"{}\"\n", storage.backend));
+    if let Some(remote) = &storage.rclone_remote {
+        content.push_str(&format!("rclone_remote = \"{}\"\n", remote));
+    } else {
+        content.push_str("# rclone_remote = \"b2:my-bucket\"\n");
+    }
+    if let Some(bwlimit) = &storage.bwlimit {
+        content.push_str(&format!("bwlimit = \"{}\"\n", bwlimit));
+    } else {
+        content.push_str("# bwlimit = \"10M\"\n");
+    }
+    content.push('\n');
+}
+
+/// テンプレートに GCS セクションを追加
+fn push_gcs_section(content: &mut String, table: &str, gcs: &GcsConfig) {
+    content.push_str(&format!("[{}]\n", table));
+    content.push_str(&format!("bucket = \"{}\"\n", gcs.bucket));
+    if let Some(credentials_file) = &gcs.credentials_file {
+        content.push_str(&format!("credentials_file = {:?}\n", credentials_file));
+    } else {
+        content.push_str("# credentials_file = \"~/.config/gcloud/kanri-service-account.json\"\n");
+    }
+    content.push('\n');
+}
+
+/// clean コマンドの設定
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct CleanConfig {
+    /// `kanri clean preset <name>` で使うプリセット（`[clean.presets.<name>]`）
+    #[serde(default)]
+    pub presets: BTreeMap<String, CleanPreset>,
+}
+
+/// 削除の安全設定
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct SafetyConfig {
+    /// どのクリーナーからも削除しないパス（配下・親ディレクトリも含む。先頭の `~/` はホームディレクトリ）
+    #[serde(default)]
+    pub protected_paths: Vec<PathBuf>,
+}
+
+/// 削除対象のディレクトリ名のプリセット
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct CleanPreset {
+    /// ディレクトリ名（`*` ワイルドカード可）
+    pub names: Vec<String>,
 }
 
 fn default_backend() -> String {
     "b2".to_string()
 }
 
+/// 対応しているストレージバックエンド
+const BACKENDS: &[&str] = &["b2", "rclone", "gcs"];
+
+/// 設定ファイルの内容を解析（エラーは行番号と修正のヒント付き）
+fn parse_config(content: &str) -> Result<Config> {
+    toml::from_str(content).map_err(|e| {
+        let location = e
+            .span()
+            .map(|span| {
+                let before = &content[..span.start.min(content.len())];
+                let line = before.matches('\n').count() + 1;
+                format!(" at line {}", line)
+            })
+            .unwrap_or_default();
+        let message = e.message().trim_end();
+        let hint = parse_error_hint(message)
+            .map(|hint| format!(" ({})", hint))
+            .unwrap_or_default();
+        crate::Error::Config(format!(
+            "Invalid config file{}: {}{}",
+            location, message, hint
+        ))
+    })
+}
+
+/// よくある解析エラーに対する修正のヒント
+fn parse_error_hint(message: &str) -> Option<String> {
+    // "unknown field `buckt`, expected one of `bucket`, `application_key_id`, ..."
+    if let Some(rest) = message.strip_prefix("unknown field `") {
+        let (field, expected) = rest.split_once('`')?;
+        let candidates: Vec<&str> = expected.split('`').skip(1).step_by(2).collect();
+        return Some(match closest_match(field, &candidates) {
+            Some(suggestion) => format!("did you mean `{}`?", suggestion),
+            None if candidates.is_empty() => format!("remove `{}`", field),
+            None => format!(
+                "remove `{}` or use one of: {}",
+                field,
+                candidates.join(", ")
+            ),
+        });
+    }
+
+    // "missing field `bucket`"
+    if let Some(rest) = message.strip_prefix("missing field `") {
+        let field = rest.split('`').next()?;
+        return Some(format!("add `{} = ...` to the table", field));
+    }
+
+    None
+}
+
+/// 候補のうち、タイプミスとみなせるほど近いものを返す
+fn closest_match<'a>(input: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(input, candidate), *candidate))
+        .filter(|(distance, candidate)| *distance <= (candidate.len() / 3).max(1))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// レーベンシュタイン距離
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                previous
+            } else {
+                1 + previous.min(row[j]).min(current)
+            };
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
+/// B2 セクションを検証
+fn validate_b2(table: &str, b2: &B2Config, errors: &mut Vec<String>) {
+    if b2.bucket.trim().is_empty() {
+        errors.push(format!(
+            "{}.bucket is empty: set the bucket name (e.g. bucket = \"my-bucket\")",
+            table
+        ));
+    }
+    for (key, value) in [
+        ("sync_threads", b2.sync_threads),
+        ("upload_threads", b2.upload_threads),
+        ("file_threads", b2.file_threads),
+    ] {
+        if value == Some(0) {
+            errors.push(format!(
+                "{}.{} must be at least 1 (remove it to use the default)",
+                table, key
+            ));
+        }
+    }
+}
+
+/// Storage セクションを検証
+fn validate_storage(table: &str, storage: &StorageConfig, errors: &mut Vec<String>) {
+    if !BACKENDS.contains(&storage.backend.as_str()) {
+        errors.push(format!(
+            "{}.backend = \"{}\" is not supported: use \"b2\", \"rclone\" or \"gcs\"",
+            table, storage.backend
+        ));
+    }
+    let remote_missing = storage
+        .rclone_remote
+        .as_deref()
+        .is_none_or(|remote| remote.trim().is_empty());
+    if storage.backend == "rclone" && remote_missing {
+        errors.push(format!(
+            "{}.rclone_remote is required when backend = \"rclone\" (e.g. rclone_remote = \"b2:my-bucket\")",
+            table
+        ));
+    }
+    if storage.bwlimit.as_deref().is_some_and(|bwlimit| bwlimit.trim().is_empty()) {
+        errors.push(format!(
+            "{}.bwlimit is empty: set a limit like \"10M\" or remove it",
+            table
+        ));
+    }
+}
+
+/// GCS セクションを検証（`table` は storage テーブルの親。トップレベルなら空）
+fn validate_gcs(table: &str, storage: Option<&StorageConfig>, gcs: Option<&GcsConfig>, errors: &mut Vec<String>) {
+    let gcs_table = if table.is_empty() {
+        "gcs".to_string()
+    } else {
+        format!("{}.gcs", table)
+    };
+    match gcs {
+        Some(gcs) if gcs.bucket.trim().is_empty() => errors.push(format!(
+            "{}.bucket is empty: set the bucket name (e.g. bucket = \"my-bucket\")",
+            gcs_table
+        )),
+        None if storage.is_some_and(|storage| storage.backend == "gcs") => errors.push(format!(
+            "[{}] is required when backend = \"gcs\" (e.g. bucket = \"my-bucket\")",
+            gcs_table
+        )),
+        _ => {}
+    }
+}
+
 impl Config {
     /// 設定ファイルのパスを取得
     pub fn config_path() -> Result<PathBuf> {
@@ -58,13 +413,191 @@ impl Config {
             crate::Error::Config(format!("Failed to read config file: {}", e))
         })?;
 
-        let config: Config = toml::from_str(&content).map_err(|e| {
-            crate::Error::Config(format!("Failed to parse config file: {}", e))
+        let config = parse_config(&content)?;
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// 設定を読み込み（検証は行わない）
+    ///
+    /// 不正な値を含む設定を `init-b2` などで上書き・修正する場合に使う
+    pub fn load_unchecked() -> Result<Self> {
+        let path = Self::config_path()?;
+
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+
+        let content = fs::read_to_string(&path).map_err(|e| {
+            crate::Error::Config(format!("Failed to read config file: {}", e))
         })?;
 
+        parse_config(&content)
+    }
+
+    /// 設定値を検証
+    ///
+    /// よくある間違い（空のバケット名、rclone_remote のない rclone バックエンドなど）を
+    /// 項目名と修正方法を添えてまとめて報告する
+    pub fn validate(&self) -> Result<()> {
+        let mut errors = Vec::new();
+
+        if let Some(b2) = &self.b2 {
+            validate_b2("b2", b2, &mut errors);
+        }
+        if let Some(storage) = &self.storage {
+            validate_storage("storage", storage, &mut errors);
+        }
+        validate_gcs("", self.storage.as_ref(), self.gcs.as_ref(), &mut errors);
+        for (name, profile) in &self.profiles {
+            let table = format!("profiles.{}", profile_key(name));
+            if let Some(b2) = &profile.b2 {
+                validate_b2(&format!("{}.b2", table), b2, &mut errors);
+            }
+            if let Some(storage) = &profile.storage {
+                validate_storage(&format!("{}.storage", table), storage, &mut errors);
+            }
+            validate_gcs(&table, profile.storage.as_ref(), profile.gcs.as_ref(), &mut errors);
+        }
+
+        if let Some(name) = &self.default_profile {
+            if !self.profiles.contains_key(name) {
+                let available: Vec<&str> = self.profiles.keys().map(|k| k.as_str()).collect();
+                errors.push(if available.is_empty() {
+                    format!(
+                        "default_profile = \"{}\" is not defined: add [profiles.{}] or remove default_profile",
+                        name,
+                        profile_key(name)
+                    )
+                } else {
+                    format!(
+                        "default_profile = \"{}\" is not defined (available: {})",
+                        name,
+                        available.join(", ")
+                    )
+                });
+            }
+        }
+
+        if self.commands.as_ref().and_then(|commands| commands.timeout_secs) == Some(0) {
+            errors.push(
+                "commands.timeout_secs must be at least 1 (remove it to use the default of 30)"
+                    .to_string(),
+            );
+        }
+        if self.commands.as_ref().and_then(|commands| commands.list_timeout_secs) == Some(0) {
+            errors.push(format!(
+                "commands.list_timeout_secs must be at least 1 (remove it to use the default of {})",
+                crate::utils::DEFAULT_LIST_TIMEOUT.as_secs()
+            ));
+        }
+
+        if let Some(clean) = &self.clean {
+            for (name, preset) in &clean.presets {
+                if preset.names.is_empty() {
+                    errors.push(format!(
+                        "clean.presets.{}.names is empty: list directory names (e.g. names = [\".next\"])",
+                        profile_key(name)
+                    ));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(crate::Error::Config(format!(
+                "Invalid config:\n  - {}",
+                errors.join("\n  - ")
+            )))
+        }
+    }
+
+    /// 設定を読み込み、プロファイルを選択
+    pub fn load_profile(profile: Option<&str>) -> Result<Self> {
+        let mut config = Self::load()?;
+        config.select_profile(profile)?;
         Ok(config)
     }
 
+    /// 使用するプロファイルを選択
+    ///
+    /// 優先順位: 引数（--profile） > default_profile > トップレベルの設定
+    pub fn select_profile(&mut self, profile: Option<&str>) -> Result<()> {
+        let Some(name) = profile.or(self.default_profile.as_deref()) else {
+            self.active_profile = None;
+            return Ok(());
+        };
+
+        if !self.profiles.contains_key(name) {
+            return Err(crate::Error::Config(format!("Profile not found: {}", name)));
+        }
+
+        self.active_profile = Some(name.to_string());
+        Ok(())
+    }
+
+    /// 選択中のプロファイル名（トップレベルの設定を使う場合は None）
+    pub fn active_profile(&self) -> Option<&str> {
+        self.active_profile.as_deref()
+    }
+
+    /// 選択中のプロファイルの B2 設定
+    pub fn b2_config(&self) -> Option<&B2Config> {
+        match self.active_profile() {
+            Some(name) => self.profiles.get(name).and_then(|p| p.b2.as_ref()),
+            None => self.b2.as_ref(),
+        }
+    }
+
+    /// 選択中のプロファイルの B2 設定（変更用）
+    pub fn b2_config_mut(&mut self) -> &mut Option<B2Config> {
+        match &self.active_profile {
+            Some(name) => &mut self.profiles.entry(name.clone()).or_default().b2,
+            None => &mut self.b2,
+        }
+    }
+
+    /// 選択中のプロファイルの Storage 設定
+    pub fn storage_config(&self) -> Option<&StorageConfig> {
+        match self.active_profile() {
+            Some(name) => self.profiles.get(name).and_then(|p| p.storage.as_ref()),
+            None => self.storage.as_ref(),
+        }
+    }
+
+    /// 選択中のプロファイルの Storage 設定（変更用）
+    pub fn storage_config_mut(&mut self) -> &mut Option<StorageConfig> {
+        match &self.active_profile {
+            Some(name) => &mut self.profiles.entry(name.clone()).or_default().storage,
+            None => &mut self.storage,
+        }
+    }
+
+    /// 選択中のプロファイルの GCS 設定
+    pub fn gcs_config(&self) -> Option<&GcsConfig> {
+        match self.active_profile() {
+            Some(name) => self.profiles.get(name).and_then(|p| p.gcs.as_ref()),
+            None => self.gcs.as_ref(),
+        }
+    }
+
+    /// 転送帯域の上限を上書き（--bwlimit 指定時）
+    pub fn with_bwlimit(mut self, bwlimit: Option<String>) -> Self {
+        if let Some(bwlimit) = bwlimit {
+            self.storage_config_mut()
+                .get_or_insert_with(StorageConfig::default)
+                .bwlimit = Some(bwlimit);
+        }
+        self
+    }
+
+    /// 転送帯域の上限
+    pub fn bwlimit(&self) -> Option<&str> {
+        self.storage_config().and_then(|s| s.bwlimit.as_deref())
+    }
+
     /// 設定を保存
     pub fn save(&self) -> Result<()> {
         let path = Self::config_path()?;
@@ -80,7 +613,7 @@ impl Config {
             crate::Error::Config(format!("Failed to serialize config: {}", e))
         })?;
 
-        fs::write(&path, content).map_err(|e| {
+        crate::utils::write_atomic(&path, content).map_err(|e| {
             crate::Error::Config(format!("Failed to write config file: {}", e))
         })?;
 
@@ -98,58 +631,157 @@ impl Config {
             })?;
         }
 
+        crate::utils::write_atomic(&path, self.template_content()).map_err(|e| {
+            crate::Error::Config(format!("Failed to write config file: {}", e))
+        })?;
+
+        Ok(())
+    }
+
+    /// テンプレート付きの設定ファイルの内容
+    fn template_content(&self) -> String {
         let mut content = String::new();
 
+        // デフォルトプロファイル（テーブルより前に置く必要がある）
+        if let Some(profile) = &self.default_profile {
+            content.push_str(&format!("default_profile = \"{}\"\n\n", profile));
+        } else {
+            content.push_str("# default_profile = \"work\"\n\n");
+        }
+
         // B2 設定
         if let Some(b2) = &self.b2 {
-            content.push_str(&format!("[b2]\n"));
-            content.push_str(&format!("bucket = \"{}\"\n", b2.bucket));
-            if let Some(key_id) = &b2.application_key_id {
-                content.push_str(&format!("application_key_id = \"{}\"\n", key_id));
-            } else {
-                content.push_str("# application_key_id = \"your-key-id\"\n");
-            }
-            if let Some(key) = &b2.application_key {
-                content.push_str(&format!("application_key = \"{}\"\n", key));
-            } else {
-                content.push_str("# application_key = \"your-key\"\n");
-            }
-            content.push_str("\n");
+            push_b2_section(&mut content, "b2", b2);
         } else {
             content.push_str("# [b2]\n");
             content.push_str("# bucket = \"my-bucket\"\n");
             content.push_str("# application_key_id = \"your-key-id\"\n");
             content.push_str("# application_key = \"your-key\"\n");
-            content.push_str("\n");
+            content.push_str("# max_retries = 3\n");
+            content.push_str("# sync_threads = 10\n");
+            content.push_str("# upload_threads = 4\n");
+            content.push_str("# file_threads = 4\n");
+            content.push('\n');
         }
 
         // Storage 設定
         if let Some(storage) = &self.storage {
-            content.push_str(&format!("[storage]\n"));
-            content.push_str(&format!("backend = \"{}\"\n", storage.backend));
-            if let Some(remote) = &storage.rclone_remote {
-                content.push_str(&format!("rclone_remote = \"{}\"\n", remote));
-            } else {
-                content.push_str("# rclone_remote = \"b2:my-bucket\"\n");
-            }
-            content.push_str("\n");
+            push_storage_section(&mut content, "storage", storage);
         } else {
             content.push_str("# [storage]\n");
-            content.push_str("# backend = \"b2\"  # or \"rclone\"\n");
+            content.push_str("# backend = \"b2\"  # or \"rclone\" / \"gcs\"\n");
             content.push_str("# rclone_remote = \"b2:my-bucket\"  # required when backend = \"rclone\"\n");
-            content.push_str("\n");
+            content.push_str("# bwlimit = \"10M\"  # transfer rate limit (rclone only)\n");
+            content.push('\n');
+        }
+
+        // GCS 設定
+        if let Some(gcs) = &self.gcs {
+            push_gcs_section(&mut content, "gcs", gcs);
+        } else {
+            content.push_str("# [gcs]  # used when backend = \"gcs\"\n");
+            content.push_str("# bucket = \"my-bucket\"\n");
+            content.push_str("# credentials_file = \"~/.config/gcloud/kanri-service-account.json\"\n");
+            content.push('\n');
+        }
+
+        // Cache 設定
+        if let Some(cache) = &self.cache {
+            content.push_str("[cache]\n");
+            content.push_str(&format!("safe_patterns = {:?}\n", cache.safe_patterns));
+            content.push_str(&format!("unsafe_patterns = {:?}\n", cache.unsafe_patterns));
+            content.push('\n');
+        } else {
+            content.push_str("# [cache]\n");
+            content.push_str("# safe_patterns = [\"com.example.internal-tool\"]\n");
+            content.push_str("# unsafe_patterns = [\"com.example.important\"]\n");
+            content.push('\n');
+        }
+
+        // 外部コマンド設定
+        let timeout_secs = self.commands.as_ref().and_then(|commands| commands.timeout_secs);
+        let list_timeout_secs = self.commands.as_ref().and_then(|commands| commands.list_timeout_secs);
+        if timeout_secs.is_some() || list_timeout_secs.is_some() {
+            content.push_str("[commands]\n");
+        } else {
+            content.push_str("# [commands]\n");
+        }
+        match timeout_secs {
+            Some(timeout_secs) => content.push_str(&format!("timeout_secs = {}\n", timeout_secs)),
+            None => content.push_str(&format!(
+                "# timeout_secs = {}\n",
+                crate::utils::DEFAULT_COMMAND_TIMEOUT.as_secs()
+            )),
+        }
+        match list_timeout_secs {
+            Some(list_timeout_secs) => {
+                content.push_str(&format!("list_timeout_secs = {}\n", list_timeout_secs))
+            }
+            None => content.push_str(&format!(
+                "# list_timeout_secs = {}\n",
+                crate::utils::DEFAULT_LIST_TIMEOUT.as_secs()
+            )),
+        }
+        content.push('\n');
+
+        // clean プリセット
+        match &self.clean {
+            Some(clean) if !clean.presets.is_empty() => {
+                for (name, preset) in &clean.presets {
+                    content.push_str(&format!("[clean.presets.{}]\n", profile_key(name)));
+                    content.push_str(&format!("names = {:?}\n", preset.names));
+                    content.push('\n');
+                }
+            }
+            _ => {
+                content.push_str("# [clean.presets.frontend]\n");
+                content.push_str("# names = [\".next\", \".nuxt\", \".turbo\", \".svelte-kit\"]\n");
+                content.push('\n');
+            }
+        }
+
+        // 安全設定
+        match &self.safety {
+            Some(safety) if !safety.protected_paths.is_empty() => {
+                content.push_str("[safety]\n");
+                content.push_str(&format!("protected_paths = {:?}\n", safety.protected_paths));
+            }
+            _ => {
+                content.push_str("# [safety]\n");
+                content.push_str("# protected_paths = [\"~/work/production-data\"]\n");
+            }
+        }
+        content.push('\n');
+
+        // プロファイル設定
+        if self.profiles.is_empty() {
+            content.push_str("# [profiles.work.b2]\n");
+            content.push_str("# bucket = \"work-bucket\"\n");
+            content.push_str("# [profiles.personal.storage]\n");
+            content.push_str("# backend = \"rclone\"\n");
+            content.push_str("# rclone_remote = \"b2:personal-bucket\"\n");
+            content.push('\n');
+        }
+        for (name, profile) in &self.profiles {
+            let name = profile_key(name);
+            if let Some(b2) = &profile.b2 {
+                push_b2_section(&mut content, &format!("profiles.{}.b2", name), b2);
+            }
+            if let Some(storage) = &profile.storage {
+                push_storage_section(&mut content, &format!("profiles.{}.storage", name), storage);
+            }
+            if let Some(gcs) = &profile.gcs {
+                push_gcs_section(&mut content, &format!("profiles.{}.gcs", name), gcs);
+            }
+            if profile.b2.is_none() && profile.storage.is_none() && profile.gcs.is_none() {
+                content.push_str(&format!("[profiles.{}]\n\n", name));
+            }
         }
 
         // ヘッダーコメントを追加
         let header = "# Kanri Configuration File\n\
                       # See https://github.com/yourusername/kanri for more details\n\n";
-        let final_content = format!("{}{}", header, content);
-
-        fs::write(&path, final_content).map_err(|e| {
-            crate::Error::Config(format!("Failed to write config file: {}", e))
-        })?;
-
-        Ok(())
+        format!("{}{}", header, content)
     }
 
     /// B2 認証情報を取得（環境変数優先）
@@ -157,10 +789,9 @@ impl Config {
         // 環境変数を優先
         let key_id = env::var("B2_APPLICATION_KEY_ID")
             .or_else(|_| {
-                self.b2
-                    .as_ref()
+                self.b2_config()
                     .and_then(|b2| b2.application_key_id.clone())
-                    .ok_or_else(|| env::VarError::NotPresent)
+                    .ok_or(env::VarError::NotPresent)
             })
             .map_err(|_| {
                 crate::Error::Config(
@@ -170,10 +801,9 @@ impl Config {
 
         let key = env::var("B2_APPLICATION_KEY")
             .or_else(|_| {
-                self.b2
-                    .as_ref()
+                self.b2_config()
                     .and_then(|b2| b2.application_key.clone())
-                    .ok_or_else(|| env::VarError::NotPresent)
+                    .ok_or(env::VarError::NotPresent)
             })
             .map_err(|_| {
                 crate::Error::Config("B2_APPLICATION_KEY not found in environment or config".into())
@@ -184,35 +814,81 @@ impl Config {
 
     /// B2 バケット名を取得
     pub fn get_b2_bucket(&self) -> Result<String> {
-        self.b2
-            .as_ref()
+        self.b2_config()
             .map(|b2| b2.bucket.clone())
             .ok_or_else(|| crate::Error::Config("B2 bucket not configured".into()))
     }
 
+    /// GCS のサービスアカウントキーのパスを取得（環境変数 GOOGLE_APPLICATION_CREDENTIALS 優先）
+    ///
+    /// 設定ファイルのパスの先頭の `~/` はホームディレクトリに展開する
+    pub fn get_gcs_credentials_file(&self) -> Result<PathBuf> {
+        if let Some(path) = env::var_os("GOOGLE_APPLICATION_CREDENTIALS").filter(|v| !v.is_empty()) {
+            return Ok(PathBuf::from(path));
+        }
+        let path = self
+            .gcs_config()
+            .and_then(|gcs| gcs.credentials_file.clone())
+            .ok_or_else(|| {
+                crate::Error::Config(
+                    "GOOGLE_APPLICATION_CREDENTIALS not found in environment or gcs.credentials_file".into(),
+                )
+            })?;
+        Ok(match (path.strip_prefix("~"), env::var_os("HOME")) {
+            (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+            _ => path,
+        })
+    }
+
+    /// アーカイブ先のバケット名を取得（backend = "gcs" なら [gcs]、それ以外は [b2] のバケット）
+    pub fn get_bucket(&self) -> Result<String> {
+        if self.get_storage_backend() != "gcs" {
+            return self.get_b2_bucket();
+        }
+        self.gcs_config()
+            .map(|gcs| gcs.bucket.clone())
+            .ok_or_else(|| crate::Error::Config("GCS bucket not configured".into()))
+    }
+
     /// StorageClient を作成
     pub fn create_storage_client(&self) -> Result<Box<dyn crate::StorageClient>> {
         let backend = self
-            .storage
-            .as_ref()
+            .storage_config()
             .map(|s| s.backend.as_str())
             .unwrap_or("b2");
 
         match backend {
             "b2" => {
                 let (key_id, key) = self.get_b2_credentials()?;
-                let client = crate::b2::B2Client::new(key_id, key)?;
+                let mut client = crate::b2::B2Client::new(key_id, key)?;
+                if let Some(max_retries) = self.b2_config().and_then(|b2| b2.max_retries) {
+                    client = client.with_retry_policy(crate::b2::RetryPolicy::new(max_retries));
+                }
+                if let Some(sync_threads) = self.b2_config().and_then(|b2| b2.sync_threads) {
+                    client = client.with_sync_threads(sync_threads);
+                }
+                if let Some(upload_threads) = self.b2_config().and_then(|b2| b2.upload_threads) {
+                    client = client.with_upload_threads(upload_threads);
+                }
+                if let Some(file_threads) = self.b2_config().and_then(|b2| b2.file_threads) {
+                    client = client.with_file_threads(file_threads);
+                }
                 Ok(Box::new(client))
             }
             "rclone" => {
                 let remote = self
-                    .storage
-                    .as_ref()
+                    .storage_config()
                     .and_then(|s| s.rclone_remote.clone())
                     .ok_or_else(|| {
                         crate::Error::Config("Rclone remote not configured".into())
                     })?;
-                let client = crate::rclone::RcloneClient::new(remote)?;
+                let client = crate::rclone::RcloneClient::new(remote)?
+                    .with_bwlimit(self.bwlimit().map(|s| s.to_string()));
+                Ok(Box::new(client))
+            }
+            "gcs" => {
+                let credentials_file = self.get_gcs_credentials_file()?;
+                let client = crate::gcs::GcsClient::new(credentials_file, self.get_bucket()?)?;
                 Ok(Box::new(client))
             }
             _ => Err(crate::Error::Config(format!(
@@ -222,10 +898,75 @@ impl Config {
         }
     }
 
+    /// キャッシュが削除しても安全かどうか判定
+    ///
+    /// 優先順位: unsafe_patterns > safe_patterns > 組み込みのデフォルト
+    pub fn cache_safety(&self, name: &str) -> bool {
+        crate::cache::is_safe_cache(name, self.cache.as_ref())
+    }
+
+    /// 外部コマンドのタイムアウト（未設定なら None）
+    pub fn command_timeout(&self) -> Option<Duration> {
+        self.commands
+            .as_ref()
+            .and_then(|commands| commands.timeout_secs)
+            .map(Duration::from_secs)
+    }
+
+    /// ファイル一覧を取得するコマンドのタイムアウト（未設定なら None）
+    pub fn list_timeout(&self) -> Option<Duration> {
+        self.commands
+            .as_ref()
+            .and_then(|commands| commands.list_timeout_secs)
+            .map(Duration::from_secs)
+    }
+
+    /// 削除しないパス（`[safety] protected_paths`、先頭の `~/` はホームディレクトリに展開）
+    pub fn protected_paths(&self) -> Vec<PathBuf> {
+        let home = env::var_os("HOME").map(PathBuf::from);
+        self.safety
+            .iter()
+            .flat_map(|safety| &safety.protected_paths)
+            .map(|path| match (path.strip_prefix("~"), &home) {
+                (Ok(rest), Some(home)) => home.join(rest),
+                _ => path.clone(),
+            })
+            .collect()
+    }
+
+    /// 定義済みのプリセット名（名前順）
+    pub fn preset_names(&self) -> Vec<&str> {
+        self.clean
+            .as_ref()
+            .map(|clean| clean.presets.keys().map(|k| k.as_str()).collect())
+            .unwrap_or_default()
+    }
+
+    /// プリセットのディレクトリ名を取得
+    pub fn resolve_preset(&self, name: &str) -> Result<Vec<String>> {
+        let preset = self
+            .clean
+            .as_ref()
+            .and_then(|clean| clean.presets.get(name))
+            .ok_or_else(|| {
+                let available = self.preset_names();
+                crate::Error::Config(if available.is_empty() {
+                    format!("Preset not found: {} (no presets defined in [clean.presets])", name)
+                } else {
+                    format!("Preset not found: {} (available: {})", name, available.join(", "))
+                })
+            })?;
+
+        if preset.names.is_empty() {
+            return Err(crate::Error::Config(format!("Preset has no names: {}", name)));
+        }
+
+        Ok(preset.names.clone())
+    }
+
     /// ストレージバックエンド名を取得
     pub fn get_storage_backend(&self) -> String {
-        self.storage
-            .as_ref()
+        self.storage_config()
             .map(|s| s.backend.clone())
             .unwrap_or_else(|| "b2".to_string())
     }
@@ -242,8 +983,14 @@ mod tests {
                 bucket: "my-bucket".to_string(),
                 application_key_id: Some("key-id".to_string()),
                 application_key: Some("key".to_string()),
+                max_retries: None,
+                sync_threads: None,
+                upload_threads: None,
+                file_threads: None,
             }),
             storage: None,
+            cache: None,
+            ..Default::default()
         };
 
         let toml = toml::to_string(&config).unwrap();
@@ -261,11 +1008,18 @@ mod tests {
                 bucket: "my-bucket".to_string(),
                 application_key_id: None,
                 application_key: None,
+                max_retries: None,
+                sync_threads: None,
+                upload_threads: None,
+                file_threads: None,
             }),
             storage: Some(StorageConfig {
                 backend: "rclone".to_string(),
                 rclone_remote: Some("b2:my-bucket".to_string()),
+                bwlimit: None,
             }),
+            cache: None,
+            ..Default::default()
         };
 
         let toml = toml::to_string(&config).unwrap();
@@ -281,6 +1035,8 @@ mod tests {
         let config = Config {
             b2: None,
             storage: None,
+            cache: None,
+            ..Default::default()
         };
 
         assert_eq!(config.get_storage_backend(), "b2");
@@ -293,7 +1049,10 @@ mod tests {
             storage: Some(StorageConfig {
                 backend: "rclone".to_string(),
                 rclone_remote: Some("b2:bucket".to_string()),
+                bwlimit: None,
             }),
+            cache: None,
+            ..Default::default()
         };
 
         assert_eq!(config.get_storage_backend(), "rclone");
@@ -313,8 +1072,14 @@ mod tests {
                 bucket: "test-bucket".to_string(),
                 application_key_id: None,
                 application_key: None,
+                max_retries: None,
+                sync_threads: None,
+                upload_threads: None,
+                file_threads: None,
             }),
             storage: None,
+            cache: None,
+            ..Default::default()
         };
 
         // テンプレート保存
@@ -339,5 +1104,310 @@ mod tests {
         assert!(content.contains("# [storage]"));
         assert!(content.contains("# backend = \"b2\""));
         assert!(content.contains("# rclone_remote = \"b2:my-bucket\""));
+
+        // Cache設定がコメントアウトで含まれているか
+        assert!(content.contains("# [cache]"));
+    }
+
+    #[test]
+    fn test_cache_safety_precedence() {
+        let toml = r#"
+[cache]
+safe_patterns = ["com.example.tool", "com.apple.Safari"]
+unsafe_patterns = ["Homebrew", "com.example.tool.data"]
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+
+        // 追加した safe パターン
+        assert!(config.cache_safety("com.example.tool"));
+        // デフォルトの unsafe を safe で上書き
+        assert!(config.cache_safety("com.apple.Safari"));
+        // デフォルトの safe を unsafe で上書き
+        assert!(!config.cache_safety("Homebrew"));
+        // unsafe は safe より優先
+        assert!(!config.cache_safety("com.example.tool.data"));
+        // 設定にないものはデフォルト
+        assert!(config.cache_safety("pip"));
+        assert!(!config.cache_safety("some.random.app"));
+
+        assert!(Config::default().cache_safety("Homebrew"));
+    }
+
+    const PROFILES_TOML: &str = r#"
+default_profile = "personal"
+
+[b2]
+bucket = "legacy-bucket"
+
+[profiles.work.b2]
+bucket = "work-bucket"
+
+[profiles.personal.b2]
+bucket = "personal-bucket"
+
+[profiles.personal.storage]
+backend = "rclone"
+rclone_remote = "b2:personal-bucket"
+"#;
+
+    #[test]
+    fn test_profile_precedence() {
+        let mut config: Config = toml::from_str(PROFILES_TOML).unwrap();
+
+        // --profile が最優先
+        config.select_profile(Some("work")).unwrap();
+        assert_eq!(config.active_profile(), Some("work"));
+        assert_eq!(config.get_b2_bucket().unwrap(), "work-bucket");
+        assert_eq!(config.get_storage_backend(), "b2");
+
+        // 未指定なら default_profile
+        config.select_profile(None).unwrap();
+        assert_eq!(config.active_profile(), Some("personal"));
+        assert_eq!(config.get_b2_bucket().unwrap(), "personal-bucket");
+        assert_eq!(config.get_storage_backend(), "rclone");
+
+        // default_profile もなければトップレベルの設定
+        config.default_profile = None;
+        config.select_profile(None).unwrap();
+        assert_eq!(config.active_profile(), None);
+        assert_eq!(config.get_b2_bucket().unwrap(), "legacy-bucket");
+
+        // 存在しないプロファイルはエラー
+        assert!(config.select_profile(Some("unknown")).is_err());
+    }
+
+    #[test]
+    fn test_legacy_config_without_profiles() {
+        let mut config: Config = toml::from_str("[b2]\nbucket = \"my-bucket\"\n").unwrap();
+        config.select_profile(None).unwrap();
+
+        assert!(config.profiles.is_empty());
+        assert_eq!(config.get_b2_bucket().unwrap(), "my-bucket");
+    }
+
+    #[test]
+    fn test_template_content_profiles() {
+        let mut config: Config = toml::from_str(PROFILES_TOML).unwrap();
+        config.profiles.insert("my team".to_string(), Profile::default());
+
+        let content = config.template_content();
+        assert!(content.contains("default_profile = \"personal\""));
+        assert!(content.contains("[profiles.work.b2]"));
+        assert!(content.contains("[profiles.\"my team\"]"));
+
+        // 書き出したテンプレートを読み直しても同じ設定になる
+        let parsed: Config = toml::from_str(&content).unwrap();
+        assert_eq!(parsed.default_profile.as_deref(), Some("personal"));
+        assert_eq!(parsed.profiles.len(), 3);
+        assert_eq!(
+            parsed.profiles["personal"].storage.as_ref().unwrap().rclone_remote.as_deref(),
+            Some("b2:personal-bucket")
+        );
+    }
+
+    #[test]
+    fn test_bwlimit_override() {
+        let config: Config =
+            toml::from_str("[storage]\nbackend = \"rclone\"\nbwlimit = \"5M\"\n").unwrap();
+        assert_eq!(config.bwlimit(), Some("5M"));
+
+        // --bwlimit が設定より優先
+        let config = config.with_bwlimit(Some("1M".to_string()));
+        assert_eq!(config.bwlimit(), Some("1M"));
+        assert_eq!(config.get_storage_backend(), "rclone");
+
+        // [storage] がなくても設定できる
+        let config = Config::default().with_bwlimit(Some("10M".to_string()));
+        assert_eq!(config.bwlimit(), Some("10M"));
+        assert_eq!(config.get_storage_backend(), "b2");
+        assert_eq!(Config::default().with_bwlimit(None).bwlimit(), None);
+    }
+
+    #[test]
+    fn test_resolve_preset() {
+        let toml = r#"
+[clean.presets.frontend]
+names = [".next", ".nuxt", ".turbo"]
+
+[clean.presets.infra]
+names = [".terraform"]
+
+[clean.presets.empty]
+names = []
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+
+        assert_eq!(
+            config.resolve_preset("frontend").unwrap(),
+            vec![".next", ".nuxt", ".turbo"]
+        );
+        assert_eq!(config.preset_names(), vec!["empty", "frontend", "infra"]);
+
+        // 存在しないプリセットは利用可能な一覧をエラーに含める
+        let err = config.resolve_preset("backend").unwrap_err().to_string();
+        assert!(err.contains("backend"));
+        assert!(err.contains("frontend, infra"));
+
+        assert!(config.resolve_preset("empty").is_err());
+        assert!(Config::default().resolve_preset("frontend").is_err());
+
+        // テンプレートに書き出しても読み直せる
+        let parsed: Config = toml::from_str(&config.template_content()).unwrap();
+        assert_eq!(parsed.resolve_preset("infra").unwrap(), vec![".terraform"]);
+    }
+
+    #[test]
+    fn test_parse_config_errors() {
+        let err = parse_config("[b2]\nbuckt = \"my-bucket\"\n").unwrap_err().to_string();
+        assert!(err.contains("line 2"), "{}", err);
+        assert!(err.contains("unknown field `buckt`"), "{}", err);
+        assert!(err.contains("did you mean `bucket`?"), "{}", err);
+
+        let err = parse_config("[b2]\napplication_key = \"key\"\n").unwrap_err().to_string();
+        assert!(err.contains("missing field `bucket`"), "{}", err);
+        assert!(err.contains("add `bucket = ...`"), "{}", err);
+
+        let err = parse_config("[storage]\nbackend = \"rclone\"\nsomething = 1\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("remove `something` or use one of"), "{}", err);
+
+        assert!(parse_config("[b2]\nbucket = \"my-bucket\"\n").is_ok());
+    }
+
+    #[test]
+    fn test_validate() {
+        let config = parse_config(
+            r#"
+default_profile = "personal"
+
+[b2]
+bucket = ""
+sync_threads = 0
+
+[storage]
+backend = "rclone"
+
+[profiles.work.storage]
+backend = "s3"
+rclone_remote = "s3:bucket"
+
+[profiles.home.b2]
+bucket = "home-bucket"
+
+[clean.presets.frontend]
+names = []
+"#,
+        )
+        .unwrap();
+
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("b2.bucket is empty"), "{}", err);
+        assert!(err.contains("b2.sync_threads must be at least 1"), "{}", err);
+        assert!(err.contains("storage.rclone_remote is required"), "{}", err);
+        assert!(err.contains("profiles.work.storage.backend = \"s3\" is not supported"), "{}", err);
+        assert!(err.contains("default_profile = \"personal\" is not defined (available: home, work)"), "{}", err);
+        assert!(err.contains("clean.presets.frontend.names is empty"), "{}", err);
+        assert!(!err.contains("profiles.home"), "{}", err);
+
+        assert!(Config::default().validate().is_ok());
+        assert!(parse_config(&Config::default().template_content())
+            .unwrap()
+            .validate()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_command_timeout() {
+        assert_eq!(Config::default().command_timeout(), None);
+
+        let config = parse_config("[commands]\ntimeout_secs = 5\n").unwrap();
+        assert_eq!(config.command_timeout(), Some(Duration::from_secs(5)));
+        assert!(config.template_content().contains("[commands]\ntimeout_secs = 5\n"));
+
+        let err = parse_config("[commands]\ntimeout_secs = 0\n")
+            .unwrap()
+            .validate()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("commands.timeout_secs must be at least 1"), "{}", err);
+
+        assert_eq!(Config::default().list_timeout(), None);
+        let config = parse_config("[commands]\nlist_timeout_secs = 3600\n").unwrap();
+        assert_eq!(config.list_timeout(), Some(Duration::from_secs(3600)));
+        assert_eq!(config.command_timeout(), None);
+        assert!(config.template_content().contains("[commands]\n# timeout_secs = 30\nlist_timeout_secs = 3600\n"));
+        let err = parse_config("[commands]\nlist_timeout_secs = 0\n")
+            .unwrap()
+            .validate()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("commands.list_timeout_secs must be at least 1"), "{}", err);
+    }
+
+    #[test]
+    fn test_protected_paths() {
+        assert!(Config::default().protected_paths().is_empty());
+
+        let config = parse_config("[safety]\nprotected_paths = [\"/srv/data\", \"~/prod\"]\n").unwrap();
+        let home = PathBuf::from(env::var("HOME").unwrap());
+        assert_eq!(
+            config.protected_paths(),
+            vec![PathBuf::from("/srv/data"), home.join("prod")]
+        );
+        assert!(config
+            .template_content()
+            .contains("[safety]\nprotected_paths = [\"/srv/data\", \"~/prod\"]\n"));
+    }
+
+    #[test]
+    fn test_gcs_config() {
+        let config = parse_config(
+            r#"
+[b2]
+bucket = "b2-bucket"
+
+[storage]
+backend = "gcs"
+
+[gcs]
+bucket = "gcs-bucket"
+credentials_file = "/etc/kanri/sa.json"
+
+[profiles.work.storage]
+backend = "gcs"
+
+[profiles.home.b2]
+bucket = "home-bucket"
+"#,
+        )
+        .unwrap();
+
+        // backend = "gcs" のプロファイルに [gcs] がなければエラー
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("[profiles.work.gcs] is required when backend = \"gcs\""), "{}", err);
+        assert!(!err.contains("profiles.home"), "{}", err);
+
+        assert_eq!(config.get_storage_backend(), "gcs");
+        assert_eq!(config.get_bucket().unwrap(), "gcs-bucket");
+        assert_eq!(
+            config.gcs_config().unwrap().credentials_file,
+            Some(PathBuf::from("/etc/kanri/sa.json"))
+        );
+        assert!(config
+            .template_content()
+            .contains("[gcs]\nbucket = \"gcs-bucket\"\ncredentials_file = \"/etc/kanri/sa.json\"\n"));
+
+        // gcs 以外のバックエンドでは従来どおり [b2] のバケット
+        let mut config = config;
+        config.select_profile(Some("home")).unwrap();
+        assert_eq!(config.get_bucket().unwrap(), "home-bucket");
+
+        let err = parse_config("[gcs]\nbucket = \"\"\n")
+            .unwrap()
+            .validate()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("gcs.bucket is empty"), "{}", err);
     }
 }
@@ -0,0 +1,115 @@
+use std::path::PathBuf;
+
+use crate::{
+    cleanable::{Cleanable, CleanableItem},
+    cmake::matches_pattern,
+    utils, Result,
+};
+
+/// 名前を指定してディレクトリを探すクリーナー
+///
+/// `.next` `.turbo` `.terraform` など、専用のクリーナーがないビルド成果物向け。
+/// 名前には `*` ワイルドカードが使える
+pub struct CustomCleaner {
+    pub search_path: PathBuf,
+    /// 削除対象のディレクトリ名（例: ".turbo", "elm-stuff", ".svelte-*"）
+    pub names: Vec<String>,
+}
+
+impl CustomCleaner {
+    pub fn new(search_path: PathBuf, names: Vec<String>) -> Self {
+        Self { search_path, names }
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        self.names.iter().any(|pattern| matches_pattern(name, pattern))
+    }
+}
+
+impl Cleanable for CustomCleaner {
+    fn scan(&self) -> Result<Vec<CleanableItem>> {
+        let mut items = Vec::new();
+        if self.names.is_empty() {
+            return Ok(items);
+        }
+
+        // `.next` などの隠しディレクトリ自体が削除対象になるため、パターンに一致する名前は --no-hidden でも残す
+        let walker = utils::walk_dir(&self.search_path).min_depth(1);
+        let mut walker =
+            utils::walk_entries(walker, |name| self.matches(name), |e| e.file_name() != ".git");
+
+        while let Some(entry) = walker.next() {
+            let Ok(entry) = entry else {
+                continue;
+            };
+            if !entry.file_type().is_dir() {
+                continue;
+            }
+
+            let name = entry.file_name().to_string_lossy();
+            if !self.matches(&name) {
+                continue;
+            }
+
+            // 見つかったディレクトリの中は探さない
+            walker.skip_current_dir();
+
+            let path = entry.into_path();
+            let size = utils::calculate_dir_size(&path)?;
+            items.push(CleanableItem::new(path.display().to_string(), path, size));
+        }
+
+        Ok(items)
+    }
+
+    fn name(&self) -> &str {
+        "Custom"
+    }
+
+    fn icon(&self) -> &str {
+        "🧹"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_custom_cleaner_scan() -> Result<()> {
+        let temp = TempDir::new()?;
+        let app = temp.path().join("app");
+        fs::create_dir_all(app.join(".next").join("cache"))?;
+        fs::write(app.join(".next").join("cache").join("data"), vec![0u8; 100])?;
+        fs::create_dir_all(app.join(".turbo"))?;
+        fs::write(app.join(".turbo").join("log"), vec![0u8; 10])?;
+
+        // 一致したディレクトリの中にある同名ディレクトリは数えない
+        fs::create_dir_all(app.join(".next").join(".turbo"))?;
+
+        // 一致しないディレクトリ・ファイル
+        fs::create_dir_all(app.join("src"))?;
+        fs::write(app.join(".terraform"), "not a dir")?;
+
+        let cleaner = CustomCleaner::new(
+            temp.path().to_path_buf(),
+            vec![".next".to_string(), ".tur*".to_string(), ".terraform".to_string()],
+        );
+        let mut items = cleaner.scan()?;
+        items.sort_by(|a, b| a.path.cmp(&b.path));
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].path, app.join(".next"));
+        assert_eq!(items[0].size, 100);
+        assert_eq!(items[1].path, app.join(".turbo"));
+        assert_eq!(items[1].size, 10);
+
+        assert!(CustomCleaner::new(temp.path().to_path_buf(), Vec::new())
+            .scan()?
+            .is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,256 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::Result;
+
+/// 保存する診断履歴の最大件数
+pub const MAX_SNAPSHOTS: usize = 100;
+
+/// 診断結果のカテゴリごとの合計
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SnapshotCategory {
+    /// カテゴリキー（例: rust, node）
+    pub key: String,
+    /// 表示名
+    pub name: String,
+    /// 合計サイズ（バイト）
+    pub total_size: u64,
+}
+
+/// 1 回分の診断結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnoseSnapshot {
+    /// 実行日時
+    pub timestamp: DateTime<Utc>,
+    /// 合計サイズ（バイト）
+    pub total_size: u64,
+    /// カテゴリごとの合計
+    pub categories: Vec<SnapshotCategory>,
+}
+
+impl DiagnoseSnapshot {
+    /// 現在時刻で新しいスナップショットを作成
+    pub fn new(categories: Vec<SnapshotCategory>) -> Self {
+        let total_size = categories.iter().map(|c| c.total_size).sum();
+        Self {
+            timestamp: Utc::now(),
+            total_size,
+            categories,
+        }
+    }
+
+    /// キーでカテゴリのサイズを取得（存在しない場合は 0）
+    pub fn category_size(&self, key: &str) -> u64 {
+        self.categories
+            .iter()
+            .find(|c| c.key == key)
+            .map(|c| c.total_size)
+            .unwrap_or(0)
+    }
+}
+
+/// カテゴリごとの前回比
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryDelta {
+    /// カテゴリキー
+    pub key: String,
+    /// 表示名
+    pub name: String,
+    /// 前回のサイズ（バイト）
+    pub previous: u64,
+    /// 今回のサイズ（バイト）
+    pub current: u64,
+}
+
+impl CategoryDelta {
+    /// 増減（バイト）
+    pub fn delta(&self) -> i64 {
+        self.current as i64 - self.previous as i64
+    }
+}
+
+/// 診断履歴
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiagnoseHistory {
+    pub snapshots: Vec<DiagnoseSnapshot>,
+}
+
+impl DiagnoseHistory {
+    /// 診断履歴ファイルのパスを取得
+    pub fn history_path() -> Result<PathBuf> {
+        let home = std::env::var("HOME")
+            .map_err(|_| crate::Error::Config("HOME environment variable not set".into()))?;
+        Ok(PathBuf::from(home)
+            .join(".kanri")
+            .join("diagnose_history.json"))
+    }
+
+    /// 診断履歴を読み込み
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::history_path()?)
+    }
+
+    /// 指定したファイルから診断履歴を読み込み
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path).map_err(|e| {
+            crate::Error::Config(format!("Failed to read diagnose history: {}", e))
+        })?;
+
+        serde_json::from_str(&content).map_err(|e| {
+            crate::Error::Config(format!("Failed to parse diagnose history: {}", e))
+        })
+    }
+
+    /// 診断履歴を保存
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::history_path()?)
+    }
+
+    /// 指定したファイルに診断履歴を保存
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                crate::Error::Config(format!("Failed to create history directory: {}", e))
+            })?;
+        }
+
+        let content = serde_json::to_string_pretty(self).map_err(|e| {
+            crate::Error::Config(format!("Failed to serialize diagnose history: {}", e))
+        })?;
+
+        crate::utils::write_atomic(path, content).map_err(|e| {
+            crate::Error::Config(format!("Failed to write diagnose history: {}", e))
+        })?;
+
+        Ok(())
+    }
+
+    /// スナップショットを追加（MAX_SNAPSHOTS を超えた古いものは削除）
+    pub fn push(&mut self, snapshot: DiagnoseSnapshot) {
+        self.snapshots.push(snapshot);
+        if self.snapshots.len() > MAX_SNAPSHOTS {
+            let excess = self.snapshots.len() - MAX_SNAPSHOTS;
+            self.snapshots.drain(..excess);
+        }
+    }
+
+    /// 直近 N 件の合計サイズ（古い順）
+    pub fn recent_totals(&self, n: usize) -> Vec<u64> {
+        let start = self.snapshots.len().saturating_sub(n);
+        self.snapshots[start..].iter().map(|s| s.total_size).collect()
+    }
+}
+
+/// 2 つのスナップショットをカテゴリごとに比較
+///
+/// どちらか一方にしか存在しないカテゴリは、もう一方を 0 として扱う
+pub fn compare(previous: &DiagnoseSnapshot, current: &DiagnoseSnapshot) -> Vec<CategoryDelta> {
+    let mut deltas: Vec<CategoryDelta> = current
+        .categories
+        .iter()
+        .map(|c| CategoryDelta {
+            key: c.key.clone(),
+            name: c.name.clone(),
+            previous: previous.category_size(&c.key),
+            current: c.total_size,
+        })
+        .collect();
+
+    for c in &previous.categories {
+        if !deltas.iter().any(|d| d.key == c.key) {
+            deltas.push(CategoryDelta {
+                key: c.key.clone(),
+                name: c.name.clone(),
+                previous: c.total_size,
+                current: 0,
+            });
+        }
+    }
+
+    deltas
+}
+
+/// 値の推移をスパークライン文字列に変換
+pub fn sparkline(values: &[u64]) -> String {
+    const BARS: &[char] = &['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let (Some(&min), Some(&max)) = (values.iter().min(), values.iter().max()) else {
+        return String::new();
+    };
+    let range = max - min;
+
+    values
+        .iter()
+        .map(|&v| {
+            if range == 0 {
+                BARS[BARS.len() / 2]
+            } else {
+                let index = ((v - min) as f64 / range as f64 * (BARS.len() - 1) as f64).round();
+                BARS[index as usize]
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn category(key: &str, total_size: u64) -> SnapshotCategory {
+        SnapshotCategory {
+            key: key.to_string(),
+            name: key.to_string(),
+            total_size,
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        let previous = DiagnoseSnapshot::new(vec![category("rust", 100), category("docker", 50)]);
+        let current = DiagnoseSnapshot::new(vec![category("rust", 150), category("node", 30)]);
+
+        let deltas = compare(&previous, &current);
+        assert_eq!(deltas.len(), 3);
+        assert_eq!(deltas[0].key, "rust");
+        assert_eq!(deltas[0].delta(), 50);
+        assert_eq!(deltas[1].key, "node");
+        assert_eq!(deltas[1].delta(), 30);
+        assert_eq!(deltas[2].key, "docker");
+        assert_eq!(deltas[2].delta(), -50);
+    }
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[]), "");
+        assert_eq!(sparkline(&[0, 7, 14]), "▁▅█");
+        assert_eq!(sparkline(&[5, 5]), "▅▅");
+    }
+
+    #[test]
+    fn test_history_cap_and_persistence() -> Result<()> {
+        let temp = TempDir::new()?;
+        let path = temp.path().join("diagnose_history.json");
+
+        let mut history = DiagnoseHistory::default();
+        for i in 0..(MAX_SNAPSHOTS as u64 + 5) {
+            history.push(DiagnoseSnapshot::new(vec![category("rust", i)]));
+        }
+        assert_eq!(history.snapshots.len(), MAX_SNAPSHOTS);
+        assert_eq!(history.snapshots[0].total_size, 5);
+
+        history.save_to(&path)?;
+        let loaded = DiagnoseHistory::load_from(&path)?;
+        assert_eq!(loaded.snapshots.len(), MAX_SNAPSHOTS);
+        assert_eq!(loaded.recent_totals(3), vec![102, 103, 104]);
+
+        Ok(())
+    }
+}
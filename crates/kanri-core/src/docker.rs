@@ -1,6 +1,7 @@
 use std::process::Command;
+use std::time::SystemTime;
 
-use crate::{Error, Result};
+use crate::{utils, Error, Result};
 
 /// Docker システム情報
 #[derive(Debug, Clone)]
@@ -9,20 +10,33 @@ pub struct DockerInfo {
     pub reclaimable: String,
 }
 
+impl DockerInfo {
+    /// 削除可能なサイズをバイト単位で取得
+    ///
+    /// reclaimable は "X.X GB" のような形式なので、数値部分を GB としてパースする
+    pub fn reclaimable_bytes(&self) -> Option<u64> {
+        let size_gb = self.reclaimable.split_whitespace().next()?.parse::<f64>().ok()?;
+        Some((size_gb * 1024.0 * 1024.0 * 1024.0) as u64)
+    }
+}
+
+/// Docker CLI のバージョン（例: "Docker version 24.0.7, build afdd53b"。インストールされていなければ None）
+pub fn version() -> Option<String> {
+    utils::tool_version(Command::new("docker").arg("--version"))
+}
+
 /// Docker がインストールされているかチェック
 pub fn is_docker_installed() -> bool {
-    Command::new("docker")
-        .arg("--version")
-        .output()
+    utils::run_command_with_timeout(Command::new("docker").arg("--version"), utils::command_timeout())
         .map(|output| output.status.success())
         .unwrap_or(false)
 }
 
 /// Docker デーモンが起動しているかチェック
+///
+/// デーモンが応答しない場合もタイムアウトで「起動していない」とみなす
 pub fn is_docker_running() -> bool {
-    Command::new("docker")
-        .arg("info")
-        .output()
+    utils::run_command_with_timeout(Command::new("docker").arg("info"), utils::command_timeout())
         .map(|output| output.status.success())
         .unwrap_or(false)
 }
@@ -41,15 +55,13 @@ pub fn get_system_info() -> Result<DockerInfo> {
         ));
     }
 
-    let output = Command::new("docker")
-        .arg("system")
-        .arg("df")
-        .output()?;
+    let output = utils::run_command_with_timeout(
+        Command::new("docker").arg("system").arg("df"),
+        utils::command_timeout(),
+    )?;
 
     if !output.status.success() {
-        return Err(Error::InvalidPath(
-            "Docker システム情報の取得に失敗しました".to_string(),
-        ));
+        return Err(Error::external_tool("docker system df", &output));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -74,11 +86,97 @@ pub fn get_system_info() -> Result<DockerInfo> {
     Ok(DockerInfo { reclaimable })
 }
 
+/// Docker を最後に使った日時（取得できなければ None）
+///
+/// Docker はイメージの最終使用日時を記録しないため、コンテナの作成日時（= イメージを実行した日時）と
+/// イメージの作成日時のうち最も新しいものを使う
+pub fn last_used() -> Option<SystemTime> {
+    let queries: [&[&str]; 2] = [
+        &["ps", "--all", "--format", "{{.CreatedAt}}"],
+        &["image", "ls", "--format", "{{.CreatedAt}}"],
+    ];
+
+    queries
+        .iter()
+        .filter_map(|args| {
+            let output = utils::run_command_with_timeout(
+                Command::new("docker").args(*args),
+                utils::command_timeout(),
+            )
+            .ok()?;
+            output.status.success().then_some(output)
+        })
+        .flat_map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(parse_created_at)
+                .collect::<Vec<_>>()
+        })
+        .max()
+}
+
+/// `docker ps` / `docker image ls` の CreatedAt（"2024-01-15 10:30:00 +0900 JST"）をパース
+fn parse_created_at(value: &str) -> Option<SystemTime> {
+    // 末尾のタイムゾーン略称は chrono で解釈できないので、オフセットまでを使う
+    let mut parts = value.split_whitespace();
+    let date_time = format!("{} {} {}", parts.next()?, parts.next()?, parts.next()?);
+    let parsed = chrono::DateTime::parse_from_str(&date_time, "%Y-%m-%d %H:%M:%S %z").ok()?;
+    Some(parsed.into())
+}
+
+/// "7d" や "168h" のような期間を Docker の `--filter until=` に渡せる形式（Go の duration）に変換
+///
+/// 単位は s / m / h / d / w で、"1d12h" のように組み合わせられる。
+/// Go の duration は日・週を扱えないため、時間（割り切れなければ分・秒）に換算する
+pub fn parse_until(value: &str) -> Result<String> {
+    let invalid = || {
+        Error::Config(format!(
+            "Invalid duration '{}' (expected e.g. 168h, 7d, 2w, 1d12h)",
+            value
+        ))
+    };
+
+    let mut seconds: u64 = 0;
+    let mut number = String::new();
+    for c in value.trim().chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => 24 * 60 * 60,
+            'w' => 7 * 24 * 60 * 60,
+            _ => return Err(invalid()),
+        };
+        let n: u64 = number.parse().map_err(|_| invalid())?;
+        seconds = n
+            .checked_mul(unit)
+            .and_then(|s| seconds.checked_add(s))
+            .ok_or_else(invalid)?;
+        number.clear();
+    }
+    if !number.is_empty() || seconds == 0 {
+        return Err(invalid());
+    }
+
+    Ok(if seconds.is_multiple_of(3600) {
+        format!("{}h", seconds / 3600)
+    } else if seconds.is_multiple_of(60) {
+        format!("{}m", seconds / 60)
+    } else {
+        format!("{}s", seconds)
+    })
+}
+
 /// Docker システムをクリーンアップ（未使用データを削除）
 ///
 /// `all`: true の場合、使用されていないイメージもすべて削除
 /// `volumes`: true の場合、ボリュームも削除
-pub fn clean_system(all: bool, volumes: bool) -> Result<String> {
+/// `until`: 指定した場合、その期間より前に作成されたものだけを削除（`parse_until` の形式）
+pub fn clean_system(all: bool, volumes: bool, until: Option<&str>) -> Result<String> {
     if !is_docker_installed() {
         return Err(Error::InvalidPath(
             "Docker がインストールされていません".to_string(),
@@ -101,24 +199,117 @@ pub fn clean_system(all: bool, volumes: bool) -> Result<String> {
         args.push("--volumes");
     }
 
+    let filter = until.map(|until| format!("until={}", until));
+    if let Some(filter) = &filter {
+        args.push("--filter");
+        args.push(filter);
+    }
+
     let output = Command::new("docker").args(&args).output()?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(Error::InvalidPath(format!(
-            "Docker クリーンアップに失敗しました: {}",
-            stderr
-        )));
+        return Err(Error::external_tool("docker system prune", &output));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.to_string())
+}
+
+/// dangling イメージ（`<none>` タグのイメージ）だけを削除
+///
+/// 停止中のコンテナやボリュームには触れない（`docker image prune --force`）。
+/// `until` を指定した場合、その期間より前に作成されたものだけを削除
+pub fn prune_dangling_images(until: Option<&str>) -> Result<String> {
+    if !is_docker_installed() {
+        return Err(Error::InvalidPath(
+            "Docker がインストールされていません".to_string(),
+        ));
+    }
+
+    if !is_docker_running() {
+        return Err(Error::InvalidPath(
+            "Docker デーモンが起動していません".to_string(),
+        ));
+    }
+
+    let mut command = Command::new("docker");
+    command.args(["image", "prune", "--force"]);
+    if let Some(until) = until {
+        command.arg("--filter").arg(format!("until={}", until));
+    }
+    let output = command.output()?;
+
+    if !output.status.success() {
+        return Err(Error::external_tool("docker image prune", &output));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     Ok(stdout.to_string())
 }
 
+/// prune コマンドの出力（"Total reclaimed space: 1.2GB"）から解放されたバイト数を取得
+pub fn parse_reclaimed_space(output: &str) -> Option<u64> {
+    let size = output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Total reclaimed space:"))?
+        .trim();
+
+    let split = size.find(|c: char| !(c.is_ascii_digit() || c == '.'))?;
+    let (number, unit) = size.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let multiplier = match unit {
+        "B" => 1.0,
+        "kB" | "KB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        "TB" => 1e12,
+        _ => return None,
+    };
+    Some((number * multiplier) as u64)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_reclaimed_space() {
+        let output = "Deleted Images:\ndeleted: sha256:abc\n\nTotal reclaimed space: 1.5GB\n";
+        assert_eq!(parse_reclaimed_space(output), Some(1_500_000_000));
+        assert_eq!(parse_reclaimed_space("Total reclaimed space: 0B"), Some(0));
+        assert_eq!(parse_reclaimed_space("Total reclaimed space: 12.3kB"), Some(12_300));
+        assert_eq!(parse_reclaimed_space("nothing to prune"), None);
+    }
+
+    #[test]
+    fn test_parse_until() {
+        assert_eq!(parse_until("168h").unwrap(), "168h");
+        assert_eq!(parse_until("7d").unwrap(), "168h");
+        assert_eq!(parse_until("2w").unwrap(), "336h");
+        assert_eq!(parse_until("1d12h").unwrap(), "36h");
+        assert_eq!(parse_until("90m").unwrap(), "90m");
+        assert_eq!(parse_until("45s").unwrap(), "45s");
+
+        assert!(parse_until("").is_err());
+        assert!(parse_until("7").is_err());
+        assert!(parse_until("0d").is_err());
+        assert!(parse_until("d").is_err());
+        assert!(parse_until("7y").is_err());
+        assert!(parse_until("-7d").is_err());
+    }
+
+    #[test]
+    fn test_parse_created_at() {
+        let parsed = parse_created_at("2024-01-15 10:30:00 +0900 JST").unwrap();
+        let expected: SystemTime = chrono::DateTime::parse_from_rfc3339("2024-01-15T01:30:00Z")
+            .unwrap()
+            .into();
+        assert_eq!(parsed, expected);
+
+        assert_eq!(parse_created_at("3 days ago"), None);
+        assert_eq!(parse_created_at(""), None);
+    }
+
     #[test]
     fn test_is_docker_installed() {
         // このテストは環境依存なので、インストール状態だけチェック
@@ -0,0 +1,269 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    cleanable::{Cleanable, CleanableItem, CleanableMetadata},
+    utils, Result,
+};
+
+/// .NET プロジェクト情報
+#[derive(Debug, Clone)]
+pub struct DotnetProject {
+    /// プロジェクトのルートディレクトリ（*.csproj / *.sln があるディレクトリ）
+    pub root: PathBuf,
+    /// bin ディレクトリのパス
+    pub bin_dir: PathBuf,
+    /// obj ディレクトリのパス
+    pub obj_dir: PathBuf,
+    /// bin ディレクトリのサイズ（バイト）
+    pub bin_size: u64,
+    /// obj ディレクトリのサイズ（バイト）
+    pub obj_size: u64,
+}
+
+impl DotnetProject {
+    /// 合計サイズ（バイト）
+    pub fn size(&self) -> u64 {
+        self.bin_size + self.obj_size
+    }
+
+    /// サイズを人間が読みやすい形式で取得
+    pub fn formatted_size(&self) -> String {
+        utils::format_size(self.size())
+    }
+}
+
+/// NuGet グローバルパッケージキャッシュ情報
+#[derive(Debug, Clone)]
+pub struct NugetCache {
+    /// キャッシュディレクトリのパス
+    pub cache_dir: PathBuf,
+    /// サイズ（バイト）
+    pub size: u64,
+}
+
+/// .NET のプロジェクト / ソリューションファイルか確認
+fn is_project_file(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("csproj" | "fsproj" | "vbproj" | "sln")
+    )
+}
+
+/// ディレクトリに .NET のプロジェクトファイルがあるか確認
+fn has_project_file(dir: &Path) -> bool {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .any(|e| {
+                    e.file_type().map(|t| t.is_file()).unwrap_or(false)
+                        && is_project_file(&e.path())
+                })
+        })
+        .unwrap_or(false)
+}
+
+/// 指定されたディレクトリ以下の .NET プロジェクトを検索
+pub fn find_dotnet_projects(search_path: &Path) -> Result<Vec<DotnetProject>> {
+    let mut projects: Vec<DotnetProject> = Vec::new();
+
+    let entries = utils::walk_entries(utils::walk_dir(search_path), |_| false, |e| {
+        let file_name = e.file_name().to_string_lossy();
+        match file_name.as_ref() {
+            "target" | ".git" | "node_modules" | ".cache" => false,
+            // プロジェクトの bin/obj と判明したディレクトリには降りない
+            "bin" | "obj" => !e
+                .path()
+                .parent()
+                .map(has_project_file)
+                .unwrap_or(false),
+            _ => true,
+        }
+    });
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        if !(entry.file_type().is_file() && is_project_file(entry.path())) {
+            continue;
+        }
+
+        let Some(project_root) = entry.path().parent() else {
+            continue;
+        };
+
+        // 同じディレクトリに複数のプロジェクトファイルがある場合の重複を避ける
+        if projects.iter().any(|p| p.root == project_root) {
+            continue;
+        }
+
+        let bin_dir = project_root.join("bin");
+        let obj_dir = project_root.join("obj");
+
+        // bin または obj が存在する場合のみ追加
+        if bin_dir.exists() || obj_dir.exists() {
+            let bin_size = if bin_dir.exists() {
+                utils::calculate_dir_size(&bin_dir)?
+            } else {
+                0
+            };
+
+            let obj_size = if obj_dir.exists() {
+                utils::calculate_dir_size(&obj_dir)?
+            } else {
+                0
+            };
+
+            projects.push(DotnetProject {
+                root: project_root.to_path_buf(),
+                bin_dir,
+                obj_dir,
+                bin_size,
+                obj_size,
+            });
+        }
+    }
+
+    Ok(projects)
+}
+
+/// NuGet グローバルパッケージキャッシュを検索
+pub fn find_nuget_cache() -> Result<Option<NugetCache>> {
+    // NUGET_PACKAGES 環境変数を確認
+    let cache_dir = if let Ok(packages) = env::var("NUGET_PACKAGES") {
+        PathBuf::from(packages)
+    } else if let Ok(home) = env::var("HOME") {
+        PathBuf::from(home).join(".nuget").join("packages")
+    } else {
+        return Ok(None);
+    };
+
+    if !cache_dir.exists() {
+        return Ok(None);
+    }
+
+    let size = utils::calculate_dir_size(&cache_dir)?;
+
+    Ok(Some(NugetCache { cache_dir, size }))
+}
+
+/// .NET プロジェクトの bin/obj を削除
+pub fn clean_project(project: &DotnetProject) -> Result<()> {
+    if project.bin_dir.exists() {
+        fs::remove_dir_all(&project.bin_dir)?;
+    }
+    if project.obj_dir.exists() {
+        fs::remove_dir_all(&project.obj_dir)?;
+    }
+    Ok(())
+}
+
+/// .NET クリーナー
+pub struct DotnetCleaner {
+    pub search_path: PathBuf,
+    /// NuGet グローバルキャッシュも対象にするか
+    pub include_global: bool,
+}
+
+impl DotnetCleaner {
+    pub fn new(search_path: PathBuf) -> Self {
+        Self {
+            search_path,
+            include_global: false,
+        }
+    }
+
+    pub fn with_global(mut self, include_global: bool) -> Self {
+        self.include_global = include_global;
+        self
+    }
+}
+
+impl Cleanable for DotnetCleaner {
+    fn scan(&self) -> Result<Vec<CleanableItem>> {
+        let mut items = Vec::new();
+
+        // 削除単位は bin / obj ディレクトリごと
+        for project in find_dotnet_projects(&self.search_path)? {
+            if project.bin_dir.exists() {
+                items.push(CleanableItem::new(
+                    format!("{} (bin)", project.root.display()),
+                    project.bin_dir,
+                    project.bin_size,
+                ));
+            }
+            if project.obj_dir.exists() {
+                items.push(CleanableItem::new(
+                    format!("{} (obj)", project.root.display()),
+                    project.obj_dir,
+                    project.obj_size,
+                ));
+            }
+        }
+
+        if self.include_global {
+            if let Some(cache) = find_nuget_cache()? {
+                // 全プロジェクトで共有されるため、削除すると再ダウンロードが必要になる
+                items.push(CleanableItem::with_metadata(
+                    "NuGet packages".to_string(),
+                    cache.cache_dir,
+                    cache.size,
+                    CleanableMetadata::needs_review(),
+                ));
+            }
+        }
+
+        Ok(items)
+    }
+
+    fn name(&self) -> &str {
+        ".NET"
+    }
+
+    fn icon(&self) -> &str {
+        "🟣"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_find_dotnet_projects() -> Result<()> {
+        let temp = TempDir::new()?;
+        let solution_dir = temp.path().join("MySolution");
+        fs::create_dir(&solution_dir)?;
+        fs::write(solution_dir.join("MySolution.sln"), "")?;
+
+        // ソリューション内の複数プロジェクト
+        for name in ["App", "Lib"] {
+            let project_dir = solution_dir.join(name);
+            fs::create_dir_all(project_dir.join("bin").join("Debug"))?;
+            fs::create_dir_all(project_dir.join("obj"))?;
+            fs::write(project_dir.join(format!("{}.csproj", name)), "<Project />")?;
+            fs::write(project_dir.join("bin").join("Debug").join("app.dll"), "dll")?;
+            fs::write(project_dir.join("obj").join("project.assets.json"), "{}")?;
+        }
+
+        // bin 配下のプロジェクトファイルは検出されない
+        let nested = solution_dir.join("App").join("bin").join("Debug");
+        fs::write(nested.join("Copied.csproj"), "")?;
+
+        let mut projects = find_dotnet_projects(temp.path())?;
+        projects.sort_by(|a, b| a.root.cmp(&b.root));
+
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects[0].root, solution_dir.join("App"));
+        assert!(projects[0].bin_size > 0);
+        assert!(projects[0].obj_size > 0);
+        assert_eq!(projects[1].root, solution_dir.join("Lib"));
+
+        clean_project(&projects[0])?;
+        assert!(!projects[0].bin_dir.exists());
+        assert!(!projects[0].obj_dir.exists());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,187 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::{
+    cleanable::{Cleanable, CleanableItem},
+    large_files, utils, Result,
+};
+
+/// デフォルトで対象にする経過日数（これより古いファイルを表示する）
+pub const DEFAULT_OLDER_THAN_DAYS: u64 = 30;
+
+/// ダウンロードフォルダのファイル情報
+#[derive(Debug, Clone)]
+pub struct DownloadItem {
+    /// ファイルのパス
+    pub path: PathBuf,
+    /// サイズ（バイト）
+    pub size: u64,
+    /// 最終更新日時
+    pub modified: Option<SystemTime>,
+}
+
+/// ~/Downloads のパス（HOME が未設定の場合は None）
+pub fn default_downloads_dir() -> Option<PathBuf> {
+    std::env::var("HOME")
+        .ok()
+        .map(|home| PathBuf::from(home).join("Downloads"))
+}
+
+/// `now` から見て `days` 日より前に更新されたか（`days` が 0 の場合は常に true）
+fn older_than(modified: Option<SystemTime>, days: u64, now: SystemTime) -> bool {
+    if days == 0 {
+        return true;
+    }
+    let threshold = now
+        .checked_sub(Duration::from_secs(days * 24 * 60 * 60))
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+    // 更新日時が取れないファイルは古いかどうか判断できないため対象外
+    modified.is_some_and(|modified| modified < threshold)
+}
+
+/// ダウンロードフォルダから古い・大きいファイルを検索（サイズの大きい順）
+///
+/// large_files と同じ探索（拡張子フィルタ含む）をファイルのみに絞り、更新日時で絞り込む
+pub fn find_old_downloads(
+    dir: &Path,
+    older_than_days: u64,
+    min_size: u64,
+    extensions: Option<&[String]>,
+) -> Result<Vec<DownloadItem>> {
+    let now = SystemTime::now();
+
+    Ok(large_files::find_large_items(dir, min_size, extensions, false, true)?
+        .into_iter()
+        .map(|item| DownloadItem {
+            modified: std::fs::metadata(&item.path).and_then(|m| m.modified()).ok(),
+            path: item.path,
+            size: item.size,
+        })
+        .filter(|item| older_than(item.modified, older_than_days, now))
+        .collect())
+}
+
+/// ダウンロードフォルダクリーナー
+pub struct DownloadsCleaner {
+    pub dir: PathBuf,
+    /// この日数より前に更新されたファイルのみ対象（0 で日数を問わない）
+    pub older_than_days: u64,
+    /// 最小サイズ（バイト）
+    pub min_size: u64,
+    pub extensions: Option<Vec<String>>,
+}
+
+impl DownloadsCleaner {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            older_than_days: DEFAULT_OLDER_THAN_DAYS,
+            min_size: 0,
+            extensions: None,
+        }
+    }
+
+    pub fn with_older_than(mut self, days: u64) -> Self {
+        self.older_than_days = days;
+        self
+    }
+
+    pub fn with_min_size(mut self, min_size: u64) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    pub fn with_extensions(mut self, extensions: Vec<String>) -> Self {
+        self.extensions = Some(extensions);
+        self
+    }
+}
+
+impl Cleanable for DownloadsCleaner {
+    fn scan(&self) -> Result<Vec<CleanableItem>> {
+        let items = find_old_downloads(
+            &self.dir,
+            self.older_than_days,
+            self.min_size,
+            self.extensions.as_deref(),
+        )?;
+
+        Ok(items
+            .into_iter()
+            .map(|item| {
+                let name = match item.modified {
+                    Some(modified) => format!(
+                        "{} ({})",
+                        item.path.display(),
+                        utils::format_age(modified)
+                    ),
+                    None => item.path.display().to_string(),
+                };
+                CleanableItem::new(name, item.path, item.size)
+            })
+            .collect())
+    }
+
+    fn name(&self) -> &str {
+        "Downloads"
+    }
+
+    fn icon(&self) -> &str {
+        "📥"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_older_than() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(100 * 24 * 60 * 60);
+        let days_ago = |days: u64| Some(now - Duration::from_secs(days * 24 * 60 * 60));
+
+        assert!(older_than(days_ago(31), 30, now));
+        assert!(!older_than(days_ago(29), 30, now));
+        assert!(!older_than(None, 30, now));
+        // 0 日なら日数を問わない
+        assert!(older_than(days_ago(0), 0, now));
+        assert!(older_than(None, 0, now));
+    }
+
+    #[test]
+    fn test_find_old_downloads() -> Result<()> {
+        let temp = TempDir::new()?;
+        let old_dmg = temp.path().join("Installer.dmg");
+        fs::write(&old_dmg, vec![0u8; 100])?;
+        let month_ago = SystemTime::now() - Duration::from_secs(60 * 24 * 60 * 60);
+        fs::File::options()
+            .write(true)
+            .open(&old_dmg)?
+            .set_modified(month_ago)?;
+        // 最近のファイルは対象外
+        fs::write(temp.path().join("today.zip"), vec![0u8; 100])?;
+
+        let items = find_old_downloads(temp.path(), 30, 0, None)?;
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].path, old_dmg);
+        assert_eq!(items[0].size, 100);
+
+        // 日数を問わなければサイズ・拡張子だけで絞り込む
+        let extensions = vec![".zip".to_string()];
+        let items = find_old_downloads(temp.path(), 0, 50, Some(&extensions))?;
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].path, temp.path().join("today.zip"));
+        assert!(find_old_downloads(temp.path(), 0, 200, None)?.is_empty());
+
+        // 削除はディレクトリではなくファイルとして行う
+        let cleaner = DownloadsCleaner::new(temp.path().to_path_buf());
+        let items = cleaner.scan()?;
+        cleaner.clean(&items, &mut |_| {})?;
+        assert!(!old_dmg.exists());
+        assert!(temp.path().join("today.zip").exists());
+
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 use std::io;
+use std::process::Output;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -23,6 +24,87 @@ pub enum Error {
 
     #[error("Archive error: {0}")]
     Archive(String),
+
+    #[error("Command timed out after {timeout:?}: {command}")]
+    Timeout {
+        command: String,
+        timeout: std::time::Duration,
+    },
+
+    #[error("`{tool}` failed{}: {}", exit_status(*.code), .stderr.trim())]
+    ExternalTool {
+        /// 実行したコマンド（例: "docker system prune"）
+        tool: String,
+        /// 終了コード（シグナルで終了した場合は None）
+        code: Option<i32>,
+        /// 標準エラー出力
+        stderr: String,
+    },
+}
+
+impl Error {
+    /// 失敗した外部コマンドの実行結果から `ExternalTool` エラーを作成
+    pub fn external_tool(tool: impl Into<String>, output: &Output) -> Self {
+        Error::ExternalTool {
+            tool: tool.into(),
+            code: output.status.code(),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        }
+    }
+
+    /// エラーの種類（JSON 出力などで機械的に判別するための識別子）
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::Io(_) => "io",
+            Error::WalkDir(_) => "walk_dir",
+            Error::InvalidPath(_) => "invalid_path",
+            Error::Cancelled => "cancelled",
+            Error::Config(_) => "config",
+            Error::B2(_) => "b2",
+            Error::Archive(_) => "archive",
+            Error::Timeout { .. } => "timeout",
+            Error::ExternalTool { .. } => "external_tool",
+        }
+    }
+}
+
+fn exit_status(code: Option<i32>) -> String {
+    match code {
+        Some(code) => format!(" with exit code {}", code),
+        None => " (terminated by signal)".to_string(),
+    }
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_external_tool_error() {
+        let output = std::process::Command::new("sh")
+            .args(["-c", "echo 'no space left' >&2; exit 3"])
+            .output()
+            .unwrap();
+
+        let err = Error::external_tool("docker system prune", &output);
+        assert!(matches!(
+            &err,
+            Error::ExternalTool { tool, code: Some(3), stderr }
+                if tool == "docker system prune" && stderr == "no space left"
+        ));
+        assert_eq!(
+            err.to_string(),
+            "`docker system prune` failed with exit code 3: no space left"
+        );
+        assert_eq!(err.kind(), "external_tool");
+    }
+
+    #[test]
+    fn test_kind() {
+        assert_eq!(Error::Config("bad".into()).kind(), "config");
+        assert_eq!(Error::Cancelled.kind(), "cancelled");
+        assert_eq!(Error::from(io::Error::other("disk")).kind(), "io");
+    }
+}
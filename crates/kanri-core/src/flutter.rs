@@ -1,6 +1,5 @@
 use std::fs;
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
 
 use crate::{cleanable::{Cleanable, CleanableItem}, utils, Result};
 
@@ -13,6 +12,8 @@ pub struct FlutterProject {
     pub build_dir: PathBuf,
     /// .dart_tool ディレクトリのパス
     pub dart_tool_dir: PathBuf,
+    /// 存在するビルド成果物ディレクトリ（build, .dart_tool）とそのサイズ
+    pub artifacts: Vec<(PathBuf, u64)>,
     /// 合計サイズ（バイト）
     pub size: u64,
 }
@@ -38,44 +39,37 @@ impl FlutterProject {
 pub fn find_flutter_projects(search_path: &Path) -> Result<Vec<FlutterProject>> {
     let mut projects = Vec::new();
 
-    for entry in WalkDir::new(search_path)
-        .into_iter()
-        .filter_entry(|e| {
-            // target, .git, node_modules, build などの大きなディレクトリはスキップ
-            let file_name = e.file_name().to_string_lossy();
-            !matches!(
-                file_name.as_ref(),
-                "target" | ".git" | "node_modules" | ".cache" | "build" | ".dart_tool"
-            )
-        })
-        .filter_map(|e| e.ok())
-    {
+    let entries = utils::walk_entries(utils::walk_dir(search_path), |_| false, |e| {
+        // target, .git, node_modules, build などの大きなディレクトリはスキップ
+        let file_name = e.file_name().to_string_lossy();
+        !matches!(
+            file_name.as_ref(),
+            "target" | ".git" | "node_modules" | ".cache" | "build" | ".dart_tool"
+        )
+    });
+
+    for entry in entries.filter_map(|e| e.ok()) {
         if entry.file_type().is_file() && entry.file_name() == "pubspec.yaml" {
             if let Some(project_root) = entry.path().parent() {
                 let build_dir = project_root.join("build");
                 let dart_tool_dir = project_root.join(".dart_tool");
 
-                // build または .dart_tool が存在する場合のみ追加
-                if build_dir.exists() || dart_tool_dir.exists() {
-                    let build_size = if build_dir.exists() {
-                        utils::calculate_dir_size(&build_dir)?
-                    } else {
-                        0
-                    };
-
-                    let dart_tool_size = if dart_tool_dir.exists() {
-                        utils::calculate_dir_size(&dart_tool_dir)?
-                    } else {
-                        0
-                    };
-
-                    let total_size = build_size + dart_tool_size;
+                let mut artifacts = Vec::new();
+                for dir in [&build_dir, &dart_tool_dir] {
+                    if dir.exists() {
+                        artifacts.push((dir.clone(), utils::calculate_dir_size(dir)?));
+                    }
+                }
 
+                // build または .dart_tool が存在する場合のみ追加
+                if !artifacts.is_empty() {
+                    let size = artifacts.iter().map(|(_, size)| size).sum();
                     projects.push(FlutterProject {
                         root: project_root.to_path_buf(),
                         build_dir,
                         dart_tool_dir,
-                        size: total_size,
+                        artifacts,
+                        size,
                     });
                 }
             }
@@ -96,18 +90,6 @@ pub fn clean_project(project: &FlutterProject) -> Result<()> {
     Ok(())
 }
 
-/// 複数の Flutter プロジェクトをクリーン
-pub fn clean_projects(projects: &[FlutterProject]) -> Result<Vec<PathBuf>> {
-    let mut cleaned = Vec::new();
-
-    for project in projects {
-        clean_project(project)?;
-        cleaned.push(project.root.clone());
-    }
-
-    Ok(cleaned)
-}
-
 /// Flutter プロジェクトクリーナー
 pub struct FlutterCleaner {
     pub search_path: PathBuf,
@@ -122,11 +104,24 @@ impl FlutterCleaner {
 impl Cleanable for FlutterCleaner {
     fn scan(&self) -> Result<Vec<CleanableItem>> {
         let projects = find_flutter_projects(&self.search_path)?;
+        let mut items = Vec::new();
+
+        // 削除単位はビルド成果物ディレクトリごと（プロジェクトのルート自体は削除しない）
+        for project in projects {
+            for (dir, size) in project.artifacts {
+                let dir_name = dir
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                items.push(CleanableItem::new(
+                    format!("{} ({})", project.root.display(), dir_name),
+                    dir,
+                    size,
+                ));
+            }
+        }
 
-        Ok(projects
-            .into_iter()
-            .map(|p| CleanableItem::new(p.root.display().to_string(), p.root.clone(), p.size))
-            .collect())
+        Ok(items)
     }
 
     fn name(&self) -> &str {
@@ -196,6 +191,7 @@ version: 1.0.0"#,
             root: project_dir.clone(),
             build_dir: build_dir.clone(),
             dart_tool_dir: dart_tool_dir.clone(),
+            artifacts: Vec::new(),
             size: 100,
         };
 
@@ -209,4 +205,35 @@ version: 1.0.0"#,
 
         Ok(())
     }
+
+    #[test]
+    fn test_flutter_cleaner_clean() -> Result<()> {
+        let temp = TempDir::new()?;
+        let project_dir = temp.path().join("app");
+        fs::create_dir_all(project_dir.join("build"))?;
+        fs::create_dir_all(project_dir.join(".dart_tool"))?;
+        fs::write(project_dir.join("pubspec.yaml"), "name: app")?;
+        fs::write(project_dir.join("build").join("app.apk"), vec![0u8; 200])?;
+        fs::write(project_dir.join(".dart_tool").join("cache"), vec![0u8; 50])?;
+
+        // 項目のパスは実際に削除するディレクトリ
+        let cleaner = FlutterCleaner::new(temp.path().to_path_buf());
+        let items = cleaner.scan()?;
+        let mut paths: Vec<(PathBuf, u64)> = items.iter().map(|item| (item.path.clone(), item.size)).collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![(project_dir.join(".dart_tool"), 50), (project_dir.join("build"), 200)]
+        );
+
+        let mut reported = 0;
+        let cleaned = cleaner.clean(&items, &mut |item| reported += item.size)?;
+        assert_eq!(cleaned.len(), 2);
+        assert_eq!(reported, 250);
+        assert!(project_dir.join("pubspec.yaml").exists());
+        assert!(!project_dir.join("build").exists());
+        assert!(!project_dir.join(".dart_tool").exists());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,493 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
+use google_cloud_storage::client::google_cloud_auth::credentials::CredentialsFile;
+use google_cloud_storage::client::{Client, ClientConfig};
+use google_cloud_storage::http::objects::delete::DeleteObjectRequest;
+use google_cloud_storage::http::objects::download::Range;
+use google_cloud_storage::http::objects::get::GetObjectRequest;
+use google_cloud_storage::http::objects::list::ListObjectsRequest;
+use google_cloud_storage::http::objects::upload::{Media, UploadObjectRequest, UploadType};
+use google_cloud_storage::http::objects::Object;
+use google_cloud_storage::http::resumable_upload_client::{ChunkSize, UploadStatus};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::runtime::Runtime;
+
+use crate::{
+    b2::{collect_upload_tasks, UploadTask},
+    storage::{RemoteFile, UploadProgress},
+    Result, StorageClient,
+};
+
+/// GCS のオブジェクトを一覧の項目に変換
+fn remote_file(object: Object) -> RemoteFile {
+    RemoteFile {
+        path: object.name,
+        size: u64::try_from(object.size).ok(),
+        modified: object
+            .updated
+            .and_then(|t| DateTime::from_timestamp(t.unix_timestamp(), t.nanosecond())),
+    }
+}
+
+/// ページ単位の一覧取得を、次のページのトークンがなくなるまで繰り返す
+///
+/// `fetch` はページトークン（最初は None）を受け取り、(項目, 次のページのトークン) を返す
+fn collect_pages<T>(
+    mut fetch: impl FnMut(Option<String>) -> Result<(Vec<T>, Option<String>)>,
+) -> Result<Vec<T>> {
+    let mut items = Vec::new();
+    let mut page_token = None;
+    loop {
+        let (page, next_page_token) = fetch(page_token)?;
+        items.extend(page);
+        match next_page_token {
+            Some(token) if !token.is_empty() => page_token = Some(token),
+            _ => return Ok(items),
+        }
+    }
+}
+
+/// 同期でアップロードが必要か（リモートに同じサイズで、ローカル以降に更新されたファイルがあれば不要）
+fn needs_upload(task: &UploadTask, local_modified: Option<SystemTime>, remote: Option<&RemoteFile>) -> bool {
+    let Some(remote) = remote else {
+        return true;
+    };
+    if remote.size != Some(task.size) {
+        return true;
+    }
+    match (local_modified, remote.modified) {
+        (Some(local), Some(remote)) => DateTime::<Utc>::from(local) > remote,
+        _ => true,
+    }
+}
+
+/// この大きさを超えるファイルは、再開可能なアップロードでチャンクごとに送る
+const RESUMABLE_UPLOAD_THRESHOLD: u64 = 64 * 1024 * 1024;
+
+/// 再開可能なアップロードのチャンクサイズ（GCS の制約で 256 KiB の倍数）
+const RESUMABLE_CHUNK_SIZE: u64 = 32 * 1024 * 1024;
+
+/// チャンクの送信に続けて失敗した場合に、送信済みの位置から再開する回数
+const RESUMABLE_CHUNK_RETRIES: u32 = 3;
+
+/// `offset` から送るチャンクの範囲（`size` はファイル全体のサイズ）
+fn chunk_range(offset: u64, size: u64) -> ChunkSize {
+    let last_byte = (offset + RESUMABLE_CHUNK_SIZE).min(size) - 1;
+    ChunkSize::new(offset, last_byte, Some(size))
+}
+
+/// google-cloud-storage のエラーを変換
+fn gcs_error(context: &'static str) -> impl Fn(google_cloud_storage::http::Error) -> crate::Error {
+    move |e| crate::Error::B2(format!("{}: {}", context, e))
+}
+
+/// Google Cloud Storage クライアント（gcloud / gsutil 不要）
+///
+/// サービスアカウントの JSON キーで認証する。API は非同期なので、専用のランタイムで同期的に実行する
+pub struct GcsClient {
+    /// サービスアカウントの JSON キーのパス
+    credentials_file: PathBuf,
+    /// health_check で接続を確認するバケット
+    bucket: String,
+    runtime: Runtime,
+    /// 認証済みのクライアント（最初に使うときに作成）
+    client: Mutex<Option<Client>>,
+}
+
+impl GcsClient {
+    pub fn new(credentials_file: PathBuf, bucket: String) -> Result<Self> {
+        if bucket.is_empty() {
+            return Err(crate::Error::Config("GCS bucket is empty".into()));
+        }
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .enable_all()
+            .build()?;
+        Ok(Self {
+            credentials_file,
+            bucket,
+            runtime,
+            client: Mutex::new(None),
+        })
+    }
+
+    /// 認証済みのクライアントを取得（初回は認証情報を読み込んで作成）
+    fn client(&self) -> Result<Client> {
+        let mut client = self.client.lock().unwrap();
+        if let Some(client) = client.as_ref() {
+            return Ok(client.clone());
+        }
+
+        let credentials_file = self.credentials_file.to_string_lossy().to_string();
+        let config = self
+            .runtime
+            .block_on(async {
+                let credentials = CredentialsFile::new_from_file(credentials_file).await?;
+                ClientConfig::default().with_credentials(credentials).await
+            })
+            .map_err(|e| {
+                crate::Error::Config(format!(
+                    "Failed to load GCS credentials from {}: {}",
+                    self.credentials_file.display(),
+                    e
+                ))
+            })?;
+
+        let created = Client::new(config);
+        *client = Some(created.clone());
+        Ok(created)
+    }
+
+    /// 1 ページ分のファイル一覧を取得
+    fn list_page(
+        &self,
+        client: &Client,
+        bucket: &str,
+        prefix: &str,
+        page_token: Option<String>,
+    ) -> Result<(Vec<RemoteFile>, Option<String>)> {
+        let request = ListObjectsRequest {
+            bucket: bucket.to_string(),
+            prefix: (!prefix.is_empty()).then(|| prefix.to_string()),
+            page_token,
+            ..Default::default()
+        };
+        let response = self
+            .runtime
+            .block_on(client.list_objects(&request))
+            .map_err(gcs_error("Failed to list files"))?;
+        let files = response.items.unwrap_or_default().into_iter().map(remote_file).collect();
+        Ok((files, response.next_page_token))
+    }
+
+    /// 再開可能なアップロードでファイルをチャンクごとに送り、チャンクごとに進捗を通知する
+    ///
+    /// チャンクの送信に失敗した場合は、GCS が受け取った位置を確認してそこから送り直す
+    fn upload_resumable(
+        &self,
+        client: &Client,
+        bucket: &str,
+        local_path: &Path,
+        remote_path: &str,
+        size: u64,
+        progress: UploadProgress,
+    ) -> Result<Object> {
+        self.runtime.block_on(async {
+            let request = UploadObjectRequest {
+                bucket: bucket.to_string(),
+                ..Default::default()
+            };
+            let uploader = client
+                .prepare_resumable_upload(&request, &UploadType::Simple(Media::new(remote_path.to_string())))
+                .await
+                .map_err(gcs_error("Failed to start resumable upload"))?;
+            let mut file = tokio::fs::File::open(local_path)
+                .await
+                .map_err(|e| crate::Error::B2(format!("Failed to open file: {}", e)))?;
+
+            let mut offset = 0;
+            let mut failures = 0;
+            loop {
+                let range = chunk_range(offset, size);
+                let mut chunk = vec![0u8; range.size() as usize];
+                file.seek(std::io::SeekFrom::Start(offset)).await?;
+                file.read_exact(&mut chunk).await?;
+
+                let status = match uploader.upload_multiple_chunk(chunk, &range).await {
+                    Ok(status) => {
+                        failures = 0;
+                        status
+                    }
+                    Err(e) if failures < RESUMABLE_CHUNK_RETRIES => {
+                        failures += 1;
+                        // 受け取った位置を確認できなければ、元のエラーを返す
+                        match uploader.status(Some(size)).await {
+                            Ok(status) => status,
+                            Err(_) => return Err(gcs_error("Failed to upload file")(e)),
+                        }
+                    }
+                    Err(e) => return Err(gcs_error("Failed to upload file")(e)),
+                };
+                offset = match status {
+                    UploadStatus::Ok(object) => return Ok(object),
+                    UploadStatus::ResumeIncomplete(uploaded) => uploaded.last_byte + 1,
+                    UploadStatus::NotStarted => 0,
+                };
+                if let Some(on_progress) = progress {
+                    on_progress(offset);
+                }
+            }
+        })
+    }
+
+    /// アップロード済みのバイト数を通知しながら、ファイルを順番にアップロード
+    fn upload_tasks(&self, bucket: &str, tasks: &[UploadTask], progress: UploadProgress) -> Result<Vec<String>> {
+        let mut uploaded = Vec::new();
+        let mut uploaded_bytes = 0;
+
+        for task in tasks {
+            uploaded.push(self.upload_file(bucket, &task.local_path, &task.remote_path, None)?);
+
+            uploaded_bytes += task.size;
+            if let Some(on_progress) = progress {
+                on_progress(uploaded_bytes);
+            }
+        }
+
+        Ok(uploaded)
+    }
+}
+
+impl StorageClient for GcsClient {
+    fn authorize(&self) -> Result<()> {
+        self.client().map(|_| ())
+    }
+
+    fn health_check(&self) -> Result<()> {
+        let client = self.client()?;
+        // 1 件だけ一覧を取得して、認証とバケットへのアクセスを確認する
+        let request = ListObjectsRequest {
+            bucket: self.bucket.clone(),
+            max_results: Some(1),
+            ..Default::default()
+        };
+        self.runtime
+            .block_on(client.list_objects(&request))
+            .map_err(gcs_error("Failed to access GCS bucket"))?;
+        Ok(())
+    }
+
+    fn upload_file(
+        &self,
+        bucket: &str,
+        local_path: &Path,
+        remote_path: &str,
+        progress: UploadProgress,
+    ) -> Result<String> {
+        let client = self.client()?;
+        let size = std::fs::metadata(local_path)?.len();
+
+        // 大きなファイルは途中で失敗しても最初から送り直さずに済むよう、チャンクごとに送る
+        if size > RESUMABLE_UPLOAD_THRESHOLD {
+            let object = self.upload_resumable(&client, bucket, local_path, remote_path, size, progress)?;
+            if let Some(on_progress) = progress {
+                on_progress(size);
+            }
+            return Ok(format!("gs://{}/{}", bucket, object.name));
+        }
+
+        let object = self.runtime.block_on(async {
+            let file = tokio::fs::File::open(local_path)
+                .await
+                .map_err(|e| crate::Error::B2(format!("Failed to open file: {}", e)))?;
+            let mut media = Media::new(remote_path.to_string());
+            media.content_length = Some(size);
+            let request = UploadObjectRequest {
+                bucket: bucket.to_string(),
+                ..Default::default()
+            };
+            client
+                .upload_object(&request, file, &UploadType::Simple(media))
+                .await
+                .map_err(gcs_error("Failed to upload file"))
+        })?;
+
+        // 1 回のリクエストで送るため、進捗は完了時にまとめて通知する
+        if let Some(on_progress) = progress {
+            on_progress(size);
+        }
+
+        Ok(format!("gs://{}/{}", bucket, object.name))
+    }
+
+    fn upload_directory(
+        &self,
+        bucket: &str,
+        local_dir: &Path,
+        remote_prefix: &str,
+        progress: UploadProgress,
+    ) -> Result<Vec<String>> {
+        let tasks = collect_upload_tasks(local_dir, remote_prefix)?;
+        self.upload_tasks(bucket, &tasks, progress)
+    }
+
+    fn sync_directory(&self, bucket: &str, local_dir: &Path, remote_prefix: &str) -> Result<()> {
+        let remote = self.list_files_detailed(bucket, remote_prefix)?;
+        let tasks: Vec<UploadTask> = collect_upload_tasks(local_dir, remote_prefix)?
+            .into_iter()
+            .filter(|task| {
+                let local_modified = std::fs::metadata(&task.local_path).and_then(|m| m.modified()).ok();
+                let existing = remote.iter().find(|file| file.path == task.remote_path);
+                needs_upload(task, local_modified, existing)
+            })
+            .collect();
+
+        self.upload_tasks(bucket, &tasks, None).map(|_| ())
+    }
+
+    fn download_file_by_name(
+        &self,
+        bucket: &str,
+        remote_path: &str,
+        local_path: &Path,
+    ) -> Result<()> {
+        let client = self.client()?;
+
+        // 親ディレクトリを作成
+        if let Some(parent) = local_path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| crate::Error::B2(format!("Failed to create parent directory: {}", e)))?;
+        }
+
+        let request = GetObjectRequest {
+            bucket: bucket.to_string(),
+            object: remote_path.to_string(),
+            ..Default::default()
+        };
+        self.runtime.block_on(async {
+            let stream = client
+                .download_streamed_object(&request, &Range::default())
+                .await
+                .map_err(gcs_error("Failed to download file"))?;
+            let mut stream = std::pin::pin!(stream);
+            let mut file = tokio::fs::File::create(local_path)
+                .await
+                .map_err(|e| crate::Error::B2(format!("Failed to create file: {}", e)))?;
+            while let Some(chunk) = stream.next().await {
+                let chunk = chunk.map_err(gcs_error("Failed to download file"))?;
+                file.write_all(&chunk).await?;
+            }
+            file.flush().await?;
+            Ok(())
+        })
+    }
+
+    fn download_directory(&self, bucket: &str, remote_prefix: &str, local_dir: &Path) -> Result<()> {
+        let prefix = format!("{}/", remote_prefix.trim_end_matches('/'));
+
+        std::fs::create_dir_all(local_dir)
+            .map_err(|e| crate::Error::B2(format!("Failed to create directory: {}", e)))?;
+
+        for file in self.list_files_detailed(bucket, &prefix)? {
+            let Some(relative) = file.path.strip_prefix(&prefix) else {
+                continue;
+            };
+            // ディレクトリのプレースホルダー（末尾が /）は飛ばす
+            if relative.is_empty() || relative.ends_with('/') {
+                continue;
+            }
+            self.download_file_by_name(bucket, &file.path, &local_dir.join(relative))?;
+        }
+
+        Ok(())
+    }
+
+    fn list_files_detailed(&self, bucket: &str, prefix: &str) -> Result<Vec<RemoteFile>> {
+        let client = self.client()?;
+        collect_pages(|page_token| self.list_page(&client, bucket, prefix, page_token))
+    }
+
+    fn delete_file(&self, bucket: &str, remote_path: &str) -> Result<()> {
+        let client = self.client()?;
+        let request = DeleteObjectRequest {
+            bucket: bucket.to_string(),
+            object: remote_path.to_string(),
+            ..Default::default()
+        };
+        self.runtime
+            .block_on(client.delete_object(&request))
+            .map_err(gcs_error("Failed to delete file"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_remote_file() {
+        let object = Object {
+            name: "backups/a.tar.gz".to_string(),
+            size: 1024,
+            updated: Some(time::OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap()),
+            ..Default::default()
+        };
+        let file = remote_file(object);
+        assert_eq!(file.path, "backups/a.tar.gz");
+        assert_eq!(file.size, Some(1024));
+        assert_eq!(file.modified, DateTime::from_timestamp(1_700_000_000, 0));
+
+        // サイズが負の値（不正なレスポンス）なら不明扱い
+        let file = remote_file(Object { size: -1, ..Default::default() });
+        assert_eq!(file.size, None);
+        assert_eq!(file.modified, None);
+    }
+
+    #[test]
+    fn test_collect_pages() -> Result<()> {
+        let pages = [
+            (vec![1, 2], Some("p2")),
+            (vec![3], Some("p3")),
+            (vec![4, 5], None),
+        ];
+        let mut requested = Vec::new();
+        let items = collect_pages(|token| {
+            let index = requested.len();
+            requested.push(token);
+            let (items, next) = &pages[index];
+            Ok((items.clone(), next.map(str::to_string)))
+        })?;
+        assert_eq!(items, vec![1, 2, 3, 4, 5]);
+        assert_eq!(requested, vec![None, Some("p2".to_string()), Some("p3".to_string())]);
+
+        // 空のトークンは最後のページとみなす
+        let items = collect_pages(|_| Ok((vec![1], Some(String::new()))))?;
+        assert_eq!(items, vec![1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_chunk_range() {
+        // チャンクサイズは GCS の制約（256 KiB の倍数）を満たす
+        assert_eq!(RESUMABLE_CHUNK_SIZE % (256 * 1024), 0);
+
+        let size = RESUMABLE_CHUNK_SIZE * 2 + 10;
+        assert_eq!(chunk_range(0, size).to_string(), format!("bytes 0-{}/{}", RESUMABLE_CHUNK_SIZE - 1, size));
+        assert_eq!(chunk_range(0, size).size(), RESUMABLE_CHUNK_SIZE);
+        // 最後のチャンクは残りのバイト数だけ
+        let last = chunk_range(RESUMABLE_CHUNK_SIZE * 2, size);
+        assert_eq!(last.size(), 10);
+        assert_eq!(last.to_string(), format!("bytes {}-{}/{}", RESUMABLE_CHUNK_SIZE * 2, size - 1, size));
+    }
+
+    #[test]
+    fn test_needs_upload() {
+        let now = SystemTime::now();
+        let task = UploadTask {
+            local_path: PathBuf::from("a.txt"),
+            remote_path: "prefix/a.txt".to_string(),
+            size: 10,
+        };
+        let remote = |size: u64, modified: SystemTime| RemoteFile {
+            path: task.remote_path.clone(),
+            size: Some(size),
+            modified: Some(modified.into()),
+        };
+
+        assert!(needs_upload(&task, Some(now), None));
+        assert!(!needs_upload(&task, Some(now), Some(&remote(10, now))));
+        assert!(needs_upload(&task, Some(now), Some(&remote(11, now))));
+        // ローカルの方が新しければアップロードし直す
+        assert!(needs_upload(
+            &task,
+            Some(now),
+            Some(&remote(10, now - Duration::from_secs(60)))
+        ));
+        assert!(needs_upload(&task, None, Some(&remote(10, now))));
+    }
+}
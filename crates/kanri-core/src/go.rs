@@ -1,10 +1,12 @@
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 use crate::{
-    cleanable::{Cleanable, CleanableItem},
-    utils, Result,
+    cleanable::{Cleanable, CleanableItem, CleanableMetadata},
+    utils::{self, DirSource},
+    Result,
 };
 
 /// Go モジュールキャッシュ情報
@@ -14,18 +16,38 @@ pub struct GoModCache {
     pub cache_dir: PathBuf,
     /// サイズ（バイト）
     pub size: u64,
+    /// キャッシュディレクトリの決まり方（GOMODCACHE・GOPATH・既定のパス）
+    pub source: DirSource,
+}
+
+/// Go のバージョン（例: "go version go1.22.0 darwin/arm64"。インストールされていなければ None）
+pub fn version() -> Option<String> {
+    utils::tool_version(Command::new("go").arg("version"))
+}
+
+/// Go モジュールキャッシュのディレクトリ（GOMODCACHE > GOPATH/pkg/mod > ~/go/pkg/mod）
+pub fn go_mod_cache_dir() -> Option<PathBuf> {
+    resolve_go_mod_cache_dir().map(|(dir, _)| dir)
+}
+
+/// Go モジュールキャッシュのディレクトリと、その決まり方
+pub fn resolve_go_mod_cache_dir() -> Option<(PathBuf, DirSource)> {
+    resolve_go_mod_cache_dir_with(|var| env::var(var).ok())
+}
+
+fn resolve_go_mod_cache_dir_with(var: impl Fn(&str) -> Option<String>) -> Option<(PathBuf, DirSource)> {
+    if let Some(gomodcache) = var("GOMODCACHE") {
+        Some((PathBuf::from(gomodcache), DirSource::Env("GOMODCACHE")))
+    } else if let Some(gopath) = var("GOPATH") {
+        Some((PathBuf::from(gopath).join("pkg").join("mod"), DirSource::Env("GOPATH")))
+    } else {
+        var("HOME").map(|home| (PathBuf::from(home).join("go").join("pkg").join("mod"), DirSource::Default))
+    }
 }
 
 /// Go モジュールキャッシュを検索
 pub fn find_go_mod_cache() -> Result<Option<GoModCache>> {
-    // GOMODCACHE 環境変数を確認
-    let cache_dir = if let Ok(gomodcache) = env::var("GOMODCACHE") {
-        PathBuf::from(gomodcache)
-    } else if let Ok(gopath) = env::var("GOPATH") {
-        PathBuf::from(gopath).join("pkg").join("mod")
-    } else if let Ok(home) = env::var("HOME") {
-        PathBuf::from(home).join("go").join("pkg").join("mod")
-    } else {
+    let Some((cache_dir, source)) = resolve_go_mod_cache_dir() else {
         return Ok(None);
     };
 
@@ -35,7 +57,7 @@ pub fn find_go_mod_cache() -> Result<Option<GoModCache>> {
 
     let size = utils::calculate_dir_size(&cache_dir)?;
 
-    Ok(Some(GoModCache { cache_dir, size }))
+    Ok(Some(GoModCache { cache_dir, size, source }))
 }
 
 /// Go モジュールキャッシュを削除
@@ -46,12 +68,59 @@ pub fn clean_mod_cache(cache: &GoModCache) -> Result<()> {
     Ok(())
 }
 
+/// モジュールキャッシュを内訳ごとの項目に分ける
+///
+/// `cache/` 以下（download・vcs など）はサブディレクトリごと、
+/// 展開済みのモジュール（github.com など）はホストごとに 1 項目にする
+fn scan_mod_cache_detailed(cache_dir: &Path) -> Result<Vec<CleanableItem>> {
+    let mut items = Vec::new();
+
+    for (name, path, size) in utils::subdir_sizes(cache_dir)? {
+        if name == "cache" {
+            for (sub_name, sub_path, sub_size) in utils::subdir_sizes(&path)? {
+                items.push(CleanableItem::with_metadata(
+                    format!("Go module cache: cache/{}", sub_name),
+                    sub_path,
+                    sub_size,
+                    CleanableMetadata::safe(),
+                ));
+            }
+        } else {
+            items.push(CleanableItem::with_metadata(
+                format!("Go modules: {}", name),
+                path,
+                size,
+                CleanableMetadata::safe(),
+            ));
+        }
+    }
+
+    Ok(items)
+}
+
 /// Go クリーナー
-pub struct GoCleaner;
+pub struct GoCleaner {
+    /// モジュールキャッシュを内訳ごとの項目に分けるか
+    pub detailed: bool,
+}
 
 impl GoCleaner {
     pub fn new() -> Self {
-        Self
+        Self { detailed: false }
+    }
+
+    /// モジュールキャッシュ全体ではなく、内訳（cache/download・展開済みモジュールなど）ごとに表示する
+    pub fn with_detailed(mut self, detailed: bool) -> Self {
+        self.detailed = detailed;
+        self
+    }
+
+    /// モジュールキャッシュを内訳ごとの項目としてスキャン
+    pub fn scan_detailed(&self) -> Result<Vec<CleanableItem>> {
+        match go_mod_cache_dir() {
+            Some(cache_dir) if cache_dir.exists() => scan_mod_cache_detailed(&cache_dir),
+            _ => Ok(Vec::new()),
+        }
     }
 }
 
@@ -63,11 +132,16 @@ impl Default for GoCleaner {
 
 impl Cleanable for GoCleaner {
     fn scan(&self) -> Result<Vec<CleanableItem>> {
+        if self.detailed {
+            return self.scan_detailed();
+        }
         if let Some(cache) = find_go_mod_cache()? {
-            Ok(vec![CleanableItem::new(
+            // 全プロジェクトで共有されるが、次回のビルドで再ダウンロードされるだけなので安全
+            Ok(vec![CleanableItem::with_metadata(
                 "Go module cache".to_string(),
                 cache.cache_dir,
                 cache.size,
+                CleanableMetadata::safe(),
             )])
         } else {
             Ok(Vec::new())
@@ -78,6 +152,10 @@ impl Cleanable for GoCleaner {
         "Go"
     }
 
+    fn resolved_dir(&self) -> Option<(PathBuf, DirSource)> {
+        resolve_go_mod_cache_dir()
+    }
+
     fn icon(&self) -> &str {
         "🐹"
     }
@@ -93,4 +171,46 @@ mod tests {
         let result = find_go_mod_cache();
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_resolve_go_mod_cache_dir() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())
+        };
+
+        assert_eq!(
+            resolve_go_mod_cache_dir_with(env(&[("GOMODCACHE", "/cache"), ("GOPATH", "/gopath")])),
+            Some((PathBuf::from("/cache"), DirSource::Env("GOMODCACHE")))
+        );
+        assert_eq!(
+            resolve_go_mod_cache_dir_with(env(&[("GOPATH", "/gopath"), ("HOME", "/home/me")])),
+            Some((PathBuf::from("/gopath/pkg/mod"), DirSource::Env("GOPATH")))
+        );
+        assert_eq!(
+            resolve_go_mod_cache_dir_with(env(&[("HOME", "/home/me")])),
+            Some((PathBuf::from("/home/me/go/pkg/mod"), DirSource::Default))
+        );
+        assert_eq!(resolve_go_mod_cache_dir_with(env(&[])), None);
+        assert_eq!(DirSource::Env("GOPATH").to_string(), "環境変数 GOPATH");
+    }
+
+    #[test]
+    fn test_scan_mod_cache_detailed() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let download = temp.path().join("cache").join("download");
+        let module = temp.path().join("github.com").join("foo").join("bar@v1.0.0");
+        fs::create_dir_all(&download)?;
+        fs::create_dir_all(&module)?;
+        fs::write(download.join("bar.zip"), vec![0u8; 100])?;
+        fs::write(module.join("go.mod"), vec![0u8; 10])?;
+
+        let items = scan_mod_cache_detailed(temp.path())?;
+        let summary: Vec<(&str, u64)> = items.iter().map(|i| (i.name.as_str(), i.size)).collect();
+        assert_eq!(
+            summary,
+            vec![("Go module cache: cache/download", 100), ("Go modules: github.com", 10)]
+        );
+        assert!(items.iter().all(|item| item.is_safe()));
+        Ok(())
+    }
 }
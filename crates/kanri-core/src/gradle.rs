@@ -1,10 +1,14 @@
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use crate::{
-    cleanable::{Cleanable, CleanableItem},
-    utils, Result,
+    cleanable::{Cleanable, CleanableItem, CleanableMetadata},
+    utils::{self, DirSource},
+    Result,
 };
 
 /// Gradle キャッシュ情報
@@ -14,16 +18,74 @@ pub struct GradleCache {
     pub cache_dir: PathBuf,
     /// サイズ（バイト）
     pub size: u64,
+    /// Gradle のユーザーホームの決まり方（GRADLE_USER_HOME・既定のパス）
+    pub source: DirSource,
 }
 
-/// Gradle キャッシュを検索
-pub fn find_gradle_cache() -> Result<Option<GradleCache>> {
-    // GRADLE_USER_HOME 環境変数を確認
-    let cache_dir = if let Ok(gradle_home) = env::var("GRADLE_USER_HOME") {
-        PathBuf::from(gradle_home)
-    } else if let Ok(home) = env::var("HOME") {
-        PathBuf::from(home).join(".gradle")
+/// 実行中の Gradle デーモン
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GradleDaemon {
+    /// プロセス ID
+    pub pid: u32,
+    /// Gradle のバージョン（`~/.gradle/daemon/<version>`）
+    pub version: String,
+}
+
+/// Gradle のバージョン（例: "Gradle 8.5"。インストールされていなければ None）
+///
+/// `gradle --version` は区切り線や JVM の情報も出力するため、"Gradle " で始まる行を使う
+pub fn version() -> Option<String> {
+    parse_gradle_version(&utils::tool_output(Command::new("gradle").arg("--version"))?)
+}
+
+fn parse_gradle_version(output: &str) -> Option<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with("Gradle "))
+        .map(str::to_string)
+}
+
+/// Gradle のユーザーホーム（GRADLE_USER_HOME または ~/.gradle）
+pub fn gradle_user_home() -> Option<PathBuf> {
+    resolve_gradle_user_home().map(|(dir, _)| dir)
+}
+
+/// Gradle のユーザーホームと、その決まり方
+pub fn resolve_gradle_user_home() -> Option<(PathBuf, DirSource)> {
+    resolve_gradle_user_home_with(|var| env::var(var).ok())
+}
+
+fn resolve_gradle_user_home_with(var: impl Fn(&str) -> Option<String>) -> Option<(PathBuf, DirSource)> {
+    if let Some(gradle_home) = var("GRADLE_USER_HOME") {
+        Some((PathBuf::from(gradle_home), DirSource::Env("GRADLE_USER_HOME")))
     } else {
+        var("HOME").map(|home| (PathBuf::from(home).join(".gradle"), DirSource::Default))
+    }
+}
+
+/// 対象にするディレクトリ（`caches_only` の場合は `<gradle home>/caches`）と、その決まり方
+fn resolve_gradle_cache_dir(caches_only: bool) -> Option<(PathBuf, DirSource)> {
+    let (gradle_home, source) = resolve_gradle_user_home()?;
+    let dir = if caches_only {
+        gradle_home.join("caches")
+    } else {
+        gradle_home
+    };
+    Some((dir, source))
+}
+
+/// 対象にするディレクトリ（`caches_only` の場合は `<gradle home>/caches`）
+fn gradle_cache_dir(caches_only: bool) -> Option<PathBuf> {
+    resolve_gradle_cache_dir(caches_only).map(|(dir, _)| dir)
+}
+
+/// Gradle キャッシュを検索
+///
+/// `caches_only`: true の場合は `caches` ディレクトリのみを対象にする
+/// （ラッパーのディストリビューションなどは残すため、再ダウンロードが減る）
+pub fn find_gradle_cache(caches_only: bool) -> Result<Option<GradleCache>> {
+    let Some((cache_dir, source)) = resolve_gradle_cache_dir(caches_only) else {
         return Ok(None);
     };
 
@@ -33,7 +95,7 @@ pub fn find_gradle_cache() -> Result<Option<GradleCache>> {
 
     let size = utils::calculate_dir_size(&cache_dir)?;
 
-    Ok(Some(GradleCache { cache_dir, size }))
+    Ok(Some(GradleCache { cache_dir, size, source }))
 }
 
 /// Gradle キャッシュを削除
@@ -44,12 +106,246 @@ pub fn clean_gradle_cache(cache: &GradleCache) -> Result<()> {
     Ok(())
 }
 
+/// Gradle のユーザーホームにある、キャッシュではないユーザー設定（削除すると復元できない）
+const USER_CONFIG_ENTRIES: &[&str] = &["gradle.properties", "init.d", "init.gradle", "init.gradle.kts"];
+
+/// ユーザー設定を含むなら要確認、キャッシュだけなら安全
+fn user_config_metadata(gradle_home: &Path) -> CleanableMetadata {
+    let found: Vec<&str> = USER_CONFIG_ENTRIES
+        .iter()
+        .copied()
+        .filter(|name| gradle_home.join(name).exists())
+        .collect();
+    if found.is_empty() {
+        return CleanableMetadata::safe();
+    }
+    CleanableMetadata {
+        is_safe: Some(false),
+        safety_label: Some(format!("⚠ ユーザー設定（{}）を含む", found.join("・"))),
+    }
+}
+
+/// デーモンのログファイル名（"daemon-12345.out.log"）からプロセス ID を取得
+fn parse_daemon_log_pid(file_name: &str) -> Option<u32> {
+    file_name
+        .strip_prefix("daemon-")?
+        .strip_suffix(".out.log")?
+        .parse()
+        .ok()
+}
+
+/// `<gradle home>/daemon/<version>/daemon-<pid>.out.log` から実行中のデーモンを検出
+///
+/// 古いログの PID が別のプロセスに再利用されている場合があるため、コマンドラインも確かめる
+pub fn find_running_daemons(gradle_home: &Path) -> Vec<GradleDaemon> {
+    let Ok(versions) = fs::read_dir(gradle_home.join("daemon")) else {
+        return Vec::new();
+    };
+
+    let mut daemons: Vec<GradleDaemon> = versions
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .flat_map(|version_dir| {
+            let version = version_dir.file_name().to_string_lossy().into_owned();
+            fs::read_dir(version_dir.path())
+                .into_iter()
+                .flatten()
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| parse_daemon_log_pid(&entry.file_name().to_string_lossy()))
+                .filter(|&pid| is_process_running(pid) && is_gradle_daemon(pid))
+                .map(|pid| GradleDaemon {
+                    pid,
+                    version: version.clone(),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    daemons.sort_by_key(|daemon| daemon.pid);
+    daemons
+}
+
+/// デーモンを停止するまで待つ時間
+const DAEMON_STOP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Gradle デーモンを停止
+///
+/// `gradle --stop` はインストールされているバージョンのデーモンしか止めないため、
+/// 残ったデーモンには SIGTERM を送り、終了するまで待つ
+pub fn stop_daemons(daemons: &[GradleDaemon]) -> Result<()> {
+    if daemons.is_empty() {
+        return Ok(());
+    }
+
+    // gradle がない・失敗した場合もシグナルで停止できるので結果は無視する
+    let _ = utils::run_command_with_timeout(
+        Command::new("gradle").arg("--stop"),
+        utils::command_timeout(),
+    );
+
+    // 検出してから PID が再利用されていれば、別のプロセスなので送らない
+    let is_running_daemon = |daemon: &GradleDaemon| is_process_running(daemon.pid) && is_gradle_daemon(daemon.pid);
+    for daemon in daemons {
+        if is_running_daemon(daemon) {
+            terminate_process(daemon.pid)?;
+        }
+    }
+
+    let deadline = Instant::now() + DAEMON_STOP_TIMEOUT;
+    while let Some(daemon) = daemons.iter().find(|d| is_running_daemon(d)) {
+        if Instant::now() >= deadline {
+            return Err(std::io::Error::other(format!(
+                "Gradle daemon {} (pid {}) did not stop",
+                daemon.version, daemon.pid
+            ))
+            .into());
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+
+    Ok(())
+}
+
+/// Gradle デーモンのメインクラス（`org.gradle.launcher.daemon.bootstrap.GradleDaemon`）
+const DAEMON_MAIN_CLASS: &str = "GradleDaemon";
+
+/// プロセスが Gradle デーモンか（コマンドラインにデーモンのメインクラスを含むか）
+fn is_gradle_daemon(pid: u32) -> bool {
+    process_command_line(pid).is_some_and(|command_line| command_line.contains(DAEMON_MAIN_CLASS))
+}
+
+/// プロセスのコマンドライン（Linux は /proc、それ以外は ps で取得。取得できなければ None）
+fn process_command_line(pid: u32) -> Option<String> {
+    if let Ok(cmdline) = fs::read(format!("/proc/{}/cmdline", pid)) {
+        return Some(String::from_utf8_lossy(&cmdline).replace('\0', " "));
+    }
+    utils::tool_output(Command::new("ps").args(["-o", "command=", "-p", &pid.to_string()]))
+        .filter(|output| !output.trim().is_empty())
+}
+
+/// プロセスが実行中か
+#[cfg(unix)]
+fn is_process_running(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: シグナル 0 は存在確認のみで、プロセスには何も送らない
+    if unsafe { libc::kill(pid, 0) } == 0 {
+        return true;
+    }
+    // 権限がないだけでプロセスは存在する
+    std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn is_process_running(_pid: u32) -> bool {
+    false
+}
+
+/// プロセスに SIGTERM を送る
+#[cfg(unix)]
+fn terminate_process(pid: u32) -> Result<()> {
+    let pid = libc::pid_t::try_from(pid).map_err(std::io::Error::other)?;
+    // SAFETY: pid は実行中の Gradle デーモンのプロセス ID
+    if unsafe { libc::kill(pid, libc::SIGTERM) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn terminate_process(_pid: u32) -> Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "Stopping Gradle daemons is not supported on this platform",
+    )
+    .into())
+}
+
 /// Gradle クリーナー
-pub struct GradleCleaner;
+pub struct GradleCleaner {
+    /// `caches` ディレクトリのみを対象にするか
+    pub caches_only: bool,
+    /// 削除前に実行中のデーモンを停止するか
+    pub stop_daemons: bool,
+    /// サブディレクトリ（caches・wrapper・daemon など）ごとの項目に分けるか
+    pub detailed: bool,
+}
 
 impl GradleCleaner {
     pub fn new() -> Self {
-        Self
+        Self {
+            caches_only: false,
+            stop_daemons: false,
+            detailed: false,
+        }
+    }
+
+    /// 全体ではなくサブディレクトリ（caches・wrapper・daemon など）ごとに表示する
+    ///
+    /// `caches_only` と組み合わせた場合は `caches` 以下のサブディレクトリごとになる
+    pub fn with_detailed(mut self, detailed: bool) -> Self {
+        self.detailed = detailed;
+        self
+    }
+
+    /// サブディレクトリごとの項目としてスキャン
+    pub fn scan_detailed(&self) -> Result<Vec<CleanableItem>> {
+        let Some(cache_dir) = gradle_cache_dir(self.caches_only).filter(|dir| dir.exists()) else {
+            return Ok(Vec::new());
+        };
+        let prefix = if self.caches_only {
+            "Gradle caches"
+        } else {
+            "Gradle"
+        };
+        let daemon_warning = self.daemon_warning();
+
+        Ok(utils::subdir_sizes(&cache_dir)?
+            .into_iter()
+            .map(|(name, path, size)| {
+                let metadata = match &daemon_warning {
+                    Some(warning) => warning.clone(),
+                    // init スクリプトはユーザーが置いたもので、再ダウンロードでは戻らない
+                    None if !self.caches_only && name == "init.d" => CleanableMetadata {
+                        is_safe: Some(false),
+                        safety_label: Some("⚠ ユーザー設定（init スクリプト）".to_string()),
+                    },
+                    // caches・wrapper・daemon などは次回のビルドで作り直される
+                    None => CleanableMetadata::safe(),
+                };
+                CleanableItem::with_metadata(format!("{}: {}", prefix, name), path, size, metadata)
+            })
+            .collect())
+    }
+
+    /// デーモンの実行中に削除すると状態が壊れるため、停止しない場合は要確認にして理由を示す
+    fn daemon_warning(&self) -> Option<CleanableMetadata> {
+        if self.stop_daemons {
+            return None;
+        }
+        let daemons = gradle_user_home()
+            .map(|home| find_running_daemons(&home))
+            .unwrap_or_default();
+        (!daemons.is_empty()).then(|| CleanableMetadata {
+            is_safe: Some(false),
+            safety_label: Some(format!(
+                "⚠ Gradle デーモン {} 件が実行中（--daemon で停止してから削除）",
+                daemons.len()
+            )),
+        })
+    }
+
+    /// `~/.gradle` 全体ではなく `~/.gradle/caches` のみを対象にする
+    pub fn with_caches_only(mut self, caches_only: bool) -> Self {
+        self.caches_only = caches_only;
+        self
+    }
+
+    /// 削除前に実行中の Gradle デーモンを停止する
+    pub fn with_stop_daemons(mut self, stop_daemons: bool) -> Self {
+        self.stop_daemons = stop_daemons;
+        self
     }
 }
 
@@ -61,34 +357,158 @@ impl Default for GradleCleaner {
 
 impl Cleanable for GradleCleaner {
     fn scan(&self) -> Result<Vec<CleanableItem>> {
-        if let Some(cache) = find_gradle_cache()? {
-            Ok(vec![CleanableItem::new(
-                "Gradle cache".to_string(),
-                cache.cache_dir,
-                cache.size,
-            )])
-        } else {
-            Ok(Vec::new())
+        if self.detailed {
+            return self.scan_detailed();
         }
+        let Some(cache) = find_gradle_cache(self.caches_only)? else {
+            return Ok(Vec::new());
+        };
+
+        let name = if self.caches_only {
+            "Gradle caches"
+        } else {
+            "Gradle cache"
+        };
+        // キャッシュは次回のビルドで再ダウンロードされるだけだが、
+        // Gradle ホーム全体の場合は gradle.properties などのユーザー設定も消えるので要確認にする
+        let metadata = self.daemon_warning().unwrap_or_else(|| {
+            if self.caches_only {
+                CleanableMetadata::safe()
+            } else {
+                user_config_metadata(&cache.cache_dir)
+            }
+        });
+
+        Ok(vec![CleanableItem::with_metadata(
+            name.to_string(),
+            cache.cache_dir,
+            cache.size,
+            metadata,
+        )])
     }
 
     fn name(&self) -> &str {
         "Gradle"
     }
 
+    fn resolved_dir(&self) -> Option<(PathBuf, DirSource)> {
+        resolve_gradle_cache_dir(self.caches_only)
+    }
+
     fn icon(&self) -> &str {
         "🐘"
     }
+
+    fn clean(
+        &self,
+        items: &[CleanableItem],
+        on_cleaned: &mut dyn FnMut(&CleanableItem),
+    ) -> Result<Vec<String>> {
+        if self.stop_daemons && !items.is_empty() {
+            if let Some(home) = gradle_user_home() {
+                stop_daemons(&find_running_daemons(&home))?;
+            }
+        }
+        crate::cleanable::clean_items(items, on_cleaned)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tempfile::TempDir;
 
     #[test]
     fn test_find_gradle_cache() {
         // 環境依存なので、エラーが出ないことだけ確認
-        let result = find_gradle_cache();
+        let result = find_gradle_cache(false);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_resolve_gradle_user_home() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())
+        };
+
+        assert_eq!(
+            resolve_gradle_user_home_with(env(&[("GRADLE_USER_HOME", "/opt/gradle"), ("HOME", "/home/me")])),
+            Some((PathBuf::from("/opt/gradle"), DirSource::Env("GRADLE_USER_HOME")))
+        );
+        assert_eq!(
+            resolve_gradle_user_home_with(env(&[("HOME", "/home/me")])),
+            Some((PathBuf::from("/home/me/.gradle"), DirSource::Default))
+        );
+        assert_eq!(resolve_gradle_user_home_with(env(&[])), None);
+    }
+
+    #[test]
+    fn test_parse_gradle_version() {
+        let output = "\n------------------------------------------------------------\nGradle 8.5\n------------------------------------------------------------\n\nBuild time:   2023-11-29 14:08:57 UTC\nKotlin:       1.9.20\n";
+        assert_eq!(parse_gradle_version(output), Some("Gradle 8.5".to_string()));
+        assert_eq!(parse_gradle_version("command not found"), None);
+    }
+
+    #[test]
+    fn test_user_config_metadata() -> Result<()> {
+        let temp = TempDir::new()?;
+        fs::create_dir_all(temp.path().join("caches"))?;
+        assert!(user_config_metadata(temp.path()).is_safe.unwrap());
+
+        fs::write(temp.path().join("gradle.properties"), "signing.password=secret")?;
+        fs::create_dir_all(temp.path().join("init.d"))?;
+        let metadata = user_config_metadata(temp.path());
+        assert_eq!(metadata.is_safe, Some(false));
+        assert_eq!(
+            metadata.safety_label.as_deref(),
+            Some("⚠ ユーザー設定（gradle.properties・init.d）を含む")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_daemon_log_pid() {
+        assert_eq!(parse_daemon_log_pid("daemon-12345.out.log"), Some(12345));
+        assert_eq!(parse_daemon_log_pid("registry.bin"), None);
+        assert_eq!(parse_daemon_log_pid("daemon-abc.out.log"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_running_daemons() -> Result<()> {
+        let temp = TempDir::new()?;
+        let version_dir = temp.path().join("daemon").join("8.5");
+        fs::create_dir_all(&version_dir)?;
+
+        // コマンドラインにデーモンのメインクラスを含むプロセス、
+        // Gradle デーモンではない実行中のプロセス（このテスト自身）、存在しないプロセスのログ
+        let mut daemon = Command::new("sh")
+            .args(["-c", "sleep 10; true", "org.gradle.launcher.daemon.bootstrap.GradleDaemon"])
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()?;
+        // fork 直後は exec 前の（このテストの）コマンドラインが見えるため、切り替わるまで待つ
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !is_gradle_daemon(daemon.id()) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        fs::write(version_dir.join(format!("daemon-{}.out.log", daemon.id())), "")?;
+        fs::write(version_dir.join(format!("daemon-{}.out.log", std::process::id())), "")?;
+        fs::write(version_dir.join("daemon-999999999.out.log"), "")?;
+        fs::write(version_dir.join("registry.bin"), "")?;
+
+        let found = find_running_daemons(temp.path());
+        daemon.kill()?;
+        daemon.wait()?;
+        assert_eq!(
+            found,
+            vec![GradleDaemon {
+                pid: daemon.id(),
+                version: "8.5".to_string(),
+            }]
+        );
+        assert!(!is_gradle_daemon(std::process::id()));
+        assert!(find_running_daemons(&temp.path().join("missing")).is_empty());
+        Ok(())
+    }
 }
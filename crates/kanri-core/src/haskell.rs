@@ -1,6 +1,5 @@
 use std::fs;
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
 
 use crate::{
     cleanable::{Cleanable, CleanableItem},
@@ -24,18 +23,18 @@ pub struct HaskellBuild {
 pub fn find_haskell_builds(search_path: &Path) -> Result<Vec<HaskellBuild>> {
     let mut builds = Vec::new();
 
-    for entry in WalkDir::new(search_path)
-        .into_iter()
-        .filter_entry(|e| {
-            let file_name = e.file_name().to_string_lossy();
-            // .stack-work, dist, dist-newstyle は検索対象なので除外しない
-            !matches!(
-                file_name.as_ref(),
-                "target" | ".git" | "node_modules" | ".cache"
-            )
-        })
-        .filter_map(|e| e.ok())
-    {
+    // .stack-work は検索対象なので --no-hidden でも降りる
+    let keep_hidden = |name: &str| name == ".stack-work";
+    let entries = utils::walk_entries(utils::walk_dir(search_path), keep_hidden, |e| {
+        let file_name = e.file_name().to_string_lossy();
+        // .stack-work, dist, dist-newstyle は検索対象なので除外しない
+        !matches!(
+            file_name.as_ref(),
+            "target" | ".git" | "node_modules" | ".cache"
+        )
+    });
+
+    for entry in entries.filter_map(|e| e.ok()) {
         let path = entry.path();
         let file_name = entry.file_name().to_string_lossy();
 
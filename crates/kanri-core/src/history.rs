@@ -0,0 +1,151 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::Result;
+
+/// クリーン / アーカイブの実行履歴（history.jsonl の 1 行）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// 実行日時
+    pub timestamp: DateTime<Utc>,
+    /// 実行したコマンド（例: "clean rust"）
+    pub command: String,
+    /// 削除したパス
+    pub paths: Vec<PathBuf>,
+    /// 解放したサイズ（バイト）
+    pub bytes_freed: u64,
+}
+
+impl HistoryEntry {
+    /// 現在時刻で新しい履歴エントリを作成
+    pub fn new(command: String, paths: Vec<PathBuf>, bytes_freed: u64) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            command,
+            paths,
+            bytes_freed,
+        }
+    }
+}
+
+/// 履歴の集計結果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HistorySummary {
+    /// 実行回数
+    pub runs: usize,
+    /// 解放したサイズの合計（バイト）
+    pub bytes_freed: u64,
+}
+
+/// 履歴ファイルのパスを取得
+pub fn history_path() -> Result<PathBuf> {
+    let home = std::env::var("HOME")
+        .map_err(|_| crate::Error::Config("HOME environment variable not set".into()))?;
+    Ok(PathBuf::from(home).join(".kanri").join("history.jsonl"))
+}
+
+/// 履歴ファイルにエントリを追記
+pub fn append_entry(entry: &HistoryEntry) -> Result<()> {
+    append_entry_to(&history_path()?, entry)
+}
+
+/// 指定したファイルにエントリを追記
+pub fn append_entry_to(path: &Path, entry: &HistoryEntry) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let line = serde_json::to_string(entry).map_err(|e| {
+        crate::Error::Config(format!("Failed to serialize history entry: {}", e))
+    })?;
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)?;
+
+    Ok(())
+}
+
+/// 履歴ファイルを読み込み（古い順）
+pub fn load_entries() -> Result<Vec<HistoryEntry>> {
+    load_entries_from(&history_path()?)
+}
+
+/// 指定したファイルから履歴を読み込み（壊れた行は無視）
+pub fn load_entries_from(path: &Path) -> Result<Vec<HistoryEntry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(path)?;
+
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// 指定日時以降の履歴を集計
+pub fn summarize_since(entries: &[HistoryEntry], since: DateTime<Utc>) -> HistorySummary {
+    entries
+        .iter()
+        .filter(|e| e.timestamp >= since)
+        .fold(HistorySummary::default(), |acc, e| HistorySummary {
+            runs: acc.runs + 1,
+            bytes_freed: acc.bytes_freed + e.bytes_freed,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_append_and_load_entries() -> Result<()> {
+        let temp = TempDir::new()?;
+        let path = temp.path().join(".kanri").join("history.jsonl");
+
+        append_entry_to(
+            &path,
+            &HistoryEntry::new("clean rust".to_string(), vec![PathBuf::from("/tmp/a/target")], 100),
+        )?;
+        append_entry_to(
+            &path,
+            &HistoryEntry::new("clean node".to_string(), vec![PathBuf::from("/tmp/b/node_modules")], 50),
+        )?;
+
+        // 壊れた行は無視される
+        let mut file = OpenOptions::new().append(true).open(&path)?;
+        writeln!(file, "{{not json")?;
+
+        let entries = load_entries_from(&path)?;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].command, "clean rust");
+        assert_eq!(entries[1].paths, vec![PathBuf::from("/tmp/b/node_modules")]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_summarize_since() {
+        let mut old = HistoryEntry::new("clean rust".to_string(), Vec::new(), 1000);
+        old.timestamp = Utc::now() - Duration::days(60);
+        let recent = HistoryEntry::new("clean node".to_string(), Vec::new(), 200);
+        let entries = vec![old, recent.clone(), recent];
+
+        let summary = summarize_since(&entries, Utc::now() - Duration::days(30));
+        assert_eq!(
+            summary,
+            HistorySummary {
+                runs: 2,
+                bytes_freed: 400,
+            }
+        );
+    }
+}
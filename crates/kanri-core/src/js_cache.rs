@@ -0,0 +1,209 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    cleanable::{Cleanable, CleanableItem, CleanableMetadata},
+    utils, Result,
+};
+
+/// JavaScript ツールのグローバルキャッシュ種別
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsCacheKind {
+    /// pnpm のコンテンツアドレッサブルストア
+    PnpmStore,
+    /// Bun のインストールキャッシュ
+    Bun,
+    /// Deno のキャッシュ（DENO_DIR）
+    Deno,
+}
+
+impl JsCacheKind {
+    /// 表示名
+    pub fn label(&self) -> &'static str {
+        match self {
+            JsCacheKind::PnpmStore => "pnpm store",
+            JsCacheKind::Bun => "Bun cache",
+            JsCacheKind::Deno => "Deno cache",
+        }
+    }
+
+    /// 削除が安全かどうか
+    ///
+    /// pnpm store は全プロジェクトで共有されるため、削除すると再ダウンロードが必要になる
+    pub fn is_safe(&self) -> bool {
+        !matches!(self, JsCacheKind::PnpmStore)
+    }
+}
+
+/// JavaScript グローバルキャッシュ情報
+#[derive(Debug, Clone)]
+pub struct JsCache {
+    /// キャッシュ種別
+    pub kind: JsCacheKind,
+    /// キャッシュディレクトリのパス
+    pub cache_dir: PathBuf,
+    /// サイズ（バイト）
+    pub size: u64,
+}
+
+/// 環境変数とホームディレクトリからキャッシュ候補のパスを解決
+fn resolve_cache_dirs(
+    home: &Path,
+    var: impl Fn(&str) -> Option<String>,
+) -> Vec<(JsCacheKind, PathBuf)> {
+    let mut dirs = Vec::new();
+
+    // pnpm: PNPM_STORE_DIR > XDG_DATA_HOME/pnpm/store > ~/Library/pnpm/store > ~/.local/share/pnpm/store
+    if let Some(store) = var("PNPM_STORE_DIR") {
+        dirs.push((JsCacheKind::PnpmStore, PathBuf::from(store)));
+    } else {
+        if let Some(data_home) = var("XDG_DATA_HOME") {
+            dirs.push((
+                JsCacheKind::PnpmStore,
+                PathBuf::from(data_home).join("pnpm").join("store"),
+            ));
+        }
+        dirs.push((
+            JsCacheKind::PnpmStore,
+            home.join("Library").join("pnpm").join("store"),
+        ));
+        dirs.push((
+            JsCacheKind::PnpmStore,
+            home.join(".local").join("share").join("pnpm").join("store"),
+        ));
+    }
+
+    // Bun: BUN_INSTALL_CACHE_DIR > BUN_INSTALL/install/cache > ~/.bun/install/cache
+    let bun_dir = if let Some(cache_dir) = var("BUN_INSTALL_CACHE_DIR") {
+        PathBuf::from(cache_dir)
+    } else if let Some(bun_install) = var("BUN_INSTALL") {
+        PathBuf::from(bun_install).join("install").join("cache")
+    } else {
+        home.join(".bun").join("install").join("cache")
+    };
+    dirs.push((JsCacheKind::Bun, bun_dir));
+
+    // Deno: DENO_DIR > ~/Library/Caches/deno > XDG_CACHE_HOME/deno > ~/.cache/deno
+    if let Some(deno_dir) = var("DENO_DIR") {
+        dirs.push((JsCacheKind::Deno, PathBuf::from(deno_dir)));
+    } else {
+        dirs.push((JsCacheKind::Deno, home.join("Library").join("Caches").join("deno")));
+        let cache_home = var("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(".cache"));
+        dirs.push((JsCacheKind::Deno, cache_home.join("deno")));
+    }
+
+    dirs
+}
+
+/// pnpm / Bun / Deno のグローバルキャッシュを検索
+pub fn find_js_caches() -> Result<Vec<JsCache>> {
+    let home = match env::var("HOME") {
+        Ok(home) => PathBuf::from(home),
+        Err(_) => return Ok(Vec::new()),
+    };
+
+    let mut caches: Vec<JsCache> = Vec::new();
+
+    for (kind, cache_dir) in resolve_cache_dirs(&home, |name| env::var(name).ok()) {
+        // 種別ごとに最初に見つかったディレクトリのみ採用
+        if caches.iter().any(|c| c.kind == kind) || !cache_dir.exists() {
+            continue;
+        }
+
+        let size = utils::calculate_dir_size_dedup(&cache_dir)?;
+        caches.push(JsCache {
+            kind,
+            cache_dir,
+            size,
+        });
+    }
+
+    Ok(caches)
+}
+
+/// JavaScript グローバルキャッシュクリーナー
+pub struct JsCacheCleaner;
+
+impl JsCacheCleaner {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for JsCacheCleaner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cleanable for JsCacheCleaner {
+    fn scan(&self) -> Result<Vec<CleanableItem>> {
+        Ok(find_js_caches()?
+            .into_iter()
+            .map(|c| {
+                let is_safe = c.kind.is_safe();
+                let metadata = CleanableMetadata {
+                    is_safe: Some(is_safe),
+                    safety_label: Some(if is_safe { "✓ 安全" } else { "⚠ 要確認" }.to_string()),
+                };
+                CleanableItem::with_metadata(c.kind.label().to_string(), c.cache_dir, c.size, metadata)
+            })
+            .collect())
+    }
+
+    fn name(&self) -> &str {
+        "JS global cache"
+    }
+
+    fn icon(&self) -> &str {
+        "📦"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_cache_dirs_with_env() {
+        let home = PathBuf::from("/Users/test");
+        let dirs = resolve_cache_dirs(&home, |name| match name {
+            "PNPM_STORE_DIR" => Some("/custom/pnpm".to_string()),
+            "BUN_INSTALL" => Some("/custom/bun".to_string()),
+            "DENO_DIR" => Some("/custom/deno".to_string()),
+            _ => None,
+        });
+
+        assert_eq!(
+            dirs,
+            vec![
+                (JsCacheKind::PnpmStore, PathBuf::from("/custom/pnpm")),
+                (JsCacheKind::Bun, PathBuf::from("/custom/bun/install/cache")),
+                (JsCacheKind::Deno, PathBuf::from("/custom/deno")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_cache_dirs_fallback() {
+        let home = PathBuf::from("/Users/test");
+        let dirs = resolve_cache_dirs(&home, |_| None);
+
+        assert!(dirs.contains(&(
+            JsCacheKind::PnpmStore,
+            PathBuf::from("/Users/test/Library/pnpm/store")
+        )));
+        assert!(dirs.contains(&(
+            JsCacheKind::Bun,
+            PathBuf::from("/Users/test/.bun/install/cache")
+        )));
+        assert!(dirs.contains(&(
+            JsCacheKind::Deno,
+            PathBuf::from("/Users/test/Library/Caches/deno")
+        )));
+        assert!(!JsCacheKind::PnpmStore.is_safe());
+        assert!(JsCacheKind::Bun.is_safe());
+    }
+}
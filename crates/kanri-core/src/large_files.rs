@@ -1,5 +1,4 @@
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
 
 use crate::{
     cleanable::{Cleanable, CleanableItem},
@@ -38,14 +37,14 @@ pub fn find_large_items(
         "__pycache__",
     ];
 
-    for entry in WalkDir::new(search_path)
-        .into_iter()
-        .filter_entry(|e| {
-            let file_name = e.file_name().to_string_lossy();
-            !excluded_dirs.contains(&file_name.as_ref())
-        })
-        .filter_map(|e| e.ok())
-    {
+    // ファイル・ディレクトリ自体の深さで制限する（プロジェクト探索のように 1 段深くはしない）
+    let walker = utils::walk_dir(search_path).max_depth(utils::max_depth().unwrap_or(usize::MAX));
+    let entries = utils::walk_entries(walker, |_| false, |e| {
+        let file_name = e.file_name().to_string_lossy();
+        !excluded_dirs.contains(&file_name.as_ref())
+    });
+
+    for entry in entries.filter_map(|e| e.ok()) {
         let path = entry.path();
         let metadata = match entry.metadata() {
             Ok(m) => m,
@@ -101,7 +100,7 @@ pub fn find_large_items(
     }
 
     // サイズ順にソート（大きい順）
-    items.sort_by(|a, b| b.size.cmp(&a.size));
+    items.sort_by_key(|i| std::cmp::Reverse(i.size));
 
     Ok(items)
 }
@@ -213,6 +212,30 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_find_large_files_skip_hidden() -> Result<()> {
+        let temp = TempDir::new()?;
+        let hidden_dir = temp.path().join(".cache").join("models");
+        fs::create_dir_all(&hidden_dir)?;
+        let large_file = hidden_dir.join("model.ckpt");
+        fs::File::create(&large_file)?.set_len(3 * 1024 * 1024 * 1024)?;
+
+        let find = || find_large_items(temp.path(), 2 * 1024 * 1024 * 1024, None, false, true);
+
+        // 大きなキャッシュが置かれやすいため、デフォルトでは隠しディレクトリも探索する
+        {
+            let _settings = utils::TestSettings::skip_hidden(false);
+            let items = find()?;
+            assert_eq!(items.len(), 1);
+            assert_eq!(items[0].path, large_file);
+        }
+        // --no-hidden で除外できる
+        let _settings = utils::TestSettings::skip_hidden(true);
+        assert!(find()?.is_empty());
+
+        Ok(())
+    }
+
     #[test]
     fn test_find_large_files_with_extension_filter() -> Result<()> {
         let temp = TempDir::new()?;
@@ -1,23 +1,40 @@
+pub mod android;
 pub mod archive;
 pub mod b2;
+pub mod browser_cache;
 pub mod cache;
 pub mod cleanable;
+pub mod cmake;
 pub mod config;
+pub mod custom;
+pub mod diagnose_history;
 pub mod docker;
+pub mod downloads;
+pub mod dotnet;
 pub mod error;
 pub mod flutter;
+pub mod gcs;
 pub mod go;
 pub mod gradle;
 pub mod haskell;
+pub mod history;
+pub mod js_cache;
 pub mod large_files;
+pub mod logs;
+pub mod ml;
 pub mod node;
+pub mod npm_cache;
+pub mod pip_cache;
 pub mod python;
+pub mod r;
 pub mod rclone;
 pub mod rust;
+pub mod stats;
 pub mod storage;
+pub mod swift;
 pub mod utils;
 pub mod xcode;
 
-pub use cleanable::{Cleanable, CleanableItem, CleanableMetadata};
+pub use cleanable::{Cleanable, CleanableItem, CleanableMetadata, SortKey};
 pub use error::{Error, Result};
-pub use storage::StorageClient;
+pub use storage::{RemoteFile, StorageClient, UploadProgress};
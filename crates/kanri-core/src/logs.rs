@@ -0,0 +1,291 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    cleanable::{Cleanable, CleanableItem, CleanableMetadata},
+    utils, Result,
+};
+
+/// クラッシュレポートを置くディレクトリ名（Logs 直下）
+const CRASH_REPORTS_DIR: &str = "DiagnosticReports";
+
+/// ログの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogKind {
+    /// クラッシュレポート（DiagnosticReports）。調査が済んでいれば削除しても安全
+    CrashReports,
+    /// アプリのログ。トラブルシューティングに使う可能性があるため要確認
+    AppLog,
+}
+
+/// ログディレクトリ情報
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    /// アプリ名（Logs 直下のディレクトリ名）
+    pub app: String,
+    /// ディレクトリのパス
+    pub path: PathBuf,
+    /// サイズ（バイト）
+    pub size: u64,
+    /// 種類
+    pub kind: LogKind,
+    /// システムの /Library/Logs 配下か
+    pub is_system: bool,
+}
+
+/// ログの検索先（ユーザーの ~/Library/Logs とシステムの /Library/Logs）
+fn log_roots(home: Option<&Path>) -> Vec<(PathBuf, bool)> {
+    let mut roots = Vec::new();
+    if let Some(home) = home {
+        roots.push((home.join("Library").join("Logs"), false));
+    }
+    roots.push((PathBuf::from("/Library/Logs"), true));
+    roots
+}
+
+/// sudo なしで削除できるか（パス自体と親ディレクトリに書き込み権限があるか）
+#[cfg(unix)]
+fn is_removable(path: &Path) -> bool {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let writable = |path: &Path| {
+        CString::new(path.as_os_str().as_bytes())
+            // SAFETY: NUL 終端された文字列を渡している
+            .map(|c_path| unsafe { libc::access(c_path.as_ptr(), libc::W_OK) } == 0)
+            .unwrap_or(false)
+    };
+    writable(path) && path.parent().is_none_or(writable)
+}
+
+#[cfg(not(unix))]
+fn is_removable(_path: &Path) -> bool {
+    true
+}
+
+/// Logs ディレクトリ直下をアプリごとにスキャン
+///
+/// 読み取れない・sudo なしでは削除できないエントリはエラーにせずスキップする
+pub fn scan_log_dir(logs_dir: &Path, is_system: bool) -> Vec<LogEntry> {
+    let Ok(read_dir) = fs::read_dir(logs_dir) else {
+        return Vec::new();
+    };
+
+    let mut entries = Vec::new();
+    for entry in read_dir.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if !entry.file_type().is_ok_and(|t| t.is_dir()) || !is_removable(&path) {
+            continue;
+        }
+        // 中身を読めないディレクトリはサイズが正しく測れないためスキップ
+        if fs::read_dir(&path).is_err() {
+            continue;
+        }
+        let Ok(size) = utils::calculate_dir_size(&path) else {
+            continue;
+        };
+        if size == 0 {
+            continue;
+        }
+
+        let app = entry.file_name().to_string_lossy().to_string();
+        let kind = if app == CRASH_REPORTS_DIR {
+            LogKind::CrashReports
+        } else {
+            LogKind::AppLog
+        };
+        entries.push(LogEntry {
+            app,
+            path,
+            size,
+            kind,
+            is_system,
+        });
+    }
+
+    entries
+}
+
+/// ~/Library/Logs と /Library/Logs をスキャン（サイズの大きい順）
+pub fn scan_logs() -> Vec<LogEntry> {
+    let home = std::env::var("HOME").ok().map(PathBuf::from);
+    let mut entries: Vec<LogEntry> = log_roots(home.as_deref())
+        .into_iter()
+        .flat_map(|(dir, is_system)| scan_log_dir(&dir, is_system))
+        .collect();
+
+    entries.sort_by_key(|e| std::cmp::Reverse(e.size));
+    entries
+}
+
+/// クラッシュレポートのファイル名からアプリ名を取得
+///
+/// 例: "Safari_2024-01-15-101500_MacBook.ips" → "Safari", "JetsamEvent-2024-01-15-101500.ips" → "JetsamEvent"
+fn crash_report_app(file_name: &str) -> &str {
+    file_name
+        .char_indices()
+        .find(|&(i, c)| {
+            matches!(c, '_' | '-')
+                && file_name
+                    .get(i + 1..i + 5)
+                    .is_some_and(|year| year.bytes().all(|b| b.is_ascii_digit()))
+        })
+        .map(|(i, _)| &file_name[..i])
+        .unwrap_or(file_name)
+}
+
+/// クラッシュレポートをアプリごとに集計（アプリ名, 件数, 合計サイズ。サイズの大きい順）
+pub fn crash_reports_by_app(dir: &Path) -> Vec<(String, usize, u64)> {
+    let mut summary: Vec<(String, usize, u64)> = Vec::new();
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return summary;
+    };
+
+    for entry in read_dir.filter_map(|e| e.ok()) {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let app = crash_report_app(&file_name);
+        match summary.iter_mut().find(|(name, _, _)| name == app) {
+            Some((_, count, size)) => {
+                *count += 1;
+                *size += metadata.len();
+            }
+            None => summary.push((app.to_string(), 1, metadata.len())),
+        }
+    }
+
+    summary.sort_by_key(|(_, _, size)| std::cmp::Reverse(*size));
+    summary
+}
+
+/// ログ・クラッシュレポートクリーナー
+pub struct LogsCleaner;
+
+impl LogsCleaner {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for LogsCleaner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cleanable for LogsCleaner {
+    fn scan(&self) -> Result<Vec<CleanableItem>> {
+        Ok(scan_logs()
+            .into_iter()
+            .map(|e| {
+                let (label, metadata) = match e.kind {
+                    LogKind::CrashReports => ("クラッシュレポート".to_string(), CleanableMetadata::safe()),
+                    LogKind::AppLog => (e.app, CleanableMetadata::needs_review()),
+                };
+                let name = if e.is_system {
+                    format!("{} (システム)", label)
+                } else {
+                    label
+                };
+                CleanableItem::with_metadata(name, e.path, e.size, metadata)
+            })
+            .collect())
+    }
+
+    fn name(&self) -> &str {
+        "Logs"
+    }
+
+    fn icon(&self) -> &str {
+        "📜"
+    }
+
+    /// クラッシュレポートはアプリごとの内訳を表示
+    fn breakdown(&self, items: &[CleanableItem]) -> Vec<(String, usize, u64)> {
+        let mut summary: Vec<(String, usize, u64)> = Vec::new();
+        for item in items.iter().filter(|i| i.path.ends_with(CRASH_REPORTS_DIR)) {
+            for (app, count, size) in crash_reports_by_app(&item.path) {
+                match summary.iter_mut().find(|(name, _, _)| *name == app) {
+                    Some((_, total_count, total_size)) => {
+                        *total_count += count;
+                        *total_size += size;
+                    }
+                    None => summary.push((app, count, size)),
+                }
+            }
+        }
+        summary.sort_by_key(|(_, _, size)| std::cmp::Reverse(*size));
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_scan_log_dir() -> Result<()> {
+        let temp = TempDir::new()?;
+        let logs = temp.path().join("Logs");
+        let crash = logs.join(CRASH_REPORTS_DIR);
+        fs::create_dir_all(&crash)?;
+        fs::write(crash.join("Safari_2024-01-15-101500_host.ips"), vec![0u8; 30])?;
+        fs::write(crash.join("Safari_2024-01-16-101500_host.ips"), vec![0u8; 20])?;
+        fs::write(crash.join("JetsamEvent-2024-01-15-101500.ips"), vec![0u8; 5])?;
+        fs::create_dir_all(logs.join("JetBrains"))?;
+        fs::write(logs.join("JetBrains").join("idea.log"), vec![0u8; 100])?;
+        // 空のディレクトリ・直下のファイルは対象外
+        fs::create_dir_all(logs.join("Empty"))?;
+        fs::write(logs.join("loose.log"), "log")?;
+
+        let mut entries = scan_log_dir(&logs, false);
+        entries.sort_by(|a, b| a.app.cmp(&b.app));
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].app, CRASH_REPORTS_DIR);
+        assert_eq!(entries[0].kind, LogKind::CrashReports);
+        assert_eq!(entries[0].size, 55);
+        assert_eq!(entries[1].app, "JetBrains");
+        assert_eq!(entries[1].kind, LogKind::AppLog);
+
+        assert_eq!(
+            crash_reports_by_app(&crash),
+            vec![("Safari".to_string(), 2, 50), ("JetsamEvent".to_string(), 1, 5)]
+        );
+
+        // 存在しない（読めない）ディレクトリはエラーにしない
+        assert!(scan_log_dir(&temp.path().join("missing"), true).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_crash_report_app() {
+        assert_eq!(crash_report_app("Safari_2024-01-15-101500_host.ips"), "Safari");
+        assert_eq!(crash_report_app("JetsamEvent-2024-01-15-101500.ips"), "JetsamEvent");
+        assert_eq!(crash_report_app("Google Chrome Helper_2024-01-15.crash"), "Google Chrome Helper");
+        assert_eq!(crash_report_app("shutdown_stall.txt"), "shutdown_stall.txt");
+        // 区切りの直後が 4 バイト未満のマルチバイト文字でもパニックしない
+        assert_eq!(crash_report_app("メモ_2024-01-15.ips"), "メモ");
+        assert_eq!(crash_report_app("a_メモ.ips"), "a_メモ.ips");
+        assert_eq!(crash_report_app("a_1あ.ips"), "a_1あ.ips");
+    }
+
+    #[test]
+    fn test_log_roots() {
+        assert_eq!(
+            log_roots(Some(Path::new("/Users/test"))),
+            vec![
+                (PathBuf::from("/Users/test/Library/Logs"), false),
+                (PathBuf::from("/Library/Logs"), true),
+            ]
+        );
+        assert_eq!(log_roots(None), vec![(PathBuf::from("/Library/Logs"), true)]);
+    }
+}
@@ -0,0 +1,210 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    cleanable::{Cleanable, CleanableItem, CleanableMetadata},
+    cmake::matches_pattern,
+    utils, Result,
+};
+
+/// デフォルトで探す機械学習・Jupyter の生成物ディレクトリ名
+pub const DEFAULT_ARTIFACT_NAMES: &[&str] = &[
+    ".ipynb_checkpoints",
+    "wandb",
+    "mlruns",
+    "lightning_logs",
+    "runs",
+];
+
+/// 削除しても安全な生成物（Jupyter の自動保存のみ）。それ以外は実験記録を含むため要確認
+const SAFE_ARTIFACT_NAMES: &[&str] = &[".ipynb_checkpoints"];
+
+/// 機械学習の生成物ディレクトリ情報
+#[derive(Debug, Clone)]
+pub struct MlArtifact {
+    /// 種類（一致したディレクトリ名。例: "wandb"）
+    pub kind: String,
+    /// 生成物ディレクトリのパス
+    pub path: PathBuf,
+    /// サイズ（バイト）
+    pub size: u64,
+}
+
+impl MlArtifact {
+    /// 削除しても安全な生成物か
+    pub fn is_safe(&self) -> bool {
+        SAFE_ARTIFACT_NAMES.contains(&self.kind.as_str())
+    }
+}
+
+/// 指定されたディレクトリ以下の機械学習の生成物を検索
+///
+/// `names` にはディレクトリ名を指定する（`*` ワイルドカードが使える）
+pub fn find_ml_artifacts(search_path: &Path, names: &[String]) -> Result<Vec<MlArtifact>> {
+    let mut artifacts = Vec::new();
+    if names.is_empty() {
+        return Ok(artifacts);
+    }
+    let matches = |name: &str| names.iter().any(|pattern| matches_pattern(name, pattern));
+
+    // .ipynb_checkpoints 自体が削除対象になるため、一致する名前は --no-hidden でも残す
+    let walker = utils::walk_dir(search_path).min_depth(1);
+    let mut walker = utils::walk_entries(walker, matches, |e| {
+        // 仮想環境や依存パッケージの中の同名ディレクトリは対象外
+        let file_name = e.file_name().to_string_lossy();
+        !matches!(
+            file_name.as_ref(),
+            ".git" | "node_modules" | "target" | ".venv" | "venv" | "site-packages"
+        )
+    });
+
+    while let Some(entry) = walker.next() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+
+        let kind = entry.file_name().to_string_lossy().into_owned();
+        if !matches(&kind) {
+            continue;
+        }
+
+        // 見つかったディレクトリの中は探さない
+        walker.skip_current_dir();
+
+        let path = entry.into_path();
+        let size = utils::calculate_dir_size(&path)?;
+        artifacts.push(MlArtifact { kind, path, size });
+    }
+
+    Ok(artifacts)
+}
+
+/// 種類（ディレクトリ名）ごとの件数と合計サイズを集計（サイズの大きい順）
+pub fn summarize_by_kind(items: &[CleanableItem]) -> Vec<(String, usize, u64)> {
+    let mut summary: Vec<(String, usize, u64)> = Vec::new();
+    for item in items {
+        let kind = item
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        match summary.iter_mut().find(|(k, _, _)| *k == kind) {
+            Some((_, count, size)) => {
+                *count += 1;
+                *size += item.size;
+            }
+            None => summary.push((kind, 1, item.size)),
+        }
+    }
+    summary.sort_by_key(|(_, _, size)| std::cmp::Reverse(*size));
+    summary
+}
+
+/// Jupyter / 機械学習の生成物クリーナー（checkpoints, wandb, mlruns など）
+pub struct MlCleaner {
+    pub search_path: PathBuf,
+    /// 対象のディレクトリ名
+    pub names: Vec<String>,
+}
+
+impl MlCleaner {
+    pub fn new(search_path: PathBuf) -> Self {
+        Self {
+            search_path,
+            names: DEFAULT_ARTIFACT_NAMES.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    /// 対象のディレクトリ名を置き換える（空の場合はデフォルトのまま）
+    pub fn with_names(mut self, names: Vec<String>) -> Self {
+        if !names.is_empty() {
+            self.names = names;
+        }
+        self
+    }
+}
+
+impl Cleanable for MlCleaner {
+    fn scan(&self) -> Result<Vec<CleanableItem>> {
+        let artifacts = find_ml_artifacts(&self.search_path, &self.names)?;
+
+        Ok(artifacts
+            .into_iter()
+            .map(|a| {
+                let metadata = if a.is_safe() {
+                    CleanableMetadata::safe()
+                } else {
+                    CleanableMetadata::needs_review()
+                };
+                let root = a.path.parent().unwrap_or(&a.path).display().to_string();
+                let name = format!("{} ({})", root, a.kind);
+                CleanableItem::with_metadata(name, a.path, a.size, metadata)
+            })
+            .collect())
+    }
+
+    fn name(&self) -> &str {
+        "ML Artifacts"
+    }
+
+    fn icon(&self) -> &str {
+        "🧪"
+    }
+
+    fn breakdown(&self, items: &[CleanableItem]) -> Vec<(String, usize, u64)> {
+        summarize_by_kind(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_find_ml_artifacts() -> Result<()> {
+        let temp = TempDir::new()?;
+        let project = temp.path().join("experiment");
+        let checkpoints = project.join("notebooks").join(".ipynb_checkpoints");
+        fs::create_dir_all(&checkpoints)?;
+        fs::write(checkpoints.join("train-checkpoint.ipynb"), vec![0u8; 10])?;
+        let wandb = project.join("wandb");
+        fs::create_dir_all(wandb.join("run-1").join("files"))?;
+        fs::write(wandb.join("run-1").join("files").join("log"), vec![0u8; 100])?;
+
+        // 一致したディレクトリの中・仮想環境の中は数えない
+        fs::create_dir_all(wandb.join("run-1").join("runs"))?;
+        fs::create_dir_all(project.join(".venv").join("lib").join("runs"))?;
+
+        let mut artifacts = find_ml_artifacts(
+            temp.path(),
+            &DEFAULT_ARTIFACT_NAMES.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+        )?;
+        artifacts.sort_by(|a, b| a.path.cmp(&b.path));
+
+        assert_eq!(artifacts.len(), 2);
+        assert_eq!(artifacts[0].path, checkpoints);
+        assert!(artifacts[0].is_safe());
+        assert_eq!(artifacts[1].path, wandb);
+        assert_eq!(artifacts[1].size, 100);
+        assert!(!artifacts[1].is_safe());
+
+        let cleaner = MlCleaner::new(temp.path().to_path_buf());
+        let summary = cleaner.breakdown(&cleaner.scan()?);
+        assert_eq!(summary[0], ("wandb".to_string(), 1, 100));
+        assert_eq!(summary[1], (".ipynb_checkpoints".to_string(), 1, 10));
+
+        // 名前を指定した場合はそれだけを探す
+        let items = MlCleaner::new(temp.path().to_path_buf())
+            .with_names(vec!["wand*".to_string()])
+            .scan()?;
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].path, wandb);
+        assert!(!items[0].is_safe());
+
+        Ok(())
+    }
+}
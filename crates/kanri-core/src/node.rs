@@ -1,6 +1,6 @@
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
 
 use crate::{cleanable::{Cleanable, CleanableItem}, utils, Result};
 
@@ -30,26 +30,26 @@ impl NodeProject {
 /// 指定されたディレクトリ以下の Node.js プロジェクトを検索
 pub fn find_node_projects(search_path: &Path) -> Result<Vec<NodeProject>> {
     let mut projects = Vec::new();
-
-    for entry in WalkDir::new(search_path)
-        .into_iter()
-        .filter_entry(|e| {
-            // target, .git, node_modules などの大きなディレクトリはスキップ
-            let file_name = e.file_name().to_string_lossy();
-            !matches!(
-                file_name.as_ref(),
-                "target" | ".git" | "node_modules" | ".cache"
-            )
-        })
-        .filter_map(|e| e.ok())
-    {
+    // シンボリックリンク先を共有している場合に二重に数えないため
+    let mut seen_dirs = HashSet::new();
+
+    let entries = utils::walk_entries(utils::walk_dir(search_path), |_| false, |e| {
+        // target, .git, node_modules などの大きなディレクトリはスキップ
+        let file_name = e.file_name().to_string_lossy();
+        !matches!(
+            file_name.as_ref(),
+            "target" | ".git" | "node_modules" | ".cache"
+        )
+    });
+
+    for entry in entries.filter_map(|e| e.ok()) {
         if entry.file_type().is_file() && entry.file_name() == "package.json" {
             if let Some(project_root) = entry.path().parent() {
                 let node_modules_dir = project_root.join("node_modules");
 
                 // node_modules ディレクトリが存在する場合のみ追加
                 if node_modules_dir.exists() {
-                    let size = utils::calculate_dir_size(&node_modules_dir)?;
+                    let size = utils::calculate_linked_dir_size(&node_modules_dir, &mut seen_dirs)?;
 
                     projects.push(NodeProject {
                         root: project_root.to_path_buf(),
@@ -72,26 +72,25 @@ pub fn clean_project(project: &NodeProject) -> Result<()> {
     Ok(())
 }
 
-/// 複数の Node.js プロジェクトをクリーン
-pub fn clean_projects(projects: &[NodeProject]) -> Result<Vec<PathBuf>> {
-    let mut cleaned = Vec::new();
-
-    for project in projects {
-        clean_project(project)?;
-        cleaned.push(project.root.clone());
-    }
-
-    Ok(cleaned)
-}
-
 /// Node.js プロジェクトクリーナー
 pub struct NodeCleaner {
     pub search_path: PathBuf,
+    /// 最小サイズ（バイト）
+    pub min_size: u64,
 }
 
 impl NodeCleaner {
     pub fn new(search_path: PathBuf) -> Self {
-        Self { search_path }
+        Self {
+            search_path,
+            min_size: 0,
+        }
+    }
+
+    /// 最小サイズ（バイト）を設定
+    pub fn with_min_size(mut self, min_size: u64) -> Self {
+        self.min_size = min_size;
+        self
     }
 }
 
@@ -101,6 +100,7 @@ impl Cleanable for NodeCleaner {
 
         Ok(projects
             .into_iter()
+            .filter(|p| p.size >= self.min_size)
             .map(|p| CleanableItem::new(p.root.display().to_string(), p.node_modules_dir, p.size))
             .collect())
     }
@@ -147,6 +147,40 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn test_find_node_projects_symlinked_store() -> Result<()> {
+        use std::os::unix::fs::symlink;
+
+        let temp = TempDir::new()?;
+
+        // 共有ストア（pnpm などを想定）
+        let store = temp.path().join(".store");
+        fs::create_dir(&store)?;
+        fs::write(store.join("pkg.js"), vec![0u8; 100])?;
+
+        for name in ["app-a", "app-b"] {
+            let project_dir = temp.path().join(name);
+            fs::create_dir(&project_dir)?;
+            fs::write(project_dir.join("package.json"), "{}")?;
+            symlink(&store, project_dir.join("node_modules"))?;
+        }
+
+        let mut projects = find_node_projects(temp.path())?;
+        projects.sort_by(|a, b| a.root.cmp(&b.root));
+
+        // 両方検出されるが、ストアのサイズは一度だけ数える
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects.iter().map(|p| p.size).sum::<u64>(), 100);
+
+        // 削除はリンクのみで、ストア本体は残る
+        clean_project(&projects[0])?;
+        assert!(!projects[0].node_modules_dir.exists());
+        assert!(store.join("pkg.js").exists());
+
+        Ok(())
+    }
+
     #[test]
     fn test_clean_project() -> Result<()> {
         let temp = TempDir::new()?;
@@ -171,4 +205,33 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_node_cleaner_clean() -> Result<()> {
+        let temp = TempDir::new()?;
+        for (name, size) in [("small", 10), ("large", 300)] {
+            let node_modules_dir = temp.path().join(name).join("node_modules");
+            fs::create_dir_all(&node_modules_dir)?;
+            fs::write(temp.path().join(name).join("package.json"), "{}")?;
+            fs::write(node_modules_dir.join("index.js"), vec![0u8; size])?;
+        }
+
+        let items = NodeCleaner::new(temp.path().to_path_buf()).scan()?;
+        assert_eq!(items.iter().map(|i| i.size).sum::<u64>(), 310);
+
+        // 最小サイズ未満の node_modules は残す
+        let cleaner = NodeCleaner::new(temp.path().to_path_buf()).with_min_size(100);
+        let items = cleaner.scan()?;
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].path, temp.path().join("large").join("node_modules"));
+        let mut reported = 0;
+        let cleaned = cleaner.clean(&items, &mut |item| reported += item.size)?;
+        assert_eq!(cleaned.len(), 1);
+        assert_eq!(reported, 300);
+        assert!(!temp.path().join("large").join("node_modules").exists());
+        assert!(temp.path().join("large").join("package.json").exists());
+        assert!(temp.path().join("small").join("node_modules").exists());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,318 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::{
+    cleanable::{Cleanable, CleanableItem, CleanableMetadata},
+    utils, Error, Result,
+};
+
+/// npm / yarn のグローバルキャッシュ種別
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NpmCacheKind {
+    /// npm のキャッシュ（`<npm cache>/_cacache`）
+    Npm,
+    /// yarn (v1) のキャッシュ
+    Yarn,
+}
+
+impl NpmCacheKind {
+    /// すべての種別
+    pub const ALL: [NpmCacheKind; 2] = [NpmCacheKind::Npm, NpmCacheKind::Yarn];
+
+    /// 表示名
+    pub fn label(&self) -> &'static str {
+        match self {
+            NpmCacheKind::Npm => "npm cache",
+            NpmCacheKind::Yarn => "yarn cache",
+        }
+    }
+
+    /// コマンド名
+    pub fn tool(&self) -> &'static str {
+        match self {
+            NpmCacheKind::Npm => "npm",
+            NpmCacheKind::Yarn => "yarn",
+        }
+    }
+
+    /// キャッシュディレクトリを問い合わせる引数
+    fn query_args(&self) -> &'static [&'static str] {
+        match self {
+            NpmCacheKind::Npm => &["config", "get", "cache"],
+            NpmCacheKind::Yarn => &["cache", "dir"],
+        }
+    }
+
+    /// ツール自身でキャッシュを削除する引数
+    fn clean_args(&self) -> &'static [&'static str] {
+        match self {
+            NpmCacheKind::Npm => &["cache", "clean", "--force"],
+            NpmCacheKind::Yarn => &["cache", "clean"],
+        }
+    }
+
+    /// ツールが返したパスから実際に削除するディレクトリを決める
+    ///
+    /// npm のキャッシュディレクトリにはログなども含まれるため、中身の `_cacache` だけを対象にする
+    fn content_dir(&self, cache_dir: PathBuf) -> PathBuf {
+        match self {
+            NpmCacheKind::Npm => cache_dir.join("_cacache"),
+            NpmCacheKind::Yarn => cache_dir,
+        }
+    }
+}
+
+/// npm / yarn のグローバルキャッシュ情報
+#[derive(Debug, Clone)]
+pub struct NpmCache {
+    /// キャッシュ種別
+    pub kind: NpmCacheKind,
+    /// キャッシュディレクトリのパス
+    pub cache_dir: PathBuf,
+    /// サイズ（バイト）
+    pub size: u64,
+    /// ツールがインストールされているか（false の場合は環境変数・既定のパスから推測）
+    pub tool_available: bool,
+}
+
+/// ツールがインストールされていない場合に、環境変数とホームディレクトリから候補のパスを解決
+fn fallback_cache_dirs(
+    kind: NpmCacheKind,
+    home: &Path,
+    var: impl Fn(&str) -> Option<String>,
+) -> Vec<PathBuf> {
+    match kind {
+        // npm: npm_config_cache > ~/.npm
+        NpmCacheKind::Npm => {
+            let cache_dir = var("npm_config_cache")
+                .or_else(|| var("NPM_CONFIG_CACHE"))
+                .map(PathBuf::from)
+                .unwrap_or_else(|| home.join(".npm"));
+            vec![cache_dir]
+        }
+        // yarn: YARN_CACHE_FOLDER > ~/Library/Caches/Yarn > XDG_CACHE_HOME/yarn > ~/.cache/yarn
+        NpmCacheKind::Yarn => {
+            if let Some(folder) = var("YARN_CACHE_FOLDER") {
+                return vec![PathBuf::from(folder)];
+            }
+            let cache_home = var("XDG_CACHE_HOME")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| home.join(".cache"));
+            vec![
+                home.join("Library").join("Caches").join("Yarn"),
+                cache_home.join("yarn"),
+            ]
+        }
+    }
+}
+
+/// ツールにキャッシュディレクトリを問い合わせる（インストールされていない・失敗した場合は None）
+fn query_cache_dir(kind: NpmCacheKind) -> Option<PathBuf> {
+    let output = utils::run_command_with_timeout(
+        Command::new(kind.tool()).args(kind.query_args()),
+        utils::command_timeout(),
+    )
+    .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_cache_dir(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// 問い合わせ結果の最後の行をパスとして解釈（"undefined" や空行は無視）
+fn parse_cache_dir(stdout: &str) -> Option<PathBuf> {
+    let line = stdout.lines().map(str::trim).rfind(|line| !line.is_empty())?;
+    (line != "undefined" && line != "null").then(|| PathBuf::from(line))
+}
+
+/// npm / yarn のグローバルキャッシュを検索
+///
+/// ツールがあればキャッシュディレクトリを問い合わせ、なければ環境変数・既定のパスを使う
+pub fn find_npm_caches() -> Result<Vec<NpmCache>> {
+    let home = env::var("HOME").map(PathBuf::from).ok();
+    let mut caches = Vec::new();
+
+    for kind in NpmCacheKind::ALL {
+        let (candidates, tool_available) = match query_cache_dir(kind) {
+            Some(cache_dir) => (vec![cache_dir], true),
+            None => match &home {
+                Some(home) => (fallback_cache_dirs(kind, home, |name| env::var(name).ok()), false),
+                None => continue,
+            },
+        };
+
+        // 最初に見つかったディレクトリのみ採用
+        let Some(cache_dir) = candidates
+            .into_iter()
+            .map(|dir| kind.content_dir(dir))
+            .find(|dir| dir.is_dir())
+        else {
+            continue;
+        };
+
+        let size = utils::calculate_dir_size(&cache_dir)?;
+        caches.push(NpmCache {
+            kind,
+            cache_dir,
+            size,
+            tool_available,
+        });
+    }
+
+    Ok(caches)
+}
+
+/// ツール自身のコマンド（`npm cache clean --force` / `yarn cache clean`）でキャッシュを削除
+pub fn clean_with_tool(kind: NpmCacheKind) -> Result<()> {
+    let output = Command::new(kind.tool()).args(kind.clean_args()).output()?;
+    if !output.status.success() {
+        return Err(Error::external_tool(
+            format!("{} {}", kind.tool(), kind.clean_args().join(" ")),
+            &output,
+        ));
+    }
+    Ok(())
+}
+
+/// npm / yarn キャッシュクリーナー
+pub struct NpmCacheCleaner {
+    /// ツール自身のコマンドで削除するか（ツールがない場合はディレクトリを直接削除）
+    pub use_tool: bool,
+}
+
+impl NpmCacheCleaner {
+    pub fn new() -> Self {
+        Self { use_tool: false }
+    }
+
+    /// ディレクトリを直接削除する代わりに、ツール自身のコマンドで削除する
+    pub fn with_use_tool(mut self, use_tool: bool) -> Self {
+        self.use_tool = use_tool;
+        self
+    }
+}
+
+impl Default for NpmCacheCleaner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cleanable for NpmCacheCleaner {
+    fn scan(&self) -> Result<Vec<CleanableItem>> {
+        Ok(find_npm_caches()?
+            .into_iter()
+            .map(|c| {
+                let name = if c.tool_available {
+                    c.kind.label().to_string()
+                } else {
+                    format!("{}（{} 未インストール）", c.kind.label(), c.kind.tool())
+                };
+                CleanableItem::with_metadata(name, c.cache_dir, c.size, CleanableMetadata::safe())
+            })
+            .collect())
+    }
+
+    fn name(&self) -> &str {
+        "npm/yarn cache"
+    }
+
+    fn icon(&self) -> &str {
+        "📦"
+    }
+
+    fn clean(
+        &self,
+        items: &[CleanableItem],
+        on_cleaned: &mut dyn FnMut(&CleanableItem),
+    ) -> Result<Vec<String>> {
+        let mut cleaned = Vec::new();
+
+        for item in items {
+            if crate::utils::is_protected_path(&item.path) {
+                continue;
+            }
+            let kind = NpmCacheKind::ALL
+                .into_iter()
+                .find(|kind| item.name.starts_with(kind.label()));
+
+            // ツールが見つからない場合はディレクトリの直接削除にフォールバック
+            match kind {
+                Some(kind) if self.use_tool && query_cache_dir(kind).is_some() => clean_with_tool(kind)?,
+                _ => {
+                    if item.path.exists() {
+                        fs::remove_dir_all(&item.path)?;
+                    }
+                }
+            }
+            cleaned.push(item.name.clone());
+            on_cleaned(item);
+        }
+
+        Ok(cleaned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fallback_cache_dirs() {
+        let home = PathBuf::from("/Users/test");
+
+        assert_eq!(
+            fallback_cache_dirs(NpmCacheKind::Npm, &home, |_| None),
+            vec![PathBuf::from("/Users/test/.npm")]
+        );
+        assert_eq!(
+            fallback_cache_dirs(NpmCacheKind::Npm, &home, |name| {
+                (name == "npm_config_cache").then(|| "/custom/npm".to_string())
+            }),
+            vec![PathBuf::from("/custom/npm")]
+        );
+
+        assert_eq!(
+            fallback_cache_dirs(NpmCacheKind::Yarn, &home, |_| None),
+            vec![
+                PathBuf::from("/Users/test/Library/Caches/Yarn"),
+                PathBuf::from("/Users/test/.cache/yarn"),
+            ]
+        );
+        assert_eq!(
+            fallback_cache_dirs(NpmCacheKind::Yarn, &home, |name| {
+                (name == "YARN_CACHE_FOLDER").then(|| "/custom/yarn".to_string())
+            }),
+            vec![PathBuf::from("/custom/yarn")]
+        );
+    }
+
+    #[test]
+    fn test_parse_cache_dir() {
+        assert_eq!(
+            parse_cache_dir("/Users/test/.npm\n"),
+            Some(PathBuf::from("/Users/test/.npm"))
+        );
+        // yarn は警告の後にパスを出力することがある
+        assert_eq!(
+            parse_cache_dir("warning package.json: No license field\n/Users/test/Library/Caches/Yarn/v6\n\n"),
+            Some(PathBuf::from("/Users/test/Library/Caches/Yarn/v6"))
+        );
+        assert_eq!(parse_cache_dir("undefined\n"), None);
+        assert_eq!(parse_cache_dir(""), None);
+    }
+
+    #[test]
+    fn test_content_dir() {
+        assert_eq!(
+            NpmCacheKind::Npm.content_dir(PathBuf::from("/Users/test/.npm")),
+            PathBuf::from("/Users/test/.npm/_cacache")
+        );
+        assert_eq!(
+            NpmCacheKind::Yarn.content_dir(PathBuf::from("/Users/test/.cache/yarn")),
+            PathBuf::from("/Users/test/.cache/yarn")
+        );
+    }
+}
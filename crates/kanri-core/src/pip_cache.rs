@@ -0,0 +1,407 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::{
+    cleanable::{Cleanable, CleanableItem, CleanableMetadata},
+    utils, Error, Result,
+};
+
+/// pip を実行できる Python インタプリタ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PythonInterpreter {
+    /// 表示名（例: "python3", "pyenv 3.12.1"）
+    pub label: String,
+    /// 実行するコマンド（PATH 上のコマンド名または絶対パス）
+    pub program: PathBuf,
+}
+
+/// pip のキャッシュ情報（同じディレクトリを使うインタプリタはまとめる）
+#[derive(Debug, Clone)]
+pub struct PipCache {
+    /// キャッシュディレクトリのパス（wheel キャッシュ `wheels/` と HTTP キャッシュを含む）
+    pub cache_dir: PathBuf,
+    /// サイズ（バイト）
+    pub size: u64,
+    /// このキャッシュを使うインタプリタ（空の場合は pip が見つからず既定のパスから推測）
+    pub interpreters: Vec<PythonInterpreter>,
+}
+
+/// 調べるインタプリタ（PATH 上の python3 と pyenv でインストールした各バージョン）
+fn candidate_interpreters(pyenv_root: Option<&Path>) -> Vec<PythonInterpreter> {
+    let mut interpreters = vec![PythonInterpreter {
+        label: "python3".to_string(),
+        program: PathBuf::from("python3"),
+    }];
+    if let Some(root) = pyenv_root {
+        interpreters.extend(pyenv_interpreters(root));
+    }
+    interpreters
+}
+
+/// pyenv でインストールしたバージョンのインタプリタ（`<PYENV_ROOT>/versions/<version>/bin/python`）
+fn pyenv_interpreters(pyenv_root: &Path) -> Vec<PythonInterpreter> {
+    let Ok(entries) = fs::read_dir(pyenv_root.join("versions")) else {
+        return Vec::new();
+    };
+
+    let mut versions: Vec<(String, PathBuf)> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|entry| {
+            let program = entry.path().join("bin").join("python");
+            program
+                .is_file()
+                .then(|| (entry.file_name().to_string_lossy().to_string(), program))
+        })
+        .collect();
+    // 3.9 が 3.10 より前になるよう、数値の部分は数値として並べる
+    versions.sort_by_cached_key(|(version, _)| {
+        version
+            .split('.')
+            .map(|part| part.parse::<u64>().map_err(|_| part.to_string()))
+            .collect::<Vec<_>>()
+    });
+    versions
+        .into_iter()
+        .map(|(version, program)| PythonInterpreter {
+            label: format!("pyenv {}", version),
+            program,
+        })
+        .collect()
+}
+
+/// pyenv のルートディレクトリ（PYENV_ROOT > ~/.pyenv）
+fn pyenv_root(home: Option<&Path>) -> Option<PathBuf> {
+    env::var("PYENV_ROOT")
+        .map(PathBuf::from)
+        .ok()
+        .or_else(|| home.map(|home| home.join(".pyenv")))
+}
+
+/// pip が見つからない場合に、環境変数とホームディレクトリから候補のパスを解決
+///
+/// PIP_CACHE_DIR > ~/Library/Caches/pip > XDG_CACHE_HOME/pip > ~/.cache/pip
+fn fallback_cache_dirs(home: &Path, var: impl Fn(&str) -> Option<String>) -> Vec<PathBuf> {
+    if let Some(dir) = var("PIP_CACHE_DIR") {
+        return vec![PathBuf::from(dir)];
+    }
+    let cache_home = var("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.join(".cache"));
+    vec![
+        home.join("Library").join("Caches").join("pip"),
+        cache_home.join("pip"),
+    ]
+}
+
+/// インタプリタの pip にキャッシュディレクトリを問い合わせる（`python -m pip cache dir`）
+///
+/// pip がない・キャッシュが無効になっている場合は None
+fn query_cache_dir(interpreter: &PythonInterpreter) -> Option<PathBuf> {
+    let output = utils::run_command_with_timeout(
+        Command::new(&interpreter.program).args(["-m", "pip", "cache", "dir"]),
+        utils::command_timeout(),
+    )
+    .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_cache_dir(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// 問い合わせ結果の最後の行をパスとして解釈（警告・空行は無視）
+fn parse_cache_dir(stdout: &str) -> Option<PathBuf> {
+    stdout
+        .lines()
+        .map(str::trim)
+        .rfind(|line| !line.is_empty() && !line.starts_with("WARNING:") && !line.starts_with("ERROR:"))
+        .map(PathBuf::from)
+}
+
+/// 同じキャッシュディレクトリを使うインタプリタをまとめる（最初に見つかった順）
+fn group_by_cache_dir(
+    resolved: Vec<(PythonInterpreter, PathBuf)>,
+) -> Vec<(PathBuf, Vec<PythonInterpreter>)> {
+    let mut groups: Vec<(PathBuf, Vec<PythonInterpreter>)> = Vec::new();
+    for (interpreter, cache_dir) in resolved {
+        match groups.iter_mut().find(|(dir, _)| *dir == cache_dir) {
+            Some((_, interpreters)) => interpreters.push(interpreter),
+            None => groups.push((cache_dir, vec![interpreter])),
+        }
+    }
+    groups
+}
+
+/// pip のキャッシュを検索
+///
+/// python3 と pyenv の各バージョンの pip にキャッシュディレクトリを問い合わせ、
+/// どれも答えなければ環境変数・既定のパスを使う
+pub fn find_pip_caches() -> Result<Vec<PipCache>> {
+    let home = env::var("HOME").map(PathBuf::from).ok();
+
+    let resolved: Vec<(PythonInterpreter, PathBuf)> =
+        candidate_interpreters(pyenv_root(home.as_deref()).as_deref())
+            .into_iter()
+            .filter_map(|interpreter| {
+                let cache_dir = query_cache_dir(&interpreter)?;
+                Some((interpreter, cache_dir))
+            })
+            .collect();
+
+    let groups = if resolved.is_empty() {
+        // 最初に見つかったディレクトリのみ採用
+        let Some(cache_dir) = home
+            .map(|home| fallback_cache_dirs(&home, |name| env::var(name).ok()))
+            .unwrap_or_default()
+            .into_iter()
+            .find(|dir| dir.is_dir())
+        else {
+            return Ok(Vec::new());
+        };
+        vec![(cache_dir, Vec::new())]
+    } else {
+        group_by_cache_dir(resolved)
+    };
+
+    let mut caches = Vec::new();
+    for (cache_dir, interpreters) in groups {
+        if !cache_dir.is_dir() {
+            continue;
+        }
+        let size = utils::calculate_dir_size(&cache_dir)?;
+        caches.push(PipCache {
+            cache_dir,
+            size,
+            interpreters,
+        });
+    }
+
+    Ok(caches)
+}
+
+/// pip 自身のコマンド（`python -m pip cache purge`）でキャッシュを削除
+pub fn purge_with_tool(interpreter: &PythonInterpreter) -> Result<()> {
+    let output = utils::run_command_with_timeout(
+        Command::new(&interpreter.program).args(["-m", "pip", "cache", "purge"]),
+        utils::command_timeout(),
+    )?;
+    if !output.status.success() {
+        return Err(Error::external_tool(
+            format!("{} -m pip cache purge", interpreter.program.display()),
+            &output,
+        ));
+    }
+    Ok(())
+}
+
+/// pip キャッシュクリーナー
+pub struct PipCacheCleaner {
+    /// pip 自身のコマンドで削除するか（pip がない場合はディレクトリを直接削除）
+    pub use_tool: bool,
+}
+
+impl PipCacheCleaner {
+    pub fn new() -> Self {
+        Self { use_tool: false }
+    }
+
+    /// ディレクトリを直接削除する代わりに、`pip cache purge` で削除する
+    pub fn with_use_tool(mut self, use_tool: bool) -> Self {
+        self.use_tool = use_tool;
+        self
+    }
+}
+
+impl Default for PipCacheCleaner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cleanable for PipCacheCleaner {
+    fn scan(&self) -> Result<Vec<CleanableItem>> {
+        Ok(find_pip_caches()?
+            .into_iter()
+            .map(|c| {
+                let (name, detail) = if c.interpreters.is_empty() {
+                    ("pip cache（pip 未インストール）".to_string(), None)
+                } else {
+                    let labels: Vec<&str> = c.interpreters.iter().map(|i| i.label.as_str()).collect();
+                    ("pip cache".to_string(), Some(labels.join(", ")))
+                };
+                CleanableItem::with_metadata(name, c.cache_dir, c.size, CleanableMetadata::safe())
+                    .with_detail(detail)
+            })
+            .collect())
+    }
+
+    fn name(&self) -> &str {
+        "pip cache"
+    }
+
+    fn icon(&self) -> &str {
+        "🐍"
+    }
+
+    fn clean(
+        &self,
+        items: &[CleanableItem],
+        on_cleaned: &mut dyn FnMut(&CleanableItem),
+    ) -> Result<Vec<String>> {
+        // --use-tool では、キャッシュごとに使っているインタプリタの pip で削除する
+        let caches = if self.use_tool {
+            find_pip_caches()?
+        } else {
+            Vec::new()
+        };
+        // PIP_CACHE_DIR などでホームやシステムのディレクトリが指定されていれば、1 件も削除せずに止める
+        for item in items {
+            utils::assert_safe_target(&item.path)?;
+        }
+        let mut cleaned = Vec::new();
+
+        for item in items {
+            if utils::is_cancel_requested() {
+                break;
+            }
+            if utils::is_protected_path(&item.path) {
+                continue;
+            }
+            let interpreter = caches
+                .iter()
+                .find(|cache| cache.cache_dir == item.path)
+                .and_then(|cache| cache.interpreters.first());
+
+            // pip が見つからない場合はディレクトリの直接削除にフォールバック
+            match interpreter {
+                Some(interpreter) => purge_with_tool(interpreter)?,
+                None => {
+                    if item.path.exists() {
+                        fs::remove_dir_all(&item.path)?;
+                    }
+                }
+            }
+            cleaned.push(item.name.clone());
+            on_cleaned(item);
+        }
+
+        Ok(cleaned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interpreter(label: &str) -> PythonInterpreter {
+        PythonInterpreter {
+            label: label.to_string(),
+            program: PathBuf::from(label),
+        }
+    }
+
+    #[test]
+    fn test_fallback_cache_dirs() {
+        let home = PathBuf::from("/Users/test");
+
+        assert_eq!(
+            fallback_cache_dirs(&home, |_| None),
+            vec![
+                PathBuf::from("/Users/test/Library/Caches/pip"),
+                PathBuf::from("/Users/test/.cache/pip"),
+            ]
+        );
+        assert_eq!(
+            fallback_cache_dirs(&home, |name| {
+                (name == "XDG_CACHE_HOME").then(|| "/custom/cache".to_string())
+            })[1],
+            PathBuf::from("/custom/cache/pip")
+        );
+        assert_eq!(
+            fallback_cache_dirs(&home, |name| {
+                (name == "PIP_CACHE_DIR").then(|| "/custom/pip".to_string())
+            }),
+            vec![PathBuf::from("/custom/pip")]
+        );
+    }
+
+    #[test]
+    fn test_parse_cache_dir() {
+        assert_eq!(
+            parse_cache_dir("/Users/test/Library/Caches/pip\n"),
+            Some(PathBuf::from("/Users/test/Library/Caches/pip"))
+        );
+        assert_eq!(
+            parse_cache_dir("WARNING: pip is being invoked by an old script wrapper.\n/home/test/.cache/pip\n\n"),
+            Some(PathBuf::from("/home/test/.cache/pip"))
+        );
+        assert_eq!(
+            parse_cache_dir("ERROR: pip cache commands can not function since cache is disabled.\n"),
+            None
+        );
+        assert_eq!(parse_cache_dir(""), None);
+    }
+
+    #[test]
+    fn test_pyenv_interpreters() -> Result<()> {
+        use tempfile::TempDir;
+
+        let temp = TempDir::new()?;
+        for version in ["3.12.1", "3.9.18", "3.11.7"] {
+            let bin = temp.path().join("versions").join(version).join("bin");
+            fs::create_dir_all(&bin)?;
+            fs::write(bin.join("python"), "")?;
+        }
+        // python のないディレクトリは対象外
+        fs::create_dir_all(temp.path().join("versions").join("broken"))?;
+
+        let interpreters = pyenv_interpreters(temp.path());
+        let labels: Vec<&str> = interpreters.iter().map(|i| i.label.as_str()).collect();
+        assert_eq!(labels, vec!["pyenv 3.9.18", "pyenv 3.11.7", "pyenv 3.12.1"]);
+        assert_eq!(
+            interpreters[1].program,
+            temp.path().join("versions/3.11.7/bin/python")
+        );
+
+        // pyenv のないホームでは python3 のみ
+        assert_eq!(candidate_interpreters(Some(&temp.path().join("missing"))).len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_group_by_cache_dir() {
+        let shared = PathBuf::from("/Users/test/Library/Caches/pip");
+        let custom = PathBuf::from("/custom/pip");
+        let groups = group_by_cache_dir(vec![
+            (interpreter("python3"), shared.clone()),
+            (interpreter("pyenv 3.11.7"), custom.clone()),
+            (interpreter("pyenv 3.12.1"), shared.clone()),
+        ]);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].0, shared);
+        assert_eq!(
+            groups[0].1,
+            vec![interpreter("python3"), interpreter("pyenv 3.12.1")]
+        );
+        assert_eq!(groups[1], (custom, vec![interpreter("pyenv 3.11.7")]));
+    }
+
+    #[test]
+    fn test_clean_refuses_unsafe_target() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let cache_dir = temp.path().join("pip");
+        fs::create_dir_all(&cache_dir)?;
+        let items = vec![
+            CleanableItem::new("pip cache".to_string(), cache_dir.clone(), 0),
+            CleanableItem::new("pip cache".to_string(), PathBuf::from("/"), 0),
+        ];
+
+        // 危険なパスが 1 件でもあれば、他の項目も削除しない
+        assert!(PipCacheCleaner::new().clean(&items, &mut |_| {}).is_err());
+        assert!(cache_dir.exists());
+        Ok(())
+    }
+}
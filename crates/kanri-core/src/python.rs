@@ -1,6 +1,5 @@
 use std::fs;
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
 
 use crate::{
     cleanable::{Cleanable, CleanableItem},
@@ -22,17 +21,17 @@ pub struct PythonVenv {
 pub fn find_python_venvs(search_path: &Path) -> Result<Vec<PythonVenv>> {
     let mut venvs = Vec::new();
 
-    for entry in WalkDir::new(search_path)
-        .into_iter()
-        .filter_entry(|e| {
-            let file_name = e.file_name().to_string_lossy();
-            !matches!(
-                file_name.as_ref(),
-                "target" | ".git" | "node_modules" | ".cache"
-            )
-        })
-        .filter_map(|e| e.ok())
-    {
+    // .venv・.env は検索対象なので --no-hidden でも降りる
+    let keep_hidden = |name: &str| matches!(name, ".venv" | ".env");
+    let entries = utils::walk_entries(utils::walk_dir(search_path), keep_hidden, |e| {
+        let file_name = e.file_name().to_string_lossy();
+        !matches!(
+            file_name.as_ref(),
+            "target" | ".git" | "node_modules" | ".cache"
+        )
+    });
+
+    for entry in entries.filter_map(|e| e.ok()) {
         let path = entry.path();
         let file_name = entry.file_name().to_string_lossy();
 
@@ -127,4 +126,24 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_find_python_venvs_skip_hidden() -> Result<()> {
+        let temp = TempDir::new()?;
+        // .venv 自体は隠しディレクトリだが削除対象
+        let dot_venv = temp.path().join("app").join(".venv");
+        let hidden_venv = temp.path().join(".archive").join("old").join("venv");
+        for venv in [&dot_venv, &hidden_venv] {
+            fs::create_dir_all(venv)?;
+            fs::write(venv.join("pyvenv.cfg"), "test")?;
+        }
+
+        let _settings = utils::TestSettings::skip_hidden(true);
+        let venvs = find_python_venvs(temp.path())?;
+
+        assert_eq!(venvs.len(), 1);
+        assert_eq!(venvs[0].venv_dir, dot_venv);
+
+        Ok(())
+    }
 }
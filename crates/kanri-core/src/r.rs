@@ -0,0 +1,208 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    cleanable::{Cleanable, CleanableItem, CleanableMetadata},
+    utils, Result,
+};
+
+/// renv を使う R プロジェクト情報
+#[derive(Debug, Clone)]
+pub struct RProject {
+    /// プロジェクトのルートディレクトリ（renv.lock があるディレクトリ）
+    pub root: PathBuf,
+    /// renv/library ディレクトリのパス
+    pub library_dir: PathBuf,
+    /// サイズ（バイト）
+    pub size: u64,
+}
+
+/// R のグローバルキャッシュ情報
+#[derive(Debug, Clone)]
+pub struct RCache {
+    /// キャッシュディレクトリのパス
+    pub cache_dir: PathBuf,
+    /// サイズ（バイト）
+    pub size: u64,
+}
+
+/// 指定されたディレクトリ以下の renv プロジェクトを検索
+pub fn find_r_projects(search_path: &Path) -> Result<Vec<RProject>> {
+    let mut projects = Vec::new();
+
+    let entries = utils::walk_entries(utils::walk_dir(search_path), |_| false, |e| {
+        // renv 配下にはパッケージのソースが大量に含まれるため降りない
+        let file_name = e.file_name().to_string_lossy();
+        !matches!(
+            file_name.as_ref(),
+            "target" | ".git" | "node_modules" | ".cache" | "renv"
+        )
+    });
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        if entry.file_type().is_file() && entry.file_name() == "renv.lock" {
+            if let Some(project_root) = entry.path().parent() {
+                let library_dir = project_root.join("renv").join("library");
+
+                // renv/library が存在する場合のみ追加（renv::restore() で再構築できる）
+                if library_dir.is_dir() {
+                    let size = utils::calculate_dir_size(&library_dir)?;
+
+                    projects.push(RProject {
+                        root: project_root.to_path_buf(),
+                        library_dir,
+                        size,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(projects)
+}
+
+/// 環境変数とホームディレクトリから R のグローバルキャッシュの候補を解決
+fn global_cache_dirs(home: &Path, var: impl Fn(&str) -> Option<String>) -> Vec<PathBuf> {
+    // renv: RENV_PATHS_CACHE > XDG_CACHE_HOME/R/renv > ~/.cache/R/renv
+    let renv_cache = var("RENV_PATHS_CACHE").map(PathBuf::from).unwrap_or_else(|| {
+        var("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(".cache"))
+            .join("R")
+            .join("renv")
+    });
+
+    vec![
+        renv_cache,
+        // macOS では renv キャッシュや CRAN からのダウンロードがここに置かれる
+        home.join("Library").join("Caches").join("org.R-project.R"),
+    ]
+}
+
+/// R のグローバルキャッシュ（renv キャッシュ・CRAN キャッシュ）を検索
+pub fn find_r_caches() -> Result<Vec<RCache>> {
+    let home = match env::var("HOME") {
+        Ok(home) => PathBuf::from(home),
+        Err(_) => return Ok(Vec::new()),
+    };
+
+    let mut caches: Vec<RCache> = Vec::new();
+    for cache_dir in global_cache_dirs(&home, |name| env::var(name).ok()) {
+        // RENV_PATHS_CACHE が org.R-project.R 配下を指す場合に二重計上しない
+        if !cache_dir.is_dir() || caches.iter().any(|c| cache_dir.starts_with(&c.cache_dir)) {
+            continue;
+        }
+        caches.retain(|c| !c.cache_dir.starts_with(&cache_dir));
+
+        let size = utils::calculate_dir_size(&cache_dir)?;
+        caches.push(RCache { cache_dir, size });
+    }
+
+    Ok(caches)
+}
+
+/// R プロジェクトの renv/library を削除
+pub fn clean_project(project: &RProject) -> Result<()> {
+    if project.library_dir.exists() {
+        fs::remove_dir_all(&project.library_dir)?;
+    }
+    Ok(())
+}
+
+/// R (renv / CRAN) クリーナー
+pub struct RCleaner {
+    pub search_path: PathBuf,
+}
+
+impl RCleaner {
+    pub fn new(search_path: PathBuf) -> Self {
+        Self { search_path }
+    }
+}
+
+impl Cleanable for RCleaner {
+    fn scan(&self) -> Result<Vec<CleanableItem>> {
+        let mut items: Vec<CleanableItem> = find_r_projects(&self.search_path)?
+            .into_iter()
+            .map(|p| CleanableItem::new(p.root.display().to_string(), p.library_dir, p.size))
+            .collect();
+
+        // renv キャッシュは全プロジェクトの library から参照されるため要確認扱い
+        for cache in find_r_caches()? {
+            items.push(CleanableItem::with_metadata(
+                "R cache".to_string(),
+                cache.cache_dir,
+                cache.size,
+                CleanableMetadata::needs_review(),
+            ));
+        }
+
+        Ok(items)
+    }
+
+    fn name(&self) -> &str {
+        "R"
+    }
+
+    fn icon(&self) -> &str {
+        "📊"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_find_r_projects() -> Result<()> {
+        let temp = TempDir::new()?;
+        let project_dir = temp.path().join("analysis");
+        let library_dir = project_dir.join("renv").join("library");
+        fs::create_dir_all(library_dir.join("R-4.3").join("dplyr"))?;
+        fs::write(project_dir.join("renv.lock"), "{}")?;
+        fs::write(library_dir.join("R-4.3").join("dplyr").join("DESCRIPTION"), "pkg")?;
+
+        // renv/library がないプロジェクトは除外
+        let clean_dir = temp.path().join("fresh");
+        fs::create_dir(&clean_dir)?;
+        fs::write(clean_dir.join("renv.lock"), "{}")?;
+
+        let projects = find_r_projects(temp.path())?;
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].root, project_dir);
+        assert!(projects[0].size > 0);
+
+        clean_project(&projects[0])?;
+        assert!(!library_dir.exists());
+        assert!(project_dir.join("renv.lock").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_global_cache_dirs() {
+        let home = PathBuf::from("/Users/test");
+
+        assert_eq!(
+            global_cache_dirs(&home, |_| None),
+            vec![
+                PathBuf::from("/Users/test/.cache/R/renv"),
+                PathBuf::from("/Users/test/Library/Caches/org.R-project.R"),
+            ]
+        );
+        assert_eq!(
+            global_cache_dirs(&home, |name| {
+                (name == "RENV_PATHS_CACHE").then(|| "/custom/renv".to_string())
+            })[0],
+            PathBuf::from("/custom/renv")
+        );
+        assert_eq!(
+            global_cache_dirs(&home, |name| {
+                (name == "XDG_CACHE_HOME").then(|| "/xdg".to_string())
+            })[0],
+            PathBuf::from("/xdg/R/renv")
+        );
+    }
+}
@@ -1,11 +1,23 @@
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
+use std::process::{Command, Output};
 
-use crate::{Result, StorageClient};
+use chrono::{DateTime, Utc};
+
+use crate::{
+    storage::{RemoteFile, UploadProgress},
+    utils, Result, StorageClient,
+};
+
+/// rclone のバージョン（例: "rclone v1.66.0"。インストールされていなければ None）
+pub fn version() -> Option<String> {
+    utils::tool_version(Command::new("rclone").arg("version"))
+}
 
 /// Rclone CLI のラッパー
 pub struct RcloneClient {
     remote: String,
+    /// 転送帯域の上限（rclone の --bwlimit にそのまま渡す。例: "10M"）
+    bwlimit: Option<String>,
 }
 
 impl RcloneClient {
@@ -13,17 +25,30 @@ impl RcloneClient {
         if remote.is_empty() {
             return Err(crate::Error::Config("Rclone remote is empty".into()));
         }
-        Ok(Self { remote })
+        Ok(Self {
+            remote,
+            bwlimit: None,
+        })
+    }
+
+    /// 転送帯域の上限を設定
+    pub fn with_bwlimit(mut self, bwlimit: Option<String>) -> Self {
+        self.bwlimit = bwlimit;
+        self
+    }
+
+    /// 転送系コマンドに付ける --bwlimit 引数
+    fn bwlimit_args(&self) -> Vec<&str> {
+        match &self.bwlimit {
+            Some(bwlimit) => vec!["--bwlimit", bwlimit],
+            None => Vec::new(),
+        }
     }
 
     /// Rclone CLI がインストールされているか確認
     pub fn is_installed() -> bool {
-        Command::new("rclone")
-            .arg("version")
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .status()
-            .map(|s| s.success())
+        utils::run_command_with_timeout(Command::new("rclone").arg("version"), utils::command_timeout())
+            .map(|output| output.status.success())
             .unwrap_or(false)
     }
 
@@ -36,26 +61,37 @@ impl RcloneClient {
 impl StorageClient for RcloneClient {
     fn authorize(&self) -> Result<()> {
         // rclone は設定ファイルベースなので、ここでは接続テストを行う
-        let output = Command::new("rclone")
-            .arg("lsd")
-            .arg(&self.remote)
-            .arg("--max-depth")
-            .arg("1")
-            .output()
-            .map_err(|e| crate::Error::B2(format!("Failed to run rclone lsd: {}", e)))?;
+        let output = run_metadata_command(
+            Command::new("rclone")
+                .arg("lsd")
+                .arg(&self.remote)
+                .arg("--max-depth")
+                .arg("1"),
+            "Failed to run rclone lsd",
+        )?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(crate::Error::B2(format!(
-                "Failed to access rclone remote: {}",
-                stderr
-            )));
+            return Err(crate::Error::external_tool("rclone lsd", &output));
         }
 
         Ok(())
     }
 
-    fn upload_file(&self, _bucket: &str, local_path: &Path, remote_path: &str) -> Result<String> {
+    fn health_check(&self) -> Result<()> {
+        if !RcloneClient::is_installed() {
+            return Err(crate::Error::B2("Rclone CLI is not installed".into()));
+        }
+        // lsd でリモートに到達できるか確認
+        self.authorize()
+    }
+
+    fn upload_file(
+        &self,
+        _bucket: &str,
+        local_path: &Path,
+        remote_path: &str,
+        progress: UploadProgress,
+    ) -> Result<String> {
         let remote_full = self.build_remote_path(remote_path);
 
         let output = Command::new("rclone")
@@ -63,12 +99,17 @@ impl StorageClient for RcloneClient {
             .arg(local_path)
             .arg(&remote_full)
             .arg("--progress")
+            .args(self.bwlimit_args())
             .output()
             .map_err(|e| crate::Error::B2(format!("Failed to upload file: {}", e)))?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(crate::Error::B2(format!("Upload failed: {}", stderr)));
+            return Err(crate::Error::external_tool("rclone copyto", &output));
+        }
+
+        // rclone の進捗出力は解析しないため、完了時にまとめて通知する
+        if let Some(on_progress) = progress {
+            on_progress(std::fs::metadata(local_path).map(|m| m.len()).unwrap_or(0));
         }
 
         Ok(remote_full)
@@ -79,6 +120,7 @@ impl StorageClient for RcloneClient {
         _bucket: &str,
         local_dir: &Path,
         remote_prefix: &str,
+        progress: UploadProgress,
     ) -> Result<Vec<String>> {
         let remote_full = self.build_remote_path(remote_prefix);
 
@@ -87,18 +129,28 @@ impl StorageClient for RcloneClient {
             .arg(local_dir)
             .arg(&remote_full)
             .arg("--progress")
+            .args(self.bwlimit_args())
             .output()
             .map_err(|e| crate::Error::B2(format!("Failed to upload directory: {}", e)))?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(crate::Error::B2(format!("Upload failed: {}", stderr)));
+            return Err(crate::Error::external_tool("rclone copy", &output));
+        }
+
+        if let Some(on_progress) = progress {
+            on_progress(utils::calculate_dir_size(local_dir)?);
         }
 
         // rclone copy は個別のファイルIDを返さないので、空のベクタを返す
         Ok(vec![])
     }
 
+    fn sync_directory(&self, bucket: &str, local_dir: &Path, remote_prefix: &str) -> Result<()> {
+        // rclone copy は変更のあったファイルのみ転送するため、そのまま同期として使える
+        self.upload_directory(bucket, local_dir, remote_prefix, None)
+            .map(|_| ())
+    }
+
     fn download_file_by_name(
         &self,
         _bucket: &str,
@@ -118,55 +170,180 @@ impl StorageClient for RcloneClient {
             .arg(&remote_full)
             .arg(local_path)
             .arg("--progress")
+            .args(self.bwlimit_args())
             .output()
             .map_err(|e| crate::Error::B2(format!("Failed to download file: {}", e)))?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(crate::Error::B2(format!("Download failed: {}", stderr)));
+            return Err(crate::Error::external_tool("rclone copyto", &output));
         }
 
         Ok(())
     }
 
-    fn list_files(&self, _bucket: &str, prefix: &str) -> Result<Vec<String>> {
-        let remote_full = self.build_remote_path(prefix);
+    fn download_directory(&self, _bucket: &str, remote_prefix: &str, local_dir: &Path) -> Result<()> {
+        let remote_full = self.build_remote_path(remote_prefix.trim_end_matches('/'));
+
+        std::fs::create_dir_all(local_dir)
+            .map_err(|e| crate::Error::B2(format!("Failed to create directory: {}", e)))?;
 
+        // --ignore-times: 更新日時・サイズが同じでも常に上書きする（ファイル単位の復元と同じ動作）
         let output = Command::new("rclone")
-            .arg("lsf")
+            .arg("copy")
             .arg(&remote_full)
-            .arg("--recursive")
-            .arg("--files-only")
+            .arg(local_dir)
+            .arg("--ignore-times")
+            .args(self.bwlimit_args())
             .output()
-            .map_err(|e| crate::Error::B2(format!("Failed to list files: {}", e)))?;
+            .map_err(|e| crate::Error::B2(format!("Failed to download directory: {}", e)))?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(crate::Error::B2(format!("List files failed: {}", stderr)));
+            return Err(crate::Error::external_tool("rclone copy", &output));
+        }
+
+        Ok(())
+    }
+
+    fn file_exists_with_sha(&self, _bucket: &str, remote_path: &str, sha256: &str) -> Result<bool> {
+        let remote_full = self.build_remote_path(remote_path);
+
+        let output = run_metadata_command(
+            Command::new("rclone")
+                .arg("hashsum")
+                .arg("sha256")
+                .arg(&remote_full),
+            "Failed to run rclone hashsum",
+        )?;
+
+        // ファイルがない・リモートが SHA256 に対応していない場合はアップロードする
+        if !output.status.success() {
+            return Ok(false);
         }
 
         let stdout = String::from_utf8_lossy(&output.stdout);
-        let files: Vec<String> = stdout
-            .lines()
-            .filter(|line| !line.is_empty())
-            .map(|line| {
-                // prefix を付けて完全なパスを返す
-                if prefix.is_empty() {
-                    line.to_string()
-                } else {
-                    PathBuf::from(prefix).join(line).to_string_lossy().to_string()
-                }
-            })
-            .collect();
+        Ok(parse_hashsum(&stdout).is_some_and(|remote| remote.eq_ignore_ascii_case(sha256)))
+    }
 
-        Ok(files)
+    fn list_files_detailed(&self, _bucket: &str, prefix: &str) -> Result<Vec<RemoteFile>> {
+        let remote_full = self.build_remote_path(prefix);
+
+        let output = utils::run_command_with_timeout(
+            Command::new("rclone")
+                .arg("lsjson")
+                .arg(&remote_full)
+                .arg("--recursive")
+                .arg("--files-only"),
+            utils::list_timeout(),
+        )
+        .map_err(|e| match e {
+            crate::Error::Io(e) => crate::Error::B2(format!("Failed to list files: {}", e)),
+            e => e,
+        })?;
+
+        if !output.status.success() {
+            return Err(crate::Error::external_tool("rclone lsjson", &output));
+        }
+
+        parse_lsjson(&String::from_utf8_lossy(&output.stdout), prefix)
+            .ok_or_else(|| crate::Error::B2("Failed to parse rclone lsjson output".into()))
     }
+
+    fn delete_file(&self, _bucket: &str, remote_path: &str) -> Result<()> {
+        let remote_full = self.build_remote_path(remote_path);
+
+        let output = run_metadata_command(
+            Command::new("rclone").arg("deletefile").arg(&remote_full),
+            "Failed to delete file",
+        )?;
+
+        if !output.status.success() {
+            return Err(crate::Error::external_tool("rclone deletefile", &output));
+        }
+
+        Ok(())
+    }
+}
+
+/// 転送を伴わない rclone コマンドをタイムアウト付きで実行
+fn run_metadata_command(command: &mut Command, context: &str) -> Result<Output> {
+    utils::run_command_with_timeout(command, utils::command_timeout()).map_err(|e| match e {
+        crate::Error::Io(e) => crate::Error::B2(format!("{}: {}", context, e)),
+        e => e,
+    })
+}
+
+/// `rclone lsjson` の出力からファイル一覧を取得
+///
+/// パスは `prefix` からの相対パスなので、`prefix` を付けて完全なパスにする
+fn parse_lsjson(json: &str, prefix: &str) -> Option<Vec<RemoteFile>> {
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    let files = value
+        .as_array()?
+        .iter()
+        .filter(|file| !file.get("IsDir").and_then(|d| d.as_bool()).unwrap_or(false))
+        .filter_map(|file| {
+            let relative = file.get("Path")?.as_str()?;
+            let path = if prefix.is_empty() {
+                relative.to_string()
+            } else {
+                PathBuf::from(prefix).join(relative).to_string_lossy().to_string()
+            };
+            // サイズがわからないファイルは -1 になる
+            let size = file.get("Size").and_then(|size| size.as_u64());
+            let modified = file
+                .get("ModTime")
+                .and_then(|t| t.as_str())
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                .map(|t| t.with_timezone(&Utc));
+            Some(RemoteFile {
+                path,
+                size,
+                modified,
+            })
+        })
+        .collect();
+    Some(files)
+}
+
+/// `rclone hashsum` の出力（"<hash>  <name>"）から最初のハッシュを取得
+///
+/// ハッシュを計算できないファイルは空欄や "UNSUPPORTED" になるため除外する
+fn parse_hashsum(output: &str) -> Option<String> {
+    let hash = output.lines().next()?.split_whitespace().next()?;
+    hash.chars()
+        .all(|c| c.is_ascii_hexdigit())
+        .then(|| hash.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_lsjson() {
+        let json = r#"[
+{"Path":"20250101_000000/a.bin","Name":"a.bin","Size":100,"ModTime":"2025-01-01T09:00:00+09:00","IsDir":false},
+{"Path":"20250101_000000/b.bin","Name":"b.bin","Size":-1,"ModTime":"invalid","IsDir":false},
+{"Path":"20250101_000000/sub","Name":"sub","Size":-1,"IsDir":true}
+]"#;
+        assert_eq!(
+            parse_lsjson(json, "archive"),
+            Some(vec![
+                RemoteFile {
+                    path: "archive/20250101_000000/a.bin".to_string(),
+                    size: Some(100),
+                    modified: DateTime::from_timestamp(1735689600, 0),
+                },
+                RemoteFile {
+                    path: "archive/20250101_000000/b.bin".to_string(),
+                    size: None,
+                    modified: None,
+                },
+            ])
+        );
+        assert_eq!(parse_lsjson("not json", ""), None);
+    }
+
     #[test]
     fn test_rclone_cli_check() {
         let installed = RcloneClient::is_installed();
@@ -182,4 +359,26 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_bwlimit_args() -> Result<()> {
+        let client = RcloneClient::new("b2:my-bucket".to_string())?;
+        assert!(client.bwlimit_args().is_empty());
+
+        let client = client.with_bwlimit(Some("10M".to_string()));
+        assert_eq!(client.bwlimit_args(), vec!["--bwlimit", "10M"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_hashsum() {
+        assert_eq!(
+            parse_hashsum("b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9  data.bin\n")
+                .as_deref(),
+            Some("b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9")
+        );
+        assert_eq!(parse_hashsum("UNSUPPORTED  data.bin\n"), None);
+        assert_eq!(parse_hashsum(""), None);
+    }
 }
@@ -1,5 +1,8 @@
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime};
 use walkdir::WalkDir;
 
 use crate::{cleanable::{Cleanable, CleanableItem}, utils, Result};
@@ -13,6 +16,10 @@ pub struct RustProject {
     pub target_dir: PathBuf,
     /// target ディレクトリのサイズ（バイト）
     pub size: u64,
+    /// Cargo ワークスペースのルートか（`[workspace]` を含む Cargo.toml）
+    pub is_workspace: bool,
+    /// ワークスペース配下で見つかったメンバーのディレクトリ
+    pub members: Vec<PathBuf>,
 }
 
 impl RustProject {
@@ -27,43 +34,104 @@ impl RustProject {
     }
 }
 
+/// Cargo.toml が `[workspace]` を含むか
+///
+/// `[package]` を持たない仮想マニフェストもワークスペースとして扱う
+fn is_workspace_manifest(manifest: &Path) -> bool {
+    fs::read_to_string(manifest)
+        .ok()
+        .and_then(|content| content.parse::<toml::Table>().ok())
+        .is_some_and(|table| table.contains_key("workspace"))
+}
+
+/// Cargo のバージョン（例: "cargo 1.75.0 (1d8b05cdd 2023-11-20)"。インストールされていなければ None）
+pub fn cargo_version() -> Option<String> {
+    utils::tool_version(Command::new("cargo").arg("--version"))
+}
+
 /// 指定されたディレクトリ以下の Rust プロジェクトを検索
+///
+/// ワークスペースのメンバーはルートの target を共有するため、
+/// target はワークスペースのルートにまとめて 1 件として数える
 pub fn find_rust_projects(search_path: &Path) -> Result<Vec<RustProject>> {
-    let mut projects = Vec::new();
-
-    for entry in WalkDir::new(search_path)
-        .into_iter()
-        .filter_entry(|e| {
-            // target, .git, node_modules などの大きなディレクトリはスキップ
-            let file_name = e.file_name().to_string_lossy();
-            !matches!(
-                file_name.as_ref(),
-                "target" | ".git" | "node_modules" | ".cache"
-            )
-        })
-        .filter_map(|e| e.ok())
-    {
+    let mut manifest_dirs = Vec::new();
+
+    let entries = utils::walk_entries(utils::walk_dir(search_path), |_| false, |e| {
+        // target, .git, node_modules などの大きなディレクトリはスキップ
+        let file_name = e.file_name().to_string_lossy();
+        !matches!(
+            file_name.as_ref(),
+            "target" | ".git" | "node_modules" | ".cache"
+        )
+    });
+
+    for entry in entries.filter_map(|e| e.ok()) {
         if entry.file_type().is_file() && entry.file_name() == "Cargo.toml" {
             if let Some(project_root) = entry.path().parent() {
-                let target_dir = project_root.join("target");
+                manifest_dirs.push(project_root.to_path_buf());
+            }
+        }
+    }
 
-                // target ディレクトリが存在する場合のみ追加
-                if target_dir.exists() {
-                    let size = utils::calculate_dir_size(&target_dir)?;
+    // 親ディレクトリのワークスペースを先に確定させる
+    manifest_dirs.sort();
+    let workspace_roots: Vec<&PathBuf> = manifest_dirs
+        .iter()
+        .filter(|dir| is_workspace_manifest(&dir.join("Cargo.toml")))
+        .collect();
 
-                    projects.push(RustProject {
-                        root: project_root.to_path_buf(),
-                        target_dir,
-                        size,
-                    });
-                }
-            }
+    let mut projects = Vec::new();
+    // シンボリックリンク先や共有 target を二重に数えないため
+    let mut seen_dirs = HashSet::new();
+
+    for project_root in &manifest_dirs {
+        let is_workspace = workspace_roots.contains(&project_root);
+        let target_dir = project_root.join("target");
+
+        // target ディレクトリが存在する場合のみ追加
+        if !target_dir.exists() {
+            continue;
+        }
+
+        let size = utils::calculate_linked_dir_size(&target_dir, &mut seen_dirs)?;
+        if size == 0
+            && projects
+                .iter()
+                .any(|p: &RustProject| same_dir(&p.target_dir, &target_dir))
+        {
+            continue;
         }
+
+        let members = if is_workspace {
+            manifest_dirs
+                .iter()
+                .filter(|dir| *dir != project_root && dir.starts_with(project_root))
+                .cloned()
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        projects.push(RustProject {
+            root: project_root.clone(),
+            target_dir,
+            size,
+            is_workspace,
+            members,
+        });
     }
 
     Ok(projects)
 }
 
+/// 同じディレクトリを指しているか（シンボリックリンクを解決して比較）
+fn same_dir(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
 /// Rust プロジェクトの target ディレクトリを削除
 pub fn clean_project(project: &RustProject) -> Result<()> {
     if project.target_exists() {
@@ -72,39 +140,272 @@ pub fn clean_project(project: &RustProject) -> Result<()> {
     Ok(())
 }
 
-/// 複数の Rust プロジェクトをクリーン
-pub fn clean_projects(projects: &[RustProject]) -> Result<Vec<PathBuf>> {
-    let mut cleaned = Vec::new();
+/// target 内で最終更新が older_than_days 日より前のファイル（パス, サイズ）
+///
+/// 削除対象の判定なので、シンボリックリンクはたどらない
+fn stale_files(target_dir: &Path, older_than_days: u64) -> Vec<(PathBuf, u64)> {
+    let cutoff = SystemTime::now()
+        .checked_sub(Duration::from_secs(older_than_days * 24 * 60 * 60))
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+
+    WalkDir::new(target_dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| {
+            let metadata = e.metadata().ok()?;
+            let modified = metadata.modified().ok()?;
+            (modified < cutoff).then(|| (e.into_path(), metadata.len()))
+        })
+        .collect()
+}
+
+/// 古い成果物のサイズ（clean_stale で解放されるバイト数）
+pub fn stale_size(project: &RustProject, older_than_days: u64) -> u64 {
+    stale_files(&project.target_dir, older_than_days)
+        .iter()
+        .map(|(_, size)| size)
+        .sum()
+}
+
+/// target 内の古い成果物だけを削除（`cargo sweep --time` 相当）
+///
+/// 最終更新が older_than_days 日より前のファイルを削除し、空になったディレクトリも片付ける。
+/// 最近のビルドで使われた成果物は残るため、インクリメンタルビルドが効く。解放したバイト数を返す
+pub fn clean_stale(project: &RustProject, older_than_days: u64) -> Result<u64> {
+    let mut freed = 0;
 
-    for project in projects {
-        clean_project(project)?;
-        cleaned.push(project.root.clone());
+    for (path, size) in stale_files(&project.target_dir, older_than_days) {
+        fs::remove_file(&path)?;
+        freed += size;
     }
 
-    Ok(cleaned)
+    // 空になったディレクトリを深い順に削除（target 自体は残す）
+    for entry in WalkDir::new(&project.target_dir)
+        .min_depth(1)
+        .contents_first(true)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_dir())
+    {
+        if fs::read_dir(entry.path())?.next().is_none() {
+            fs::remove_dir(entry.path())?;
+        }
+    }
+
+    Ok(freed)
+}
+
+/// target 直下にあってもプロファイルの出力先ではないディレクトリ
+const NON_PROFILE_DIRS: &[&str] = &["doc", "package", "tmp"];
+
+/// プロファイル名から target 内の出力ディレクトリ名を取得（dev プロファイルの出力先は debug）
+fn profile_dir_name(profile: &str) -> &str {
+    match profile {
+        "dev" => "debug",
+        other => other,
+    }
+}
+
+/// target 内で指定したプロファイルの出力ディレクトリ（存在するもののみ）
+///
+/// `target/<profile>` に加え、クロスコンパイル時の `target/<triple>/<profile>` も含める
+pub fn profile_dirs(project: &RustProject, profiles: &[String]) -> Result<Vec<PathBuf>> {
+    let mut names = Vec::new();
+    for profile in profiles {
+        let mut components = Path::new(profile).components();
+        if !matches!(
+            (components.next(), components.next()),
+            (Some(std::path::Component::Normal(_)), None)
+        ) {
+            return Err(crate::Error::InvalidPath(format!(
+                "Invalid Cargo profile name: {}",
+                profile
+            )));
+        }
+        names.push(profile_dir_name(profile));
+    }
+
+    let mut dirs = Vec::new();
+    for name in &names {
+        let dir = project.target_dir.join(name);
+        if dir.is_dir() && !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+
+    // target/<triple>/<profile>
+    if let Ok(entries) = fs::read_dir(&project.target_dir) {
+        for entry in entries.filter_map(|e| e.ok()) {
+            let child = entry.file_name().to_string_lossy().into_owned();
+            if !entry.file_type().is_ok_and(|t| t.is_dir())
+                || NON_PROFILE_DIRS.contains(&child.as_str())
+                || names.contains(&child.as_str())
+            {
+                continue;
+            }
+            for name in &names {
+                let dir = entry.path().join(name);
+                if dir.is_dir() && !dirs.contains(&dir) {
+                    dirs.push(dir);
+                }
+            }
+        }
+    }
+
+    dirs.sort();
+    Ok(dirs)
+}
+
+/// 指定したプロファイルの出力ディレクトリの合計サイズ（clean_profiles で解放されるバイト数）
+pub fn profiles_size(project: &RustProject, profiles: &[String]) -> Result<u64> {
+    profile_dirs(project, profiles)?
+        .iter()
+        .map(|dir| utils::calculate_dir_size(dir))
+        .sum()
+}
+
+/// target 内の指定したプロファイルの出力ディレクトリだけを削除（例: debug を消して release は残す）
+///
+/// doc/ や package/、他のプロファイルは残す。解放したバイト数を返す
+pub fn clean_profiles(project: &RustProject, profiles: &[String]) -> Result<u64> {
+    let mut freed = 0;
+    for dir in profile_dirs(project, profiles)? {
+        freed += utils::calculate_dir_size(&dir)?;
+        fs::remove_dir_all(&dir)?;
+    }
+    Ok(freed)
 }
 
 /// Rust プロジェクトクリーナー
 pub struct RustCleaner {
     pub search_path: PathBuf,
+    /// 最小サイズ（バイト。削除対象になるサイズで比べる）
+    pub min_size: u64,
+    /// この日数より前の成果物だけを削除する（--stale）
+    pub stale_days: Option<u64>,
+    /// target 内の指定したプロファイルの出力だけを削除する（--profile-dir）
+    pub profiles: Vec<String>,
 }
 
 impl RustCleaner {
     pub fn new(search_path: PathBuf) -> Self {
-        Self { search_path }
+        Self {
+            search_path,
+            min_size: 0,
+            stale_days: None,
+            profiles: Vec::new(),
+        }
+    }
+
+    /// 最小サイズ（バイト）を設定
+    pub fn with_min_size(mut self, min_size: u64) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /// target 全体ではなく、古い成果物だけを対象にする
+    pub fn with_stale_days(mut self, days: Option<u64>) -> Self {
+        self.stale_days = days;
+        self
+    }
+
+    /// target 全体ではなく、指定したプロファイルの出力だけを対象にする
+    pub fn with_profiles(mut self, profiles: Vec<String>) -> Self {
+        self.profiles = profiles;
+        self
+    }
+}
+
+/// スキャン結果の項目（パスは target ディレクトリ）からプロジェクトを復元
+fn project_of_item(item: &CleanableItem) -> RustProject {
+    RustProject {
+        root: item.path.parent().map(Path::to_path_buf).unwrap_or_default(),
+        target_dir: item.path.clone(),
+        size: item.size,
+        is_workspace: false,
+        members: Vec::new(),
     }
 }
 
 impl Cleanable for RustCleaner {
     fn scan(&self) -> Result<Vec<CleanableItem>> {
-        let projects = find_rust_projects(&self.search_path)?;
+        let mut projects = find_rust_projects(&self.search_path)?;
+
+        // --stale: サイズは削除対象になる古い成果物のみ
+        if let Some(days) = self.stale_days {
+            for project in &mut projects {
+                project.size = stale_size(project, days);
+            }
+            projects.retain(|p| p.size > 0);
+        }
+        // --profile-dir: 削除単位はプロファイルの出力ディレクトリごと
+        if !self.profiles.is_empty() {
+            let mut items = Vec::new();
+            for project in &projects {
+                let dirs = profile_dirs(project, &self.profiles)?
+                    .into_iter()
+                    .map(|dir| Ok((utils::calculate_dir_size(&dir)?, dir)))
+                    .collect::<Result<Vec<(u64, PathBuf)>>>()?;
+                // 最小サイズはプロジェクトごとの合計で比べる
+                let size: u64 = dirs.iter().map(|(size, _)| size).sum();
+                if size == 0 || size < self.min_size {
+                    continue;
+                }
+                for (size, dir) in dirs {
+                    let relative = dir.strip_prefix(&project.target_dir).unwrap_or(&dir).to_string_lossy().to_string();
+                    items.push(CleanableItem::new(
+                        format!("{} ({})", project.root.display(), relative),
+                        dir,
+                        size,
+                    ));
+                }
+            }
+            return Ok(items);
+        }
+        projects.retain(|p| p.size >= self.min_size);
 
         Ok(projects
             .into_iter()
-            .map(|p| CleanableItem::new(p.root.display().to_string(), p.target_dir, p.size))
+            .map(|p| {
+                let detail = p
+                    .is_workspace
+                    .then(|| format!("ワークスペース: {} メンバー", p.members.len()));
+                CleanableItem::new(p.root.display().to_string(), p.target_dir, p.size).with_detail(detail)
+            })
             .collect())
     }
 
+    /// --stale では target 内の古い成果物だけを削除する（項目のパスは target ディレクトリ）
+    fn clean(
+        &self,
+        items: &[CleanableItem],
+        on_cleaned: &mut dyn FnMut(&CleanableItem),
+    ) -> Result<Vec<String>> {
+        let Some(days) = self.stale_days else {
+            return crate::cleanable::clean_items(items, on_cleaned);
+        };
+
+        for item in items {
+            utils::assert_safe_target(&item.path)?;
+        }
+
+        let mut cleaned = Vec::new();
+        for item in items {
+            if utils::is_cancel_requested() {
+                break;
+            }
+            if utils::is_protected_path(&item.path) {
+                continue;
+            }
+            clean_stale(&project_of_item(item), days)?;
+            cleaned.push(item.name.clone());
+            on_cleaned(item);
+        }
+
+        Ok(cleaned)
+    }
+
     fn name(&self) -> &str {
         "Rust"
     }
@@ -144,6 +445,186 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_find_rust_projects_skip_hidden() -> Result<()> {
+        let temp = TempDir::new()?;
+        let hidden_project = temp.path().join(".local").join("vendored");
+        fs::create_dir_all(hidden_project.join("target"))?;
+        fs::write(hidden_project.join("Cargo.toml"), "[package]\nname = \"vendored\"")?;
+
+        // デフォルトでは隠しディレクトリ内のプロジェクトも見つかる
+        {
+            let _settings = utils::TestSettings::skip_hidden(false);
+            assert_eq!(find_rust_projects(temp.path())?.len(), 1);
+        }
+        // --no-hidden では降りない
+        let _settings = utils::TestSettings::skip_hidden(true);
+        assert!(find_rust_projects(temp.path())?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_rust_projects_workspace() -> Result<()> {
+        let temp = TempDir::new()?;
+        let workspace_dir = temp.path().join("workspace");
+
+        // [package] を持たない仮想マニフェスト
+        fs::create_dir_all(&workspace_dir)?;
+        fs::write(
+            workspace_dir.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\nresolver = \"2\"\n",
+        )?;
+
+        for name in ["app", "lib"] {
+            let member_dir = workspace_dir.join("crates").join(name);
+            fs::create_dir_all(member_dir.join("src"))?;
+            fs::write(
+                member_dir.join("Cargo.toml"),
+                format!("[package]\nname = \"{}\"\nversion = \"0.1.0\"\n", name),
+            )?;
+        }
+
+        // メンバーのビルド成果物はワークスペースルートの target に出力される
+        let target_dir = workspace_dir.join("target");
+        fs::create_dir_all(target_dir.join("debug"))?;
+        fs::write(target_dir.join("debug").join("app"), vec![0u8; 100])?;
+
+        let projects = find_rust_projects(temp.path())?;
+
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].root, workspace_dir);
+        assert_eq!(projects[0].target_dir, target_dir);
+        assert_eq!(projects[0].size, 100);
+        assert!(projects[0].is_workspace);
+        assert_eq!(
+            projects[0].members,
+            vec![
+                workspace_dir.join("crates").join("app"),
+                workspace_dir.join("crates").join("lib"),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_find_rust_projects_workspace_linked_target() -> Result<()> {
+        use std::os::unix::fs::symlink;
+
+        let temp = TempDir::new()?;
+        let workspace_dir = temp.path().join("workspace");
+        let member_dir = workspace_dir.join("member");
+        fs::create_dir_all(&member_dir)?;
+        fs::write(
+            workspace_dir.join("Cargo.toml"),
+            "[package]\nname = \"root\"\n\n[workspace]\nmembers = [\"member\"]\n",
+        )?;
+        fs::write(member_dir.join("Cargo.toml"), "[package]\nname = \"member\"\n")?;
+
+        let target_dir = workspace_dir.join("target");
+        fs::create_dir(&target_dir)?;
+        fs::write(target_dir.join("lib.rlib"), vec![0u8; 10])?;
+
+        // メンバーの target がルートの target を指している場合も 1 件にまとめる
+        symlink(&target_dir, member_dir.join("target"))?;
+
+        let projects = find_rust_projects(temp.path())?;
+
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].root, workspace_dir);
+        assert!(projects[0].is_workspace);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_clean_profiles() -> Result<()> {
+        let temp = TempDir::new()?;
+        let target_dir = temp.path().join("target");
+        for dir in [
+            "debug/deps",
+            "release/deps",
+            "doc/debug",
+            "aarch64-apple-darwin/debug/deps",
+            "aarch64-apple-darwin/release",
+        ] {
+            fs::create_dir_all(target_dir.join(dir))?;
+        }
+        fs::write(target_dir.join("debug/deps/libfoo.rlib"), vec![0u8; 100])?;
+        fs::write(target_dir.join("release/deps/libfoo.rlib"), vec![0u8; 50])?;
+        fs::write(target_dir.join("doc/debug/index.html"), vec![0u8; 5])?;
+        fs::write(target_dir.join("aarch64-apple-darwin/debug/deps/libfoo.rlib"), vec![0u8; 20])?;
+
+        let project = RustProject {
+            root: temp.path().to_path_buf(),
+            target_dir: target_dir.clone(),
+            size: 175,
+            is_workspace: false,
+            members: Vec::new(),
+        };
+
+        // dev プロファイルの出力先は debug。クロスコンパイルの出力も含め、doc は対象外
+        let profiles = vec!["dev".to_string()];
+        assert_eq!(
+            profile_dirs(&project, &profiles)?,
+            vec![target_dir.join("aarch64-apple-darwin/debug"), target_dir.join("debug")]
+        );
+        assert_eq!(profiles_size(&project, &profiles)?, 120);
+        assert_eq!(profiles_size(&project, &["bench-lto".to_string()])?, 0);
+        assert!(profiles_size(&project, &["../src".to_string()]).is_err());
+
+        assert_eq!(clean_profiles(&project, &profiles)?, 120);
+        assert!(!target_dir.join("debug").exists());
+        assert!(!target_dir.join("aarch64-apple-darwin/debug").exists());
+        assert!(target_dir.join("release/deps/libfoo.rlib").exists());
+        assert!(target_dir.join("doc/debug/index.html").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_clean_stale() -> Result<()> {
+        let temp = TempDir::new()?;
+        let target_dir = temp.path().join("target");
+        let old_dir = target_dir.join("debug").join("incremental").join("old-abc");
+        let deps_dir = target_dir.join("debug").join("deps");
+        fs::create_dir_all(&old_dir)?;
+        fs::create_dir_all(&deps_dir)?;
+
+        let old_file = old_dir.join("query-cache.bin");
+        let fresh_file = deps_dir.join("libfoo.rlib");
+        fs::write(&old_file, vec![0u8; 100])?;
+        fs::write(&fresh_file, vec![0u8; 10])?;
+
+        // 30 日前に更新されたことにする
+        let old_time = SystemTime::now() - Duration::from_secs(30 * 24 * 60 * 60);
+        fs::File::options()
+            .write(true)
+            .open(&old_file)?
+            .set_modified(old_time)?;
+
+        let project = RustProject {
+            root: temp.path().to_path_buf(),
+            target_dir: target_dir.clone(),
+            size: 110,
+            is_workspace: false,
+            members: Vec::new(),
+        };
+
+        assert_eq!(stale_size(&project, 7), 100);
+        assert_eq!(stale_size(&project, 60), 0);
+
+        assert_eq!(clean_stale(&project, 7)?, 100);
+        assert!(!old_file.exists());
+        assert!(!old_dir.exists());
+        assert!(fresh_file.exists());
+        assert!(target_dir.exists());
+
+        Ok(())
+    }
+
     #[test]
     fn test_clean_project() -> Result<()> {
         let temp = TempDir::new()?;
@@ -158,6 +639,8 @@ mod tests {
             root: project_dir.clone(),
             target_dir: target_dir.clone(),
             size: 100,
+            is_workspace: false,
+            members: Vec::new(),
         };
 
         assert!(target_dir.exists());
@@ -168,4 +651,49 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_rust_cleaner_clean() -> Result<()> {
+        let temp = TempDir::new()?;
+        for (name, debug, release) in [("small", 10, 0), ("large", 300, 200)] {
+            let project_dir = temp.path().join(name);
+            fs::create_dir_all(project_dir.join("target").join("debug"))?;
+            fs::write(project_dir.join("Cargo.toml"), "[package]\nname = \"test\"")?;
+            fs::write(project_dir.join("target").join("debug").join("a"), vec![0u8; debug])?;
+            if release > 0 {
+                fs::create_dir_all(project_dir.join("target").join("release"))?;
+                fs::write(project_dir.join("target").join("release").join("b"), vec![0u8; release])?;
+            }
+        }
+        let large = temp.path().join("large").join("target");
+        let small = temp.path().join("small").join("target");
+
+        // --profile-dir: 項目はプロファイルの出力ディレクトリで、それだけを削除する
+        let cleaner = RustCleaner::new(temp.path().to_path_buf()).with_profiles(vec!["release".to_string()]);
+        let items = cleaner.scan()?;
+        assert_eq!(items.len(), 1);
+        assert_eq!((items[0].path.clone(), items[0].size), (large.join("release"), 200));
+        let cleaned = cleaner.clean(&items, &mut |_| {})?;
+        assert_eq!(cleaned, vec![items[0].name.clone()]);
+        assert!(!large.join("release").exists());
+        assert!(large.join("debug").exists());
+
+        // 最小サイズは target 全体のサイズで比べ、target ごと削除する
+        let cleaner = RustCleaner::new(temp.path().to_path_buf()).with_min_size(100);
+        let items = cleaner.scan()?;
+        assert_eq!(items.len(), 1);
+        assert_eq!((items[0].path.clone(), items[0].size), (large.clone(), 300));
+        let mut reported = 0;
+        let cleaned = cleaner.clean(&items, &mut |item| reported += item.size)?;
+        assert_eq!(cleaned.len(), 1);
+        assert_eq!(reported, 300);
+        assert!(!large.exists());
+        assert!(small.exists());
+
+        // --stale: 古い成果物がなければ対象にならない
+        let cleaner = RustCleaner::new(temp.path().to_path_buf()).with_stale_days(Some(1));
+        assert!(cleaner.scan()?.is_empty());
+
+        Ok(())
+    }
 }
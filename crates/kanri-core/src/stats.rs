@@ -0,0 +1,135 @@
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::Result;
+
+/// clean --stats-only の 1 回分の結果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatsEntry {
+    /// クリーナー（実行したコマンド。例: "clean rust"）
+    pub cleaner: String,
+    /// 実行日時
+    pub timestamp: DateTime<Utc>,
+    /// 見つかった件数
+    pub count: usize,
+    /// 合計サイズ（バイト）
+    pub total_size: u64,
+}
+
+impl StatsEntry {
+    /// 現在時刻で新しいエントリを作成
+    pub fn new(cleaner: String, count: usize, total_size: u64) -> Self {
+        Self {
+            cleaner,
+            timestamp: Utc::now(),
+            count,
+            total_size,
+        }
+    }
+}
+
+/// クリーナーごとのサイズレポート（--report で複数回の実行を 1 つの JSON にまとめる）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StatsReport {
+    /// 実行結果（古い順）
+    pub entries: Vec<StatsEntry>,
+}
+
+impl StatsReport {
+    /// レポートファイルを読み込み（存在しない場合は空のレポート）
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content).map_err(|e| {
+            crate::Error::Config(format!(
+                "Failed to parse stats report {}: {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+
+    /// エントリを追加（同じクリーナー・日時のエントリは置き換える）
+    pub fn merge(&mut self, entry: StatsEntry) {
+        match self
+            .entries
+            .iter_mut()
+            .find(|e| e.cleaner == entry.cleaner && e.timestamp == entry.timestamp)
+        {
+            Some(existing) => *existing = entry,
+            None => self.entries.push(entry),
+        }
+        self.entries
+            .sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then_with(|| a.cleaner.cmp(&b.cleaner)));
+    }
+
+    /// レポートファイルに書き出す
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let content = serde_json::to_string_pretty(self).map_err(|e| {
+            crate::Error::Config(format!("Failed to serialize stats report: {}", e))
+        })?;
+        crate::utils::write_atomic(path, content)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_merge_and_save() -> Result<()> {
+        let temp = TempDir::new()?;
+        let path = temp.path().join("reports").join("stats.json");
+
+        // 存在しないファイルは空のレポートとして読み込む
+        let mut report = StatsReport::load(&path)?;
+        assert!(report.entries.is_empty());
+
+        let mut earlier = StatsEntry::new("clean rust".to_string(), 3, 3000);
+        earlier.timestamp = Utc::now() - Duration::hours(1);
+        let node = StatsEntry::new("clean node".to_string(), 2, 200);
+        report.merge(node.clone());
+        report.merge(earlier.clone());
+        report.save(&path)?;
+
+        // 別の実行で追記すると、既存のエントリとまとめて日時順に並ぶ
+        let mut report = StatsReport::load(&path)?;
+        let rust = StatsEntry::new("clean rust".to_string(), 4, 4000);
+        report.merge(rust.clone());
+        assert_eq!(report.entries, vec![earlier.clone(), node.clone(), rust]);
+
+        // 同じクリーナー・日時のエントリは置き換える
+        let mut updated = earlier.clone();
+        updated.total_size = 3500;
+        report.merge(updated);
+        assert_eq!(report.entries.len(), 3);
+        assert_eq!(report.entries[0].total_size, 3500);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_invalid() -> Result<()> {
+        let temp = TempDir::new()?;
+        let path = temp.path().join("stats.json");
+        fs::write(&path, "{not json")?;
+
+        assert!(StatsReport::load(&path).is_err());
+
+        Ok(())
+    }
+}
@@ -1,23 +1,77 @@
 use std::path::Path;
 
+use chrono::{DateTime, Utc};
+
 use crate::Result;
 
+/// アップロードの進捗コールバック
+///
+/// その呼び出しでアップロード済みになったバイト数（累計）を受け取る
+pub type UploadProgress<'a> = Option<&'a dyn Fn(u64)>;
+
+/// リモートのファイル（一覧取得の結果）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteFile {
+    /// パス（バケット内）
+    pub path: String,
+    /// サイズ（バイト）。取得できなかった場合は None
+    pub size: Option<u64>,
+    /// 更新日時。取得できなかった場合は None
+    pub modified: Option<DateTime<Utc>>,
+}
+
 /// クラウドストレージクライアントの共通インターフェース
-pub trait StorageClient {
+///
+/// 並列ダウンロードなどでスレッド間共有できるよう `Send + Sync` を要求する
+pub trait StorageClient: Send + Sync {
     /// 認証を行う
     fn authorize(&self) -> Result<()>;
 
+    /// 利用可能か確認（CLI のインストールと認証・接続）
+    ///
+    /// デフォルトでは認証のみ試す
+    fn health_check(&self) -> Result<()> {
+        self.authorize()
+    }
+
     /// ファイルをアップロード
-    fn upload_file(&self, bucket: &str, local_path: &Path, remote_path: &str) -> Result<String>;
+    fn upload_file(
+        &self,
+        bucket: &str,
+        local_path: &Path,
+        remote_path: &str,
+        progress: UploadProgress,
+    ) -> Result<String>;
 
-    /// ディレクトリを再帰的にアップロード
+    /// SHA256 を計算済みのファイルをアップロード
+    ///
+    /// アップロード時にハッシュを記録するバックエンドで、ファイルをもう一度読まずに済ませる。
+    /// デフォルトでは `upload_file` と同じ
+    fn upload_file_with_sha256(
+        &self,
+        bucket: &str,
+        local_path: &Path,
+        remote_path: &str,
+        _sha256: &str,
+        progress: UploadProgress,
+    ) -> Result<String> {
+        self.upload_file(bucket, local_path, remote_path, progress)
+    }
+
+    /// ディレクトリを再帰的にアップロード（進捗はディレクトリ全体の累計）
     fn upload_directory(
         &self,
         bucket: &str,
         local_dir: &Path,
         remote_prefix: &str,
+        progress: UploadProgress,
     ) -> Result<Vec<String>>;
 
+    /// ディレクトリを同期（変更のあったファイルのみ並列でアップロード）
+    ///
+    /// 中断しても再実行すればアップロード済みのファイルはスキップされる
+    fn sync_directory(&self, bucket: &str, local_dir: &Path, remote_prefix: &str) -> Result<()>;
+
     /// ファイルをダウンロード
     fn download_file_by_name(
         &self,
@@ -26,6 +80,44 @@ pub trait StorageClient {
         local_path: &Path,
     ) -> Result<()>;
 
-    /// ファイル一覧を取得
-    fn list_files(&self, bucket: &str, prefix: &str) -> Result<Vec<String>>;
+    /// リモートのディレクトリ（プレフィックス）配下をまとめてローカルのディレクトリにダウンロード
+    ///
+    /// 相対的な配置を保ったまま、既存のファイルは上書きする。ファイルごとにコマンドを起動しないため大量のファイルで高速
+    fn download_directory(&self, bucket: &str, remote_prefix: &str, local_dir: &Path) -> Result<()>;
+
+    /// 指定パスに同じ内容（SHA256 が一致）のファイルが既にあるか確認
+    ///
+    /// ハッシュを確認できないバックエンドでは false（常にアップロードする）
+    fn file_exists_with_sha(&self, _bucket: &str, _remote_path: &str, _sha256: &str) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// ファイル一覧をサイズ・更新日時付きで取得
+    fn list_files_detailed(&self, bucket: &str, prefix: &str) -> Result<Vec<RemoteFile>>;
+
+    /// ファイル一覧（パスのみ）を取得
+    fn list_files(&self, bucket: &str, prefix: &str) -> Result<Vec<String>> {
+        Ok(self
+            .list_files_detailed(bucket, prefix)?
+            .into_iter()
+            .map(|file| file.path)
+            .collect())
+    }
+
+    /// ファイルを削除
+    fn delete_file(&self, bucket: &str, remote_path: &str) -> Result<()>;
+
+    /// アップロードしたファイルの自動削除（set_expiry）に対応しているか
+    fn supports_expiry(&self) -> bool {
+        false
+    }
+
+    /// `prefix` 以下のファイルをアップロードから `days` 日後に自動削除するよう設定
+    ///
+    /// 対応していないバックエンドではエラー
+    fn set_expiry(&self, _bucket: &str, _prefix: &str, _days: u32) -> Result<()> {
+        Err(crate::Error::Config(
+            "This storage backend does not support file expiry".into(),
+        ))
+    }
 }
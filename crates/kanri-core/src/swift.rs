@@ -0,0 +1,188 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    cleanable::{Cleanable, CleanableItem, CleanableMetadata},
+    utils, Result,
+};
+
+/// Swift Package Manager プロジェクト情報
+#[derive(Debug, Clone)]
+pub struct SwiftProject {
+    /// プロジェクトのルートディレクトリ（Package.swift があるディレクトリ）
+    pub root: PathBuf,
+    /// .build ディレクトリのパス
+    pub build_dir: PathBuf,
+    /// サイズ（バイト）
+    pub size: u64,
+}
+
+impl SwiftProject {
+    /// サイズを人間が読みやすい形式で取得
+    pub fn formatted_size(&self) -> String {
+        utils::format_size(self.size)
+    }
+}
+
+/// SwiftPM グローバルキャッシュ情報
+#[derive(Debug, Clone)]
+pub struct SwiftPmCache {
+    /// キャッシュディレクトリのパス
+    pub cache_dir: PathBuf,
+    /// サイズ（バイト）
+    pub size: u64,
+}
+
+/// 指定されたディレクトリ以下の SwiftPM プロジェクトを検索
+pub fn find_swift_projects(search_path: &Path) -> Result<Vec<SwiftProject>> {
+    let mut projects = Vec::new();
+
+    let entries = utils::walk_entries(utils::walk_dir(search_path), |_| false, |e| {
+        // .build 配下には依存パッケージの Package.swift が含まれるため降りない
+        let file_name = e.file_name().to_string_lossy();
+        !matches!(
+            file_name.as_ref(),
+            "target" | ".git" | "node_modules" | ".cache" | ".build"
+        )
+    });
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        if entry.file_type().is_file() && entry.file_name() == "Package.swift" {
+            if let Some(project_root) = entry.path().parent() {
+                let build_dir = project_root.join(".build");
+
+                // .build ディレクトリが存在する場合のみ追加
+                if build_dir.is_dir() {
+                    let size = utils::calculate_dir_size(&build_dir)?;
+
+                    projects.push(SwiftProject {
+                        root: project_root.to_path_buf(),
+                        build_dir,
+                        size,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(projects)
+}
+
+/// SwiftPM グローバルキャッシュを検索
+pub fn find_swiftpm_cache() -> Result<Option<SwiftPmCache>> {
+    let home = match env::var("HOME") {
+        Ok(home) => PathBuf::from(home),
+        Err(_) => return Ok(None),
+    };
+
+    let cache_dir = home
+        .join("Library")
+        .join("Caches")
+        .join("org.swift.swiftpm");
+
+    if !cache_dir.exists() {
+        return Ok(None);
+    }
+
+    let size = utils::calculate_dir_size(&cache_dir)?;
+
+    Ok(Some(SwiftPmCache { cache_dir, size }))
+}
+
+/// SwiftPM プロジェクトの .build を削除
+pub fn clean_project(project: &SwiftProject) -> Result<()> {
+    if project.build_dir.exists() {
+        fs::remove_dir_all(&project.build_dir)?;
+    }
+    Ok(())
+}
+
+/// Swift Package Manager クリーナー
+pub struct SwiftCleaner {
+    pub search_path: PathBuf,
+    /// SwiftPM グローバルキャッシュも対象にするか
+    pub include_global: bool,
+}
+
+impl SwiftCleaner {
+    pub fn new(search_path: PathBuf) -> Self {
+        Self {
+            search_path,
+            include_global: false,
+        }
+    }
+
+    pub fn with_global(mut self, include_global: bool) -> Self {
+        self.include_global = include_global;
+        self
+    }
+}
+
+impl Cleanable for SwiftCleaner {
+    fn scan(&self) -> Result<Vec<CleanableItem>> {
+        let mut items: Vec<CleanableItem> = find_swift_projects(&self.search_path)?
+            .into_iter()
+            .map(|p| CleanableItem::new(p.root.display().to_string(), p.build_dir, p.size))
+            .collect();
+
+        if self.include_global {
+            if let Some(cache) = find_swiftpm_cache()? {
+                // リポジトリのクローンを含むため、削除すると再取得が必要になる
+                items.push(CleanableItem::with_metadata(
+                    "SwiftPM cache".to_string(),
+                    cache.cache_dir,
+                    cache.size,
+                    CleanableMetadata::needs_review(),
+                ));
+            }
+        }
+
+        Ok(items)
+    }
+
+    fn name(&self) -> &str {
+        "Swift"
+    }
+
+    fn icon(&self) -> &str {
+        "🐦"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_find_swift_projects() -> Result<()> {
+        let temp = TempDir::new()?;
+        let project_dir = temp.path().join("MyPackage");
+        let build_dir = project_dir.join(".build");
+        fs::create_dir_all(&build_dir)?;
+        fs::write(project_dir.join("Package.swift"), "// swift-tools-version:5.9")?;
+        fs::write(build_dir.join("debug.yaml"), "build data")?;
+
+        // .build 配下の依存パッケージは検出されない
+        let checkout = build_dir.join("checkouts").join("Dependency");
+        fs::create_dir_all(checkout.join(".build"))?;
+        fs::write(checkout.join("Package.swift"), "")?;
+
+        // .build がないプロジェクトは除外
+        let clean_dir = temp.path().join("CleanPackage");
+        fs::create_dir(&clean_dir)?;
+        fs::write(clean_dir.join("Package.swift"), "")?;
+
+        let projects = find_swift_projects(temp.path())?;
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].root, project_dir);
+        assert!(projects[0].size > 0);
+
+        clean_project(&projects[0])?;
+        assert!(!build_dir.exists());
+        assert!(project_dir.join("Package.swift").exists());
+
+        Ok(())
+    }
+}
@@ -1,13 +1,289 @@
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
 use walkdir::WalkDir;
 
 use crate::Result;
 
+/// ディレクトリサイズのキャッシュ（(パス, ハードリンク重複除外) -> サイズ）
+type SizeCache = HashMap<(PathBuf, bool), u64>;
+static SIZE_CACHE: OnceLock<Mutex<SizeCache>> = OnceLock::new();
+
+/// シンボリックリンクをたどるか（--follow-symlinks）
+static FOLLOW_SYMLINKS: AtomicBool = AtomicBool::new(false);
+
+/// 探索・サイズ計算でシンボリックリンクをたどるかを設定
+///
+/// たどらない場合（デフォルト）、ディレクトリ内のシンボリックリンクはサイズに含めない
+pub fn set_follow_symlinks(follow: bool) {
+    FOLLOW_SYMLINKS.store(follow, Ordering::Relaxed);
+}
+
+/// シンボリックリンクをたどる設定か
+pub fn follow_symlinks() -> bool {
+    FOLLOW_SYMLINKS.load(Ordering::Relaxed)
+}
+
+/// 探索する深さの上限（--depth、usize::MAX は無制限）
+static MAX_DEPTH: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+/// 探索する深さの上限を設定（None で無制限）
+///
+/// サイズ計算には影響しない。見つかったディレクトリのサイズは常に配下すべてを数える
+pub fn set_max_depth(depth: Option<usize>) {
+    MAX_DEPTH.store(depth.unwrap_or(usize::MAX), Ordering::Relaxed);
+}
+
+/// 探索する深さの上限（無制限なら None）
+pub fn max_depth() -> Option<usize> {
+    match MAX_DEPTH.load(Ordering::Relaxed) {
+        usize::MAX => None,
+        depth => Some(depth),
+    }
+}
+
+/// 隠しディレクトリ（`.` で始まる）に降りないか（--no-hidden）
+static SKIP_HIDDEN: AtomicBool = AtomicBool::new(false);
+
+/// 探索で隠しディレクトリに降りないかを設定
+///
+/// デフォルトでは隠しディレクトリも探索する（大きなファイルの検索では大きなキャッシュが見つかることが多い）。
+/// サイズ計算には影響しない
+pub fn set_skip_hidden(skip: bool) {
+    SKIP_HIDDEN.store(skip, Ordering::Relaxed);
+}
+
+/// 隠しディレクトリに降りない設定か
+pub fn skip_hidden() -> bool {
+    #[cfg(test)]
+    if let Some(skip) = TEST_SKIP_HIDDEN.get() {
+        return skip;
+    }
+    SKIP_HIDDEN.load(Ordering::Relaxed)
+}
+
+/// Ctrl-C で削除の中断が要求されたか
+static CANCEL_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Ctrl-C で即終了せず、中断の要求として扱う状態か（削除中・watch 中）
+static CANCELLABLE: AtomicBool = AtomicBool::new(false);
+
+/// Ctrl-C を中断の要求として扱うかを設定
+///
+/// 有効な間は削除ループが項目の区切りで `is_cancel_requested` を確認し、現在の項目の削除後に止まる
+pub fn set_cancellable(cancellable: bool) {
+    CANCELLABLE.store(cancellable, Ordering::SeqCst);
+}
+
+/// 中断を要求する（Ctrl-C ハンドラから呼ぶ）
+///
+/// 中断できない状態、または既に中断を要求済み（2 回目の Ctrl-C）の場合は false を返す。
+/// その場合、呼び出し元はすぐに終了する
+pub fn request_cancel() -> bool {
+    CANCELLABLE.load(Ordering::SeqCst) && !CANCEL_REQUESTED.swap(true, Ordering::SeqCst)
+}
+
+/// 中断が要求されたか
+pub fn is_cancel_requested() -> bool {
+    CANCEL_REQUESTED.load(Ordering::SeqCst)
+}
+
+/// 削除しないパス（clean --protect・設定の `[safety] protected_paths`）
+static PROTECTED_PATHS: RwLock<Vec<PathBuf>> = RwLock::new(Vec::new());
+
+/// 削除しないパスを設定
+///
+/// 設定した後は、削除処理（`clean_items` など）が該当する項目を飛ばす
+pub fn set_protected_paths(paths: Vec<PathBuf>) {
+    *PROTECTED_PATHS.write().unwrap() = paths;
+}
+
+/// 設定済みの削除しないパス
+pub fn protected_paths() -> Vec<PathBuf> {
+    PROTECTED_PATHS.read().unwrap().clone()
+}
+
+/// パスが保護されたパスと重なるか（同じパス・配下のパス・保護されたパスを含む親ディレクトリ）
+///
+/// シンボリックリンクや `..` で回避されないよう、両方を正規化してから比べる
+pub fn is_protected(path: &Path, protected: &[PathBuf]) -> bool {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    protected.iter().any(|protected| {
+        let protected = protected.canonicalize().unwrap_or_else(|_| protected.clone());
+        path.starts_with(&protected) || protected.starts_with(&path)
+    })
+}
+
+/// 設定済みの削除しないパスと重なるか
+pub fn is_protected_path(path: &Path) -> bool {
+    let protected = PROTECTED_PATHS.read().unwrap();
+    !protected.is_empty() && is_protected(path, &protected)
+}
+
+/// それ自体を削除・探索の対象にしてはいけないシステムのディレクトリ（配下のディレクトリは対象にできる）
+const CRITICAL_PATHS: &[&str] = &[
+    "/",
+    "/System",
+    "/Library",
+    "/Users",
+    "/Applications",
+    "/Volumes",
+    "/private",
+    "/private/var",
+    "/home",
+    "/usr",
+    "/etc",
+    "/var",
+];
+
+/// システムのディレクトリも対象にするか（--i-know-what-im-doing）
+static ALLOW_CRITICAL_TARGETS: AtomicBool = AtomicBool::new(false);
+
+/// システムのディレクトリ・ホームディレクトリそのものを対象にすることを許可するかを設定
+pub fn set_allow_critical_targets(allow: bool) {
+    ALLOW_CRITICAL_TARGETS.store(allow, Ordering::Relaxed);
+}
+
+/// パスがシステムのディレクトリ・ホームディレクトリ・ボリュームのルートそのものでないことを確認
+///
+/// `kanri clean large-files -p / -d` のような指定でマシンを壊さないよう、削除の前に呼ぶ。
+/// --i-know-what-im-doing が指定されている場合は確認しない
+pub fn assert_safe_target(path: &Path) -> Result<()> {
+    if ALLOW_CRITICAL_TARGETS.load(Ordering::Relaxed) {
+        return Ok(());
+    }
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    match critical_target(path, home.as_deref()) {
+        Some(kind) => Err(crate::Error::InvalidPath(format!(
+            "Refusing to operate on {} ({}); pass --i-know-what-im-doing to override",
+            path.display(),
+            kind
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// 対象にしてはいけないパスなら、その種類を返す
+fn critical_target(path: &Path, home: Option<&Path>) -> Option<&'static str> {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let home = home.map(|home| home.canonicalize().unwrap_or_else(|_| home.to_path_buf()));
+
+    if home.as_deref() == Some(path.as_path()) {
+        Some("home directory")
+    } else if path.parent() == Some(Path::new("/Volumes")) {
+        Some("volume root")
+    } else if path.parent().is_none() || CRITICAL_PATHS.iter().any(|critical| path == Path::new(critical)) {
+        Some("system directory")
+    } else {
+        None
+    }
+}
+
+/// 設定により探索から外す隠しディレクトリか（起点自体は外さない）
+pub fn is_skipped_hidden(entry: &walkdir::DirEntry) -> bool {
+    skip_hidden() && is_hidden_dir(entry)
+}
+
+fn is_hidden_dir(entry: &walkdir::DirEntry) -> bool {
+    entry.depth() > 0
+        && entry.file_type().is_dir()
+        && entry.file_name().to_string_lossy().starts_with('.')
+}
+
+/// 探索するエントリを絞り込むイテレータ（--no-hidden の設定をすべてのクリーナーで共通に反映する）
+///
+/// `filter` が false を返したエントリとその配下は返さない。
+/// `keep_hidden` が true を返す名前の隠しディレクトリ（`.venv` など削除対象そのもの）は --no-hidden でも返す
+pub fn walk_entries<K, F>(
+    walker: WalkDir,
+    keep_hidden: K,
+    mut filter: F,
+) -> walkdir::FilterEntry<walkdir::IntoIter, impl FnMut(&walkdir::DirEntry) -> bool>
+where
+    K: Fn(&str) -> bool,
+    F: FnMut(&walkdir::DirEntry) -> bool,
+{
+    walker.into_iter().filter_entry(move |e| {
+        (!is_skipped_hidden(e) || keep_hidden(&e.file_name().to_string_lossy())) && filter(e)
+    })
+}
+
+/// シンボリックリンク・深さの設定を反映した、プロジェクト探索用の WalkDir
+///
+/// 深さの上限はプロジェクトのディレクトリの深さ（検索パス直下が 1）として扱う。
+/// `Cargo.toml` などの目印や削除対象はその 1 つ下にあるため、1 段深くまで探索する。
+/// 起点自体がシンボリックリンクの場合は設定にかかわらずリンク先を探索する
+pub fn walk_dir(path: &Path) -> WalkDir {
+    walk_dir_with(path, follow_symlinks()).max_depth(project_walk_depth(max_depth()))
+}
+
+/// プロジェクトの深さの上限から、探索する深さの上限を求める
+fn project_walk_depth(depth: Option<usize>) -> usize {
+    depth.map_or(usize::MAX, |depth| depth.saturating_add(1))
+}
+
+fn walk_dir_with(path: &Path, follow: bool) -> WalkDir {
+    WalkDir::new(path).follow_links(follow)
+}
+
+/// ディレクトリサイズのキャッシュを有効化
+///
+/// 有効化後は、同じディレクトリのサイズを `clear_size_cache` まで再利用する。
+/// 配下の変更は検出しないため、`diagnose --watch` のように更新ごとにクリアする用途でのみ使用する
+pub fn enable_size_cache() {
+    SIZE_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+}
+
+/// キャッシュしたディレクトリサイズをすべて破棄する
+pub fn clear_size_cache() {
+    if let Some(cache) = SIZE_CACHE.get() {
+        cache.lock().unwrap().clear();
+    }
+}
+
+/// キャッシュが有効ならキャッシュを使ってサイズを取得
+fn cached_size(path: &Path, dedup: bool, compute: impl Fn(&Path) -> u64) -> u64 {
+    let Some(cache) = SIZE_CACHE.get() else {
+        return compute(path);
+    };
+
+    let key = (path.to_path_buf(), dedup);
+    if let Some(&size) = cache.lock().unwrap().get(&key) {
+        return size;
+    }
+
+    let size = compute(path);
+    cache.lock().unwrap().insert(key, size);
+    size
+}
+
 /// ディレクトリのサイズを再帰的に計算
 pub fn calculate_dir_size(path: &Path) -> Result<u64> {
+    Ok(cached_size(path, false, |p| walk_dir_size(p, follow_symlinks())))
+}
+
+/// 削除対象ディレクトリのサイズ（シンボリックリンクならリンク先のサイズ）
+///
+/// pnpm のストアや共有 target のように複数のプロジェクトが同じディレクトリを指す場合、
+/// 二重に数えないよう `seen` に記録済みのリンク先は 0 とする
+pub fn calculate_linked_dir_size(path: &Path, seen: &mut HashSet<PathBuf>) -> Result<u64> {
+    let resolved = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if !seen.insert(resolved.clone()) {
+        return Ok(0);
+    }
+    calculate_dir_size(&resolved)
+}
+
+fn walk_dir_size(path: &Path, follow: bool) -> u64 {
     let mut total_size = 0u64;
 
-    for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
+    for entry in walk_dir_with(path, follow).into_iter().filter_map(|e| e.ok()) {
         if entry.file_type().is_file() {
             if let Ok(metadata) = entry.metadata() {
                 total_size += metadata.len();
@@ -15,7 +291,539 @@ pub fn calculate_dir_size(path: &Path) -> Result<u64> {
         }
     }
 
-    Ok(total_size)
+    total_size
+}
+
+/// 直下のサブディレクトリとそのサイズ（名前順）
+pub fn subdir_sizes(path: &Path) -> Result<Vec<(String, PathBuf, u64)>> {
+    let mut subdirs = Vec::new();
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            let dir = entry.path();
+            let size = calculate_dir_size(&dir)?;
+            subdirs.push((entry.file_name().to_string_lossy().into_owned(), dir, size));
+        }
+    }
+    subdirs.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(subdirs)
+}
+
+/// ディレクトリのサイズを再帰的に計算（ハードリンクを重複カウントしない）
+///
+/// 同じ (device, inode) を持つファイルは一度だけ加算する
+pub fn calculate_dir_size_dedup(path: &Path) -> Result<u64> {
+    Ok(cached_size(path, true, |p| walk_dir_size_dedup(p, follow_symlinks())))
+}
+
+/// ディレクトリのサイズ（ハードリンクを重複カウントしない）と、配下で最も新しい更新日時を 1 回の走査で取得
+///
+/// `last_modified` と異なり全階層を調べる。サイズのキャッシュは使わない
+pub fn calculate_dir_size_and_modified_dedup(path: &Path) -> Result<(u64, Option<SystemTime>)> {
+    Ok(walk_dir_stats_dedup(path, follow_symlinks()))
+}
+
+fn walk_dir_size_dedup(path: &Path, follow: bool) -> u64 {
+    walk_dir_stats_dedup(path, follow).0
+}
+
+fn walk_dir_stats_dedup(path: &Path, follow: bool) -> (u64, Option<SystemTime>) {
+    let mut total_size = 0u64;
+    let mut newest: Option<SystemTime> = None;
+    let mut seen = HashSet::new();
+
+    for entry in walk_dir_with(path, follow).into_iter().filter_map(|e| e.ok()) {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if let Ok(modified) = metadata.modified() {
+            newest = newest.max(Some(modified));
+        }
+        if entry.file_type().is_file() {
+            if let Some(key) = inode_key(&metadata) {
+                if !seen.insert(key) {
+                    continue;
+                }
+            }
+            total_size += metadata.len();
+        }
+    }
+
+    (total_size, newest)
+}
+
+/// ファイルの (device, inode) を取得（ハードリンクが複数ある場合のみ）
+#[cfg(unix)]
+fn inode_key(metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+
+    if metadata.nlink() > 1 {
+        Some((metadata.dev(), metadata.ino()))
+    } else {
+        None
+    }
+}
+
+#[cfg(not(unix))]
+fn inode_key(_metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// 最終更新日時を調べる階層の深さ
+///
+/// ビルド成果物やキャッシュの中身まで全走査すると遅いため、浅い階層の mtime で代用する
+const LAST_MODIFIED_DEPTH: usize = 3;
+
+/// パスとその配下（浅い階層のみ）のうち最も新しい更新日時
+pub fn last_modified(path: &Path) -> Option<SystemTime> {
+    walk_dir_with(path, follow_symlinks())
+        .max_depth(LAST_MODIFIED_DEPTH)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok()?.modified().ok())
+        .max()
+}
+
+/// 複数のパスのうち最も新しい更新日時（どれも取得できなければ None）
+pub fn newest_modified<P: AsRef<Path>>(paths: impl IntoIterator<Item = P>) -> Option<SystemTime> {
+    paths
+        .into_iter()
+        .filter_map(|path| last_modified(path.as_ref()))
+        .max()
+}
+
+/// 空のディレクトリか（読めない場合は false）
+fn is_empty_dir(path: &Path) -> bool {
+    std::fs::read_dir(path).is_ok_and(|mut entries| entries.next().is_none())
+}
+
+/// 削除した項目の親ディレクトリから `root` に向かって、空になったディレクトリを削除し、削除した数を返す
+///
+/// 上にたどるのは空のディレクトリが続く間だけで、兄弟や配下にもともとあった空のディレクトリ
+/// （`.git/refs/tags` など）には触れない。`root` の外の項目は対象外。`root` 自体は削除しない
+pub fn prune_empty_parents(removed: &[PathBuf], root: &Path) -> Result<usize> {
+    let mut parents: Vec<&Path> = removed
+        .iter()
+        .filter_map(|path| path.parent())
+        .filter(|parent| parent.starts_with(root) && *parent != root)
+        .collect();
+    parents.sort();
+    parents.dedup();
+    // 深い階層から処理し、子を削除して空になった親もまとめて削除する
+    parents.sort_by_key(|parent| std::cmp::Reverse(parent.components().count()));
+
+    let mut count = 0;
+    for parent in parents {
+        let mut dir = parent;
+        while dir != root && dir.starts_with(root) && is_empty_dir(dir) {
+            std::fs::remove_dir(dir)?;
+            count += 1;
+            match dir.parent() {
+                Some(next) => dir = next,
+                None => break,
+            }
+        }
+    }
+    Ok(count)
+}
+
+/// 経過時間を「3日前」のような形式に変換（未来の日時は「たった今」）
+pub fn format_age(time: SystemTime) -> String {
+    let secs = SystemTime::now()
+        .duration_since(time)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = 60 * MINUTE;
+    const DAY: u64 = 24 * HOUR;
+
+    match secs {
+        s if s < MINUTE => "たった今".to_string(),
+        s if s < HOUR => format!("{}分前", s / MINUTE),
+        s if s < DAY => format!("{}時間前", s / HOUR),
+        s if s < 30 * DAY => format!("{}日前", s / DAY),
+        s if s < 365 * DAY => format!("{}か月前", s / (30 * DAY)),
+        s => format!("{}年前", s / (365 * DAY)),
+    }
+}
+
+/// クラウド同期フォルダ（ホームからの相対パス, サービス名）
+const SYNC_FOLDERS: &[(&str, &str)] = &[
+    ("Library/Mobile Documents", "iCloud Drive"),
+    ("Dropbox", "Dropbox"),
+    ("Google Drive", "Google Drive"),
+    ("OneDrive", "OneDrive"),
+];
+
+/// パスがクラウド同期フォルダ内にあればサービス名を返す
+///
+/// 同期フォルダ内のファイルを削除すると、他の端末やクラウドとの再同期が大量に発生する
+pub fn is_in_sync_folder(path: &Path) -> Option<&'static str> {
+    let home = PathBuf::from(std::env::var_os("HOME")?);
+    let home = home.canonicalize().unwrap_or(home);
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    sync_folder_under(&path, &home)
+}
+
+/// ホームディレクトリを指定して同期フォルダを判定
+fn sync_folder_under(path: &Path, home: &Path) -> Option<&'static str> {
+    let relative = path.strip_prefix(home).ok()?;
+
+    // macOS 12.3 以降のファイルプロバイダ（~/Library/CloudStorage/<Provider>-<Account>）
+    if let Ok(rest) = relative.strip_prefix("Library/CloudStorage") {
+        let provider = rest.components().next()?.as_os_str().to_string_lossy();
+        return Some(if provider.starts_with("Dropbox") {
+            "Dropbox"
+        } else if provider.starts_with("GoogleDrive") {
+            "Google Drive"
+        } else if provider.starts_with("OneDrive") {
+            "OneDrive"
+        } else {
+            "クラウドストレージ"
+        });
+    }
+
+    SYNC_FOLDERS
+        .iter()
+        .find(|(dir, _)| relative.starts_with(dir))
+        .map(|(_, service)| *service)
+}
+
+/// 外部コマンドのデフォルトのタイムアウト（メタデータ取得など短時間で終わる操作向け）
+pub const DEFAULT_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 外部コマンドのタイムアウト（秒、設定の commands.timeout_secs）
+static COMMAND_TIMEOUT_SECS: AtomicU64 = AtomicU64::new(DEFAULT_COMMAND_TIMEOUT.as_secs());
+
+/// 外部コマンドのタイムアウトを設定
+pub fn set_command_timeout(timeout: Duration) {
+    COMMAND_TIMEOUT_SECS.store(timeout.as_secs().max(1), Ordering::Relaxed);
+}
+
+/// 外部コマンドのタイムアウト
+pub fn command_timeout() -> Duration {
+    Duration::from_secs(COMMAND_TIMEOUT_SECS.load(Ordering::Relaxed))
+}
+
+/// ファイル一覧を取得するコマンドのデフォルトのタイムアウト（件数に比例して時間がかかるため長めにする）
+pub const DEFAULT_LIST_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// ファイル一覧を取得するコマンドのタイムアウト（秒、設定の commands.list_timeout_secs）
+static LIST_TIMEOUT_SECS: AtomicU64 = AtomicU64::new(DEFAULT_LIST_TIMEOUT.as_secs());
+
+/// ファイル一覧を取得するコマンドのタイムアウトを設定
+pub fn set_list_timeout(timeout: Duration) {
+    LIST_TIMEOUT_SECS.store(timeout.as_secs().max(1), Ordering::Relaxed);
+}
+
+/// ファイル一覧を取得するコマンド（`b2 file ls --recursive`・`rclone lsjson` など）のタイムアウト
+pub fn list_timeout() -> Duration {
+    Duration::from_secs(LIST_TIMEOUT_SECS.load(Ordering::Relaxed))
+}
+
+/// 外部コマンドを実行し、時間内に終わらなければ強制終了して `Error::Timeout` を返す
+///
+/// stdin は閉じた状態で起動する（認証の入力待ちなどで止まらないように）
+pub fn run_command_with_timeout(command: &mut Command, timeout: Duration) -> Result<Output> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // パイプが詰まって子プロセスが止まらないよう別スレッドで読み切る
+    let stdout_reader = read_pipe(child.stdout.take());
+    let stderr_reader = read_pipe(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            // 強制終了後に回収してゾンビプロセスを残さない
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(crate::Error::Timeout {
+                command: describe_command(command),
+                timeout,
+            });
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+
+    Ok(Output {
+        status,
+        stdout: stdout_reader.join().unwrap_or_default(),
+        stderr: stderr_reader.join().unwrap_or_default(),
+    })
+}
+
+/// パイプを別スレッドで最後まで読む
+fn read_pipe(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
+
+/// エラーメッセージ用のコマンドライン（例: "docker system df"）
+fn describe_command(command: &Command) -> String {
+    std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|arg| arg.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// ボリュームの容量
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DiskUsage {
+    /// 全体の容量（バイト）
+    pub total: u64,
+    /// 一般ユーザーが使える空き容量（バイト）
+    pub free: u64,
+}
+
+impl DiskUsage {
+    /// `bytes` がディスク全体の何 % にあたるか
+    pub fn percent_of_total(&self, bytes: u64) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        bytes as f64 / self.total as f64 * 100.0
+    }
+
+    /// `bytes` を解放した後の空き容量（全体の容量を超えない）
+    pub fn free_after(&self, bytes: u64) -> u64 {
+        self.free.saturating_add(bytes).min(self.total)
+    }
+}
+
+/// 実測の解放量が期待値より大幅に少ないとみなす割合（期待値の半分未満）
+const RECLAIM_SHORTFALL_RATIO: f64 = 0.5;
+
+/// 他のプロセスの書き込みによる誤差を無視するため、これより小さい削除では判定しない
+const RECLAIM_SHORTFALL_MIN_BYTES: u64 = 100 * 1024 * 1024;
+
+/// 削除前後で実測した空き容量の増加が、削除したサイズより大幅に少ないか
+///
+/// 開いたままのファイルや APFS のローカルスナップショットが領域を保持している場合に起きる
+pub fn is_reclaim_shortfall(measured: u64, expected: u64) -> bool {
+    expected >= RECLAIM_SHORTFALL_MIN_BYTES
+        && (measured as f64) < expected as f64 * RECLAIM_SHORTFALL_RATIO
+}
+
+/// Time Machine のローカルスナップショット名の接頭辞
+const LOCAL_SNAPSHOT_PREFIX: &str = "com.apple.TimeMachine.";
+
+/// ルートボリュームの Time Machine ローカルスナップショット（`tmutil listlocalsnapshots /`）
+///
+/// 削除したファイルの領域はスナップショットが残っている間は解放されない。macOS 以外では常に空
+pub fn local_snapshots() -> Result<Vec<String>> {
+    if !cfg!(target_os = "macos") {
+        return Ok(Vec::new());
+    }
+
+    let output = run_command_with_timeout(
+        Command::new("tmutil").args(["listlocalsnapshots", "/"]),
+        command_timeout(),
+    )?;
+    if !output.status.success() {
+        return Err(crate::Error::external_tool("tmutil listlocalsnapshots", &output));
+    }
+    Ok(parse_local_snapshots(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// `tmutil listlocalsnapshots` の出力からスナップショット名を取り出す（見出し行などは除く）
+fn parse_local_snapshots(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with(LOCAL_SNAPSHOT_PREFIX))
+        .map(str::to_string)
+        .collect()
+}
+
+/// ローカルスナップショットを間引いて `purge_bytes` 以上の領域を空ける（`tmutil thinlocalsnapshots / <bytes> 4`）
+///
+/// 緊急度は最も高い 4 を指定する。tmutil の出力を返す
+pub fn thin_local_snapshots(purge_bytes: u64) -> Result<String> {
+    if !cfg!(target_os = "macos") {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "Local snapshots are only supported on macOS",
+        )
+        .into());
+    }
+
+    let output = run_command_with_timeout(
+        Command::new("tmutil").args(["thinlocalsnapshots", "/", &purge_bytes.to_string(), "4"]),
+        command_timeout(),
+    )?;
+    if !output.status.success() {
+        return Err(crate::Error::external_tool("tmutil thinlocalsnapshots", &output));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// パスがあるボリュームの容量を取得
+///
+/// パスが存在しない場合（削除済みなど）は、存在する親ディレクトリで調べる
+pub fn disk_usage(path: &Path) -> Result<DiskUsage> {
+    let absolute = std::path::absolute(path)?;
+    let path = absolute
+        .ancestors()
+        .find(|p| p.exists())
+        .ok_or_else(|| crate::Error::InvalidPath(path.display().to_string()))?;
+    statvfs(path)
+}
+
+#[cfg(unix)]
+fn statvfs(path: &Path) -> Result<DiskUsage> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|_| crate::Error::InvalidPath(path.display().to_string()))?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: c_path は NUL 終端された文字列で、stat は書き込み可能な領域
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+
+    let fragment_size = stat.f_frsize as u64;
+    Ok(DiskUsage {
+        total: stat.f_blocks as u64 * fragment_size,
+        free: stat.f_bavail as u64 * fragment_size,
+    })
+}
+
+#[cfg(not(unix))]
+fn statvfs(_path: &Path) -> Result<DiskUsage> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "Disk usage is not supported on this platform",
+    )
+    .into())
+}
+
+/// ファイルの排他ロック（アドバイザリロック）
+///
+/// 保持している間、同じファイルをロックしようとする他のプロセスは解放されるまで待つ。
+/// ロックは drop で（プロセスが異常終了した場合も OS によって）解放される
+pub struct FileLock {
+    _file: std::fs::File,
+}
+
+impl FileLock {
+    /// ロックファイルを（なければ作成して）開き、排他ロックを取る
+    pub fn acquire(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)?;
+        file.lock()?;
+        Ok(Self { _file: file })
+    }
+}
+
+/// アトミックな書き込みに使う一時ファイルのパス（書き込み先と同じディレクトリの `<ファイル名>.tmp.<pid>.<連番>`）
+///
+/// ロックを取らずに書き込むファイル（設定・統計など）もあるため、同時に書き込む kanri 同士や
+/// スレッド同士で一時ファイルを共有しないよう、呼び出しごとに異なる名前にする
+fn atomic_temp_path(path: &Path) -> PathBuf {
+    static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(format!(
+        ".tmp.{}.{:x}{:x}",
+        std::process::id(),
+        nanos,
+        SEQUENCE.fetch_add(1, Ordering::Relaxed)
+    ));
+    path.with_file_name(file_name)
+}
+
+/// 一時ファイルに書き出してから rename で置き換える
+///
+/// 書き込みの途中で中断しても、元のファイルは壊れずに残る。同時に書き込んだ場合は、
+/// どちらか一方の内容全体が残る。シンボリックリンクはリンク先を置き換え、既存のファイルのパーミッションは引き継ぐ
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    use std::io::Write;
+
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let permissions = std::fs::metadata(&path).map(|m| m.permissions()).ok();
+    let temp_path = atomic_temp_path(&path);
+    // 他の書き込みの一時ファイルを消さないよう、自分で作った場合だけ後始末する
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&temp_path)?;
+    let result = file
+        .write_all(contents.as_ref())
+        .and_then(|_| match permissions {
+            Some(permissions) => file.set_permissions(permissions),
+            None => Ok(()),
+        })
+        .and_then(|_| file.sync_all())
+        .and_then(|_| std::fs::rename(&temp_path, &path));
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// ツールを実行し、成功した場合の標準出力（インストールされていない・失敗した場合は None）
+pub fn tool_output(command: &mut Command) -> Option<String> {
+    let output = run_command_with_timeout(command, command_timeout()).ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// ツールのバージョン表示（`--version` など）の最初の空でない行
+pub fn tool_version(command: &mut Command) -> Option<String> {
+    tool_output(command).as_deref().and_then(first_line)
+}
+
+/// 最初の空でない行（前後の空白を除く）
+fn first_line(text: &str) -> Option<String> {
+    text.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+/// 環境変数などから解決したディレクトリの決まり方
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirSource {
+    /// 環境変数で指定された（またはそこから導いた）パス
+    Env(&'static str),
+    /// 環境変数の指定がないため既定のパス（ホームディレクトリ配下）
+    Default,
+}
+
+impl fmt::Display for DirSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DirSource::Env(var) => write!(f, "環境変数 {}", var),
+            DirSource::Default => write!(f, "既定のパス"),
+        }
+    }
 }
 
 /// バイトサイズを人間が読みやすい形式に変換
@@ -35,10 +843,138 @@ pub fn format_size(bytes: u64) -> String {
     format!("{:.2} {}", size, UNITS[unit_index])
 }
 
+/// サイズの指定（例: 5G, 500M, 1024）をバイト数に変換（単位は 1024 倍、末尾の B は省略可）
+pub fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim().to_ascii_uppercase();
+    let value = value.strip_suffix('B').unwrap_or(&value);
+    let (number, multiplier) = match value.char_indices().last()? {
+        (i, 'K') => (&value[..i], 1024),
+        (i, 'M') => (&value[..i], 1024 * 1024),
+        (i, 'G') => (&value[..i], 1024 * 1024 * 1024),
+        (i, 'T') => (&value[..i], 1024 * 1024 * 1024 * 1024),
+        _ => (value, 1),
+    };
+    number.trim().parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
+/// CSV の 1 行を作成（RFC 4180: カンマ・ダブルクォート・改行を含むフィールドはクォートする）
+pub fn csv_row<S: AsRef<str>>(fields: &[S]) -> String {
+    fields
+        .iter()
+        .map(|field| {
+            let field = field.as_ref();
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// テストは並列に実行されるため、探索設定はグローバルではなくテストのスレッドごとに持つ
+#[cfg(test)]
+thread_local! {
+    static TEST_SKIP_HIDDEN: std::cell::Cell<Option<bool>> = const { std::cell::Cell::new(None) };
+}
+
+/// テスト中だけ（そのテストのスレッドでのみ）探索設定を変更し、終了時（パニック時も）に元に戻す
+#[cfg(test)]
+pub(crate) struct TestSettings;
+
+#[cfg(test)]
+impl TestSettings {
+    pub(crate) fn skip_hidden(skip: bool) -> Self {
+        TEST_SKIP_HIDDEN.set(Some(skip));
+        Self
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestSettings {
+    fn drop(&mut self) {
+        TEST_SKIP_HIDDEN.set(None);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_walk_entries_hidden() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        for dir in [".cache/big", "src", ".venv"] {
+            std::fs::create_dir_all(temp.path().join(dir))?;
+        }
+
+        let names = |skip: bool| {
+            let _settings = TestSettings::skip_hidden(skip);
+            let mut names: Vec<String> = walk_entries(walk_dir(temp.path()), |name| name == ".venv", |_| true)
+                .filter_map(|e| e.ok())
+                .filter(|e| e.depth() > 0)
+                .map(|e| e.path().strip_prefix(temp.path()).unwrap().display().to_string())
+                .collect();
+            names.sort();
+            names
+        };
+
+        // デフォルトでは隠しディレクトリも探索する
+        assert_eq!(names(false), vec![".cache", ".cache/big", ".venv", "src"]);
+        // --no-hidden では降りないが、削除対象の名前は残す
+        assert_eq!(names(true), vec![".venv", "src"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_age() {
+        let ago = |secs: u64| SystemTime::now() - Duration::from_secs(secs);
+
+        assert_eq!(format_age(SystemTime::now() + Duration::from_secs(60)), "たった今");
+        assert_eq!(format_age(ago(5)), "たった今");
+        assert_eq!(format_age(ago(5 * 60)), "5分前");
+        assert_eq!(format_age(ago(2 * 60 * 60)), "2時間前");
+        assert_eq!(format_age(ago(3 * 24 * 60 * 60)), "3日前");
+        assert_eq!(format_age(ago(90 * 24 * 60 * 60)), "3か月前");
+        assert_eq!(format_age(ago(800 * 24 * 60 * 60)), "2年前");
+    }
+
+    #[test]
+    fn test_last_modified() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let old = temp.path().join("old");
+        let new = temp.path().join("new");
+        std::fs::create_dir(&old)?;
+        std::fs::create_dir(&new)?;
+        std::fs::write(old.join("a.txt"), "a")?;
+        std::fs::write(new.join("b.txt"), "b")?;
+
+        // old 以下は 30 日前に更新されたことにする
+        let old_time = SystemTime::now() - Duration::from_secs(30 * 24 * 60 * 60);
+        for path in [old.join("a.txt"), old.clone()] {
+            std::fs::File::open(&path)?.set_modified(old_time)?;
+        }
+
+        assert_eq!(last_modified(&old), Some(old_time));
+        assert_eq!(calculate_dir_size_and_modified_dedup(&old)?, (1, Some(old_time)));
+        let newest = newest_modified([&old, &new]).unwrap();
+        assert!(newest > old_time);
+        assert_eq!(Some(newest), last_modified(&new));
+        assert_eq!(newest_modified(Vec::<PathBuf>::new()), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_csv_row() {
+        assert_eq!(csv_row(&["rust", "3", "1024", "true"]), "rust,3,1024,true");
+        assert_eq!(
+            csv_row(&["clean custom", "a,b", "say \"hi\""]),
+            "clean custom,\"a,b\",\"say \"\"hi\"\"\""
+        );
+        assert_eq!(csv_row(&["line\nbreak"]), "\"line\nbreak\"");
+    }
+
     #[test]
     fn test_format_size() {
         assert_eq!(format_size(0), "0 B");
@@ -47,4 +983,387 @@ mod tests {
         assert_eq!(format_size(1024 * 1024), "1.00 MB");
         assert_eq!(format_size(1024 * 1024 * 1024), "1.00 GB");
     }
+
+    #[test]
+    fn test_is_reclaim_shortfall() {
+        const GB: u64 = 1024 * 1024 * 1024;
+        assert!(!is_reclaim_shortfall(GB, GB));
+        assert!(!is_reclaim_shortfall(GB / 2, GB));
+        assert!(is_reclaim_shortfall(GB / 10, GB));
+        assert!(is_reclaim_shortfall(0, GB));
+        // 小さな削除は誤差の範囲として判定しない
+        assert!(!is_reclaim_shortfall(0, 1024 * 1024));
+    }
+
+    #[test]
+    fn test_write_atomic() -> Result<()> {
+        use tempfile::TempDir;
+
+        let temp = TempDir::new()?;
+        let path = temp.path().join("index.json");
+        write_atomic(&path, r#"{"archives":[]}"#)?;
+        assert_eq!(std::fs::read_to_string(&path)?, r#"{"archives":[]}"#);
+
+        // 書き込みの途中で中断した（一時ファイルが途中まで書かれた）状態でも元のファイルは壊れない
+        let leftover = atomic_temp_path(&path);
+        std::fs::write(&leftover, r#"{"archi"#)?;
+        assert_eq!(std::fs::read_to_string(&path)?, r#"{"archives":[]}"#);
+
+        // 次の書き込みは別の一時ファイルを使い、自分の一時ファイルは残さない
+        assert_ne!(atomic_temp_path(&path), atomic_temp_path(&path));
+        write_atomic(&path, "updated")?;
+        assert_eq!(std::fs::read_to_string(&path)?, "updated");
+        assert_eq!(std::fs::read_to_string(&leftover)?, r#"{"archi"#);
+        assert_eq!(std::fs::read_dir(temp.path())?.count(), 2);
+
+        // シンボリックリンクはリンクのまま、リンク先を書き換える
+        #[cfg(unix)]
+        {
+            let link = temp.path().join("link.json");
+            std::os::unix::fs::symlink(&path, &link)?;
+            write_atomic(&link, "via link")?;
+            assert!(link.symlink_metadata()?.file_type().is_symlink());
+            assert_eq!(std::fs::read_to_string(&path)?, "via link");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_atomic_never_partial() -> Result<()> {
+        use std::sync::atomic::AtomicBool;
+        use std::sync::Arc;
+        use tempfile::TempDir;
+
+        const LEN: usize = 1024 * 1024;
+        let temp = TempDir::new()?;
+        let path = temp.path().join("config.toml");
+        write_atomic(&path, vec![b'a'; LEN])?;
+
+        // 書き込み中に読んでも、常に古い内容か新しい内容のどちらか全体が見える
+        let done = Arc::new(AtomicBool::new(false));
+        let reader = {
+            let path = path.clone();
+            let done = Arc::clone(&done);
+            thread::spawn(move || {
+                let mut reads = 0;
+                while !done.load(Ordering::Relaxed) {
+                    let content = std::fs::read(&path).unwrap();
+                    assert_eq!(content.len(), LEN);
+                    assert!(content.iter().all(|&b| b == content[0]));
+                    reads += 1;
+                }
+                reads
+            })
+        };
+        for i in 0..20 {
+            write_atomic(&path, vec![if i % 2 == 0 { b'b' } else { b'a' }; LEN])?;
+        }
+        done.store(true, Ordering::Relaxed);
+        assert!(reader.join().unwrap() > 0);
+
+        // 同時に書き込んでも内容が混ざらない
+        let writers: Vec<_> = [b'c', b'd', b'e', b'f']
+            .into_iter()
+            .map(|byte| {
+                let path = path.clone();
+                thread::spawn(move || {
+                    for _ in 0..5 {
+                        write_atomic(&path, vec![byte; LEN]).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        let content = std::fs::read(&path)?;
+        assert_eq!(content.len(), LEN);
+        assert!(content.iter().all(|&b| b == content[0]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_lock() -> Result<()> {
+        use tempfile::TempDir;
+
+        let temp = TempDir::new()?;
+        let path = temp.path().join("nested").join("index.lock");
+        let lock = FileLock::acquire(&path)?;
+
+        // 保持している間は他のハンドルからロックできない
+        let other = std::fs::File::open(&path)?;
+        assert!(other.try_lock().is_err());
+
+        drop(lock);
+        assert!(other.try_lock().is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_local_snapshots() {
+        let output = "Snapshots for disk /:\n\
+                      com.apple.TimeMachine.2025-11-14-130523.local\n\
+                      com.apple.TimeMachine.2025-11-14-140612.local\n";
+        assert_eq!(
+            parse_local_snapshots(output),
+            vec![
+                "com.apple.TimeMachine.2025-11-14-130523.local",
+                "com.apple.TimeMachine.2025-11-14-140612.local",
+            ]
+        );
+        assert!(parse_local_snapshots("Snapshots for disk /:\n").is_empty());
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("1024"), Some(1024));
+        assert_eq!(parse_size("500M"), Some(500 * 1024 * 1024));
+        assert_eq!(parse_size("5G"), Some(5 * 1024 * 1024 * 1024));
+        assert_eq!(parse_size("5gb"), Some(5 * 1024 * 1024 * 1024));
+        assert_eq!(parse_size("1T"), Some(1024 * 1024 * 1024 * 1024));
+        assert_eq!(parse_size(""), None);
+        assert_eq!(parse_size("G"), None);
+        assert_eq!(parse_size("1.5G"), None);
+    }
+
+    #[test]
+    fn test_project_walk_depth() -> Result<()> {
+        use tempfile::TempDir;
+
+        let temp = TempDir::new()?;
+        std::fs::create_dir_all(temp.path().join("app").join("nested").join("lib"))?;
+        std::fs::write(temp.path().join("app").join("Cargo.toml"), "")?;
+        std::fs::write(temp.path().join("app").join("nested").join("lib").join("Cargo.toml"), "")?;
+
+        let manifests = |depth| {
+            walk_dir_with(temp.path(), false)
+                .max_depth(project_walk_depth(depth))
+                .into_iter()
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_name() == "Cargo.toml")
+                .count()
+        };
+
+        // 検索パス直下のプロジェクト（app）は --depth 1 で見つかる
+        assert_eq!(manifests(Some(1)), 1);
+        assert_eq!(manifests(Some(2)), 1);
+        assert_eq!(manifests(Some(3)), 2);
+        assert_eq!(manifests(None), 2);
+        assert_eq!(project_walk_depth(Some(usize::MAX)), usize::MAX);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_run_command_with_timeout() -> Result<()> {
+        let output = run_command_with_timeout(
+            Command::new("sh").args(["-c", "echo out; echo err >&2"]),
+            Duration::from_secs(10),
+        )?;
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"out\n");
+        assert_eq!(output.stderr, b"err\n");
+
+        let started = Instant::now();
+        let err = run_command_with_timeout(
+            Command::new("sleep").arg("10"),
+            Duration::from_millis(100),
+        )
+        .unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(matches!(err, crate::Error::Timeout { .. }));
+        assert_eq!(err.to_string(), "Command timed out after 100ms: sleep 10");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune_empty_parents() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let root = temp.path().join("search");
+        std::fs::create_dir_all(root.join("videos/2023/raw"))?;
+        std::fs::create_dir_all(root.join("videos/2024"))?;
+        std::fs::write(root.join("videos/2024/clip.mp4"), "keep")?;
+        std::fs::create_dir_all(root.join("unrelated/empty"))?;
+
+        // 削除済みの大きなファイルの親から空のディレクトリを上にたどって削除
+        let removed = vec![root.join("videos/2023/raw/big.mov"), root.join("top.iso")];
+        assert_eq!(prune_empty_parents(&removed, &root)?, 2);
+        assert!(!root.join("videos/2023").exists());
+        assert!(root.join("videos/2024/clip.mp4").exists());
+        // 削除した項目と関係ない空のディレクトリは残す
+        assert!(root.join("unrelated/empty").exists());
+
+        // 削除した項目の親に残っている空のディレクトリも残す
+        std::fs::create_dir_all(root.join("project/.git/refs/tags"))?;
+        assert_eq!(prune_empty_parents(&[root.join("project/target")], &root)?, 0);
+        assert!(root.join("project/.git/refs/tags").exists());
+        std::fs::remove_dir_all(root.join("project"))?;
+
+        // root 直下の項目を削除して root が空になっても root は残す
+        std::fs::remove_dir_all(root.join("videos"))?;
+        std::fs::remove_dir_all(root.join("unrelated"))?;
+        assert_eq!(prune_empty_parents(&[root.join("videos")], &root)?, 0);
+        assert!(root.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tool_version() {
+        assert_eq!(
+            tool_version(Command::new("sh").args(["-c", "printf '\\n  tool 1.2.3  \\nbuild abc\\n'"])),
+            Some("tool 1.2.3".to_string())
+        );
+        assert_eq!(tool_version(Command::new("sh").args(["-c", "echo 1.0; exit 1"])), None);
+        assert_eq!(tool_version(&mut Command::new("kanri-no-such-tool")), None);
+        assert_eq!(first_line("\n\n"), None);
+    }
+
+    #[test]
+    fn test_critical_target() {
+        let home = Some(Path::new("/Users/me"));
+
+        assert_eq!(critical_target(Path::new("/"), home), Some("system directory"));
+        assert_eq!(critical_target(Path::new("/System"), home), Some("system directory"));
+        assert_eq!(critical_target(Path::new("/Users"), home), Some("system directory"));
+        assert_eq!(critical_target(Path::new("/Users/me"), home), Some("home directory"));
+        assert_eq!(critical_target(Path::new("/Users/me/"), home), Some("home directory"));
+        assert_eq!(critical_target(Path::new("/Volumes/Backup"), home), Some("volume root"));
+
+        // 配下のディレクトリは対象にできる
+        assert_eq!(critical_target(Path::new("/Users/me/Library/Caches"), home), None);
+        assert_eq!(critical_target(Path::new("/Volumes/Backup/old"), home), None);
+        assert_eq!(critical_target(Path::new("/Users/me"), None), None);
+    }
+
+    #[test]
+    fn test_is_protected() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let data = temp.path().join("work").join("data");
+        std::fs::create_dir_all(&data)?;
+        std::fs::create_dir_all(temp.path().join("work").join("app").join("target"))?;
+        let protected = vec![data.clone()];
+
+        assert!(is_protected(&data, &protected));
+        assert!(is_protected(&data.join("cache"), &protected));
+        // 親ディレクトリを削除すると保護されたパスも消える
+        assert!(is_protected(&temp.path().join("work"), &protected));
+        // 名前が前方一致するだけのパスは対象外
+        assert!(!is_protected(&temp.path().join("work").join("data-old"), &protected));
+        assert!(!is_protected(&temp.path().join("work").join("app").join("target"), &protected));
+        // `..` を含むパスも正規化して比べる
+        assert!(is_protected(&temp.path().join("work").join("app").join("..").join("data"), &protected));
+        assert!(!is_protected(&data, &[]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_disk_usage_percent() {
+        let usage = DiskUsage {
+            total: 1000,
+            free: 200,
+        };
+        assert_eq!(usage.percent_of_total(50), 5.0);
+        assert_eq!(usage.free_after(300), 500);
+        assert_eq!(usage.free_after(5000), 1000);
+        assert_eq!(DiskUsage { total: 0, free: 0 }.percent_of_total(10), 0.0);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_disk_usage() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let usage = disk_usage(temp.path())?;
+        assert!(usage.total > 0);
+        assert!(usage.free <= usage.total);
+
+        // 存在しないパスは親ディレクトリのボリュームで調べる
+        let missing = disk_usage(&temp.path().join("deleted").join("target"))?;
+        assert_eq!(missing.total, usage.total);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_folder_under() {
+        let home = Path::new("/Users/me");
+
+        assert_eq!(
+            sync_folder_under(
+                Path::new("/Users/me/Library/Mobile Documents/com~apple~CloudDocs/app/node_modules"),
+                home
+            ),
+            Some("iCloud Drive")
+        );
+        assert_eq!(
+            sync_folder_under(Path::new("/Users/me/Dropbox/work/target"), home),
+            Some("Dropbox")
+        );
+        assert_eq!(
+            sync_folder_under(
+                Path::new("/Users/me/Library/CloudStorage/GoogleDrive-me@example.com/My Drive/app"),
+                home
+            ),
+            Some("Google Drive")
+        );
+        assert_eq!(
+            sync_folder_under(Path::new("/Users/me/Library/CloudStorage/Box-Box/app"), home),
+            Some("クラウドストレージ")
+        );
+        assert_eq!(sync_folder_under(Path::new("/Users/me/dev/app/target"), home), None);
+        assert_eq!(sync_folder_under(Path::new("/Users/me/DropboxBackup/app"), home), None);
+        assert_eq!(sync_folder_under(Path::new("/tmp/Dropbox/app"), home), None);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_walk_dir_size_symlinks() -> Result<()> {
+        use std::os::unix::fs::symlink;
+        use tempfile::TempDir;
+
+        let temp = TempDir::new()?;
+        let store = temp.path().join("store");
+        std::fs::create_dir(&store)?;
+        std::fs::write(store.join("pkg.js"), vec![0u8; 100])?;
+
+        let project = temp.path().join("project");
+        std::fs::create_dir(&project)?;
+        std::fs::write(project.join("own.js"), vec![0u8; 10])?;
+        symlink(&store, project.join("linked"))?;
+
+        // たどらない場合、内部のリンクは数えない
+        assert_eq!(walk_dir_size(&project, false), 10);
+        assert_eq!(walk_dir_size(&project, true), 110);
+
+        // 起点がリンクならリンク先を数える
+        let link = temp.path().join("link");
+        symlink(&store, &link)?;
+        assert_eq!(walk_dir_size(&link, false), 100);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_calculate_dir_size_dedup_hard_links() -> Result<()> {
+        use tempfile::TempDir;
+
+        let temp = TempDir::new()?;
+        let original = temp.path().join("original.bin");
+        std::fs::write(&original, vec![0u8; 1000])?;
+        std::fs::hard_link(&original, temp.path().join("link1.bin"))?;
+        std::fs::hard_link(&original, temp.path().join("link2.bin"))?;
+        std::fs::write(temp.path().join("other.bin"), vec![0u8; 10])?;
+
+        assert_eq!(calculate_dir_size(temp.path())?, 3010);
+        assert_eq!(calculate_dir_size_dedup(temp.path())?, 1010);
+
+        Ok(())
+    }
 }
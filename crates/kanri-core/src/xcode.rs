@@ -1,10 +1,15 @@
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::SystemTime;
+
+use serde::Deserialize;
 
 use crate::{
-    cleanable::{Cleanable, CleanableItem},
-    utils, Result,
+    cleanable::{Cleanable, CleanableItem, CleanableMetadata},
+    utils::{self, DirSource},
+    Result,
 };
 
 /// Xcode DerivedData 情報
@@ -14,17 +19,78 @@ pub struct XcodeDerivedData {
     pub derived_data_dir: PathBuf,
     /// サイズ（バイト）
     pub size: u64,
+    /// DerivedData ディレクトリの決まり方（常に ~/Library/Developer 配下の既定のパス）
+    pub source: DirSource,
+}
+
+/// シミュレータのデバイス情報
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simulator {
+    /// デバイスの UDID
+    pub udid: String,
+    /// デバイス名（例: "iPhone 14"）
+    pub name: String,
+    /// ランタイム（例: "iOS 17.2"）
+    pub runtime: String,
+    /// デバイスのディレクトリ（CoreSimulator/Devices/<UDID>）
+    pub device_dir: PathBuf,
+    /// デバイスのディレクトリのサイズ（バイト、`measure_size` を呼ぶまでは 0）
+    pub size: u64,
+    /// 利用可能か（ランタイムが削除済みなどの場合は false）
+    pub is_available: bool,
+}
+
+impl Simulator {
+    /// デバイスのディレクトリのサイズを計算して `size` に設定
+    pub fn measure_size(&mut self) -> Result<()> {
+        if self.device_dir.exists() {
+            self.size = utils::calculate_dir_size(&self.device_dir)?;
+        }
+        Ok(())
+    }
+}
+
+/// DeviceSupport のバージョンごとのディレクトリ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceSupportDir {
+    /// OS（DeviceSupport のディレクトリ名。例: "iOS DeviceSupport"）
+    pub platform: String,
+    /// ディレクトリのパス
+    pub path: PathBuf,
+    /// OS バージョン（例: [17, 2]）
+    pub version: Vec<u32>,
+    /// 最終更新日時
+    pub modified: Option<SystemTime>,
+    /// サイズ（バイト）
+    pub size: u64,
+}
+
+/// 既定で OS ごとに残す DeviceSupport の数
+pub const DEFAULT_DEVICE_SUPPORT_KEEP: usize = 1;
+
+/// DeviceSupport のディレクトリ名（OS ごと）
+const DEVICE_SUPPORT_DIRS: &[&str] = &[
+    "iOS DeviceSupport",
+    "watchOS DeviceSupport",
+    "tvOS DeviceSupport",
+    "visionOS DeviceSupport",
+];
+
+/// ~/Library/Developer
+fn developer_dir() -> Option<PathBuf> {
+    env::var("HOME")
+        .ok()
+        .map(|home| PathBuf::from(home).join("Library").join("Developer"))
+}
+
+/// Xcode DerivedData のディレクトリ（~/Library/Developer/Xcode/DerivedData）
+pub fn derived_data_dir() -> Option<PathBuf> {
+    developer_dir().map(|dir| dir.join("Xcode").join("DerivedData"))
 }
 
 /// Xcode DerivedData を検索
 pub fn find_xcode_derived_data() -> Result<Option<XcodeDerivedData>> {
-    let derived_data_dir = if let Ok(home) = env::var("HOME") {
-        PathBuf::from(home)
-            .join("Library")
-            .join("Developer")
-            .join("Xcode")
-            .join("DerivedData")
-    } else {
+    let Some(derived_data_dir) = derived_data_dir() else {
         return Ok(None);
     };
 
@@ -37,6 +103,7 @@ pub fn find_xcode_derived_data() -> Result<Option<XcodeDerivedData>> {
     Ok(Some(XcodeDerivedData {
         derived_data_dir,
         size,
+        source: DirSource::Default,
     }))
 }
 
@@ -48,12 +115,271 @@ pub fn clean_derived_data(data: &XcodeDerivedData) -> Result<()> {
     Ok(())
 }
 
+/// DeviceSupport のディレクトリ名から OS バージョンを取得
+///
+/// 例: "17.2 (21C62)" や "iPhone15,2 17.2 (21C62)" -> [17, 2]
+fn parse_device_support_version(name: &str) -> Option<Vec<u32>> {
+    name.split_whitespace().find_map(|token| {
+        token
+            .split('.')
+            .map(|part| part.parse::<u32>().ok())
+            .collect::<Option<Vec<_>>>()
+    })
+}
+
+/// ~/Library/Developer/Xcode の DeviceSupport を一覧（OS ごとに古い順）
+pub fn list_device_support() -> Result<Vec<DeviceSupportDir>> {
+    match developer_dir() {
+        Some(developer_dir) => list_device_support_in(&developer_dir.join("Xcode")),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// DeviceSupport を一覧（OS ごとに、バージョン・更新日時の古い順）
+///
+/// バージョンを読み取れないディレクトリは含まない
+fn list_device_support_in(xcode_dir: &Path) -> Result<Vec<DeviceSupportDir>> {
+    let mut dirs = Vec::new();
+
+    for dir_name in DEVICE_SUPPORT_DIRS {
+        let Ok(entries) = fs::read_dir(xcode_dir.join(dir_name)) else {
+            continue;
+        };
+
+        let mut versions: Vec<DeviceSupportDir> = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+            .filter_map(|e| {
+                let version = parse_device_support_version(&e.file_name().to_string_lossy())?;
+                Some(DeviceSupportDir {
+                    platform: dir_name.to_string(),
+                    path: e.path(),
+                    version,
+                    modified: utils::last_modified(&e.path()),
+                    size: 0,
+                })
+            })
+            .collect();
+
+        // 同じバージョン（端末の機種違い）は更新日時で並べる
+        versions.sort_by(|a, b| {
+            a.version
+                .cmp(&b.version)
+                .then_with(|| a.modified.cmp(&b.modified))
+                .then_with(|| a.path.cmp(&b.path))
+        });
+
+        for dir in &mut versions {
+            dir.size = utils::calculate_dir_size(&dir.path)?;
+        }
+        dirs.extend(versions);
+    }
+
+    Ok(dirs)
+}
+
+/// 古い DeviceSupport（OS ごとに新しい `keep` 件以外）を検索
+///
+/// 実機を接続するたびにバージョンごとのシンボルが数 GB 単位で追加されるが、
+/// 古いバージョンは端末を更新した後は使われない
+pub fn find_old_device_support(xcode_dir: &Path, keep: usize) -> Result<Vec<DeviceSupportDir>> {
+    let dirs = list_device_support_in(xcode_dir)?;
+
+    let mut old_versions = Vec::new();
+    for dir_name in DEVICE_SUPPORT_DIRS {
+        let platform: Vec<&DeviceSupportDir> = dirs.iter().filter(|d| d.platform == *dir_name).collect();
+        let old_count = platform.len().saturating_sub(keep);
+        old_versions.extend(platform.into_iter().take(old_count).cloned());
+    }
+
+    Ok(old_versions)
+}
+
+/// `xcrun simctl list devices --json` の出力
+#[derive(Debug, Deserialize)]
+struct SimctlDeviceList {
+    devices: std::collections::BTreeMap<String, Vec<SimctlDevice>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SimctlDevice {
+    udid: String,
+    name: String,
+    #[serde(default)]
+    data_path: Option<PathBuf>,
+    #[serde(default = "default_available")]
+    is_available: bool,
+}
+
+fn default_available() -> bool {
+    true
+}
+
+/// ランタイム識別子を表示名に変換
+///
+/// 例: "com.apple.CoreSimulator.SimRuntime.iOS-17-2" -> "iOS 17.2"
+fn runtime_name(identifier: &str) -> String {
+    let name = identifier.rsplit('.').next().unwrap_or(identifier);
+    match name.split_once('-') {
+        Some((os, version)) => format!("{} {}", os, version.replace('-', ".")),
+        None => name.to_string(),
+    }
+}
+
+/// simctl の JSON からシミュレータ一覧を取得（サイズは未計算）
+fn parse_simulators(json: &str, devices_dir: &Path) -> Vec<Simulator> {
+    let Ok(list) = serde_json::from_str::<SimctlDeviceList>(json) else {
+        return Vec::new();
+    };
+
+    list.devices
+        .into_iter()
+        .flat_map(|(runtime, devices)| {
+            let runtime = runtime_name(&runtime);
+            devices.into_iter().map(move |device| {
+                // dataPath は Devices/<UDID>/data を指す
+                let device_dir = device
+                    .data_path
+                    .as_deref()
+                    .and_then(Path::parent)
+                    .map(Path::to_path_buf)
+                    .unwrap_or_else(|| devices_dir.join(&device.udid));
+                Simulator {
+                    udid: device.udid,
+                    name: device.name,
+                    runtime: runtime.clone(),
+                    device_dir,
+                    size: 0,
+                    is_available: device.is_available,
+                }
+            })
+        })
+        .collect()
+}
+
+/// simctl（Xcode コマンドラインツール）が使えるか確認
+pub fn is_simctl_available() -> bool {
+    utils::run_command_with_timeout(
+        Command::new("xcrun").args(["simctl", "help"]),
+        utils::command_timeout(),
+    )
+    .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+/// シミュレータ一覧をランタイム・デバイスごとに取得
+///
+/// デバイスごとのサイズは計算しない。必要なデバイスだけ `Simulator::measure_size` で計算する
+pub fn list_simulators() -> Result<Vec<Simulator>> {
+    let Some(developer_dir) = developer_dir() else {
+        return Ok(Vec::new());
+    };
+
+    let output = utils::run_command_with_timeout(
+        Command::new("xcrun").args(["simctl", "list", "devices", "--json"]),
+        utils::command_timeout(),
+    )?;
+
+    if !output.status.success() {
+        return Err(std::io::Error::other(format!(
+            "xcrun simctl list failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+        .into());
+    }
+
+    Ok(parse_simulators(
+        &String::from_utf8_lossy(&output.stdout),
+        &developer_dir.join("CoreSimulator").join("Devices"),
+    ))
+}
+
+/// 利用できないシミュレータをサイズ付きで取得
+fn unavailable_simulators() -> Result<Vec<Simulator>> {
+    let mut simulators: Vec<Simulator> = list_simulators()?
+        .into_iter()
+        .filter(|s| !s.is_available)
+        .collect();
+    for simulator in &mut simulators {
+        simulator.measure_size()?;
+    }
+    Ok(simulators)
+}
+
+/// 利用できないシミュレータを検索（xcrun がない環境では空）
+pub fn find_unavailable_simulators() -> Vec<Simulator> {
+    unavailable_simulators().unwrap_or_default()
+}
+
+/// 利用できないシミュレータをすべて削除（`xcrun simctl delete unavailable`）し、出力を返す
+pub fn delete_unavailable_simulators() -> Result<String> {
+    let output = Command::new("xcrun")
+        .args(["simctl", "delete", "unavailable"])
+        .output()?;
+
+    if !output.status.success() {
+        return Err(std::io::Error::other(format!(
+            "xcrun simctl delete unavailable failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+        .into());
+    }
+
+    let mut message = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !stderr.trim().is_empty() {
+        if !message.is_empty() {
+            message.push('\n');
+        }
+        message.push_str(stderr.trim());
+    }
+    Ok(message)
+}
+
+/// シミュレータを削除（`xcrun simctl delete`）
+///
+/// ディレクトリを直接消すと CoreSimulator の管理情報と食い違うため、simctl に任せる
+pub fn delete_simulators(udids: &[&str]) -> Result<()> {
+    if udids.is_empty() {
+        return Ok(());
+    }
+
+    let output = Command::new("xcrun")
+        .args(["simctl", "delete"])
+        .args(udids)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(std::io::Error::other(format!(
+            "xcrun simctl delete failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+        .into());
+    }
+
+    Ok(())
+}
+
 /// Xcode クリーナー
-pub struct XcodeCleaner;
+///
+/// DerivedData・シミュレータのキャッシュ・古い DeviceSupport・利用できないシミュレータ・Archives を対象にする
+pub struct XcodeCleaner {
+    /// OS ごとに残す DeviceSupport の数（新しいものから）
+    device_support_keep: usize,
+}
 
 impl XcodeCleaner {
     pub fn new() -> Self {
-        Self
+        Self {
+            device_support_keep: DEFAULT_DEVICE_SUPPORT_KEEP,
+        }
+    }
+
+    /// OS ごとに残す DeviceSupport の数を設定
+    pub fn with_device_support_keep(mut self, keep: usize) -> Self {
+        self.device_support_keep = keep;
+        self
     }
 }
 
@@ -63,31 +389,176 @@ impl Default for XcodeCleaner {
     }
 }
 
+/// シミュレータのデバイスディレクトリか（削除に simctl を使う）
+fn is_simulator_device_dir(path: &Path) -> bool {
+    path.parent()
+        .is_some_and(|parent| parent.ends_with(Path::new("CoreSimulator").join("Devices")))
+}
+
+/// 利用できないシミュレータの削除対象の項目
+fn unavailable_simulator_item(simulator: Simulator) -> CleanableItem {
+    CleanableItem::with_metadata(
+        format!("シミュレータ（利用不可）: {} ({})", simulator.name, simulator.runtime),
+        simulator.device_dir,
+        simulator.size,
+        CleanableMetadata::safe(),
+    )
+}
+
+/// シミュレータの項目を UDID（デバイスディレクトリ名）を指定して simctl で削除
+fn clean_simulator_items(
+    items: &[&CleanableItem],
+    on_cleaned: &mut dyn FnMut(&CleanableItem),
+) -> Result<Vec<String>> {
+    let udids: Vec<&str> = items
+        .iter()
+        .filter_map(|item| item.path.file_name().and_then(|n| n.to_str()))
+        .collect();
+    delete_simulators(&udids)?;
+    items.iter().for_each(|item| on_cleaned(item));
+
+    Ok(items.iter().map(|item| item.name.clone()).collect())
+}
+
+/// 利用できない iOS シミュレータのクリーナー（削除は xcrun simctl delete）
+pub struct SimulatorCleaner;
+
+impl SimulatorCleaner {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for SimulatorCleaner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cleanable for SimulatorCleaner {
+    fn scan(&self) -> Result<Vec<CleanableItem>> {
+        Ok(unavailable_simulators()?
+            .into_iter()
+            .map(unavailable_simulator_item)
+            .collect())
+    }
+
+    fn name(&self) -> &str {
+        "iOS シミュレータ"
+    }
+
+    fn icon(&self) -> &str {
+        "📱"
+    }
+
+    fn clean(
+        &self,
+        items: &[CleanableItem],
+        on_cleaned: &mut dyn FnMut(&CleanableItem),
+    ) -> Result<Vec<String>> {
+        let simulators: Vec<&CleanableItem> = items
+            .iter()
+            .filter(|item| !crate::utils::is_protected_path(&item.path))
+            .collect();
+        clean_simulator_items(&simulators, on_cleaned)
+    }
+}
+
 impl Cleanable for XcodeCleaner {
     fn scan(&self) -> Result<Vec<CleanableItem>> {
+        let mut items = Vec::new();
+
         if let Some(data) = find_xcode_derived_data()? {
-            Ok(vec![CleanableItem::new(
+            items.push(CleanableItem::with_metadata(
                 "Xcode DerivedData".to_string(),
                 data.derived_data_dir,
                 data.size,
-            )])
-        } else {
-            Ok(Vec::new())
+                CleanableMetadata::safe(),
+            ));
+        }
+
+        let Some(developer_dir) = developer_dir() else {
+            return Ok(items);
+        };
+
+        // シミュレータのキャッシュ（必要に応じて再生成される）
+        let simulator_caches = developer_dir.join("CoreSimulator").join("Caches");
+        if simulator_caches.exists() {
+            items.push(CleanableItem::with_metadata(
+                "CoreSimulator Caches".to_string(),
+                simulator_caches.clone(),
+                utils::calculate_dir_size(&simulator_caches)?,
+                CleanableMetadata::safe(),
+            ));
+        }
+
+        // 古い DeviceSupport（必要になれば実機接続時に再取得される）
+        for dir in find_old_device_support(&developer_dir.join("Xcode"), self.device_support_keep)? {
+            let version = dir
+                .path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            items.push(CleanableItem::with_metadata(
+                format!("{} {}", dir.platform, version),
+                dir.path,
+                dir.size,
+                CleanableMetadata::safe(),
+            ));
         }
+
+        items.extend(find_unavailable_simulators().into_iter().map(unavailable_simulator_item));
+
+        // 配布用のビルドを含むため要確認
+        let archives = developer_dir.join("Xcode").join("Archives");
+        if archives.exists() {
+            items.push(CleanableItem::with_metadata(
+                "Xcode Archives".to_string(),
+                archives.clone(),
+                utils::calculate_dir_size(&archives)?,
+                CleanableMetadata::needs_review(),
+            ));
+        }
+
+        Ok(items)
     }
 
     fn name(&self) -> &str {
         "Xcode"
     }
 
+    fn resolved_dir(&self) -> Option<(PathBuf, DirSource)> {
+        developer_dir().map(|dir| (dir, DirSource::Default))
+    }
+
     fn icon(&self) -> &str {
         "🔨"
     }
+
+    fn clean(
+        &self,
+        items: &[CleanableItem],
+        on_cleaned: &mut dyn FnMut(&CleanableItem),
+    ) -> Result<Vec<String>> {
+        let (simulators, others): (Vec<&CleanableItem>, Vec<&CleanableItem>) = items
+            .iter()
+            .filter(|item| !crate::utils::is_protected_path(&item.path))
+            .partition(|item| is_simulator_device_dir(&item.path));
+
+        let simulators = clean_simulator_items(&simulators, on_cleaned)?;
+
+        let others: Vec<CleanableItem> = others.into_iter().cloned().collect();
+        let mut cleaned = crate::cleanable::clean_items(&others, &mut *on_cleaned)?;
+        cleaned.extend(simulators);
+
+        Ok(cleaned)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tempfile::TempDir;
 
     #[test]
     fn test_find_xcode_derived_data() {
@@ -95,4 +566,151 @@ mod tests {
         let result = find_xcode_derived_data();
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_parse_device_support_version() {
+        assert_eq!(parse_device_support_version("17.2 (21C62)"), Some(vec![17, 2]));
+        assert_eq!(
+            parse_device_support_version("iPhone15,2 16.4.1 (20E252)"),
+            Some(vec![16, 4, 1])
+        );
+        assert_eq!(parse_device_support_version("Extras"), None);
+    }
+
+    #[test]
+    fn test_find_old_device_support() -> Result<()> {
+        let temp = TempDir::new()?;
+        let ios = temp.path().join("iOS DeviceSupport");
+        for version in ["16.4 (20E247)", "17.2 (21C62)", "9.3 (13E233)"] {
+            fs::create_dir_all(ios.join(version).join("Symbols"))?;
+            fs::write(ios.join(version).join("Symbols").join("dyld"), vec![0u8; 100])?;
+        }
+        let watchos = temp.path().join("watchOS DeviceSupport");
+        fs::create_dir_all(watchos.join("Watch6,1 10.2 (21S364)"))?;
+
+        let old = find_old_device_support(temp.path(), DEFAULT_DEVICE_SUPPORT_KEEP)?;
+
+        // 17.2 と唯一の watchOS は残す（9.3 < 16.4 を数値で比較）
+        assert_eq!(
+            old.iter().map(|d| (d.path.clone(), d.size)).collect::<Vec<_>>(),
+            vec![
+                (ios.join("9.3 (13E233)"), 100),
+                (ios.join("16.4 (20E247)"), 100),
+            ]
+        );
+
+        // 新しい 2 件を残す
+        let old = find_old_device_support(temp.path(), 2)?;
+        assert_eq!(old.iter().map(|d| d.path.clone()).collect::<Vec<_>>(), vec![ios.join("9.3 (13E233)")]);
+
+        // 0 件ならすべて削除対象
+        assert_eq!(find_old_device_support(temp.path(), 0)?.len(), 4);
+        assert!(find_old_device_support(temp.path(), 10)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_device_support() -> Result<()> {
+        let temp = TempDir::new()?;
+        let ios = temp.path().join("iOS DeviceSupport");
+        for name in ["17.2 (21C62)", "iPhone15,2 16.4.1 (20E252)", "Extras"] {
+            fs::create_dir_all(ios.join(name))?;
+        }
+
+        let dirs = list_device_support_in(temp.path())?;
+        assert_eq!(
+            dirs.iter().map(|d| (d.platform.as_str(), d.version.clone())).collect::<Vec<_>>(),
+            vec![("iOS DeviceSupport", vec![16, 4, 1]), ("iOS DeviceSupport", vec![17, 2])]
+        );
+        assert!(dirs.iter().all(|d| d.modified.is_some()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_runtime_name() {
+        assert_eq!(runtime_name("com.apple.CoreSimulator.SimRuntime.iOS-17-2"), "iOS 17.2");
+        assert_eq!(runtime_name("com.apple.CoreSimulator.SimRuntime.watchOS-10-0"), "watchOS 10.0");
+        assert_eq!(runtime_name("unknown"), "unknown");
+    }
+
+    #[test]
+    fn test_parse_simulators() {
+        let json = r#"{
+  "devices" : {
+    "com.apple.CoreSimulator.SimRuntime.iOS-15-5" : [
+      {
+        "dataPath" : "/Users/me/Library/Developer/CoreSimulator/Devices/AAAA-1111/data",
+        "udid" : "AAAA-1111",
+        "isAvailable" : false,
+        "availabilityError" : "runtime profile not found",
+        "name" : "iPhone 13"
+      }
+    ],
+    "com.apple.CoreSimulator.SimRuntime.iOS-17-2" : [
+      {
+        "udid" : "BBBB-2222",
+        "isAvailable" : true,
+        "name" : "iPhone 15"
+      }
+    ]
+  }
+}"#;
+        let devices_dir = Path::new("/Users/me/Library/Developer/CoreSimulator/Devices");
+        let simulators = parse_simulators(json, devices_dir);
+
+        assert_eq!(
+            simulators,
+            vec![
+                Simulator {
+                    udid: "AAAA-1111".to_string(),
+                    name: "iPhone 13".to_string(),
+                    runtime: "iOS 15.5".to_string(),
+                    device_dir: devices_dir.join("AAAA-1111"),
+                    size: 0,
+                    is_available: false,
+                },
+                Simulator {
+                    udid: "BBBB-2222".to_string(),
+                    name: "iPhone 15".to_string(),
+                    runtime: "iOS 17.2".to_string(),
+                    device_dir: devices_dir.join("BBBB-2222"),
+                    size: 0,
+                    is_available: true,
+                },
+            ]
+        );
+        assert!(parse_simulators("not json", devices_dir).is_empty());
+
+        assert!(is_simulator_device_dir(&devices_dir.join("AAAA-1111")));
+        assert!(!is_simulator_device_dir(&devices_dir.join("AAAA-1111").join("data")));
+        assert!(!is_simulator_device_dir(Path::new("/Users/me/Library/Developer/CoreSimulator/Caches")));
+    }
+
+    #[test]
+    fn test_simulator_measure_size() -> Result<()> {
+        let temp = TempDir::new()?;
+        let device_dir = temp.path().join("AAAA-1111");
+        std::fs::create_dir_all(device_dir.join("data"))?;
+        std::fs::write(device_dir.join("data").join("app.bin"), vec![0u8; 300])?;
+
+        let mut simulator = Simulator {
+            udid: "AAAA-1111".to_string(),
+            name: "iPhone 13".to_string(),
+            runtime: "iOS 15.5".to_string(),
+            device_dir,
+            size: 0,
+            is_available: false,
+        };
+        simulator.measure_size()?;
+        assert_eq!(simulator.size, 300);
+
+        // ディレクトリがなければ 0 のまま
+        simulator.device_dir = temp.path().join("BBBB-2222");
+        simulator.size = 0;
+        simulator.measure_size()?;
+        assert_eq!(simulator.size, 0);
+        Ok(())
+    }
 }